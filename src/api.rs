@@ -1,21 +1,140 @@
-use crate::config::Host;
-use anyhow::{Context, Result};
+use crate::config::{Host, PendingApiOp};
+use anyhow::Result;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
 
+pub const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// Connect and read/write timeout for API calls, from
+/// `AppConfig::api_timeout_secs`.
+static TIMEOUT_SECS: AtomicU64 = AtomicU64::new(DEFAULT_TIMEOUT_SECS);
+
+pub fn set_timeout(secs: u64) {
+    TIMEOUT_SECS.store(secs.max(1), Ordering::Relaxed);
+}
+
+/// ureq agent with the configured timeouts, so a stalled API fails instead
+/// of hanging the sync.
+fn agent() -> ureq::Agent {
+    let timeout = Duration::from_secs(TIMEOUT_SECS.load(Ordering::Relaxed));
+    ureq::AgentBuilder::new()
+        .timeout_connect(timeout)
+        .timeout_read(timeout)
+        .timeout_write(timeout)
+        .build()
+}
+
+/// Appears in the error when the API refuses the key (HTTP 401/403); the
+/// GUI watches for it to ask for a new key.
+pub const AUTH_REJECTED: &str = "API key rejected";
+
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Sends the request built by `request` (with `body` as JSON, if any),
+/// retrying connection failures and 5xx responses with exponential backoff
+/// (0.5s, then 1s). Auth errors and other 4xx responses fail at once.
+fn call_with_retry(
+    what: &str,
+    request: impl Fn() -> ureq::Request,
+    body: Option<&serde_json::Value>,
+) -> Result<ureq::Response> {
+    call(what, request, body, MAX_ATTEMPTS)
+}
+
+/// `call_with_retry` with at most `max_attempts` tries; 1 sends it only once.
+fn call(
+    what: &str,
+    request: impl Fn() -> ureq::Request,
+    body: Option<&serde_json::Value>,
+    max_attempts: u32,
+) -> Result<ureq::Response> {
+    let mut delay = Duration::from_millis(500);
+    let mut attempt = 1;
+    loop {
+        let sent = match body {
+            Some(body) => request().send_json(body.clone()),
+            None => request().call(),
+        };
+        let err = match sent {
+            Ok(resp) => return Ok(resp),
+            Err(ureq::Error::Status(code @ (401 | 403), _)) => {
+                anyhow::bail!("{} (HTTP {}), re-enter it in Settings", AUTH_REJECTED, code)
+            }
+            Err(err) => err,
+        };
+        if !is_transient_ureq(&err) || attempt >= max_attempts {
+            let attempts = if attempt > 1 { format!(" after {} attempts", attempt) } else { String::new() };
+            return Err(anyhow::Error::new(err).context(format!("{}{}", what, attempts)));
+        }
+        thread::sleep(delay);
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+/// Upper bound on pages followed in one fetch, in case a server keeps
+/// handing out cursors.
+const MAX_PAGES: u32 = 100;
+
+/// Where the next page of connections is, as announced by the last response.
+enum NextPage {
+    Cursor(String),
+    Page(u64),
+}
+
+fn next_page(json: &serde_json::Value) -> Option<NextPage> {
+    if let Some(cursor) = json.get("next_cursor").and_then(|c| c.as_str()).filter(|c| !c.is_empty()) {
+        return Some(NextPage::Cursor(cursor.to_string()));
+    }
+    let page = json.get("page").and_then(|p| p.as_u64())?;
+    let more = json.get("has_more").and_then(|m| m.as_bool()).unwrap_or(false)
+        || json
+            .get("total_pages")
+            .and_then(|t| t.as_u64())
+            .is_some_and(|total| page < total);
+    more.then_some(NextPage::Page(page + 1))
+}
+
+/// Fetches every connection, following `next_cursor` or `page` /
+/// `total_pages` (`has_more`) until the server reports no further pages.
 pub fn fetch_from_api(api_url: &str, api_key: &str) -> Result<Vec<Host>> {
     let url = format!("{}/api/cli/ssh", api_url);
-    let resp = ureq::get(&url)
-        .set("Authorization", &format!("Bearer {}", api_key))
-        .call()
-        .context("API fetch failed")?;
-
-    let json: serde_json::Value = resp.into_json()?;
-    let connections = json
-        .get("connections")
-        .and_then(|c| c.as_array())
-        .cloned()
-        .unwrap_or_default();
+    let mut connections: Vec<serde_json::Value> = Vec::new();
+    let mut next: Option<NextPage> = None;
+    for _ in 0..MAX_PAGES {
+        let resp = call_with_retry(
+            "API fetch failed",
+            || {
+                let req = agent().get(&url).set("Authorization", &format!("Bearer {}", api_key));
+                match &next {
+                    Some(NextPage::Cursor(cursor)) => req.query("cursor", cursor),
+                    Some(NextPage::Page(page)) => req.query("page", &page.to_string()),
+                    None => req,
+                }
+            },
+            None,
+        )?;
+        let json: serde_json::Value = resp.into_json()?;
+        if let Some(page) = json.get("connections").and_then(|c| c.as_array()) {
+            connections.extend(page.iter().cloned());
+        }
+        next = match (next_page(&json), &next) {
+            (Some(NextPage::Cursor(new)), Some(NextPage::Cursor(old))) if new == *old => {
+                anyhow::bail!("API fetch failed: server repeated page cursor '{}'", new)
+            }
+            (found, _) => found,
+        };
+        if next.is_none() {
+            return Ok(parse_connections(connections));
+        }
+    }
+    // A partial list would replace the full local one, so refuse it.
+    anyhow::bail!("API fetch failed: more than {} pages of connections", MAX_PAGES)
+}
 
-    let hosts: Vec<Host> = connections
+fn parse_connections(connections: Vec<serde_json::Value>) -> Vec<Host> {
+    connections
         .into_iter()
         .filter_map(|c| {
             Some(Host {
@@ -31,11 +150,63 @@ pub fn fetch_from_api(api_url: &str, api_key: &str) -> Result<Vec<Host>> {
                     .get("password")
                     .and_then(|v| v.as_str())
                     .map(|s| s.to_string()),
+                ..Default::default()
             })
         })
-        .collect();
+        .collect()
+}
+
+/// A failed request with secrets masked out of its message. The HTTP status
+/// (`None` for a connection failure) is kept so callers can still tell a
+/// deleted host or a server outage apart.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: Option<u16>,
+    transient: bool,
+    msg: String,
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// `err` flattened with `host`'s secrets masked; the request body carried the
+/// password, and a server may echo it back in an error.
+fn redacted(host: &Host, err: anyhow::Error) -> anyhow::Error {
+    let status = http_status(&err);
+    let transient = is_transient(&err);
+    anyhow::Error::new(ApiError { status, transient, msg: host.redact(&format!("{:#}", err)) })
+}
+
+/// HTTP status of a failed call, whether or not its message was redacted.
+pub fn http_status(err: &anyhow::Error) -> Option<u16> {
+    if let Some(api) = err.downcast_ref::<ApiError>() {
+        return api.status;
+    }
+    match err.downcast_ref::<ureq::Error>() {
+        Some(ureq::Error::Status(code, _)) => Some(*code),
+        _ => None,
+    }
+}
 
-    Ok(hosts)
+/// Whether a failed call is worth sending again later: the server could not
+/// be reached or answered 5xx. A rejected key or another 4xx won't improve.
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    if let Some(api) = err.downcast_ref::<ApiError>() {
+        return api.transient;
+    }
+    err.downcast_ref::<ureq::Error>().is_some_and(is_transient_ureq)
+}
+
+fn is_transient_ureq(err: &ureq::Error) -> bool {
+    match err {
+        ureq::Error::Status(code, _) => *code >= 500,
+        ureq::Error::Transport(_) => true,
+    }
 }
 
 pub fn create_on_api(api_url: &str, api_key: &str, host: &Host) -> Result<String> {
@@ -48,11 +219,20 @@ pub fn create_on_api(api_url: &str, api_key: &str, host: &Host) -> Result<String
         "password": host.password.clone().unwrap_or_default(),
     });
 
-    let resp = ureq::post(&url)
-        .set("Authorization", &format!("Bearer {}", api_key))
-        .set("Content-Type", "application/json")
-        .send_json(body)
-        .context("API create failed")?;
+    // A POST that timed out may still have created the host, so it is never
+    // repeated here; the queued create is reconciled by alias before it is
+    // sent again (see `apply_op`).
+    let resp = call(
+        "API create failed",
+        || {
+            agent().post(&url)
+                .set("Authorization", &format!("Bearer {}", api_key))
+                .set("Content-Type", "application/json")
+        },
+        Some(&body),
+        1,
+    )
+    .map_err(|e| redacted(host, e))?;
 
     let json: serde_json::Value = resp.into_json()?;
     let id = json
@@ -74,20 +254,78 @@ pub fn update_on_api(api_url: &str, api_key: &str, host: &Host) -> Result<()> {
         "password": host.password.clone().unwrap_or_default(),
     });
 
-    ureq::put(&url)
-        .set("Authorization", &format!("Bearer {}", api_key))
-        .set("Content-Type", "application/json")
-        .send_json(body)
-        .context("API update failed")?;
+    call_with_retry(
+        "API update failed",
+        || {
+            agent().put(&url)
+                .set("Authorization", &format!("Bearer {}", api_key))
+                .set("Content-Type", "application/json")
+        },
+        Some(&body),
+    )
+    .map_err(|e| redacted(host, e))?;
 
     Ok(())
 }
 
 pub fn delete_on_api(api_url: &str, api_key: &str, id: &str) -> Result<()> {
     let url = format!("{}/api/cli/ssh/{}", api_url, id);
-    ureq::delete(&url)
-        .set("Authorization", &format!("Bearer {}", api_key))
-        .call()
-        .context("API delete failed")?;
+    call_with_retry(
+        "API delete failed",
+        || agent().delete(&url).set("Authorization", &format!("Bearer {}", api_key)),
+        None,
+    )?;
     Ok(())
 }
+
+/// Creates `host`, unless an earlier attempt whose answer was lost already
+/// did: a server connection with the same alias is updated and its id
+/// returned instead.
+fn create_or_adopt(api_url: &str, api_key: &str, host: &Host) -> Result<String> {
+    let existing = fetch_from_api(api_url, api_key)
+        .map_err(|e| redacted(host, e))?
+        .into_iter()
+        .find(|h| h.alias == host.alias)
+        .and_then(|h| h.id);
+    match existing {
+        Some(id) => {
+            update_on_api(api_url, api_key, &Host { id: Some(id.clone()), ..host.clone() })?;
+            Ok(id)
+        }
+        None => create_on_api(api_url, api_key, host),
+    }
+}
+
+/// Sends one host change. `host` is the current local copy for creates and
+/// updates; if it is gone there is nothing left to send. Returns the new id
+/// of a created host.
+pub fn apply_op(api_url: &str, api_key: &str, op: &PendingApiOp, host: Option<&Host>) -> Result<Option<String>> {
+    let sent = match (op, host) {
+        (PendingApiOp::Create { .. }, Some(host)) => return create_or_adopt(api_url, api_key, host).map(Some),
+        (PendingApiOp::Update { .. }, Some(host)) => update_on_api(api_url, api_key, host),
+        (PendingApiOp::Delete { id }, _) => delete_on_api(api_url, api_key, id),
+        (_, None) => return Ok(None),
+    };
+    // The host was deleted on the server meanwhile; nothing left to change.
+    if sent.as_ref().err().and_then(http_status) == Some(404) {
+        return Ok(None);
+    }
+    sent.map(|_| None)
+}
+
+/// Replays queued changes in order, stopping at the first failure that may
+/// pass (see `is_transient`). A change the server refuses is dropped rather
+/// than replayed forever. Returns how many were handled, and the error that
+/// stopped the replay.
+pub fn replay_pending(api_url: &str, api_key: &str, ops: &[(PendingApiOp, Option<Host>)]) -> (usize, Result<()>) {
+    for (done, (op, host)) in ops.iter().enumerate() {
+        match apply_op(api_url, api_key, op, host.as_ref()) {
+            // A rejected API key carries no status; stop there so nothing is dropped.
+            Err(e) if is_transient(&e) || http_status(&e).is_none() => {
+                return (done, Err(e.context("replaying offline changes")));
+            }
+            _ => {}
+        }
+    }
+    (ops.len(), Ok(()))
+}
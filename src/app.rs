@@ -1,24 +1,33 @@
 use iced::keyboard::{key::Named, Key, Modifiers};
-use iced::widget::{button, column, container, rich_text, row, scrollable, text, text_input, Column};
+use iced::widget::{
+    button, column, container, rich_text, row, scrollable, stack, text, text_input, Column,
+};
 use iced::{event, keyboard, Alignment, Element, Font, Length, Subscription, Task};
-use std::collections::HashMap;
-use std::io::{Read, Write};
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Read;
 use std::process::{Child, ChildStdin};
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
-use std::net::TcpStream;
 use std::time::Duration;
+use sha2::{Digest, Sha256};
 use sysinfo::{Disks, System};
 use vt100::Parser;
 
 use crate::api;
-use crate::config::{self, AppConfig, AppTheme, Host, Language, LayoutPreset};
+use crate::config::{self, AppConfig, AppTheme, Host, HostSort, Language, LayoutPreset, PendingApiOp};
+use crate::forward;
 use crate::ftp;
+use crate::history;
 use crate::i18n::Texts;
-use rfd;
+use crate::placeholders;
+use crate::session_log::{self, SessionLog};
+use crate::ssh;
 use crate::terminal::bridge;
+use crate::terminal::hyperlink::{self, LinkTracker};
+use crate::terminal::protocol;
 use crate::theme;
-use crate::ui::{dialogs, ftp_panel, sidebar, status_bar, tab_bar, toolbar};
+use crate::ui::{chart, dialogs, ftp_panel, measure, sidebar, status_bar, tab_bar, toolbar};
 
 const TERMINAL_ROWS: u16 = 40;
 const TERMINAL_COLS: u16 = 132;
@@ -29,7 +38,7 @@ fn normalize_api_url(input: &str) -> String {
 
 // --- Security audit types ---
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SecuritySeverity {
     Critical,
     High,
@@ -39,6 +48,9 @@ pub enum SecuritySeverity {
 }
 
 impl SecuritySeverity {
+    pub const ALL: [SecuritySeverity; 5] =
+        [Self::Critical, Self::High, Self::Medium, Self::Low, Self::Info];
+
     pub fn label(&self) -> &'static str {
         match self {
             Self::Critical => "CRITICAL",
@@ -75,8 +87,125 @@ pub struct SecurityFinding {
     pub severity: SecuritySeverity,
     pub category: String,
     pub message: String,
+    /// Same finding with host names, commands and password details stripped,
+    /// used when the audit dialog hides sensitive details.
+    pub redacted: String,
+    pub action: Option<AuditAction>,
+}
+
+/// Remediation offered next to a finding in the audit dialog.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuditAction {
+    /// Install the local public key on the host and drop the saved password.
+    InstallKey(usize),
+    EditHost(usize),
+    UseHttps,
+    OpenSettings,
+    OpenCustomCommands,
+}
+
+impl AuditAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::InstallKey(_) => "Switch to key auth",
+            Self::EditHost(_) => "Edit host",
+            Self::UseHttps => "Change API URL to HTTPS",
+            Self::OpenSettings => "Open settings",
+            Self::OpenCustomCommands => "Edit commands",
+        }
+    }
+}
+
+/// Stable keys for the Critical/High findings, used to diff audit runs.
+fn serious_finding_keys(findings: &[SecurityFinding]) -> Vec<String> {
+    findings
+        .iter()
+        .filter(|f| matches!(f.severity, SecuritySeverity::Critical | SecuritySeverity::High))
+        .map(|f| format!("{}|{}", f.category, f.message))
+        .collect()
+}
+
+/// Renders audit findings as a Markdown report grouped by severity, or as a
+/// JSON document. `redact` exports the same stripped text the dialog shows.
+fn audit_report(findings: &[SecurityFinding], markdown: bool, redact: bool) -> String {
+    let generated = session_log::iso_timestamp();
+    let message = |f: &SecurityFinding| if redact { f.redacted.clone() } else { f.message.clone() };
+    if !markdown {
+        let items: Vec<serde_json::Value> = findings
+            .iter()
+            .map(|f| {
+                serde_json::json!({
+                    "severity": f.severity.label(),
+                    "category": f.category,
+                    "message": message(f),
+                    "timestamp": generated,
+                })
+            })
+            .collect();
+        let doc = serde_json::json!({ "generated": generated, "findings": items });
+        return serde_json::to_string_pretty(&doc).unwrap_or_default();
+    }
+
+    let mut out = format!("# termissh Security Audit\n\nGenerated: {generated}\n");
+    for severity in SecuritySeverity::ALL {
+        let group: Vec<&SecurityFinding> = findings.iter().filter(|f| f.severity == severity).collect();
+        if group.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n## {} ({})\n\n", severity.label(), group.len()));
+        for f in group {
+            out.push_str(&format!("- **{}**: {}\n", f.category, message(f)));
+        }
+    }
+    if findings.is_empty() {
+        out.push_str("\nNo findings.\n");
+    }
+    out
+}
+
+/// First public key found in `~/.ssh`, preferring ed25519.
+fn local_public_key() -> Option<String> {
+    let home = directories::BaseDirs::new()?.home_dir().to_path_buf();
+    ["id_ed25519.pub", "id_ecdsa.pub", "id_rsa.pub"]
+        .iter()
+        .filter_map(|name| std::fs::read_to_string(home.join(".ssh").join(name)).ok())
+        .map(|key| key.trim().to_string())
+        .find(|key| !key.is_empty())
+}
+
+/// Whether a custom command script wipes files or disks or fork-bombs the
+/// host: a recursive `rm` however its flags are written, `mkfs`, or `dd`
+/// onto a device. Used by the audit and before a command is run.
+pub fn is_destructive(script: &str) -> bool {
+    let squashed: String = script.chars().filter(|c| !c.is_whitespace()).collect();
+    if squashed.contains(":(){:|:&}") {
+        return true;
+    }
+    script
+        .split([';', '&', '|', '\n', '(', ')', '`', '{', '}'])
+        .any(|command| {
+            let words: Vec<&str> = command
+                .split_whitespace()
+                .map(|w| w.trim_matches(|c| c == '"' || c == '\''))
+                .skip_while(|w| matches!(*w, "sudo" | "doas" | "exec" | "command" | "nice" | "nohup" | "xargs" | "$"))
+                .collect();
+            let Some(program) = words.first().map(|w| w.rsplit('/').next().unwrap_or(w)) else {
+                return false;
+            };
+            let args = &words[1..];
+            match program {
+                "rm" => args.iter().take_while(|a| **a != "--").any(|a| {
+                    *a == "--recursive"
+                        || (a.starts_with('-') && !a.starts_with("--") && a.contains(['r', 'R']))
+                }),
+                "dd" => args.iter().any(|a| a.starts_with("of=/dev/")),
+                p => p == "mkfs" || p.starts_with("mkfs."),
+            }
+        })
 }
 
+/// Audits the saved configuration. Findings may mention host aliases and
+/// password lengths, but never the password value itself.
 pub fn run_security_audit(config: &AppConfig, api_url: &str) -> Vec<SecurityFinding> {
     let mut findings: Vec<SecurityFinding> = Vec::new();
 
@@ -86,7 +215,7 @@ pub fn run_security_audit(config: &AppConfig, api_url: &str) -> Vec<SecurityFind
         "pass", "test", "guest", "login", "master",
     ];
 
-    for host in &config.hosts {
+    for (i, host) in config.hosts.iter().enumerate() {
         // Root login
         if host.username == "root" {
             findings.push(SecurityFinding {
@@ -96,6 +225,8 @@ pub fn run_security_audit(config: &AppConfig, api_url: &str) -> Vec<SecurityFind
                     "[{}] Root login detected — use a non-root user with sudo instead",
                     host.alias
                 ),
+                redacted: "Root login configured on a host".into(),
+                action: Some(AuditAction::EditHost(i)),
             });
         }
 
@@ -108,6 +239,8 @@ pub fn run_security_audit(config: &AppConfig, api_url: &str) -> Vec<SecurityFind
                     "[{}] Password saved in config — consider SSH key auth instead",
                     host.alias
                 ),
+                redacted: "Password saved in config for a host".into(),
+                action: Some(AuditAction::InstallKey(i)),
             });
 
             // Short password
@@ -120,6 +253,8 @@ pub fn run_security_audit(config: &AppConfig, api_url: &str) -> Vec<SecurityFind
                         host.alias,
                         pwd.len()
                     ),
+                    redacted: "Password shorter than the recommended length".into(),
+                    action: Some(AuditAction::EditHost(i)),
                 });
             }
 
@@ -133,6 +268,8 @@ pub fn run_security_audit(config: &AppConfig, api_url: &str) -> Vec<SecurityFind
                         "[{}] Trivial password detected — change it immediately!",
                         host.alias
                     ),
+                    redacted: "Trivial password in use".into(),
+                    action: Some(AuditAction::EditHost(i)),
                 });
             }
         }
@@ -146,16 +283,50 @@ pub fn run_security_audit(config: &AppConfig, api_url: &str) -> Vec<SecurityFind
                     "[{}] Non-standard SSH port {} — obscures but does not replace security",
                     host.alias, host.port
                 ),
+                redacted: "Non-standard SSH port on a host".into(),
+                action: None,
             });
         }
     }
 
+    // Password reuse across hosts — grouped by digest so plaintext is never compared
+    let mut by_digest: Vec<(Vec<u8>, Vec<usize>)> = Vec::new();
+    for (i, host) in config.hosts.iter().enumerate() {
+        let Some(pwd) = host.password.as_deref().filter(|p| !p.is_empty()) else {
+            continue;
+        };
+        let digest = Sha256::digest(pwd.as_bytes()).to_vec();
+        match by_digest.iter_mut().find(|(d, _)| *d == digest) {
+            Some((_, hosts)) => hosts.push(i),
+            None => by_digest.push((digest, vec![i])),
+        }
+    }
+    for (_, indices) in by_digest.iter().filter(|(_, hosts)| hosts.len() > 1) {
+        let aliases: Vec<&str> = indices
+            .iter()
+            .map(|&i| config.hosts[i].alias.as_str())
+            .collect();
+        findings.push(SecurityFinding {
+            severity: SecuritySeverity::High,
+            category: "Password Reuse".into(),
+            message: format!(
+                "Same password used on {} hosts: {} — use a unique password per host",
+                aliases.len(),
+                aliases.join(", ")
+            ),
+            redacted: "Password reused across hosts".into(),
+            action: Some(AuditAction::EditHost(indices[0])),
+        });
+    }
+
     // HTTP API endpoint
     if api_url.starts_with("http://") && !api_url.is_empty() {
         findings.push(SecurityFinding {
             severity: SecuritySeverity::High,
             category: "API Security".into(),
             message: "API URL uses plain HTTP — switch to HTTPS to protect your API key".into(),
+            redacted: "API URL uses plain HTTP — switch to HTTPS to protect your API key".into(),
+            action: Some(AuditAction::UseHttps),
         });
     }
 
@@ -166,13 +337,15 @@ pub fn run_security_audit(config: &AppConfig, api_url: &str) -> Vec<SecurityFind
                 severity: SecuritySeverity::Medium,
                 category: "API Key".into(),
                 message: "API key format looks unusual — expected format: termi_<uuid>".into(),
+                redacted: "API key format looks unusual — expected format: termi_<uuid>".into(),
+                action: Some(AuditAction::OpenSettings),
             });
         }
     }
 
     // Custom commands with potentially dangerous scripts
     for cmd in &config.custom_commands {
-        if cmd.script.contains("rm -rf") || cmd.script.contains("mkfs") || cmd.script.contains(":(){:|:&}") {
+        if is_destructive(&cmd.script) {
             findings.push(SecurityFinding {
                 severity: SecuritySeverity::High,
                 category: "Custom Command".into(),
@@ -180,6 +353,8 @@ pub fn run_security_audit(config: &AppConfig, api_url: &str) -> Vec<SecurityFind
                     "[{}] Custom command '{}' contains potentially destructive operations",
                     cmd.trigger, cmd.description
                 ),
+                redacted: "Custom command contains potentially destructive operations".into(),
+                action: Some(AuditAction::OpenCustomCommands),
             });
         }
         if cmd.script.contains("sudo") {
@@ -190,15 +365,41 @@ pub fn run_security_audit(config: &AppConfig, api_url: &str) -> Vec<SecurityFind
                     "[{}] Custom command '{}' uses sudo — ensure you trust this script",
                     cmd.trigger, cmd.description
                 ),
+                redacted: "Custom command uses sudo".into(),
+                action: Some(AuditAction::OpenCustomCommands),
             });
         }
     }
 
-    // Config encryption confirmation (always show as positive)
+    // Where the config key lives decides what "encrypted at rest" is worth
+    let (severity, storage) = match config::key_store() {
+        Ok(config::KeyStore::MasterPassword) => (
+            SecuritySeverity::Info,
+            "Config is AES-256-GCM encrypted with a key derived from your master password (Argon2id)".to_string(),
+        ),
+        Ok(config::KeyStore::Keyring) => (
+            SecuritySeverity::Info,
+            "Config is AES-256-GCM encrypted on disk with a random key held in the OS keyring".to_string(),
+        ),
+        Ok(config::KeyStore::File(path)) => (
+            SecuritySeverity::Low,
+            format!(
+                "Config is AES-256-GCM encrypted, but no OS keyring was available: the key is in {} \
+                 (owner-only), so anyone who can read your files can decrypt it",
+                path.display()
+            ),
+        ),
+        Err(e) => (SecuritySeverity::Medium, format!("Config encryption key is unavailable: {:#}", e)),
+    };
     findings.push(SecurityFinding {
-        severity: SecuritySeverity::Info,
+        severity,
         category: "Storage".into(),
-        message: "Config is AES-256-GCM encrypted on disk — credentials are protected at rest".into(),
+        redacted: match severity {
+            SecuritySeverity::Info => "Config is encrypted at rest".into(),
+            _ => "Config encryption key is not held in the OS keyring".into(),
+        },
+        message: storage,
+        action: None,
     });
 
     if findings.iter().filter(|f| f.severity != SecuritySeverity::Info).count() == 0 {
@@ -206,6 +407,8 @@ pub fn run_security_audit(config: &AppConfig, api_url: &str) -> Vec<SecurityFind
             severity: SecuritySeverity::Info,
             category: "Overall".into(),
             message: "No critical security issues found — good job!".into(),
+            redacted: "No critical security issues found — good job!".into(),
+            action: None,
         });
     }
 
@@ -213,6 +416,161 @@ pub fn run_security_audit(config: &AppConfig, api_url: &str) -> Vec<SecurityFind
     findings
 }
 
+// --- Host connection errors ---
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HostErrorKind {
+    AuthFailed,
+    Unresolved,
+    Unreachable,
+    KeyRejected,
+    Other,
+}
+
+impl HostErrorKind {
+    pub fn classify(error: &str) -> Self {
+        let e = error.to_lowercase();
+        if e.contains("publickey") || e.contains("key rejected") || e.contains("key auth") {
+            Self::KeyRejected
+        } else if e.contains("auth") {
+            Self::AuthFailed
+        } else if e.contains("could not resolve") {
+            Self::Unresolved
+        } else if e.contains("connection failed")
+            || e.contains("refused")
+            || e.contains("timed out")
+            || e.contains("unreachable")
+            || e.contains("handshake")
+        {
+            Self::Unreachable
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Connection form field the quick-fix action focuses.
+    pub fn fix_field(self) -> &'static str {
+        match self {
+            Self::AuthFailed => "password",
+            Self::Unresolved | Self::Unreachable => "hostname",
+            Self::KeyRejected => "identity_file",
+            Self::Other => "alias",
+        }
+    }
+
+    pub fn fix_hint(self) -> &'static str {
+        match self {
+            Self::AuthFailed => "Click to fix the password",
+            Self::Unresolved => "Hostname does not resolve; click to fix it",
+            Self::Unreachable => "Click to fix the hostname / port",
+            Self::KeyRejected => "Click to check the key file",
+            Self::Other => "Click to edit the host",
+        }
+    }
+}
+
+/// Upper bound of the auto-reconnect backoff (1s, 2s, 4s, ... 30s).
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+/// Wait after the first shell output before typing a host's `on_connect`
+/// command, so it isn't swallowed while the prompt is still being drawn.
+const ON_CONNECT_DELAY: Duration = Duration::from_millis(500);
+
+/// `script` as typed into the shell on the user's behalf (custom commands,
+/// `on_connect`). The leading space keeps it out of the history file in bash
+/// with `HISTCONTROL=ignorespace`/`ignoreboth` (the Debian and Ubuntu default)
+/// and zsh with `HIST_IGNORE_SPACE`; other shells just see the space. Only
+/// the first line is marked, since later lines may be heredoc content.
+fn unlogged(script: &str) -> String {
+    format!(" {}", script)
+}
+
+/// Entries in the toolbar's Recent menu.
+const RECENT_HOSTS: usize = 5;
+
+/// Clipboard lifetime after Ctrl+Shift+V when the setting is off.
+const DEFAULT_CLIPBOARD_CLEAR_SECS: u64 = 30;
+
+/// Whether the text left of the cursor asks for a secret, e.g. `[sudo] password for ann: `.
+fn is_password_prompt(prompt: &str) -> bool {
+    let prompt = prompt.trim_end().to_lowercase();
+    prompt.ends_with(':')
+        && ["password", "passphrase", "passcode", "token", "secret"].iter().any(|w| prompt.contains(w))
+}
+
+/// Whether a line typed before Enter must stay out of the history: it was
+/// typed at a password prompt, or the terminal did not echo it (echo off).
+/// `prompt` is the screen text left of the cursor.
+fn is_unechoed_input(prompt: &str, typed: &str) -> bool {
+    let shown = prompt.trim_end();
+    is_password_prompt(prompt) || !shown.ends_with(typed.trim_end())
+}
+
+/// Whether pasted `text` is probably a credential: anything pasted at a
+/// password prompt, or a single token mixing at least three character
+/// classes (paths and URLs excepted).
+fn looks_secret(text: &str, prompt: &str) -> bool {
+    if is_password_prompt(prompt) {
+        return true;
+    }
+    let token = text.trim_end_matches(['\r', '\n']);
+    if !(8..=128).contains(&token.len())
+        || token.chars().any(char::is_whitespace)
+        || token.contains("://")
+        || token.starts_with(['/', '~', '.'])
+    {
+        return false;
+    }
+    let classes = [
+        token.chars().any(|c| c.is_ascii_lowercase()),
+        token.chars().any(|c| c.is_ascii_uppercase()),
+        token.chars().any(|c| c.is_ascii_digit()),
+        token.chars().any(|c| !c.is_ascii_alphanumeric()),
+    ];
+    classes.iter().filter(|&&present| present).count() >= 3
+}
+
+/// Samples kept per host by the latency monitor.
+const LATENCY_WINDOW: usize = 30;
+
+/// TCP connect time to `hostname:port` in ms, or `None` when unreachable.
+fn tcp_ping(hostname: &str, port: u16) -> Option<u128> {
+    let addr = *ssh::resolve(hostname, port).ok()?.first()?;
+    let start = std::time::Instant::now();
+    std::net::TcpStream::connect_timeout(&addr, Duration::from_secs(3)).ok()?;
+    Some(start.elapsed().as_millis())
+}
+
+/// Ports probed by the pre-connect scan: SSH, web, and common databases.
+pub const SCAN_PORTS: [u16; 7] = [22, 80, 443, 3306, 5432, 6379, 8080];
+
+/// Splits the comma-separated tag field, dropping blanks and duplicates.
+fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Last meaningful line printed by a relay that exited with an error.
+fn relay_failure_line(screen: &str) -> Option<String> {
+    screen
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(|l| l.to_string())
+}
+
 // --- Data structures ---
 
 #[derive(Debug)]
@@ -224,20 +582,146 @@ pub struct TerminalTab {
     pub ssh_process: Option<SshProcessInfo>,
     pub relay_error: Option<String>,
     pub output: String,
+    /// Remote directory tree, one line per entry, indented by depth.
     pub structure: Vec<String>,
+    /// Directories of `structure` (relative to its root) that are opened.
+    pub structure_expanded: HashSet<String>,
     pub ftp: FtpState,
     // Terminal UX
     pub font_size: f32,
     pub search_active: bool,
     pub search_query: String,
+    /// Index of the focused search match, counted from the top of the output.
+    pub search_current: usize,
+    pub search_mode: SearchMode,
+    pub search_case_sensitive: bool,
     pub quick_cmds_visible: bool,
     // Input tracking & suggestions
     pub input_buffer: String,
-    pub command_history: Vec<String>,
+    /// The line may have been edited in ways `input_buffer` can't follow
+    /// (cursor moves, Ctrl+W, remote completion). Aliases, suggestions and
+    /// history recording stay off until the next Enter or Ctrl+C.
+    pub input_dirty: bool,
+    pub command_history: VecDeque<String>,
+    /// Runs per command on this host, used to rank suggestions.
+    pub command_counts: history::Counts,
     pub suggestion_index: Option<usize>,
+    /// Ctrl+R search over `command_history`, while it is open.
+    pub history_search: Option<HistorySearch>,
     // System management panel
     pub sys_open: bool,
     pub sys_state: crate::syspanel::SysState,
+    // Auto-reconnect
+    pub auto_reconnect: bool,
+    pub reconnect_attempts: u32,
+    /// When the next automatic reconnect fires, if one is scheduled.
+    pub reconnect_at: Option<std::time::Instant>,
+    pub connected_at: Option<std::time::Instant>,
+    /// Output (or a bell) arrived while the tab was not shown.
+    pub has_activity: bool,
+    /// Latest usage gauges of the host, and whether a refresh is in flight.
+    pub remote_info: Option<crate::syspanel::RemoteInfo>,
+    pub remote_info_pending: bool,
+    /// Detected before the first gauge refresh and kept for the session.
+    pub remote_os: Option<crate::syspanel::RemoteOs>,
+    /// Recent samples for the sidebar chart, oldest first.
+    pub remote_history: VecDeque<crate::syspanel::RemoteInfo>,
+}
+
+/// How the terminal search query is read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchMode {
+    Text,
+    Regex,
+}
+
+/// Compiled terminal search query.
+enum SearchMatcher {
+    Text { query: String, case_sensitive: bool },
+    Regex(regex::Regex),
+}
+
+impl SearchMatcher {
+    /// Byte ranges of every match in `text`.
+    fn find(&self, text: &str) -> Vec<(usize, usize)> {
+        match self {
+            Self::Text { query, case_sensitive: true } => text
+                .match_indices(query.as_str())
+                .map(|(start, m)| (start, start + m.len()))
+                .collect(),
+            Self::Text { query, case_sensitive: false } => find_matches(text, query),
+            // Empty matches (`x*`) have nothing to highlight.
+            Self::Regex(re) => re
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| (m.start(), m.end()))
+                .collect(),
+        }
+    }
+}
+
+/// State of the Ctrl+R reverse history search.
+#[derive(Debug, Default)]
+pub struct HistorySearch {
+    pub query: String,
+    /// Matches skipped by pressing Ctrl+R again, newest first.
+    pub skip: usize,
+}
+
+impl TerminalTab {
+    /// The search query compiled for the current mode; `None` while it is
+    /// empty, the regex error when it doesn't compile.
+    fn search_matcher(&self) -> Option<Result<SearchMatcher, String>> {
+        if self.search_query.is_empty() {
+            return None;
+        }
+        Some(match self.search_mode {
+            SearchMode::Text => Ok(SearchMatcher::Text {
+                query: self.search_query.clone(),
+                case_sensitive: self.search_case_sensitive,
+            }),
+            SearchMode::Regex => regex::RegexBuilder::new(&self.search_query)
+                .case_insensitive(!self.search_case_sensitive)
+                .build()
+                .map(SearchMatcher::Regex)
+                .map_err(|e| match e {
+                    regex::Error::Syntax(msg) => msg.lines().last().unwrap_or_default().trim().to_string(),
+                    other => other.to_string(),
+                }),
+        })
+    }
+
+    /// Command the reverse history search currently points at.
+    fn history_search_match(&self) -> Option<&str> {
+        let search = self.history_search.as_ref()?;
+        let query = search.query.to_lowercase();
+        let mut seen = HashSet::new();
+        let matches: Vec<&str> = self
+            .command_history
+            .iter()
+            .rev()
+            .map(String::as_str)
+            .filter(|cmd| cmd.to_lowercase().contains(&query) && seen.insert(*cmd))
+            .collect();
+        if matches.is_empty() {
+            return None;
+        }
+        Some(matches[search.skip % matches.len()])
+    }
+
+    /// Adds a command the user ran to the history and run counts, on disk too.
+    fn record_command(&mut self, cmd: &str) {
+        let changed = history::push(&mut self.command_history, cmd);
+        history::count(&mut self.command_counts, cmd);
+        // Quick Connect sessions keep their history in memory only.
+        if self.host.adhoc {
+            return;
+        }
+        if changed {
+            history::save(&self.host.alias, &self.command_history);
+        }
+        history::save_counts(&self.host.alias, &self.command_counts);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -250,6 +734,11 @@ struct TerminalRuntime {
     stdin: Arc<Mutex<ChildStdin>>,
     rx: mpsc::Receiver<Vec<u8>>,
     parser: Parser,
+    /// OSC 8 links seen in the output, which the parser does not keep.
+    links: LinkTracker,
+    /// Host's `on_connect` command, still waiting to be sent.
+    on_connect: Option<String>,
+    on_connect_at: Option<std::time::Instant>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -275,6 +764,14 @@ pub enum FtpStatus {
     Error(String),
 }
 
+/// Side of the split view the active tab is drawn on.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SplitPane {
+    #[default]
+    Left,
+    Right,
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub enum FtpLayout {
     #[default]
@@ -297,6 +794,19 @@ pub struct FtpState {
     pub search_query: String,
     pub search_results: Option<Vec<ftp::FtpEntry>>,
     pub searching: bool,
+    /// (path, new name) while an entry is being renamed inline.
+    pub renaming: Option<(String, String)>,
+    /// Name typed into the inline "New Folder" input, while it is open.
+    pub new_folder: Option<String>,
+    /// (bytes done, bytes total) of the running upload or download.
+    pub transfer: Option<(u64, u64)>,
+    /// Files still waiting to be uploaded, processed one at a time.
+    pub upload_queue: VecDeque<std::path::PathBuf>,
+    /// File name of the upload in flight.
+    pub current_upload: Option<String>,
+    pub uploads_ok: usize,
+    pub uploads_total: usize,
+    pub upload_failures: Vec<String>,
 }
 
 impl Default for FtpState {
@@ -314,6 +824,14 @@ impl Default for FtpState {
             search_query: String::new(),
             search_results: None,
             searching: false,
+            renaming: None,
+            new_folder: None,
+            transfer: None,
+            upload_queue: VecDeque::new(),
+            current_upload: None,
+            uploads_ok: 0,
+            uploads_total: 0,
+            upload_failures: Vec::new(),
         }
     }
 }
@@ -326,16 +844,62 @@ pub enum Message {
     SelectHost(usize),
     ConnectToHost(usize),
     CloseTab(usize),
+    ReconnectTab(usize),
+    TerminalAutoReconnectToggle,
     SwitchTab(usize),
 
     // CRUD dialogs
     OpenNewDialog,
     OpenEditDialog(usize),
+    OpenTunnels(usize),
+    TunnelAdd,
+    TunnelRemove(usize),
+    TunnelStart(usize),
+    TunnelStop(usize),
+    TunnelsTick,
+    OpenHostFix(usize),
+    ToggleHostGroup(String),
+    MoveHostUp(usize),
+    MoveHostDown(usize),
+    DismissNotification,
+
+    // Host export / import
+    OpenExportHosts,
+    ExportIncludePasswordsChanged(bool),
+    ExportHosts,
+    ExportHostsTo(Option<std::path::PathBuf>, bool),
+    ImportHosts,
+    ImportHostsFrom(Option<std::path::PathBuf>),
+    ImportSshConfig,
+    SubmitKeyPassphrase,
+    OpenQuickConnect,
+    ConnectionEnvAdd,
+    ConnectionEnvRemove(usize),
+    /// Connect to the `user@host:port` typed in the Quick Connect dialog.
+    SubmitQuickConnect,
+    /// Master password entered in the startup unlock dialog.
+    SubmitUnlock,
+    Unlocked(Result<Box<AppConfig>, String>),
+    /// Load the config again once its key may be reachable.
+    RetryConfigKey,
+    /// Set the config whose key is lost aside and start over.
+    ReplaceConfigKey,
+    SetMasterPassword,
+    RemoveMasterPassword,
+    Quit,
+    AcceptHostKey,
+    HostKeyTrusted(Box<Host>, Result<(), String>),
     OpenDeleteConfirm(usize),
     ConfirmDelete(usize),
+    ToggleMultiSelect,
+    HostSortChanged(HostSort),
+    ToggleHostChecked(usize),
+    OpenBulkDeleteConfirm,
+    ConfirmBulkDelete,
     CloseDialog,
     SaveDialog,
     DialogFieldChanged(String, String),
+    DialogTogglePasswordVisible,
 
     // Settings
     OpenSettings,
@@ -347,10 +911,20 @@ pub enum Message {
     // Ping
     PingAll,
     PingResult(usize, Option<u128>),
+    /// Probe `SCAN_PORTS` on a host; results arrive per port as (hostname, port, open).
+    ScanPorts(usize),
+    PortScanResult(String, u16, bool),
+    ToggleLatencyMonitor(usize),
+    LatencyTick,
+    LatencySample(usize, Option<u128>),
 
     // API sync
     SyncFromApi,
-    SyncComplete(Result<Vec<Host>, String>),
+    /// Queued changes replayed, then the fetched hosts or the error.
+    SyncComplete(usize, Result<Vec<Host>, String>),
+    /// A host change was sent: the id of a created host, or the error and
+    /// whether it is worth retrying.
+    HostChangeSent(PendingApiOp, Result<Option<String>, (bool, String)>),
 
     // System info
     SystemInfoTick,
@@ -359,10 +933,20 @@ pub enum Message {
     ToggleTheme,
     ToggleLanguage,
     SettingsThemeChanged(AppTheme),
+    OpenThemeEditor,
+    OpenConfigBackups,
+    RestoreConfigBackup(usize),
+    SystemThemeCheck,
+    SystemThemeDetected(AppTheme),
+    ThemeEditorCopyFrom(AppTheme),
+    SaveThemeEditor,
     SettingsLanguageChanged(Language),
 
     // FTP / structure
     RefreshStructure,
+    StructureLoaded(u64, Vec<String>),
+    StructureToggleDir(usize),
+    StructureChildren(u64, String, Vec<String>),
 
     // SFTP browser
     FtpToggle,
@@ -374,18 +958,50 @@ pub enum Message {
     FtpDownloadFile(String),
     FtpDownloadResult(Result<String, String>),
     FtpPickUploadFile,
-    FtpUploadChosen(Option<std::path::PathBuf>),
-    FtpUploadResult(Result<(), String>),
+    /// Files picked for upload, with the id of the tab they were picked in.
+    FtpUploadChosen(u64, Option<Vec<std::path::PathBuf>>),
+    FtpUploadResult(u64, Result<(), String>),
     FtpSearchQueryChanged(String),
     FtpSearchSubmit,
     FtpSearchResult(Result<Vec<ftp::FtpEntry>, String>),
     FtpClearSearch,
+    FtpAskDelete(String, bool),
+    FtpDeleteEntry(String),
+    FtpStartRename(String),
+    FtpRenameInput(String),
+    FtpCancelRename,
+    FtpRename(String, String),
+    FtpOpResult(Result<String, String>),
+    FtpStartMakeDir,
+    FtpNewFolderInput(String),
+    FtpCancelMakeDir,
+    FtpMakeDir(String),
+    FtpTransferPoll,
+    FtpOpenChmod(String, u32),
+    FtpApplyChmod,
 
     // Embedded terminal bridge
     TerminalKeyPressed(Key, Modifiers),
     TerminalSendBytes(Vec<u8>),
+    TerminalToggleBroadcast,
+    TerminalSplitToggle,
+    TerminalSplitFocus,
     TerminalClear,
     TerminalSendCtrlC,
+    /// Middle click on the output: paste the primary selection (X11/Wayland)
+    /// or, where there is none, the clipboard.
+    TerminalMiddlePaste,
+    /// Pointer over the active tab's output, relative to its top left.
+    TerminalPointerMoved(iced::Point),
+    TerminalSelectStart,
+    TerminalSelectEnd,
+    TerminalPaste(String),
+    /// Ctrl+Shift+V: paste, then clear the clipboard even if the text
+    /// doesn't look secret.
+    TerminalPasteSecret(String),
+    ClipboardClearTick,
+    /// A link in the terminal output was clicked.
+    OpenUrl(String),
     TerminalPoll,
     TerminalFontSizeInc,
     TerminalFontSizeDec,
@@ -393,6 +1009,12 @@ pub enum Message {
     TerminalSearchToggle,
     TerminalSearchChanged(String),
     TerminalSearchClose,
+    /// Enter in the search box; Shift+Enter goes back.
+    TerminalSearchSubmit,
+    TerminalSearchStep(i32),
+    TerminalSearchToggleCase,
+    TerminalSearchToggleRegex,
+    ModifiersChanged(keyboard::Modifiers),
     TerminalQuickCmdsToggle,
     TerminalQuickCmd(String),
 
@@ -403,11 +1025,26 @@ pub enum Message {
     SettingsFontSizeChanged(f32),
     SettingsShowBordersChanged(bool),
     SettingsSuggestionsChanged(bool),
+    SettingsBackgroundAuditChanged(bool),
+    SettingsSysPrefetchChanged(bool),
+    SettingsAutoLogChanged(bool),
+    SettingsLinkifyChanged(bool),
+    SettingsCopyOnSelectChanged(bool),
+    SettingsBellNotifyChanged(bool),
+    SettingsLogAnsiChanged(bool),
+    SettingsConnectTimeoutChanged(u64),
+    SettingsKeepaliveChanged(u64),
+    SettingsClipboardClearChanged(u64),
+    SettingsApiTimeoutChanged(u64),
+    SettingsMaxReconnectChanged(u32),
 
     // Command suggestions
     TerminalSuggestionAccept(String),
+    TerminalHistorySearch,
     TerminalSuggestionMove(i32),
     TerminalCopyOutput,
+    TerminalSaveLog,
+    TerminalSaveLogTo(u64, Option<std::path::PathBuf>),
 
     // Scroll mode (keyboard navigation through terminal output)
     TerminalScrollModeToggle,
@@ -415,12 +1052,25 @@ pub enum Message {
 
     // Security audit
     OpenSecurityAudit,
+    SecurityAuditRedactChanged(bool),
+    SecurityAuditToggleSeverity(SecuritySeverity),
+    SecurityAuditFix(AuditAction),
+    /// Whether a login without the password worked after installing the key.
+    SecurityAuditKeyInstalled(Box<Host>, Result<bool, String>),
+    SecurityAuditExport,
+    SecurityAuditDeepScan,
+    SecurityAuditCancelScan,
+    /// Findings of one host, tagged with the scan run they belong to.
+    SecurityAuditHostScanned(u64, Vec<SecurityFinding>),
+    SecurityAuditExportTo(Option<std::path::PathBuf>),
 
     // Custom commands (aliases)
     OpenCustomCommands,
     AddCustomCommand,
     DeleteCustomCommand(usize),
     SaveCustomCommands,
+    SubmitCommandPrompts,
+    RunDestructiveCommand,
 
     // Reserved for future richer terminal integration
     TerminalEvent(u64, String),
@@ -432,7 +1082,22 @@ pub enum Message {
     SysPanelInput(u64, String, String),
     SysPanelFetch(u64, String),
     SysPanelAction(u64, String),
-    SysPanelFetched(u64, String, String),
+    SysPanelFetched(u64, String, crate::syspanel::ExecOutput),
+    SysPanelOutput(u64, String),
+    /// Ask before running a destructive panel command: (tab, prompt, button label, cmd).
+    SysPanelConfirm(u64, String, &'static str, String),
+    SysPanelConfirmed(u64, String),
+    /// Periodic refresh of auto-updating system panel views.
+    SysPanelTick,
+    SysPanelCopyPublicKey(u64),
+    /// Append the generated public key to the local `~/.ssh/authorized_keys`.
+    SysPanelAuthorizeLocally(u64),
+    /// Reverse-resolve the failed login sources locally; results are (ip, name).
+    SysPanelResolveSources(u64),
+    SysPanelSourcesResolved(u64, Vec<(String, Option<String>)>),
+    /// Sidebar gauges of a tab's host; `None` when they could not be read.
+    RemoteInfoFetched(u64, Option<crate::syspanel::RemoteInfo>),
+    RemoteOsDetected(u64, Option<crate::syspanel::RemoteOs>),
 }
 
 // --- Main App ---
@@ -443,6 +1108,9 @@ pub struct App {
 
     // UI state
     pub selected_host: Option<usize>,
+    /// Sidebar shows checkboxes; `checked_hosts` are the ticked host indices.
+    pub multi_select: bool,
+    pub checked_hosts: HashSet<usize>,
     pub search_query: String,
 
     // Terminal tabs
@@ -450,11 +1118,37 @@ pub struct App {
     pub active_tab: Option<usize>,
     tab_counter: u64,
     terminal_runtime: HashMap<u64, TerminalRuntime>,
+    /// Screens of sessions whose relay exited, kept so "Save Log" still has
+    /// the scrollback until the tab reconnects or closes.
+    ended_sessions: HashMap<u64, Parser>,
+    /// Progress channels of running SFTP transfers, keyed by tab id.
+    ftp_transfers: HashMap<u64, mpsc::Receiver<(u64, u64)>>,
+    /// Running port forwards keyed by local port; each knows its host.
+    forwards: HashMap<u16, forward::ForwardHandle>,
     terminal_scroll_id: scrollable::Id,
+    /// (rows, cols) that fit the terminal area, measured during layout.
+    terminal_grid: Cell<Option<(u16, u16)>>,
+    /// The same for the split pane, which shows another tab next to it.
+    split_grid: Cell<Option<(u16, u16)>>,
 
     // Scroll mode (keyboard navigation through terminal output)
     pub scroll_mode: bool,
     pub scroll_position: f32, // 0.0 = top, 1.0 = bottom
+    /// Modifier keys currently held, for widgets that only report a submit.
+    pub modifiers: keyboard::Modifiers,
+
+    /// Typed input goes to every connected tab instead of just the active one.
+    pub broadcast_mode: bool,
+
+    /// Second tab shown next to the active one. Input always goes to the
+    /// active tab, so focusing the other pane swaps the two.
+    pub split_tab: Option<usize>,
+    pub focused_pane: SplitPane,
+    split_scroll_id: scrollable::Id,
+    /// Last pointer position over the active tab's output.
+    terminal_pointer: Option<iced::Point>,
+    /// Cells selected in the active tab's output by dragging.
+    terminal_selection: Option<TermSelection>,
 
     // Dialogs
     pub dialog: Option<dialogs::DialogState>,
@@ -466,19 +1160,59 @@ pub struct App {
 
     // Ping
     pub ping_results: HashMap<usize, Option<u128>>,
+    /// Port scan results by hostname: `None` while a port is still being probed.
+    pub port_scans: HashMap<String, Vec<(u16, Option<bool>)>>,
+    /// Host index re-pinged every 2s by the latency monitor.
+    pub latency_monitor: Option<usize>,
+    /// Last `LATENCY_WINDOW` monitor samples per host index.
+    pub latency_history: HashMap<usize, VecDeque<Option<u128>>>,
 
     // Theme
     pub theme: AppTheme,
+
+    // New Critical/High findings from the background audit
+    pub audit_alerts: usize,
+    // Findings from the last deep scan of the saved hosts, and its progress
+    pub remote_findings: Vec<SecurityFinding>,
+    pub deep_scan: Option<(usize, usize)>,
+    /// Bumped when a scan starts or is cancelled; late results of older runs are dropped.
+    deep_scan_run: u64,
+
+    // Sidebar host groups the user has folded away
+    pub collapsed_groups: HashSet<String>,
+
+    // Status bar notice: (message, is_error)
+    pub notification: Option<(String, bool)>,
+
+    // API sync: in flight, and when the last one finished with its host count or error
+    pub syncing: bool,
+    pub last_sync: Option<(std::time::Instant, Result<usize, String>)>,
+    /// Last sync failed, so the host list is the cached copy from disk.
+    pub offline: bool,
+    /// Host change being sent to the API, taken off `pending_api_ops`.
+    pub api_in_flight: Option<PendingApiOp>,
+
+    /// Secret pasted from the clipboard and when to clear it, unless the
+    /// clipboard has changed by then.
+    pub clipboard_clear_at: Option<(std::time::Instant, String)>,
 }
 
 impl App {
     pub fn new() -> (Self, Task<Message>) {
         dotenv::dotenv().ok();
-        let mut config = config::load_config();
+        let (mut config, load_notice) = config::load_config();
         if config.terminal_font_size < 8.0 {
             config.terminal_font_size = 13.0;
         }
         let theme = config.theme;
+        theme::set_custom_palette(&config.custom_palette);
+        if theme == AppTheme::Auto {
+            theme::set_auto_theme(theme::detect_system_theme());
+        }
+        ssh::set_connect_timeout(config.connect_timeout_secs);
+        ssh::set_keepalive(config.keepalive_secs);
+        ssh::set_term_type(&config.term_type);
+        api::set_timeout(config.api_timeout_secs);
         let api_url = config
             .api_url
             .clone()
@@ -497,35 +1231,120 @@ impl App {
 
         let system_info = collect_system_info(&sys, &disks);
 
-        (
-            Self {
-                config,
-                api_url,
-                selected_host: None,
-                search_query: String::new(),
-                terminal_tabs: Vec::new(),
-                active_tab: None,
-                tab_counter: 0,
-                terminal_runtime: HashMap::new(),
-                terminal_scroll_id: scrollable::Id::new("terminal-output"),
-                scroll_mode: false,
-                scroll_position: 1.0,
-                dialog: None,
-                system_info,
-                sys,
-                disks,
-                ping_results: HashMap::new(),
-                theme,
-            },
-            Task::none(),
-        )
+        let mut app = Self {
+            config,
+            api_url,
+            selected_host: None,
+            multi_select: false,
+            checked_hosts: HashSet::new(),
+            search_query: String::new(),
+            terminal_tabs: Vec::new(),
+            active_tab: None,
+            tab_counter: 0,
+            terminal_runtime: HashMap::new(),
+            ended_sessions: HashMap::new(),
+            ftp_transfers: HashMap::new(),
+            forwards: HashMap::new(),
+            terminal_scroll_id: scrollable::Id::new("terminal-output"),
+            terminal_grid: Cell::new(None),
+            split_grid: Cell::new(None),
+            scroll_mode: false,
+            scroll_position: 1.0,
+            modifiers: keyboard::Modifiers::default(),
+            broadcast_mode: false,
+            split_tab: None,
+            terminal_pointer: None,
+            terminal_selection: None,
+            focused_pane: SplitPane::Left,
+            split_scroll_id: scrollable::Id::new("terminal-split-output"),
+            dialog: None,
+            system_info,
+            sys,
+            disks,
+            ping_results: HashMap::new(),
+            port_scans: HashMap::new(),
+            latency_monitor: None,
+            latency_history: HashMap::new(),
+            theme,
+            audit_alerts: 0,
+            remote_findings: Vec::new(),
+            deep_scan: None,
+            deep_scan_run: 0,
+            collapsed_groups: HashSet::new(),
+            notification: load_notice.map(|msg| (msg, true)),
+            syncing: false,
+            last_sync: None,
+            offline: false,
+            api_in_flight: None,
+            clipboard_clear_at: None,
+        };
+        if config::is_locked() {
+            // Nothing that reads hosts or saves runs until the config is unlocked.
+            app.dialog = Some(dialogs::DialogState::Unlock(dialogs::UnlockForm::default()));
+            return (app, text_input::focus(dialogs::unlock_input_id()));
+        }
+        if let Some(problem) = config::key_problem() {
+            app.dialog = Some(dialogs::DialogState::KeyUnavailable {
+                problem,
+                error: None,
+                key_file: config::key_file_hint(),
+            });
+        }
+        app.run_background_audit();
+        let startup = if app.config.api_key.is_some() {
+            Task::done(Message::SyncFromApi)
+        } else {
+            Task::none()
+        };
+        (app, startup)
     }
 
+    /// Active tab's title and remote path, e.g. `web1: /var/www — Termissh`.
     pub fn title(&self) -> String {
-        "Termissh".to_string()
+        let Some(tab) = self.active_tab.and_then(|i| self.terminal_tabs.get(i)) else {
+            return "Termissh".to_string();
+        };
+        let label = self.tab_title(tab);
+        let path = self.terminal_runtime.get(&tab.id).and_then(|rt| rt.links.cwd());
+        match path {
+            Some(path) if !self.config.title_template.contains("{path}") => {
+                format!("{}: {} — Termissh", label, path)
+            }
+            _ => format!("{} — Termissh", label),
+        }
+    }
+
+    /// `tab` rendered through the title template.
+    fn tab_title(&self, tab: &TerminalTab) -> String {
+        let runtime = self.terminal_runtime.get(&tab.id);
+        let remote_title = runtime
+            .map(|rt| rt.parser.screen().title().trim())
+            .filter(|t| !t.is_empty())
+            .unwrap_or(&tab.label);
+        let path = runtime.and_then(|rt| rt.links.cwd()).unwrap_or_default();
+        let title = self
+            .config
+            .title_template
+            .replace("{alias}", &tab.label)
+            .replace("{user}", &tab.host.username)
+            .replace("{host}", &tab.host.hostname)
+            .replace("{port}", &tab.host.port.to_string())
+            .replace("{title}", remote_title)
+            .replace("{path}", path);
+        if title.trim().is_empty() { tab.label.clone() } else { title }
     }
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
+        // While locked, only the unlock prompt is live; shortcuts and ticks
+        // must not open other dialogs or act on the empty startup config.
+        if config::is_locked()
+            && !matches!(
+                message,
+                Message::DialogFieldChanged(..) | Message::SubmitUnlock | Message::Unlocked(_) | Message::Quit
+            )
+        {
+            return Task::none();
+        }
         match message {
             Message::SelectHost(idx) => {
                 self.selected_host = Some(idx);
@@ -534,156 +1353,140 @@ impl App {
                 if idx < self.config.hosts.len() {
                     let host = self.config.hosts[idx].clone();
                     self.selected_host = Some(idx);
+                    self.config.hosts[idx].last_connected = Some(unix_now());
+                    let _ = config::save_config(&self.config);
 
-                    // Resolve relay launcher path (single-binary internal relay mode)
-                    match bridge::find_relay_binary() {
-                        Ok(relay_path) => {
-                            self.tab_counter += 1;
-                            let tab_id = self.tab_counter;
-
-                            let tab = match bridge::spawn_relay_child(&relay_path, &host) {
-                                Ok(mut child) => {
-                                    let stdin = child.stdin.take();
-                                    let stdout = child.stdout.take();
-                                    let stderr = child.stderr.take();
-
-                                    match (stdin, stdout, stderr) {
-                                        (Some(stdin), Some(stdout), Some(stderr)) => {
-                                            let (tx, rx) = mpsc::channel::<Vec<u8>>();
-                                            spawn_reader_thread(stdout, tx.clone());
-                                            spawn_reader_thread(stderr, tx);
-
-                                            self.terminal_runtime.insert(
-                                                tab_id,
-                                                TerminalRuntime {
-                                                    child,
-                                                    stdin: Arc::new(Mutex::new(stdin)),
-                                                    rx,
-                                                    parser: Parser::new(
-                                                        TERMINAL_ROWS,
-                                                        TERMINAL_COLS,
-                                                        10_000,
-                                                    ),
-                                                },
-                                            );
-
-                                            TerminalTab {
-                                                id: tab_id,
-                                                label: host.alias.clone(),
-                                                host: host.clone(),
-                                                connected: true,
-                                                ssh_process: Some(SshProcessInfo {
-                                                    relay_path: relay_path.clone(),
-                                                }),
-                                                relay_error: None,
-                                                output: format!(
-                                                    "Connected to {}@{}:{}\n",
-                                                    host.username, host.hostname, host.port
-                                                ),
-                                                structure: fetch_remote_structure(&host),
-                                                ftp: FtpState::default(),
-                                                font_size: 13.0,
-                                                search_active: false,
-                                                search_query: String::new(),
-                                                quick_cmds_visible: false,
-                                                input_buffer: String::new(),
-                                                command_history: Vec::new(),
-                                                suggestion_index: None,
-                                                sys_open: false,
-                                                sys_state: crate::syspanel::SysState::new(),
-                                            }
-                                        }
-                                        _ => TerminalTab {
-                                            id: tab_id,
-                                            label: host.alias.clone(),
-                                            host: host.clone(),
-                                            connected: false,
-                                            ssh_process: Some(SshProcessInfo {
-                                                relay_path: relay_path.clone(),
-                                            }),
-                                            relay_error: Some(
-                                                "Relay started but stdio pipes are unavailable."
-                                                    .to_string(),
-                                            ),
-                                            output: String::new(),
-                                            structure: Vec::new(),
-                                            ftp: FtpState::default(),
-                                            font_size: 13.0,
-                                            search_active: false,
-                                            search_query: String::new(),
-                                            quick_cmds_visible: false,
-                                            input_buffer: String::new(),
-                                            command_history: Vec::new(),
-                                            suggestion_index: None,
-                                            sys_open: false,
-                                            sys_state: crate::syspanel::SysState::new(),
-                                        },
-                                    }
-                                }
-                                Err(err) => TerminalTab {
-                                    id: tab_id,
-                                    label: host.alias.clone(),
-                                    host: host.clone(),
-                                    connected: false,
-                                    ssh_process: Some(SshProcessInfo {
-                                        relay_path: relay_path.clone(),
-                                    }),
-                                    relay_error: Some(err.to_string()),
-                                    output: String::new(),
-                                    structure: Vec::new(),
-                                    ftp: FtpState::default(),
-                                    font_size: 13.0,
-                                    search_active: false,
-                                    search_query: String::new(),
-                                    quick_cmds_visible: false,
-                                    input_buffer: String::new(),
-                                    command_history: Vec::new(),
-                                    suggestion_index: None,
-                                    sys_open: false,
-                                    sys_state: crate::syspanel::SysState::new(),
-                                },
-                            };
-
-                            self.terminal_tabs.push(tab);
-                            self.active_tab = Some(self.terminal_tabs.len() - 1);
-                        }
-                        Err(err) => {
-                            // Relay not found - show connection info instead
-                            self.tab_counter += 1;
-                            let tab = TerminalTab {
-                                id: self.tab_counter,
-                                label: host.alias.clone(),
-                                host: host.clone(),
-                                connected: false,
-                                ssh_process: None,
-                                relay_error: Some(err.to_string()),
-                                output: String::new(),
-                                structure: Vec::new(),
-                                ftp: FtpState::default(),
-                                font_size: 13.0,
-                                search_active: false,
-                                search_query: String::new(),
-                                quick_cmds_visible: false,
-                                input_buffer: String::new(),
-                                command_history: Vec::new(),
-                                suggestion_index: None,
-                                sys_open: false,
-                                sys_state: crate::syspanel::SysState::new(),
-                            };
-                            self.terminal_tabs.push(tab);
-                            self.active_tab = Some(self.terminal_tabs.len() - 1);
-                        }
+                    self.open_terminal(host);
+                }
+            }
+            Message::ConnectionEnvAdd => {
+                if let Some(
+                    dialogs::DialogState::NewConnection(ref mut form) | dialogs::DialogState::EditConnection(_, ref mut form),
+                ) = self.dialog
+                {
+                    let name = form.new_env.0.trim().to_string();
+                    if !Host::is_env_name(&name) {
+                        self.notification = Some((format!("'{}' is not a valid variable name", name), true));
+                        return Task::none();
+                    }
+                    let value = std::mem::take(&mut form.new_env.1);
+                    match form.env.iter_mut().find(|(k, _)| *k == name) {
+                        Some(existing) => existing.1 = value,
+                        None => form.env.push((name, value)),
+                    }
+                    form.new_env.0.clear();
+                }
+            }
+            Message::ConnectionEnvRemove(i) => {
+                if let Some(
+                    dialogs::DialogState::NewConnection(ref mut form) | dialogs::DialogState::EditConnection(_, ref mut form),
+                ) = self.dialog
+                {
+                    if i < form.env.len() {
+                        form.env.remove(i);
+                    }
+                }
+            }
+            Message::OpenQuickConnect => {
+                self.dialog = Some(dialogs::DialogState::QuickConnect(dialogs::QuickConnectForm::default()));
+                return text_input::focus(dialogs::quick_connect_input_id());
+            }
+            Message::SubmitQuickConnect => {
+                let Some(dialogs::DialogState::QuickConnect(ref mut form)) = self.dialog else {
+                    return Task::none();
+                };
+                let default_user = std::env::var("USER")
+                    .or_else(|_| std::env::var("USERNAME"))
+                    .unwrap_or_else(|_| "root".to_string());
+                match ssh::parse_target(&form.target, &default_user) {
+                    Ok((username, hostname, port)) => {
+                        let host = Host {
+                            alias: format!("{}@{}", username, ssh::socket_addr(&hostname, port)),
+                            hostname,
+                            port,
+                            username,
+                            password: Some(form.password.clone()).filter(|p| !p.is_empty()),
+                            adhoc: true,
+                            ..Default::default()
+                        };
+                        self.dialog = None;
+                        self.open_terminal(host);
+                    }
+                    Err(e) => form.error = Some(e),
+                }
+            }
+            Message::TerminalAutoReconnectToggle => {
+                let Some(i) = self.active_tab else { return Task::none(); };
+                let tab = &mut self.terminal_tabs[i];
+                tab.auto_reconnect = !tab.auto_reconnect;
+                tab.reconnect_attempts = 0;
+                tab.reconnect_at = None;
+            }
+            Message::ReconnectTab(idx) => {
+                let Some(tab) = self.terminal_tabs.get(idx) else { return Task::none(); };
+                if tab.connected {
+                    return Task::none();
+                }
+                let tab_id = tab.id;
+                // Prefer the saved entry so edits and a newly entered passphrase apply.
+                let host = self
+                    .saved_host_index(&tab.host)
+                    .map(|i| self.config.hosts[i].clone())
+                    .unwrap_or_else(|| tab.host.clone());
+                if let Some(mut stale) = self.terminal_runtime.remove(&tab_id) {
+                    let _ = stale.child.kill();
+                    let _ = stale.child.wait();
+                }
+                let result = bridge::find_relay_binary()
+                    .map_err(|e| e.to_string())
+                    .and_then(|relay_path| {
+                        self.spawn_runtime(&relay_path, &host).map(|rt| (relay_path, rt))
+                    });
+                let tab = &mut self.terminal_tabs[idx];
+                tab.reconnect_at = None;
+                match result {
+                    Ok((relay_path, runtime)) => {
+                        self.terminal_runtime.insert(tab_id, runtime);
+                        self.ended_sessions.remove(&tab_id);
+                        tab.host = host.clone();
+                        tab.connected = true;
+                        tab.connected_at = Some(std::time::Instant::now());
+                        tab.relay_error = None;
+                        tab.ssh_process = Some(SshProcessInfo { relay_path });
+                        tab.output = format!(
+                            "Reconnecting to {}@{}\n",
+                            host.username,
+                            ssh::socket_addr(&host.hostname, host.port)
+                        );
+                        tab.input_buffer.clear();
+                        tab.input_dirty = false;
+                        tab.suggestion_index = None;
+                    }
+                    Err(err) => {
+                        tab.relay_error = Some(err.clone());
+                        self.record_host_error(&host, Some(err));
                     }
                 }
             }
             Message::CloseTab(idx) => {
                 if idx < self.terminal_tabs.len() {
+                    self.terminal_selection = None;
                     let tab_id = self.terminal_tabs[idx].id;
                     if let Some(mut runtime) = self.terminal_runtime.remove(&tab_id) {
                         let _ = runtime.child.kill();
                         let _ = runtime.child.wait();
                     }
-                    self.terminal_tabs.remove(idx);
+                    self.ended_sessions.remove(&tab_id);
+                    let closed = self.terminal_tabs.remove(idx);
+                    // The system panel's session is shared by tabs of the same host.
+                    if !self.terminal_tabs.iter().any(|t| crate::syspanel::shares_session(&t.host, &closed.host)) {
+                        crate::syspanel::release_session(&closed.host);
+                    }
+                    self.split_tab = match self.split_tab {
+                        Some(split) if split == idx => None,
+                        Some(split) if split > idx => Some(split - 1),
+                        other => other,
+                    };
                     if self.terminal_tabs.is_empty() {
                         self.active_tab = None;
                     } else if let Some(active) = self.active_tab {
@@ -693,18 +1496,137 @@ impl App {
                             self.active_tab = Some(active - 1);
                         }
                     }
+                    if self.split_tab == self.active_tab {
+                        self.split_tab = None;
+                    }
                 }
             }
             Message::SwitchTab(idx) => {
+                if self.split_tab == Some(idx) {
+                    return self.update(Message::TerminalSplitFocus);
+                }
                 if idx < self.terminal_tabs.len() {
                     self.active_tab = Some(idx);
+                    self.terminal_tabs[idx].has_activity = false;
+                    self.terminal_selection = None;
+                }
+            }
+            Message::TerminalSplitToggle => {
+                if self.split_tab.take().is_some() {
+                    return Task::none();
+                }
+                let Some(active) = self.active_tab else { return Task::none(); };
+                if self.terminal_tabs.len() < 2 {
+                    self.notification = Some(("Open a second tab to split the view".into(), true));
+                    return Task::none();
                 }
+                self.split_tab = Some((active + 1) % self.terminal_tabs.len());
+                self.focused_pane = SplitPane::Left;
+            }
+            Message::TerminalSplitFocus => {
+                let Some(split) = self.split_tab else { return Task::none(); };
+                self.split_tab = self.active_tab;
+                self.active_tab = Some(split);
+                self.focused_pane = match self.focused_pane {
+                    SplitPane::Left => SplitPane::Right,
+                    SplitPane::Right => SplitPane::Left,
+                };
+                self.scroll_mode = false;
+                self.scroll_position = 1.0;
+                return scrollable::snap_to(
+                    self.terminal_scroll_id.clone(),
+                    scrollable::RelativeOffset { x: 0.0, y: 1.0 },
+                );
             }
             Message::OpenNewDialog => {
                 self.dialog = Some(dialogs::DialogState::NewConnection(
-                    dialogs::ConnectionForm::default(),
+                    dialogs::ConnectionForm {
+                        group_options: self.group_names(),
+                        ..Default::default()
+                    },
                 ));
             }
+            Message::OpenTunnels(idx) => {
+                if let Some(host) = self.config.hosts.get(idx) {
+                    self.dialog = Some(dialogs::DialogState::Tunnels {
+                        host_idx: idx,
+                        alias: host.alias.clone(),
+                        rows: Vec::new(),
+                        form: dialogs::TunnelForm {
+                            remote_host: "localhost".to_string(),
+                            ..Default::default()
+                        },
+                        error: None,
+                    });
+                    self.refresh_tunnels_dialog();
+                }
+            }
+            Message::TunnelAdd => {
+                let Some(dialogs::DialogState::Tunnels { host_idx, form, error, .. }) = self.dialog.as_mut() else {
+                    return Task::none();
+                };
+                let local_port = form.local_port.trim().parse::<u16>().ok().filter(|p| *p > 0);
+                let remote_port = form.remote_port.trim().parse::<u16>().ok().filter(|p| *p > 0);
+                let remote_host = form.remote_host.trim().to_string();
+                let (Some(local_port), Some(remote_port)) = (local_port, remote_port) else {
+                    *error = Some("Ports must be numbers between 1 and 65535".to_string());
+                    return Task::none();
+                };
+                if remote_host.is_empty() {
+                    *error = Some("Remote host is required".to_string());
+                    return Task::none();
+                }
+                let host_idx = *host_idx;
+                let Some(host) = self.config.hosts.get_mut(host_idx) else { return Task::none(); };
+                if host.tunnels.iter().any(|t| t.local_port == local_port) {
+                    *error = Some(format!("Local port {} is already used by this host", local_port));
+                    return Task::none();
+                }
+                host.tunnels.push(config::Tunnel { local_port, remote_host, remote_port });
+                let _ = config::save_config(&self.config);
+                *error = None;
+                *form = dialogs::TunnelForm {
+                    remote_host: "localhost".to_string(),
+                    ..Default::default()
+                };
+                self.refresh_tunnels_dialog();
+            }
+            Message::TunnelRemove(i) => {
+                if let Some((host_idx, tunnel)) = self.dialog_tunnel(i) {
+                    self.stop_forward(host_idx, tunnel.local_port);
+                    self.config.hosts[host_idx].tunnels.remove(i);
+                    let _ = config::save_config(&self.config);
+                    self.refresh_tunnels_dialog();
+                }
+            }
+            Message::TunnelStart(i) => {
+                if let Some((host_idx, tunnel)) = self.dialog_tunnel(i) {
+                    let host = &self.config.hosts[host_idx];
+                    let result = if self.forwards.contains_key(&tunnel.local_port) {
+                        Err(format!("Local port {} is already forwarded", tunnel.local_port))
+                    } else {
+                        forward::start(host, &tunnel)
+                    };
+                    let message = match result {
+                        Ok(handle) => {
+                            self.forwards.insert(tunnel.local_port, handle);
+                            None
+                        }
+                        Err(e) => Some(e),
+                    };
+                    if let Some(dialogs::DialogState::Tunnels { error, .. }) = self.dialog.as_mut() {
+                        *error = message;
+                    }
+                    self.refresh_tunnels_dialog();
+                }
+            }
+            Message::TunnelStop(i) => {
+                if let Some((host_idx, tunnel)) = self.dialog_tunnel(i) {
+                    self.stop_forward(host_idx, tunnel.local_port);
+                    self.refresh_tunnels_dialog();
+                }
+            }
+            Message::TunnelsTick => self.refresh_tunnels_dialog(),
             Message::OpenEditDialog(idx) => {
                 if idx < self.config.hosts.len() {
                     let host = &self.config.hosts[idx];
@@ -716,54 +1638,360 @@ impl App {
                             port: host.port.to_string(),
                             username: host.username.clone(),
                             password: host.password.clone().unwrap_or_default(),
+                            identity_file: host.identity_file.clone().unwrap_or_default(),
+                            jump_host: host.jump_host.clone().unwrap_or_default(),
+                            group: host.group.clone().unwrap_or_default(),
+                            tags: host.tags.join(", "),
+                            on_connect: host.on_connect.clone().unwrap_or_default(),
+                            collect_stats: host.collect_stats,
+                            env: host.env.clone(),
+                            new_env: (String::new(), String::new()),
+                            group_options: self.group_names(),
+                            show_password: false,
                         },
                     ));
                 }
             }
-            Message::OpenDeleteConfirm(idx) => {
-                self.dialog = Some(dialogs::DialogState::ConfirmDelete(idx));
+            Message::DismissNotification => {
+                self.notification = None;
             }
-            Message::ConfirmDelete(idx) => {
-                if idx < self.config.hosts.len() {
-                    let host = &self.config.hosts[idx];
-                    if let (Some(key), Some(id)) = (&self.config.api_key, &host.id) {
-                        let _ = api::delete_on_api(&self.api_url, key, id);
-                    }
-                    self.config.hosts.remove(idx);
-                    let _ = config::save_config(&self.config);
-                    if self.selected_host == Some(idx) {
-                        self.selected_host = None;
-                    }
+            Message::OpenExportHosts => {
+                self.dialog = Some(dialogs::DialogState::ExportHosts { include_passwords: false });
+            }
+            Message::ExportIncludePasswordsChanged(on) => {
+                if let Some(dialogs::DialogState::ExportHosts { include_passwords }) = &mut self.dialog {
+                    *include_passwords = on;
                 }
-                self.dialog = None;
             }
-            Message::CloseDialog => {
-                self.dialog = None;
+            Message::ExportHosts => {
+                let Some(dialogs::DialogState::ExportHosts { include_passwords }) = self.dialog.take() else {
+                    return Task::none();
+                };
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Export Hosts")
+                            .set_file_name("termissh-hosts.json")
+                            .add_filter("JSON", &["json"])
+                            .add_filter("CSV", &["csv"])
+                            .save_file()
+                            .await
+                            .map(|handle| handle.path().to_path_buf())
+                    },
+                    move |path| Message::ExportHostsTo(path, include_passwords),
+                );
             }
-            Message::SaveDialog => {
-                if let Some(ref dialog_state) = self.dialog.clone() {
-                    match dialog_state {
-                        dialogs::DialogState::NewConnection(form) => {
-                            let port = form.port.parse::<u16>().unwrap_or(22);
-                            let password = if form.password.is_empty() {
-                                None
-                            } else {
-                                Some(form.password.clone())
-                            };
-                            let mut new_host = Host {
-                                id: None,
-                                alias: form.alias.clone(),
-                                hostname: form.hostname.clone(),
+            Message::ExportHostsTo(path, include_passwords) => {
+                if let Some(path) = path {
+                    self.notification = Some(
+                        match config::export_hosts(&path, &self.config.hosts, include_passwords) {
+                            Ok(n) => (format!("Exported {} host(s) → {}", n, path.display()), false),
+                            Err(e) => (format!("Export failed: {}", e), true),
+                        },
+                    );
+                }
+            }
+            Message::ImportHosts => {
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Import Hosts")
+                            .add_filter("Hosts (JSON, CSV)", &["json", "csv"])
+                            .pick_file()
+                            .await
+                            .map(|handle| handle.path().to_path_buf())
+                    },
+                    Message::ImportHostsFrom,
+                );
+            }
+            Message::ImportHostsFrom(path) => {
+                if let Some(path) = path {
+                    match config::import_hosts(&path) {
+                        Ok(hosts) => {
+                            let (added, skipped, api_task) = self.merge_hosts(hosts);
+                            self.notification = Some((
+                                format!("Imported {} host(s), skipped {} duplicate(s)", added, skipped),
+                                false,
+                            ));
+                            return api_task;
+                        }
+                        Err(e) => self.notification = Some((format!("Import failed: {}", e), true)),
+                    }
+                }
+            }
+            Message::ImportSshConfig => match crate::ssh_config::load_default() {
+                Ok(hosts) => {
+                    let (added, skipped, api_task) = self.merge_hosts(hosts);
+                    self.notification = Some((
+                        format!("Imported {} host(s) from ~/.ssh/config, skipped {} duplicate(s)", added, skipped),
+                        false,
+                    ));
+                    return api_task;
+                }
+                Err(e) => self.notification = Some((e, true)),
+            },
+            Message::MoveHostUp(idx) => self.move_host(idx, true),
+            Message::MoveHostDown(idx) => self.move_host(idx, false),
+            Message::ToggleHostGroup(name) => {
+                if !self.collapsed_groups.remove(&name) {
+                    self.collapsed_groups.insert(name);
+                }
+            }
+            Message::OpenHostFix(idx) => {
+                let Some(host) = self.config.hosts.get(idx) else { return Task::none(); };
+                let field = host
+                    .last_error
+                    .as_deref()
+                    .map(|e| HostErrorKind::classify(e).fix_field())
+                    .unwrap_or("alias");
+                self.selected_host = Some(idx);
+                let _ = self.update(Message::OpenEditDialog(idx));
+                return text_input::focus(dialogs::connection_field_id(field));
+            }
+            Message::OpenDeleteConfirm(idx) => {
+                self.dialog = Some(dialogs::DialogState::ConfirmDelete(idx));
+            }
+            Message::ConfirmDelete(idx) => {
+                self.dialog = None;
+                if idx < self.config.hosts.len() {
+                    let api_task = self.remove_host(idx);
+                    let _ = config::save_config(&self.config);
+                    self.run_background_audit();
+                    return api_task;
+                }
+            }
+            Message::HostSortChanged(sort) => {
+                self.config.host_sort = sort;
+                let _ = config::save_config(&self.config);
+            }
+            Message::ToggleMultiSelect => {
+                self.multi_select = !self.multi_select;
+                self.checked_hosts.clear();
+            }
+            Message::ToggleHostChecked(idx) => {
+                if !self.checked_hosts.remove(&idx) && idx < self.config.hosts.len() {
+                    self.checked_hosts.insert(idx);
+                }
+            }
+            Message::OpenBulkDeleteConfirm => {
+                let mut indices: Vec<usize> = self.checked_hosts.iter().copied().collect();
+                indices.sort_unstable();
+                if !indices.is_empty() {
+                    let aliases = indices
+                        .iter()
+                        .filter_map(|i| self.config.hosts.get(*i))
+                        .map(|h| h.alias.clone())
+                        .collect();
+                    self.dialog = Some(dialogs::DialogState::ConfirmBulkDelete { indices, aliases });
+                }
+            }
+            Message::ConfirmBulkDelete => {
+                if let Some(dialogs::DialogState::ConfirmBulkDelete { indices, aliases }) = self.dialog.take() {
+                    // Highest index first so the remaining ones stay valid. A sync
+                    // may have replaced the list meanwhile, so check the alias too.
+                    let mut targets: Vec<(usize, String)> = indices.into_iter().zip(aliases).collect();
+                    targets.sort_unstable_by_key(|t| std::cmp::Reverse(t.0));
+                    let mut api_tasks = Vec::new();
+                    for (idx, alias) in targets {
+                        if self.config.hosts.get(idx).is_some_and(|h| h.alias == alias) {
+                            api_tasks.push(self.remove_host(idx));
+                        }
+                    }
+                    let _ = config::save_config(&self.config);
+                    self.checked_hosts.clear();
+                    self.multi_select = false;
+                    self.run_background_audit();
+                    return Task::batch(api_tasks);
+                }
+            }
+            Message::SubmitKeyPassphrase => {
+                if let Some(dialogs::DialogState::KeyPassphrase(idx, form)) = self.dialog.take() {
+                    if let Some(host) = self.config.hosts.get_mut(idx) {
+                        host.key_passphrase = Some(form.passphrase).filter(|p| !p.is_empty());
+                        return self.update(Message::ConnectToHost(idx));
+                    }
+                }
+            }
+            Message::AcceptHostKey => {
+                if let Some(dialogs::DialogState::ConfirmHostKey(host, prompt)) = self.dialog.take() {
+                    // The saved entry may have been edited since the prompt opened.
+                    let host = match self.saved_host_index(&host) {
+                        Some(idx) => self.config.hosts[idx].clone(),
+                        None if host.adhoc => *host,
+                        None => return Task::none(),
+                    };
+                    return Task::perform(
+                        async move {
+                            let res = tokio::task::spawn_blocking({
+                                let host = host.clone();
+                                move || ssh::trust_host_key(&host, &prompt.fingerprint)
+                            })
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()));
+                            (host, res)
+                        },
+                        |(host, res)| Message::HostKeyTrusted(Box::new(host), res),
+                    );
+                }
+            }
+            Message::HostKeyTrusted(host, res) => match (res, self.saved_host_index(&host)) {
+                (Ok(()), Some(idx)) => return self.update(Message::ConnectToHost(idx)),
+                (Ok(()), None) => self.open_terminal(*host),
+                (Err(e), _) => self.record_host_error(&host, Some(format!("Host key not trusted: {}", e))),
+            },
+            Message::CloseDialog => {
+                // The unlock prompt has no way around it but Quit.
+                if !config::is_locked() {
+                    self.dialog = None;
+                }
+            }
+            Message::Quit => return iced::exit(),
+            Message::SubmitUnlock => {
+                if let Some(dialogs::DialogState::Unlock(ref mut form)) = self.dialog {
+                    if form.busy || form.password.is_empty() {
+                        return Task::none();
+                    }
+                    form.busy = true;
+                    form.error = None;
+                    let password = form.password.clone();
+                    // Argon2 is deliberately slow; keep it off the UI thread.
+                    return Task::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || config::unlock(&password).map(Box::new).map_err(|e| e.to_string()))
+                                .await
+                                .unwrap_or_else(|e| Err(e.to_string()))
+                        },
+                        Message::Unlocked,
+                    );
+                }
+            }
+            Message::Unlocked(result) => {
+                let Some(dialogs::DialogState::Unlock(ref mut form)) = self.dialog else {
+                    return Task::none();
+                };
+                let cfg = match result {
+                    Ok(cfg) => cfg,
+                    Err(e) => {
+                        form.busy = false;
+                        form.password.clear();
+                        form.error = Some(e);
+                        return text_input::focus(dialogs::unlock_input_id());
+                    }
+                };
+                if form.remember {
+                    if let Err(e) = config::remember_master_key(true) {
+                        self.notification = Some((format!("Could not remember the master password: {}", e), true));
+                    }
+                }
+                self.dialog = None;
+                return self.apply_loaded_config(*cfg);
+            }
+            Message::RetryConfigKey => {
+                let (cfg, notice) = config::retry_config_key();
+                match config::key_problem() {
+                    Some(problem) => {
+                        self.dialog = Some(dialogs::DialogState::KeyUnavailable {
+                            problem,
+                            error: notice,
+                            key_file: config::key_file_hint(),
+                        });
+                    }
+                    None => {
+                        self.dialog = None;
+                        self.notification = notice.map(|msg| (msg, true));
+                        return self.apply_loaded_config(cfg);
+                    }
+                }
+            }
+            Message::ReplaceConfigKey => match config::replace_config_key() {
+                Ok(notice) => {
+                    self.dialog = None;
+                    self.notification = Some((notice, false));
+                }
+                Err(e) => {
+                    if let Some(dialogs::DialogState::KeyUnavailable { error, .. }) = self.dialog.as_mut() {
+                        *error = Some(e.to_string());
+                    }
+                }
+            },
+            Message::SetMasterPassword => {
+                if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
+                    if form.master_password.is_empty() {
+                        self.notification = Some(("Enter a master password first".to_string(), true));
+                        return Task::none();
+                    }
+                    let password = std::mem::take(&mut form.master_password);
+                    let remember = form.remember_master;
+                    self.notification = Some(match config::set_master_password(&self.config, Some(&password)) {
+                        Ok(()) => match remember.then(|| config::remember_master_key(true)) {
+                            Some(Err(e)) => (format!("Master password set, but it could not be remembered: {}", e), true),
+                            _ => ("Master password set; the config is re-encrypted with it".to_string(), false),
+                        },
+                        Err(e) => (format!("Could not set the master password: {}", e), true),
+                    });
+                    self.run_background_audit();
+                }
+            }
+            Message::RemoveMasterPassword => {
+                if !config::has_master_password() {
+                    self.notification = Some(("No master password is set".to_string(), false));
+                    return Task::none();
+                }
+                self.notification = Some(match config::set_master_password(&self.config, None) {
+                    Ok(()) => ("Master password removed; the config key is back in the OS keyring".to_string(), false),
+                    Err(e) => (format!("Could not remove the master password: {}", e), true),
+                });
+                self.run_background_audit();
+            }
+            Message::SaveDialog => {
+                if let Some(dialogs::DialogState::NewConnection(form) | dialogs::DialogState::EditConnection(_, form)) =
+                    &mut self.dialog
+                {
+                    match ssh::normalize_hostname(&form.hostname) {
+                        Ok(hostname) => form.hostname = hostname,
+                        Err(e) => {
+                            self.notification = Some((e, true));
+                            return Task::none();
+                        }
+                    }
+                }
+                let mut api_task = Task::none();
+                if let Some(ref dialog_state) = self.dialog.clone() {
+                    match dialog_state {
+                        dialogs::DialogState::NewConnection(form) => {
+                            let port = form.port.parse::<u16>().unwrap_or(22);
+                            let password = if form.password.is_empty() {
+                                None
+                            } else {
+                                Some(form.password.clone())
+                            };
+                            let identity_file = Some(form.identity_file.trim().to_string())
+                                .filter(|k| !k.is_empty());
+                            let jump_host = Some(form.jump_host.trim().to_string())
+                                .filter(|j| !j.is_empty());
+                            let group = Some(form.group.trim().to_string())
+                                .filter(|g| !g.is_empty());
+                            let on_connect = Some(form.on_connect.trim().to_string())
+                                .filter(|c| !c.is_empty());
+                            let new_host = Host {
+                                id: None,
+                                alias: form.alias.clone(),
+                                hostname: form.hostname.clone(),
                                 port,
                                 username: form.username.clone(),
                                 password,
+                                identity_file,
+                                jump_host,
+                                group,
+                                tags: parse_tags(&form.tags),
+                                on_connect,
+                                collect_stats: form.collect_stats,
+                                env: form.env.clone(),
+                                ..Default::default()
                             };
-                            if let Some(key) = &self.config.api_key {
-                                if let Ok(id) = api::create_on_api(&self.api_url, key, &new_host) {
-                                    new_host.id = Some(id);
-                                }
-                            }
+                            let alias = new_host.alias.clone();
                             self.config.hosts.push(new_host);
+                            api_task = self.send_host_change(PendingApiOp::Create { alias });
                             let _ = config::save_config(&self.config);
                         }
                         dialogs::DialogState::EditConnection(idx, form) => {
@@ -775,18 +2003,56 @@ impl App {
                                 } else {
                                     Some(form.password.clone())
                                 };
+                                let identity_file = Some(form.identity_file.trim().to_string())
+                                    .filter(|k| !k.is_empty());
+                                let jump_host = Some(form.jump_host.trim().to_string())
+                                    .filter(|j| !j.is_empty());
+                                let group = Some(form.group.trim().to_string())
+                                    .filter(|g| !g.is_empty());
+                                let on_connect = Some(form.on_connect.trim().to_string())
+                                    .filter(|c| !c.is_empty());
+                                // Fields the form doesn't show stay as they were.
                                 let updated = Host {
-                                    id: self.config.hosts[idx].id.clone(),
                                     alias: form.alias.clone(),
                                     hostname: form.hostname.clone(),
                                     port,
                                     username: form.username.clone(),
                                     password,
+                                    identity_file,
+                                    jump_host,
+                                    group,
+                                    tags: parse_tags(&form.tags),
+                                    on_connect,
+                                    collect_stats: form.collect_stats,
+                                    env: form.env.clone(),
+                                    // Saving an edit is the quick-fix path; drop the stale error.
+                                    last_error: None,
+                                    last_error_at: None,
+                                    ..self.config.hosts[idx].clone()
                                 };
-                                if let Some(key) = &self.config.api_key {
-                                    let _ = api::update_on_api(&self.api_url, key, &updated);
+                                // Running forwards were set up with the old address and login.
+                                self.stop_host_forwards(&self.config.hosts[idx].clone());
+                                let previous_alias =
+                                    std::mem::replace(&mut self.config.hosts[idx], updated.clone()).alias;
+                                match updated.id {
+                                    Some(id) => api_task = self.send_host_change(PendingApiOp::Update { id }),
+                                    None => {
+                                        // Keep a queued create pointing at the renamed host.
+                                        let previous = PendingApiOp::Create { alias: previous_alias };
+                                        let renamed = PendingApiOp::Create { alias: updated.alias.clone() };
+                                        for op in &mut self.config.pending_api_ops {
+                                            if *op == previous {
+                                                *op = renamed.clone();
+                                            }
+                                        }
+                                        // A create already on its way missed this edit; queue
+                                        // another. It becomes an update once the host has an id,
+                                        // or creates the renamed host while the old one is deleted.
+                                        if self.api_in_flight.as_ref() == Some(&previous) {
+                                            api_task = self.send_host_change(renamed);
+                                        }
+                                    }
                                 }
-                                self.config.hosts[idx] = updated;
                                 let _ = config::save_config(&self.config);
                             }
                         }
@@ -794,6 +2060,8 @@ impl App {
                     }
                 }
                 self.dialog = None;
+                self.run_background_audit();
+                return api_task;
             }
             Message::DialogFieldChanged(field, value) => {
                 if let Some(ref mut state) = self.dialog {
@@ -806,13 +2074,56 @@ impl App {
                             "port" => form.port = value,
                             "username" => form.username = value,
                             "password" => form.password = value,
+                            "identity_file" => form.identity_file = value,
+                            "jump_host" => form.jump_host = value,
+                            "group" => form.group = value,
+                            "tags" => form.tags = value,
+                            "on_connect" => form.on_connect = value,
+                            "collect_stats" => form.collect_stats = value == "true",
+                            "env_name" => form.new_env.0 = value,
+                            "env_value" => form.new_env.1 = value,
                             _ => {}
                         },
                         dialogs::DialogState::Settings(ref mut form) => match field.as_str() {
                             "api_key" => form.api_key = value,
                             "api_url" => form.api_url = value,
+                            "title_template" => form.title_template = value,
+                            "term_type" => form.term_type = value,
+                            "master_password" => form.master_password = value,
+                            "remember_master" => form.remember_master = value == "true",
+                            _ => {}
+                        },
+                        dialogs::DialogState::QuickConnect(ref mut form) => match field.as_str() {
+                            "target" => {
+                                form.target = value;
+                                form.error = None;
+                            }
+                            "password" => form.password = value,
+                            _ => {}
+                        },
+                        dialogs::DialogState::Unlock(ref mut form) => match field.as_str() {
+                            "unlock_password" => form.password = value,
+                            "unlock_remember" => form.remember = value == "true",
                             _ => {}
                         },
+                        dialogs::DialogState::KeyPassphrase(_, ref mut form) if field == "passphrase" => {
+                            form.passphrase = value;
+                        }
+                        dialogs::DialogState::FtpChmod { ref mut mode, .. } if field == "mode" => {
+                            *mode = value;
+                        }
+                        dialogs::DialogState::Tunnels { ref mut form, .. } => match field.as_str() {
+                            "local_port" => form.local_port = value,
+                            "remote_host" => form.remote_host = value,
+                            "remote_port" => form.remote_port = value,
+                            _ => {}
+                        },
+                        dialogs::DialogState::ThemeEditor(ref mut spec) => spec.set(&field, value),
+                        dialogs::DialogState::CommandPrompts { ref mut values, .. } => {
+                            if let Some(slot) = values.iter_mut().find(|(name, _)| *name == field) {
+                                slot.1 = value;
+                            }
+                        }
                         dialogs::DialogState::CustomCommands(ref mut form) => match field.as_str() {
                             "trigger" => form.new_trigger = value,
                             "script" => form.new_script = value,
@@ -823,6 +2134,15 @@ impl App {
                     }
                 }
             }
+            Message::DialogTogglePasswordVisible => {
+                if let Some(
+                    dialogs::DialogState::NewConnection(ref mut form)
+                    | dialogs::DialogState::EditConnection(_, ref mut form),
+                ) = self.dialog
+                {
+                    form.show_password = !form.show_password;
+                }
+            }
             Message::OpenSettings => {
                 self.dialog = Some(dialogs::DialogState::Settings(dialogs::SettingsForm {
                     api_key: self.config.api_key.clone().unwrap_or_default(),
@@ -833,6 +2153,22 @@ impl App {
                     terminal_font_size: self.config.terminal_font_size,
                     show_borders: self.config.show_borders,
                     suggestions_enabled: self.config.suggestions_enabled,
+                    background_audit: self.config.background_audit,
+                    sys_prefetch: self.config.sys_prefetch,
+                    connect_timeout_secs: self.config.connect_timeout_secs,
+                    keepalive_secs: self.config.keepalive_secs,
+                    clipboard_clear_secs: self.config.clipboard_clear_secs,
+                    api_timeout_secs: self.config.api_timeout_secs,
+                    auto_log: self.config.auto_log,
+                    linkify_urls: self.config.linkify_urls,
+                    copy_on_select: self.config.copy_on_select,
+                    bell_notify: self.config.bell_notify,
+                    log_keep_ansi: self.config.log_keep_ansi,
+                    max_reconnect_attempts: self.config.max_reconnect_attempts,
+                    title_template: self.config.title_template.clone(),
+                    term_type: self.config.term_type.clone(),
+                    master_password: String::new(),
+                    remember_master: false,
                 }));
             }
             Message::SaveSettings => {
@@ -856,6 +2192,10 @@ impl App {
                     if api_target_changed {
                         // Do not keep stale remote entries when endpoint or key changes.
                         self.config.hosts.retain(|h| h.id.is_none());
+                        self.config.pending_api_ops.retain(|op| matches!(op, PendingApiOp::Create { .. }));
+                        if let Some(op @ PendingApiOp::Create { .. }) = self.api_in_flight.take() {
+                            self.config.pending_api_ops.insert(0, op);
+                        }
                     }
 
                     self.theme = form.theme;
@@ -865,23 +2205,98 @@ impl App {
                     self.config.terminal_font_size = form.terminal_font_size;
                     self.config.show_borders = form.show_borders;
                     self.config.suggestions_enabled = form.suggestions_enabled;
+                    self.config.background_audit = form.background_audit;
+                    self.config.sys_prefetch = form.sys_prefetch;
+                    self.config.connect_timeout_secs = form.connect_timeout_secs;
+                    self.config.keepalive_secs = form.keepalive_secs;
+                    self.config.clipboard_clear_secs = form.clipboard_clear_secs;
+                    self.config.api_timeout_secs = form.api_timeout_secs;
+                    self.config.auto_log = form.auto_log;
+                    self.config.linkify_urls = form.linkify_urls;
+                    self.config.copy_on_select = form.copy_on_select;
+                    self.config.bell_notify = form.bell_notify;
+                    self.config.log_keep_ansi = form.log_keep_ansi;
+                    self.config.max_reconnect_attempts = form.max_reconnect_attempts;
+                    self.config.title_template = form.title_template.clone();
+                    self.config.term_type = Some(form.term_type.trim().to_string())
+                        .filter(|t| !t.is_empty())
+                        .unwrap_or_else(|| ssh::DEFAULT_TERM_TYPE.to_string());
+                    ssh::set_connect_timeout(form.connect_timeout_secs);
+                    ssh::set_keepalive(form.keepalive_secs);
+                    ssh::set_term_type(&self.config.term_type);
+                    api::set_timeout(form.api_timeout_secs);
                     let _ = config::save_config(&self.config);
-
-                    // Sync from API if key is set
-                    if let Some(ref key) = self.config.api_key {
-                        if let Ok(hosts) = api::fetch_from_api(&self.api_url, key) {
-                            self.config.hosts = hosts;
-                            let _ = config::save_config(&self.config);
-                        }
-                    }
                 }
                 self.dialog = None;
+                self.run_background_audit();
+                let mut tasks = vec![self.update(Message::SyncFromApi)];
+                if self.theme == AppTheme::Auto {
+                    tasks.push(self.update(Message::SystemThemeCheck));
+                }
+                return Task::batch(tasks);
             }
             Message::SettingsThemeChanged(t) => {
                 if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
                     form.theme = t;
                 }
             }
+            Message::SystemThemeCheck => {
+                return Task::perform(
+                    async {
+                        tokio::task::spawn_blocking(theme::detect_system_theme)
+                            .await
+                            .unwrap_or(AppTheme::Dark)
+                    },
+                    Message::SystemThemeDetected,
+                );
+            }
+            Message::SystemThemeDetected(resolved) => theme::set_auto_theme(resolved),
+            Message::OpenConfigBackups => {
+                self.dialog = Some(dialogs::DialogState::ConfigBackups(config::list_backups()));
+            }
+            Message::RestoreConfigBackup(index) => match config::restore_backup(index) {
+                Ok(cfg) => {
+                    self.theme = cfg.theme;
+                    theme::set_custom_palette(&cfg.custom_palette);
+                    ssh::set_connect_timeout(cfg.connect_timeout_secs);
+                    ssh::set_keepalive(cfg.keepalive_secs);
+                    ssh::set_term_type(&cfg.term_type);
+                    api::set_timeout(cfg.api_timeout_secs);
+                    if let Some(url) = cfg.api_url.as_deref().map(normalize_api_url).filter(|u| !u.is_empty()) {
+                        self.api_url = url;
+                    }
+                    self.notification = Some((
+                        format!("Restored config backup #{} ({} hosts)", index, cfg.hosts.len()),
+                        false,
+                    ));
+                    self.config = cfg;
+                    self.dialog = None;
+                    self.run_background_audit();
+                    if self.theme == AppTheme::Auto {
+                        return self.update(Message::SystemThemeCheck);
+                    }
+                }
+                Err(e) => {
+                    self.notification = Some((format!("Restore failed: {}", e), true));
+                }
+            },
+            Message::OpenThemeEditor => {
+                self.dialog = Some(dialogs::DialogState::ThemeEditor(self.config.custom_palette.clone()));
+            }
+            Message::ThemeEditorCopyFrom(source) => {
+                if let Some(dialogs::DialogState::ThemeEditor(ref mut spec)) = self.dialog {
+                    *spec = theme::PaletteSpec::from_palette(&theme::palette(source));
+                }
+            }
+            Message::SaveThemeEditor => {
+                if let Some(dialogs::DialogState::ThemeEditor(spec)) = self.dialog.take() {
+                    theme::set_custom_palette(&spec);
+                    self.config.custom_palette = spec;
+                    self.theme = AppTheme::Custom;
+                    self.config.theme = AppTheme::Custom;
+                    let _ = config::save_config(&self.config);
+                }
+            }
             Message::SettingsLanguageChanged(language) => {
                 if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
                     form.language = language;
@@ -891,49 +2306,246 @@ impl App {
                 self.search_query = query;
             }
             Message::PingAll => {
-                // TCP ping each host (blocking for now, TODO: async)
-                for (idx, host) in self.config.hosts.iter().enumerate() {
-                    let addr = format!("{}:{}", host.hostname, host.port);
-                    let start = std::time::Instant::now();
-                    let result =
-                        std::net::TcpStream::connect_timeout(
-                            &addr.parse().unwrap_or_else(|_| {
-                                std::net::SocketAddr::from(([0, 0, 0, 0], 0))
-                            }),
-                            Duration::from_secs(3),
-                        );
-                    match result {
-                        Ok(_) => {
-                            self.ping_results
-                                .insert(idx, Some(start.elapsed().as_millis()));
-                        }
-                        Err(_) => {
-                            self.ping_results.insert(idx, None);
-                        }
-                    }
+                // One background probe per host; results stream in as they finish.
+                let probes = self.config.hosts.iter().enumerate().map(|(idx, host)| {
+                    let hostname = host.hostname.clone();
+                    let port = host.port;
+                    Task::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || tcp_ping(&hostname, port))
+                                .await
+                                .unwrap_or(None)
+                        },
+                        move |ms| Message::PingResult(idx, ms),
+                    )
+                });
+                return Task::batch(probes);
+            }
+            Message::ToggleLatencyMonitor(idx) => {
+                if self.latency_monitor == Some(idx) {
+                    self.latency_monitor = None;
+                } else {
+                    self.latency_monitor = Some(idx);
+                    self.latency_history.remove(&idx);
+                    return self.update(Message::LatencyTick);
+                }
+            }
+            Message::LatencyTick => {
+                let Some(idx) = self.latency_monitor else {
+                    return Task::none();
+                };
+                let Some(host) = self.config.hosts.get(idx) else {
+                    self.latency_monitor = None;
+                    return Task::none();
+                };
+                let hostname = host.hostname.clone();
+                let port = host.port;
+                return Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || tcp_ping(&hostname, port))
+                            .await
+                            .unwrap_or(None)
+                    },
+                    move |ms| Message::LatencySample(idx, ms),
+                );
+            }
+            Message::LatencySample(idx, ms) => {
+                let samples = self.latency_history.entry(idx).or_default();
+                samples.push_back(ms);
+                while samples.len() > LATENCY_WINDOW {
+                    samples.pop_front();
                 }
+                self.ping_results.insert(idx, ms);
             }
             Message::PingResult(idx, ms) => {
                 self.ping_results.insert(idx, ms);
             }
+            Message::ScanPorts(idx) => {
+                let Some(host) = self.config.hosts.get(idx) else { return Task::none(); };
+                let hostname = host.hostname.clone();
+                self.port_scans.insert(hostname.clone(), SCAN_PORTS.iter().map(|&p| (p, None)).collect());
+                // Same model as PingAll: one blocking probe per port, all at once.
+                let probes = SCAN_PORTS.iter().map(|&port| {
+                    let hostname = hostname.clone();
+                    Task::perform(
+                        async move {
+                            let name = hostname.clone();
+                            let open = tokio::task::spawn_blocking(move || tcp_ping(&name, port).is_some())
+                                .await
+                                .unwrap_or(false);
+                            (hostname, open)
+                        },
+                        move |(hostname, open)| Message::PortScanResult(hostname, port, open),
+                    )
+                });
+                return Task::batch(probes);
+            }
+            Message::PortScanResult(hostname, port, open) => {
+                if let Some(ports) = self.port_scans.get_mut(&hostname) {
+                    if let Some(entry) = ports.iter_mut().find(|(p, _)| *p == port) {
+                        entry.1 = Some(open);
+                    }
+                }
+            }
             Message::SyncFromApi => {
-                if let Some(ref key) = self.config.api_key {
-                    if let Ok(hosts) = api::fetch_from_api(&self.api_url, key) {
-                        self.config.hosts = hosts;
-                        let _ = config::save_config(&self.config);
+                let Some(key) = self.config.api_key.clone() else { return Task::none(); };
+                // A change being sent is off the queue; syncing now could miss it.
+                if self.syncing || self.api_in_flight.is_some() {
+                    return Task::none();
+                }
+                self.syncing = true;
+                let api_url = self.api_url.clone();
+                // Offline changes go out first; the fetch then returns them with their ids.
+                let pending: Vec<(PendingApiOp, Option<Host>)> = self
+                    .config
+                    .pending_api_ops
+                    .iter()
+                    .map(|op| (op.clone(), self.pending_op_host(op).cloned()))
+                    .collect();
+                return Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            let (replayed, res) = api::replay_pending(&api_url, &key, &pending);
+                            let fetched = res
+                                .and_then(|_| api::fetch_from_api(&api_url, &key))
+                                .map_err(|e| format!("{:#}", e));
+                            (replayed, fetched)
+                        })
+                        .await
+                        .unwrap_or_else(|e| (0, Err(e.to_string())))
+                    },
+                    |(replayed, fetched)| Message::SyncComplete(replayed, fetched),
+                );
+            }
+            Message::HostChangeSent(op, result) => {
+                // Sent before the API key or URL changed; the answer no longer applies.
+                if self.api_in_flight.as_ref() != Some(&op) {
+                    return Task::none();
+                }
+                self.api_in_flight = None;
+                match result {
+                    Ok(Some(id)) => {
+                        if let PendingApiOp::Create { alias } = op {
+                            match self.config.hosts.iter_mut().find(|h| h.id.is_none() && h.alias == alias) {
+                                Some(host) => {
+                                    host.id = Some(id.clone());
+                                    // Edits queued meanwhile now go out as an update.
+                                    for queued in &mut self.config.pending_api_ops {
+                                        if *queued == (PendingApiOp::Create { alias: alias.clone() }) {
+                                            *queued = PendingApiOp::Update { id: id.clone() };
+                                        }
+                                    }
+                                }
+                                // Deleted while it was being created.
+                                None => self.config.pending_api_ops.push(PendingApiOp::Delete { id }),
+                            }
+                        }
+                    }
+                    Ok(None) => {}
+                    Err((true, e)) => {
+                        self.offline = true;
+                        self.config.pending_api_ops.insert(0, op);
+                        self.notification = Some((format!("API unreachable, change queued: {}", e), true));
+                    }
+                    Err((false, e)) => {
+                        self.notification = Some((format!("API refused the change: {}", e), true));
                     }
                 }
+                let _ = config::save_config(&self.config);
+                return self.send_next_host_change();
             }
-            Message::SyncComplete(result) => {
-                if let Ok(hosts) = result {
-                    self.config.hosts = hosts;
+            Message::SyncComplete(replayed, result) => {
+                self.syncing = false;
+                self.offline = result.is_err();
+                if replayed > 0 {
+                    self.config.pending_api_ops.drain(..replayed);
                     let _ = config::save_config(&self.config);
                 }
+                match result {
+                    // Changes queued while this sync ran would be lost by taking the
+                    // server's list now; go around once more to send them first.
+                    Ok(_) if !self.config.pending_api_ops.is_empty() => {
+                        return self.update(Message::SyncFromApi);
+                    }
+                    Ok(mut hosts) => {
+                        let count = hosts.len();
+                        // The API only stores name, address and credentials; everything
+                        // else (key, jump host, group, tunnels, history, …) is kept
+                        // from the matching local host, found by id and then alias.
+                        for host in &mut hosts {
+                            let local = self
+                                .config
+                                .hosts
+                                .iter()
+                                .find(|h| h.id.is_some() && h.id == host.id)
+                                .or_else(|| self.config.hosts.iter().find(|h| h.alias == host.alias));
+                            if let Some(local) = local {
+                                *host = Host {
+                                    id: host.id.take(),
+                                    alias: std::mem::take(&mut host.alias),
+                                    hostname: std::mem::take(&mut host.hostname),
+                                    port: host.port,
+                                    username: std::mem::take(&mut host.username),
+                                    password: host.password.take(),
+                                    ..local.clone()
+                                };
+                            }
+                        }
+                        self.config.hosts = hosts;
+                        let _ = config::save_config(&self.config);
+                        self.run_background_audit();
+                        // Don't hide an earlier error (e.g. a corrupt config) behind the toast.
+                        if !matches!(self.notification, Some((_, true))) {
+                            self.notification = Some((format!("Synced {} hosts from the API", count), false));
+                        }
+                        self.last_sync = Some((std::time::Instant::now(), Ok(count)));
+                    }
+                    Err(e) => {
+                        let rejected = e.contains(api::AUTH_REJECTED);
+                        self.notification = Some((format!("API sync failed: {}", e), true));
+                        self.last_sync = Some((std::time::Instant::now(), Err(e)));
+                        if rejected && self.dialog.is_none() {
+                            return self.update(Message::OpenSettings);
+                        }
+                    }
+                }
             }
             Message::SystemInfoTick => {
                 self.sys.refresh_all();
                 self.disks = Disks::new_with_refreshed_list();
                 self.system_info = collect_system_info(&self.sys, &self.disks);
+                // Only the active tab's host is shown, so only it is polled.
+                let collect = self
+                    .active_tab
+                    .and_then(|i| self.terminal_tabs.get(i))
+                    .is_some_and(|tab| self.collects_stats(tab));
+                if let Some(tab) = self.active_tab.and_then(|i| self.terminal_tabs.get_mut(i)) {
+                    if collect && tab.connected && !tab.remote_info_pending {
+                        tab.remote_info_pending = true;
+                        return match tab.remote_os {
+                            Some(os) => crate::syspanel::fetch_remote_info(tab.host.clone(), tab.id, os),
+                            None => crate::syspanel::detect_os(tab.host.clone(), tab.id),
+                        };
+                    }
+                }
+            }
+            Message::RemoteOsDetected(tab_id, os) => {
+                if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == tab_id) {
+                    tab.remote_info_pending = false;
+                    tab.remote_os = os;
+                }
+            }
+            Message::RemoteInfoFetched(tab_id, info) => {
+                if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == tab_id) {
+                    tab.remote_info_pending = false;
+                    tab.remote_info = info;
+                    if let Some(info) = info {
+                        tab.remote_history.push_back(info);
+                        if tab.remote_history.len() > chart::HISTORY_LEN {
+                            tab.remote_history.pop_front();
+                        }
+                    }
+                }
             }
             Message::ToggleTheme => {
                 let all = AppTheme::all();
@@ -941,18 +2553,87 @@ impl App {
                 self.theme = all[(cur + 1) % all.len()];
                 self.config.theme = self.theme;
                 let _ = config::save_config(&self.config);
+                if self.theme == AppTheme::Auto {
+                    return self.update(Message::SystemThemeCheck);
+                }
             }
             Message::ToggleLanguage => {
-                self.config.language = match self.config.language {
-                    Language::Turkish => Language::English,
-                    Language::English => Language::Turkish,
-                };
+                let all = Language::all();
+                let cur = all.iter().position(|&l| l == self.config.language).unwrap_or(0);
+                self.config.language = all[(cur + 1) % all.len()];
                 let _ = config::save_config(&self.config);
             }
             Message::RefreshStructure => {
-                if let Some(active) = self.active_tab {
-                    if let Some(tab) = self.terminal_tabs.get_mut(active) {
-                        tab.structure = fetch_remote_structure(&tab.host);
+                let Some(tab) = self.active_tab.and_then(|i| self.terminal_tabs.get(i)) else {
+                    return Task::none();
+                };
+                let host = tab.host.clone();
+                let tab_id = tab.id;
+                return Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || fetch_remote_structure(&host))
+                            .await
+                            .unwrap_or_else(|e| vec![format!("FTP {}", e)])
+                    },
+                    move |structure| Message::StructureLoaded(tab_id, structure),
+                );
+            }
+            Message::StructureLoaded(tab_id, structure) => {
+                if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == tab_id) {
+                    tab.structure = structure;
+                    tab.structure_expanded.clear();
+                }
+            }
+            Message::StructureToggleDir(i) => {
+                let Some(active) = self.active_tab else { return Task::none(); };
+                let Some(tab) = self.terminal_tabs.get_mut(active) else { return Task::none(); };
+                let Some(path) = structure_dir_path(&tab.structure, i) else { return Task::none(); };
+                let depth = structure_depth(&tab.structure[i]).0;
+                if tab.structure_expanded.remove(&path) {
+                    let end = tab.structure[i + 1..]
+                        .iter()
+                        .position(|l| structure_depth(l).0 <= depth)
+                        .map_or(tab.structure.len(), |n| i + 1 + n);
+                    tab.structure.drain(i + 1..end);
+                    let prefix = format!("{}/", path);
+                    tab.structure_expanded.retain(|p| !p.starts_with(&prefix));
+                    return Task::none();
+                }
+                let Some(root) = tab.structure.first().and_then(|l| l.strip_prefix("Root: ")) else {
+                    return Task::none();
+                };
+                let root = root.to_string();
+                let host = tab.host.clone();
+                let tab_id = tab.id;
+                tab.structure_expanded.insert(path.clone());
+                return Task::perform(
+                    async move {
+                        let dir = path.clone();
+                        let lines = tokio::task::spawn_blocking(move || {
+                            fetch_remote_children(&host, &root, &dir, depth + 1)
+                        })
+                        .await
+                        .unwrap_or_else(|e| vec![format!("{}FTP {}", STRUCTURE_INDENT.repeat(depth + 1), e)]);
+                        (path, lines)
+                    },
+                    move |(path, lines)| Message::StructureChildren(tab_id, path, lines),
+                );
+            }
+            Message::StructureChildren(tab_id, path, lines) => {
+                let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == tab_id) else {
+                    return Task::none();
+                };
+                // Collapsed or refreshed while loading.
+                if !tab.structure_expanded.contains(&path) {
+                    return Task::none();
+                }
+                let found = (0..tab.structure.len())
+                    .find(|&i| structure_dir_path(&tab.structure, i).as_deref() == Some(path.as_str()));
+                if let Some(i) = found {
+                    let depth = structure_depth(&tab.structure[i]).0;
+                    let already_open = tab.structure.get(i + 1).is_some_and(|l| structure_depth(l).0 > depth);
+                    if !already_open {
+                        tab.structure.splice(i + 1..i + 1, lines);
                     }
                 }
             }
@@ -1033,151 +2714,350 @@ impl App {
                     self.terminal_tabs[active].ftp = FtpState::default();
                 }
             }
-            Message::FtpNavigate(path) => {
+            Message::FtpNavigate(path) => {
+                let Some(active) = self.active_tab else { return Task::none(); };
+                if let Some(host) = self.terminal_tabs[active].ftp.connected_host.clone() {
+                    self.terminal_tabs[active].ftp.loading = true;
+                    self.terminal_tabs[active].ftp.current_path = path.clone();
+                    self.terminal_tabs[active].ftp.notification = None;
+                    return Task::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || ftp::list_directory(&host, &path))
+                                .await
+                                .unwrap_or_else(|e| Err(e.to_string()))
+                        },
+                        Message::FtpListResult,
+                    );
+                }
+            }
+            Message::FtpRefresh => {
+                let Some(active) = self.active_tab else { return Task::none(); };
+                let path = self.terminal_tabs[active].ftp.current_path.clone();
+                return self.update(Message::FtpNavigate(path));
+            }
+            Message::FtpListResult(result) => {
+                let Some(active) = self.active_tab else { return Task::none(); };
+                self.terminal_tabs[active].ftp.loading = false;
+                match result {
+                    Ok(entries) => {
+                        self.terminal_tabs[active].ftp.entries = entries;
+                        self.terminal_tabs[active].ftp.status = FtpStatus::Idle;
+                    }
+                    Err(e) => {
+                        self.terminal_tabs[active].ftp.status = FtpStatus::Error(e);
+                    }
+                }
+            }
+            Message::FtpEntryClick(path) => {
+                let Some(active) = self.active_tab else { return Task::none(); };
+                let now = std::time::Instant::now();
+                let is_double = self.terminal_tabs[active]
+                    .ftp
+                    .last_click
+                    .as_ref()
+                    .map(|(p, t)| p == &path && now.duration_since(*t).as_millis() < 400)
+                    .unwrap_or(false);
+                if is_double {
+                    self.terminal_tabs[active].ftp.last_click = None;
+                    return self.update(Message::FtpDownloadFile(path));
+                } else {
+                    self.terminal_tabs[active].ftp.last_click = Some((path.clone(), now));
+                    let cmd = format!("nano \"{}\"\r", path);
+                    return self.update(Message::TerminalSendBytes(cmd.into_bytes()));
+                }
+            }
+            Message::FtpDownloadFile(remote_path) => {
+                let Some(active) = self.active_tab else { return Task::none(); };
+                if let Some(host) = self.terminal_tabs[active].ftp.connected_host.clone() {
+                    let file_name = std::path::Path::new(&remote_path)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "file".to_string());
+                    let dl_dir = directories::UserDirs::new()
+                        .and_then(|u| u.download_dir().map(|p| p.to_path_buf()))
+                        .unwrap_or_else(|| std::path::PathBuf::from("."));
+                    let local_path = dl_dir.join(&file_name).to_string_lossy().to_string();
+                    self.terminal_tabs[active].ftp.notification = Some(("Downloading...".to_string(), false));
+                    let tx = self.start_ftp_transfer(active);
+                    return Task::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || {
+                                ftp::download_file(&host, &remote_path, &local_path, |done, total| {
+                                    let _ = tx.send((done, total));
+                                })
+                                .map(|_| local_path)
+                            })
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()))
+                        },
+                        Message::FtpDownloadResult,
+                    );
+                }
+            }
+            Message::FtpDownloadResult(result) => {
+                let Some(active) = self.active_tab else { return Task::none(); };
+                self.terminal_tabs[active].ftp.transfer = None;
+                match result {
+                    Ok(path) => {
+                        self.terminal_tabs[active].ftp.notification =
+                            Some((format!("Downloaded → {}", path), false));
+                    }
+                    Err(e) => {
+                        self.terminal_tabs[active].ftp.notification =
+                            Some((format!("Download failed: {}", e), true));
+                    }
+                }
+            }
+            Message::FtpPickUploadFile => {
+                let Some(active) = self.active_tab else { return Task::none(); };
+                let tab_id = self.terminal_tabs[active].id;
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Select Files to Upload")
+                            .pick_files()
+                            .await
+                            .map(|handles| handles.iter().map(|h| h.path().to_path_buf()).collect())
+                    },
+                    move |paths| Message::FtpUploadChosen(tab_id, paths),
+                );
+            }
+            Message::FtpUploadChosen(tab_id, paths) => {
+                let Some(active) = self.terminal_tabs.iter().position(|t| t.id == tab_id) else {
+                    return Task::none();
+                };
+                let ftp_state = &mut self.terminal_tabs[active].ftp;
+                let Some(paths) = paths.filter(|p| !p.is_empty()) else { return Task::none(); };
+                if ftp_state.upload_queue.is_empty() {
+                    ftp_state.uploads_ok = 0;
+                    ftp_state.uploads_total = 0;
+                    ftp_state.upload_failures.clear();
+                }
+                ftp_state.uploads_total += paths.len();
+                let idle = ftp_state.upload_queue.is_empty() && ftp_state.transfer.is_none();
+                ftp_state.upload_queue.extend(paths);
+                if idle {
+                    return self.next_upload(active);
+                }
+            }
+            Message::FtpAskDelete(path, is_dir) => {
+                self.dialog = Some(dialogs::DialogState::ConfirmFtpDelete { path, is_dir });
+            }
+            Message::FtpDeleteEntry(path) => {
+                self.dialog = None;
+                let Some(active) = self.active_tab else { return Task::none(); };
+                let ftp_state = &mut self.terminal_tabs[active].ftp;
+                if let Some(host) = ftp_state.connected_host.clone() {
+                    let is_dir = ftp_state.entries.iter().any(|e| e.path == path && e.is_dir);
+                    ftp_state.notification = Some(("Deleting...".to_string(), false));
+                    return Task::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || {
+                                let result = if is_dir {
+                                    ftp::delete_dir(&host, &path)
+                                } else {
+                                    ftp::delete_file(&host, &path)
+                                };
+                                result.map(|_| format!("Deleted {}", path))
+                            })
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()))
+                        },
+                        Message::FtpOpResult,
+                    );
+                }
+            }
+            Message::FtpStartRename(path) => {
+                let Some(active) = self.active_tab else { return Task::none(); };
+                let name = path.rsplit('/').next().unwrap_or_default().to_string();
+                self.terminal_tabs[active].ftp.renaming = Some((path, name));
+            }
+            Message::FtpRenameInput(value) => {
+                let Some(active) = self.active_tab else { return Task::none(); };
+                if let Some((_, name)) = self.terminal_tabs[active].ftp.renaming.as_mut() {
+                    *name = value;
+                }
+            }
+            Message::FtpCancelRename => {
+                let Some(active) = self.active_tab else { return Task::none(); };
+                self.terminal_tabs[active].ftp.renaming = None;
+            }
+            Message::FtpRename(from, to) => {
+                let Some(active) = self.active_tab else { return Task::none(); };
+                let ftp_state = &mut self.terminal_tabs[active].ftp;
+                ftp_state.renaming = None;
+                if to.trim().is_empty() || to.contains('/') || from.ends_with(&format!("/{}", to)) {
+                    return Task::none();
+                }
+                if let Some(host) = ftp_state.connected_host.clone() {
+                    let target = format!("{}/{}", ftp::parent_path(&from).trim_end_matches('/'), to.trim());
+                    return Task::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || {
+                                ftp::rename(&host, &from, &target)
+                                    .map(|_| format!("Renamed → {}", target))
+                            })
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()))
+                        },
+                        Message::FtpOpResult,
+                    );
+                }
+            }
+            Message::FtpTransferPoll => {
+                let mut finished = Vec::new();
+                for (tab_id, rx) in &self.ftp_transfers {
+                    let mut latest = None;
+                    let mut disconnected = false;
+                    loop {
+                        match rx.try_recv() {
+                            Ok(progress) => latest = Some(progress),
+                            Err(mpsc::TryRecvError::Empty) => break,
+                            Err(mpsc::TryRecvError::Disconnected) => {
+                                disconnected = true;
+                                break;
+                            }
+                        }
+                    }
+                    if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == *tab_id) {
+                        if disconnected {
+                            tab.ftp.transfer = None;
+                        } else if latest.is_some() {
+                            tab.ftp.transfer = latest;
+                        }
+                    }
+                    if disconnected {
+                        finished.push(*tab_id);
+                    }
+                }
+                for tab_id in finished {
+                    self.ftp_transfers.remove(&tab_id);
+                }
+            }
+            Message::FtpOpenChmod(path, perm) => {
+                self.dialog = Some(dialogs::DialogState::FtpChmod {
+                    path,
+                    mode: format!("{:o}", perm & 0o7777),
+                    error: None,
+                });
+            }
+            Message::FtpApplyChmod => {
+                let Some(dialogs::DialogState::FtpChmod { path, mode, error }) = self.dialog.as_mut() else {
+                    return Task::none();
+                };
+                let parsed = u32::from_str_radix(mode.trim(), 8).ok().filter(|m| *m <= 0o7777);
+                let Some(mode) = parsed else {
+                    *error = Some("Enter an octal mode such as 644 or 755".to_string());
+                    return Task::none();
+                };
+                let path = path.clone();
+                self.dialog = None;
                 let Some(active) = self.active_tab else { return Task::none(); };
                 if let Some(host) = self.terminal_tabs[active].ftp.connected_host.clone() {
-                    self.terminal_tabs[active].ftp.loading = true;
-                    self.terminal_tabs[active].ftp.current_path = path.clone();
-                    self.terminal_tabs[active].ftp.notification = None;
                     return Task::perform(
                         async move {
-                            tokio::task::spawn_blocking(move || ftp::list_directory(&host, &path))
-                                .await
-                                .unwrap_or_else(|e| Err(e.to_string()))
+                            tokio::task::spawn_blocking(move || {
+                                ftp::set_permissions(&host, &path, mode)
+                                    .map(|_| format!("{} → {}", path, ftp::format_mode(mode)))
+                            })
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()))
                         },
-                        Message::FtpListResult,
+                        Message::FtpOpResult,
                     );
                 }
             }
-            Message::FtpRefresh => {
+            Message::FtpStartMakeDir => {
                 let Some(active) = self.active_tab else { return Task::none(); };
-                let path = self.terminal_tabs[active].ftp.current_path.clone();
-                return self.update(Message::FtpNavigate(path));
+                self.terminal_tabs[active].ftp.new_folder = Some(String::new());
             }
-            Message::FtpListResult(result) => {
+            Message::FtpNewFolderInput(value) => {
                 let Some(active) = self.active_tab else { return Task::none(); };
-                self.terminal_tabs[active].ftp.loading = false;
-                match result {
-                    Ok(entries) => {
-                        self.terminal_tabs[active].ftp.entries = entries;
-                        self.terminal_tabs[active].ftp.status = FtpStatus::Idle;
-                    }
-                    Err(e) => {
-                        self.terminal_tabs[active].ftp.status = FtpStatus::Error(e);
-                    }
+                if let Some(name) = self.terminal_tabs[active].ftp.new_folder.as_mut() {
+                    *name = value;
                 }
             }
-            Message::FtpEntryClick(path) => {
+            Message::FtpCancelMakeDir => {
                 let Some(active) = self.active_tab else { return Task::none(); };
-                let now = std::time::Instant::now();
-                let is_double = self.terminal_tabs[active]
-                    .ftp
-                    .last_click
-                    .as_ref()
-                    .map(|(p, t)| p == &path && now.duration_since(*t).as_millis() < 400)
-                    .unwrap_or(false);
-                if is_double {
-                    self.terminal_tabs[active].ftp.last_click = None;
-                    return self.update(Message::FtpDownloadFile(path));
-                } else {
-                    self.terminal_tabs[active].ftp.last_click = Some((path.clone(), now));
-                    let cmd = format!("nano \"{}\"\r", path);
-                    return self.update(Message::TerminalSendBytes(cmd.into_bytes()));
-                }
+                self.terminal_tabs[active].ftp.new_folder = None;
             }
-            Message::FtpDownloadFile(remote_path) => {
+            Message::FtpMakeDir(name) => {
                 let Some(active) = self.active_tab else { return Task::none(); };
-                if let Some(host) = self.terminal_tabs[active].ftp.connected_host.clone() {
-                    let file_name = std::path::Path::new(&remote_path)
-                        .file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_else(|| "file".to_string());
-                    let dl_dir = directories::UserDirs::new()
-                        .and_then(|u| u.download_dir().map(|p| p.to_path_buf()))
-                        .unwrap_or_else(|| std::path::PathBuf::from("."));
-                    let local_path = dl_dir.join(&file_name).to_string_lossy().to_string();
-                    self.terminal_tabs[active].ftp.notification = Some(("Downloading...".to_string(), false));
+                let ftp_state = &mut self.terminal_tabs[active].ftp;
+                let name = name.trim().to_string();
+                if name.is_empty() || name.contains('/') {
+                    return Task::none();
+                }
+                ftp_state.new_folder = None;
+                if let Some(host) = ftp_state.connected_host.clone() {
+                    let path = format!("{}/{}", ftp_state.current_path.trim_end_matches('/'), name);
                     return Task::perform(
                         async move {
                             tokio::task::spawn_blocking(move || {
-                                ftp::download_file(&host, &remote_path, &local_path)
-                                    .map(|_| local_path)
+                                ftp::make_dir(&host, &path).map(|_| format!("Created {}", path))
                             })
                             .await
                             .unwrap_or_else(|e| Err(e.to_string()))
                         },
-                        Message::FtpDownloadResult,
+                        Message::FtpOpResult,
                     );
                 }
             }
-            Message::FtpDownloadResult(result) => {
+            Message::FtpOpResult(result) => {
                 let Some(active) = self.active_tab else { return Task::none(); };
                 match result {
-                    Ok(path) => {
-                        self.terminal_tabs[active].ftp.notification =
-                            Some((format!("Downloaded → {}", path), false));
+                    Ok(msg) => {
+                        let path = self.terminal_tabs[active].ftp.current_path.clone();
+                        let task = self.update(Message::FtpNavigate(path));
+                        self.terminal_tabs[active].ftp.notification = Some((msg, false));
+                        return task;
                     }
                     Err(e) => {
-                        self.terminal_tabs[active].ftp.notification =
-                            Some((format!("Download failed: {}", e), true));
-                    }
-                }
-            }
-            Message::FtpPickUploadFile => {
-                return Task::perform(
-                    async {
-                        rfd::AsyncFileDialog::new()
-                            .set_title("Select File to Upload")
-                            .pick_file()
-                            .await
-                            .map(|handle| handle.path().to_path_buf())
-                    },
-                    Message::FtpUploadChosen,
-                );
-            }
-            Message::FtpUploadChosen(maybe_path) => {
-                let Some(active) = self.active_tab else { return Task::none(); };
-                if let Some(local) = maybe_path {
-                    if let Some(host) = self.terminal_tabs[active].ftp.connected_host.clone() {
-                        let local_str = local.to_string_lossy().to_string();
-                        let file_name = local
-                            .file_name()
-                            .map(|n| n.to_string_lossy().to_string())
-                            .unwrap_or_else(|| "upload".to_string());
-                        let remote_path = format!(
-                            "{}/{}",
-                            self.terminal_tabs[active].ftp.current_path.trim_end_matches('/'),
-                            file_name
-                        );
-                        self.terminal_tabs[active].ftp.notification =
-                            Some(("Uploading...".to_string(), false));
-                        return Task::perform(
-                            async move {
-                                tokio::task::spawn_blocking(move || {
-                                    ftp::upload_file(&host, &local_str, &remote_path)
-                                })
-                                .await
-                                .unwrap_or_else(|e| Err(e.to_string()))
-                            },
-                            Message::FtpUploadResult,
-                        );
+                        self.terminal_tabs[active].ftp.notification = Some((e, true));
                     }
                 }
             }
-            Message::FtpUploadResult(result) => {
-                let Some(active) = self.active_tab else { return Task::none(); };
+            Message::FtpUploadResult(tab_id, result) => {
+                // The tab may have been closed, or another one focused, meanwhile.
+                let Some(active) = self.terminal_tabs.iter().position(|t| t.id == tab_id) else {
+                    return Task::none();
+                };
+                let ftp_state = &mut self.terminal_tabs[active].ftp;
+                ftp_state.transfer = None;
                 match result {
-                    Ok(_) => {
-                        self.terminal_tabs[active].ftp.notification =
-                            Some(("Upload complete".to_string(), false));
-                        let path = self.terminal_tabs[active].ftp.current_path.clone();
-                        return self.update(Message::FtpNavigate(path));
-                    }
+                    Ok(_) => ftp_state.uploads_ok += 1,
                     Err(e) => {
-                        self.terminal_tabs[active].ftp.notification =
-                            Some((format!("Upload failed: {}", e), true));
+                        let name = ftp_state.current_upload.take().unwrap_or_default();
+                        ftp_state.upload_failures.push(format!("{}: {}", name, e));
                     }
                 }
+                if !ftp_state.upload_queue.is_empty() {
+                    return self.next_upload(active);
+                }
+                let (ok, total) = (ftp_state.uploads_ok, ftp_state.uploads_total);
+                let summary = if ftp_state.upload_failures.is_empty() {
+                    (format!("{} of {} uploaded", ok, total), false)
+                } else {
+                    (
+                        format!(
+                            "{} of {} uploaded — failed: {}",
+                            ok,
+                            total,
+                            ftp_state.upload_failures.join("; ")
+                        ),
+                        true,
+                    )
+                };
+                ftp_state.current_upload = None;
+                let path = ftp_state.current_path.clone();
+                // FtpNavigate lists into the focused tab; a background one keeps its listing.
+                let task = if self.active_tab == Some(active) {
+                    self.update(Message::FtpNavigate(path))
+                } else {
+                    Task::none()
+                };
+                self.terminal_tabs[active].ftp.notification = Some(summary);
+                return task;
             }
             // ── Terminal UX features ──────────────────────────────────────
             Message::TerminalFontSizeInc => {
@@ -1203,11 +3083,61 @@ impl App {
             Message::TerminalSearchChanged(q) => {
                 let Some(i) = self.active_tab else { return Task::none(); };
                 self.terminal_tabs[i].search_query = q;
+                self.terminal_tabs[i].search_current = 0;
+                return self.update(Message::TerminalSearchStep(0));
+            }
+            Message::TerminalSearchSubmit => {
+                let delta = if self.modifiers.shift() { -1 } else { 1 };
+                return self.update(Message::TerminalSearchStep(delta));
+            }
+            Message::TerminalSearchStep(delta) => {
+                let Some(i) = self.active_tab else { return Task::none(); };
+                let tab = &mut self.terminal_tabs[i];
+                let Some(rt) = self.terminal_runtime.get(&tab.id) else { return Task::none(); };
+                let Some(Ok(matcher)) = tab.search_matcher() else { return Task::none(); };
+                let spans = build_terminal_spans(rt, iced::Color::WHITE, false, None);
+                let matches = search_match_lines(&spans, &matcher);
+                if matches.is_empty() {
+                    return Task::none();
+                }
+                let current = (tab.search_current as i64 + delta as i64).rem_euclid(matches.len() as i64) as usize;
+                tab.search_current = current;
+                // Center the match: offset of its line within the scrollable range.
+                let total_lines = spans.iter().map(|s| s.text.matches('\n').count()).sum::<usize>() + 1;
+                let visible = self.terminal_grid.get().map_or(24, |(rows, _)| rows as usize);
+                let top = matches[current].saturating_sub(visible / 2);
+                let range = total_lines.saturating_sub(visible).max(1);
+                self.scroll_position = (top as f32 / range as f32).clamp(0.0, 1.0);
+                return scrollable::snap_to(
+                    self.terminal_scroll_id.clone(),
+                    scrollable::RelativeOffset { x: 0.0, y: self.scroll_position },
+                );
+            }
+            Message::TerminalSearchToggleCase => {
+                let Some(i) = self.active_tab else { return Task::none(); };
+                let tab = &mut self.terminal_tabs[i];
+                tab.search_case_sensitive = !tab.search_case_sensitive;
+                tab.search_current = 0;
+                return self.update(Message::TerminalSearchStep(0));
+            }
+            Message::TerminalSearchToggleRegex => {
+                let Some(i) = self.active_tab else { return Task::none(); };
+                let tab = &mut self.terminal_tabs[i];
+                tab.search_mode = match tab.search_mode {
+                    SearchMode::Text => SearchMode::Regex,
+                    SearchMode::Regex => SearchMode::Text,
+                };
+                tab.search_current = 0;
+                return self.update(Message::TerminalSearchStep(0));
+            }
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
             }
             Message::TerminalSearchClose => {
                 let Some(i) = self.active_tab else { return Task::none(); };
                 self.terminal_tabs[i].search_active = false;
                 self.terminal_tabs[i].search_query.clear();
+                self.terminal_tabs[i].search_current = 0;
             }
             Message::TerminalQuickCmdsToggle => {
                 let Some(i) = self.active_tab else { return Task::none(); };
@@ -1227,6 +3157,31 @@ impl App {
                     form.terminal_font_size = size.clamp(8.0, 28.0);
                 }
             }
+            Message::SettingsConnectTimeoutChanged(secs) => {
+                if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
+                    form.connect_timeout_secs = secs.clamp(1, 120);
+                }
+            }
+            Message::SettingsKeepaliveChanged(secs) => {
+                if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
+                    form.keepalive_secs = secs.min(600);
+                }
+            }
+            Message::SettingsClipboardClearChanged(secs) => {
+                if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
+                    form.clipboard_clear_secs = secs.min(300);
+                }
+            }
+            Message::SettingsApiTimeoutChanged(secs) => {
+                if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
+                    form.api_timeout_secs = secs.clamp(1, 120);
+                }
+            }
+            Message::SettingsMaxReconnectChanged(n) => {
+                if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
+                    form.max_reconnect_attempts = n.clamp(1, 50);
+                }
+            }
             Message::SettingsShowBordersChanged(val) => {
                 if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
                     form.show_borders = val;
@@ -1237,6 +3192,41 @@ impl App {
                     form.suggestions_enabled = val;
                 }
             }
+            Message::SettingsBackgroundAuditChanged(val) => {
+                if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
+                    form.background_audit = val;
+                }
+            }
+            Message::SettingsSysPrefetchChanged(val) => {
+                if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
+                    form.sys_prefetch = val;
+                }
+            }
+            Message::SettingsAutoLogChanged(val) => {
+                if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
+                    form.auto_log = val;
+                }
+            }
+            Message::SettingsLinkifyChanged(val) => {
+                if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
+                    form.linkify_urls = val;
+                }
+            }
+            Message::SettingsCopyOnSelectChanged(val) => {
+                if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
+                    form.copy_on_select = val;
+                }
+            }
+            Message::SettingsBellNotifyChanged(val) => {
+                if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
+                    form.bell_notify = val;
+                }
+            }
+            Message::SettingsLogAnsiChanged(val) => {
+                if let Some(dialogs::DialogState::Settings(ref mut form)) = self.dialog {
+                    form.log_keep_ansi = val;
+                }
+            }
             Message::TerminalScrollModeToggle => {
                 self.scroll_mode = !self.scroll_mode;
                 if !self.scroll_mode {
@@ -1289,6 +3279,22 @@ impl App {
                     }
                 }
 
+                // Ctrl+R opens the reverse history search, or steps to an older match.
+                // While it is open every other key edits the query.
+                if modifiers.control() && matches!(&key, Key::Character(c) if c.as_str() == "r") {
+                    return self.update(Message::TerminalHistorySearch);
+                }
+                if self
+                    .active_tab
+                    .and_then(|i| self.terminal_tabs.get(i))
+                    .is_some_and(|t| t.history_search.is_some())
+                {
+                    if let Some(bytes) = map_key_to_bytes(key, modifiers) {
+                        return self.update(Message::TerminalSendBytes(bytes));
+                    }
+                    return Task::none();
+                }
+
                 // Intercept terminal shortcuts before passing to SSH
                 if modifiers.control() {
                     if let Key::Character(ref c) = key {
@@ -1299,11 +3305,13 @@ impl App {
                             "0" => return self.update(Message::TerminalFontSizeReset),
                             // Ctrl+V → paste from system clipboard
                             "v" => {
-                                return iced::clipboard::read().map(|content| {
-                                    Message::TerminalSendBytes(
-                                        content.unwrap_or_default().into_bytes(),
-                                    )
-                                });
+                                return iced::clipboard::read()
+                                    .map(|content| Message::TerminalPaste(content.unwrap_or_default()));
+                            }
+                            // Ctrl+Shift+V → paste as secret
+                            "V" => {
+                                return iced::clipboard::read()
+                                    .map(|content| Message::TerminalPasteSecret(content.unwrap_or_default()));
                             }
                             _ => {}
                         }
@@ -1338,6 +3346,12 @@ impl App {
                         Key::Named(Named::ArrowUp) if sugg_idx.is_some() => {
                             return self.update(Message::TerminalSuggestionMove(-1));
                         }
+                        // Shift+Tab: drop the selection and send a literal Tab, so the
+                        // remote shell's own completion stays reachable.
+                        Key::Named(Named::Tab) if sugg_idx.is_some() && modifiers.shift() => {
+                            self.terminal_tabs[active].suggestion_index = None;
+                            return self.update(Message::TerminalSendBytes(vec![b'\t']));
+                        }
                         // Tab: accept highlighted suggestion (if any)
                         Key::Named(Named::Tab) if sugg_idx.is_some() => {
                             let triggers: Vec<String> = self.config.custom_commands.iter().map(|c| c.trigger.clone()).collect();
@@ -1347,7 +3361,8 @@ impl App {
                                     return self.update(Message::TerminalSuggestionAccept(cmd));
                                 }
                             }
-                            // No match — fall through and send Tab to SSH
+                            // No match — leave suggestion mode and send Tab to SSH
+                            self.terminal_tabs[active].suggestion_index = None;
                         }
                         // Esc: dismiss suggestion selection
                         Key::Named(Named::Escape) if sugg_idx.is_some() => {
@@ -1376,18 +3391,62 @@ impl App {
                 if self.dialog.is_some() {
                     return Task::none();
                 }
+                // Typing moves the output on; a selection would soon point at other text.
+                self.terminal_selection = None;
 
-                // Phase 1: Track local input buffer + intercept custom commands
                 if let Some(active) = self.active_tab {
+                    if self.terminal_tabs.get(active).is_some_and(|t| t.history_search.is_some()) {
+                        return self.history_search_input(active, &bytes);
+                    }
+                }
+
+                // Space ticks the highlighted host while picking hosts and no terminal has focus.
+                if self.multi_select && self.active_tab.is_none() && bytes == b" " {
+                    if let Some(idx) = self.selected_host {
+                        return self.update(Message::ToggleHostChecked(idx));
+                    }
+                    return Task::none();
+                }
+
+                // Phase 1: Track local input buffer + intercept custom commands.
+                // Inside vim, less, htop, … keys aren't shell input, so nothing is
+                // tracked and no alias can be injected into the program.
+                let in_alternate_screen = self
+                    .active_tab
+                    .and_then(|i| self.terminal_tabs.get(i))
+                    .and_then(|tab| self.terminal_runtime.get(&tab.id))
+                    .is_some_and(|rt| rt.parser.screen().alternate_screen());
+                if in_alternate_screen {
+                    if let Some(tab) = self.active_tab.and_then(|i| self.terminal_tabs.get_mut(i)) {
+                        tab.input_buffer.clear();
+                        tab.input_dirty = true;
+                        tab.suggestion_index = None;
+                    }
+                } else if let Some(active) = self.active_tab {
                     if bytes.len() == 1 && bytes[0] == 13 {
-                        // Enter pressed — check for custom command alias
+                        // Enter pressed — check for custom command alias. A line the
+                        // buffer lost track of never expands, so a stale buffer can't
+                        // run an alias script the user didn't type.
                         let buffer = self
                             .terminal_tabs
                             .get(active)
-                            .map(|t| t.input_buffer.trim().to_string())
-                            .unwrap_or_default();
-
-                        if !buffer.is_empty() {
+                            .and_then(|t| typed_line(&t.input_buffer, t.input_dirty))
+                            .unwrap_or_default()
+                            .to_string();
+
+                        // `:ls`, `:df`, … expand to the quick command of that label.
+                        let quick = buffer
+                            .strip_prefix(':')
+                            .and_then(|name| QUICK_CMDS.iter().find(|(label, _)| *label == name))
+                            .map(|(_, cmd)| *cmd);
+                        if let Some(cmd) = quick {
+                            let mut replacement = vec![21u8];
+                            replacement.extend_from_slice(cmd.as_bytes());
+                            bytes = replacement;
+                            if let Some(tab) = self.terminal_tabs.get_mut(active) {
+                                tab.record_command(cmd.trim_end_matches('\r'));
+                            }
+                        } else if !buffer.is_empty() {
                             let custom = self
                                 .config
                                 .custom_commands
@@ -1396,69 +3455,69 @@ impl App {
                                 .cloned();
 
                             if let Some(cc) = custom {
-                                // Replace with Ctrl+U (clear line) + script + \r
-                                let mut replacement = vec![21u8];
-                                replacement.extend_from_slice(cc.script.as_bytes());
-                                replacement.push(b'\r');
-                                bytes = replacement;
-                            } else if let Some(tab) = self.terminal_tabs.get_mut(active) {
-                                if tab.command_history.last().map(String::as_str) != Some(buffer.as_str()) {
-                                    tab.command_history.push(buffer);
-                                    if tab.command_history.len() > 50 {
-                                        tab.command_history.remove(0);
+                                let prompts = placeholders::prompt_names(&cc.script);
+                                if prompts.is_empty() {
+                                    // Replace with Ctrl+U (clear line) + script + \r
+                                    let host = &self.terminal_tabs[active].host;
+                                    let script = placeholders::expand_command(&cc.script, host, &HashMap::new());
+                                    if is_destructive(&script) {
+                                        // Clear the typed trigger; the script waits for confirmation.
+                                        bytes = vec![21u8];
+                                        self.dialog = Some(dialogs::DialogState::ConfirmDestructive {
+                                            trigger: cc.trigger,
+                                            command: script,
+                                        });
+                                    } else {
+                                        let mut replacement = vec![21u8];
+                                        replacement.extend_from_slice(unlogged(&script).as_bytes());
+                                        replacement.push(b'\r');
+                                        bytes = replacement;
                                     }
+                                } else {
+                                    // Clear the typed trigger now; the script is sent once
+                                    // the remaining placeholders have values.
+                                    bytes = vec![21u8];
+                                    self.dialog = Some(dialogs::DialogState::CommandPrompts {
+                                        trigger: cc.trigger,
+                                        script: cc.script,
+                                        values: prompts.into_iter().map(|n| (n, String::new())).collect(),
+                                    });
+                                }
+                            } else if !is_unechoed_input(&self.active_prompt_line(), &buffer) {
+                                if let Some(tab) = self.terminal_tabs.get_mut(active) {
+                                    tab.record_command(&buffer);
                                 }
                             }
                         }
                         if let Some(tab) = self.terminal_tabs.get_mut(active) {
                             tab.input_buffer.clear();
+                            tab.input_dirty = false;
                         }
                     } else if let Some(tab) = self.terminal_tabs.get_mut(active) {
-                        if bytes.len() == 1 {
-                            match bytes[0] {
-                                127 => {
-                                    tab.input_buffer.pop();
-                                    tab.suggestion_index = None;
-                                }
-                                3 | 21 | 27 => {
-                                    tab.input_buffer.clear();
-                                    tab.suggestion_index = None;
-                                }
-                                b if b >= 32 => {
-                                    tab.input_buffer.push(b as char);
-                                    tab.suggestion_index = None;
-                                }
-                                _ => {}
-                            }
-                        } else if !bytes.is_empty() {
-                            if bytes[0] == 27 {
-                                // Escape sequence (arrow keys, function keys, cursor movement).
-                                // Clear the buffer because these keys can move the cursor or
-                                // navigate bash history, making the local buffer unreliable.
-                                // Without this, a stale buffer could accidentally match an
-                                // alias trigger and run its script unexpectedly.
-                                tab.input_buffer.clear();
-                                tab.suggestion_index = None;
-                            } else if bytes.iter().all(|&b| b >= 32) {
-                                // Multi-byte printable text (e.g., UTF-8 from IME or paste)
-                                if let Ok(s) = std::str::from_utf8(&bytes) {
-                                    tab.input_buffer.push_str(s);
-                                    tab.suggestion_index = None;
-                                }
-                            }
+                        if !bytes.is_empty() {
+                            track_input(&mut tab.input_buffer, &mut tab.input_dirty, &bytes);
+                            tab.suggestion_index = None;
                         }
                     }
                 }
 
                 // Phase 2: Send bytes to SSH stdin
                 let mut should_snap_bottom = false;
+                if self.broadcast_mode {
+                    for runtime in self.terminal_runtime.values() {
+                        if let Ok(mut stdin) = runtime.stdin.lock() {
+                            let _ = protocol::write_data(&mut *stdin, &bytes);
+                        }
+                    }
+                }
                 if let Some(active) = self.active_tab {
                     if let Some(tab) = self.terminal_tabs.get(active) {
                         if let Some(runtime) = self.terminal_runtime.get(&tab.id) {
                             let in_alternate_screen = runtime.parser.screen().alternate_screen();
-                            if let Ok(mut stdin) = runtime.stdin.lock() {
-                                let _ = stdin.write_all(&bytes);
-                                let _ = stdin.flush();
+                            if !self.broadcast_mode {
+                                if let Ok(mut stdin) = runtime.stdin.lock() {
+                                    let _ = protocol::write_data(&mut *stdin, &bytes);
+                                }
                             }
                             // Only snap to bottom when not in scroll mode
                             should_snap_bottom = !in_alternate_screen && !self.scroll_mode;
@@ -1474,40 +3533,171 @@ impl App {
                 }
                 return Task::none();
             }
+            Message::TerminalToggleBroadcast => {
+                self.broadcast_mode = !self.broadcast_mode;
+            }
+            Message::SubmitCommandPrompts => {
+                let Some(dialogs::DialogState::CommandPrompts { trigger, script, values }) = self.dialog.take() else {
+                    return Task::none();
+                };
+                let Some(active) = self.active_tab else { return Task::none(); };
+                let prompts: HashMap<String, String> = values.into_iter().collect();
+                let script = placeholders::expand_command(&script, &self.terminal_tabs[active].host, &prompts);
+                if is_destructive(&script) {
+                    self.dialog = Some(dialogs::DialogState::ConfirmDestructive { trigger, command: script });
+                    return Task::none();
+                }
+                let mut bytes = unlogged(&script).into_bytes();
+                bytes.push(b'\r');
+                return self.update(Message::TerminalSendBytes(bytes));
+            }
+            Message::RunDestructiveCommand => {
+                let Some(dialogs::DialogState::ConfirmDestructive { command, .. }) = self.dialog.take() else {
+                    return Task::none();
+                };
+                let mut bytes = unlogged(&command).into_bytes();
+                bytes.push(b'\r');
+                return self.update(Message::TerminalSendBytes(bytes));
+            }
+            Message::TerminalMiddlePaste => {
+                if self.dialog.is_some() || self.active_tab.is_none() {
+                    return Task::none();
+                }
+                return iced::clipboard::read_primary().then(|primary| match primary {
+                    Some(content) => Task::done(Message::TerminalPaste(content)),
+                    None => iced::clipboard::read().map(|content| Message::TerminalPaste(content.unwrap_or_default())),
+                });
+            }
+            Message::OpenUrl(url) => {
+                if !hyperlink::is_openable(&url) {
+                    self.notification = Some((format!("Not opening {}: only http(s) links are allowed", url), true));
+                } else if let Err(e) = open::that_detached(&url) {
+                    self.notification = Some((format!("Cannot open {}: {}", url, e), true));
+                }
+            }
+            Message::TerminalPasteSecret(content) => {
+                if content.is_empty() {
+                    return Task::none();
+                }
+                let secs = match self.config.clipboard_clear_secs {
+                    0 => DEFAULT_CLIPBOARD_CLEAR_SECS,
+                    secs => secs,
+                };
+                self.schedule_clipboard_clear(&content, secs);
+                return self.update(Message::TerminalPaste(content));
+            }
+            Message::ClipboardClearTick => {
+                let due = self
+                    .clipboard_clear_at
+                    .as_ref()
+                    .is_some_and(|(at, _)| std::time::Instant::now() >= *at);
+                if let (true, Some((_, secret))) = (due, self.clipboard_clear_at.take()) {
+                    // Leave the clipboard alone if something else was copied since.
+                    return iced::clipboard::read().then(move |current| {
+                        if current.as_deref() == Some(secret.as_str()) {
+                            iced::clipboard::write(String::new())
+                        } else {
+                            Task::none()
+                        }
+                    });
+                }
+            }
+            Message::TerminalPaste(content) => {
+                if content.is_empty() {
+                    return Task::none();
+                }
+                let secs = self.config.clipboard_clear_secs;
+                if secs > 0 && looks_secret(&content, &self.active_prompt_line()) {
+                    self.schedule_clipboard_clear(&content, secs);
+                }
+                // Programs that asked for bracketed paste (shells, vim) get the text
+                // wrapped so newlines inside it aren't taken as Enter.
+                let bracketed = self
+                    .active_tab
+                    .and_then(|i| self.terminal_tabs.get(i))
+                    .and_then(|tab| self.terminal_runtime.get(&tab.id))
+                    .is_some_and(|rt| rt.parser.screen().bracketed_paste());
+                let bytes = if bracketed {
+                    // A stray end marker inside the text would end the paste early.
+                    let body = content.replace("\x1b[201~", "");
+                    format!("\x1b[200~{}\x1b[201~", body).into_bytes()
+                } else {
+                    content.into_bytes()
+                };
+                return self.update(Message::TerminalSendBytes(bytes));
+            }
             Message::TerminalSendCtrlC => {
-                return self.update(Message::TerminalSendBytes(vec![3]));
+                // Sent as a signal frame; the relay delivers it through the remote PTY.
+                let frame = protocol::Frame::Signal("INT".to_string());
+                let targets: Vec<u64> = if self.broadcast_mode {
+                    self.terminal_runtime.keys().copied().collect()
+                } else {
+                    self.active_tab.and_then(|i| self.terminal_tabs.get(i)).map(|t| t.id).into_iter().collect()
+                };
+                for id in targets {
+                    if let Some(Ok(mut stdin)) = self.terminal_runtime.get(&id).map(|rt| rt.stdin.lock()) {
+                        let _ = protocol::write_frame(&mut *stdin, &frame);
+                    }
+                }
+                if let Some(tab) = self.active_tab.and_then(|i| self.terminal_tabs.get_mut(i)) {
+                    tab.input_buffer.clear();
+                    tab.input_dirty = false;
+                    tab.suggestion_index = None;
+                }
             }
             Message::TerminalClear => {
                 if let Some(active) = self.active_tab {
                     if let Some(tab) = self.terminal_tabs.get_mut(active) {
                         tab.output.clear();
                         if let Some(runtime) = self.terminal_runtime.get_mut(&tab.id) {
-                            runtime.parser =
-                                Parser::new(TERMINAL_ROWS, TERMINAL_COLS, 10_000);
+                            let (rows, cols) = runtime.parser.screen().size();
+                            runtime.parser = Parser::new(rows, cols, 10_000);
                         }
                     }
                 }
             }
             Message::TerminalPoll => {
+                self.apply_terminal_grid();
+                let now = std::time::Instant::now();
+                let due: Vec<usize> = self
+                    .terminal_tabs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, t)| t.reconnect_at.is_some_and(|at| at <= now))
+                    .map(|(i, _)| i)
+                    .collect();
+                for idx in due {
+                    let _ = self.update(Message::ReconnectTab(idx));
+                }
                 let ids: Vec<u64> = self.terminal_runtime.keys().copied().collect();
                 let mut to_remove: Vec<u64> = Vec::new();
+                let mut exits: Vec<(u64, Option<String>)> = Vec::new();
                 let mut should_snap_bottom = false;
                 let mut should_snap_top = false;
                 let active_id = self
                     .active_tab
                     .and_then(|idx| self.terminal_tabs.get(idx))
                     .map(|tab| tab.id);
+                let split_id = self.split_tab.and_then(|idx| self.terminal_tabs.get(idx)).map(|tab| tab.id);
+                // Also covers tabs that became active by closing another one.
+                if let Some(tab) = self.active_tab.and_then(|idx| self.terminal_tabs.get_mut(idx)) {
+                    tab.has_activity = false;
+                }
 
                 for id in ids {
                     let mut changed = false;
                     let mut should_remove = false;
 
                     if let Some(runtime) = self.terminal_runtime.get_mut(&id) {
+                        let bells = runtime.parser.screen().audible_bell_count();
                         loop {
                             match runtime.rx.try_recv() {
                                 Ok(chunk) => {
-                                    runtime.parser.process(&chunk);
+                                    runtime.links.process(&mut runtime.parser, &chunk);
                                     changed = true;
+                                    if runtime.on_connect.is_some() && runtime.on_connect_at.is_none() {
+                                        runtime.on_connect_at = Some(now + ON_CONNECT_DELAY);
+                                    }
                                 }
                                 Err(mpsc::TryRecvError::Empty) => break,
                                 Err(mpsc::TryRecvError::Disconnected) => {
@@ -1517,7 +3707,23 @@ impl App {
                             }
                         }
 
+                        if runtime.on_connect_at.is_some_and(|at| at <= now) && !should_remove {
+                            runtime.on_connect_at = None;
+                            if let Some(cmd) = runtime.on_connect.take() {
+                                if let Ok(mut stdin) = runtime.stdin.lock() {
+                                    let _ = protocol::write_data(&mut *stdin, format!("{}\r", unlogged(&cmd)).as_bytes());
+                                }
+                            }
+                        }
+
                         if let Ok(Some(status)) = runtime.child.try_wait() {
+                            let failure = if status.success() {
+                                None
+                            } else {
+                                relay_failure_line(&runtime.parser.screen().contents())
+                                    .or_else(|| Some(format!("relay exited: {}", status)))
+                            };
+                            exits.push((id, failure));
                             let exit_line = format!("\r\n[relay exited: {}]\r\n", status);
                             runtime.parser.process(exit_line.as_bytes());
                             changed = true;
@@ -1528,6 +3734,13 @@ impl App {
                             if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == id) {
                                 tab.output =
                                     normalized_screen(&runtime.parser.screen().contents());
+                                if Some(id) != active_id && Some(id) != split_id {
+                                    tab.has_activity = true;
+                                    let rang = runtime.parser.screen().audible_bell_count() != bells;
+                                    if rang && self.config.bell_notify {
+                                        notify_bell(&tab.label);
+                                    }
+                                }
                                 if Some(id) == active_id {
                                     if runtime.parser.screen().alternate_screen() {
                                         should_snap_top = true;
@@ -1545,10 +3758,63 @@ impl App {
                     }
                 }
 
+                let max_attempts = self.config.max_reconnect_attempts;
                 for id in to_remove {
-                    self.terminal_runtime.remove(&id);
+                    if let Some(runtime) = self.terminal_runtime.remove(&id) {
+                        self.ended_sessions.insert(id, runtime.parser);
+                    }
                     if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == id) {
                         tab.connected = false;
+                        // A session that stayed up a while starts a fresh backoff.
+                        if tab.connected_at.take().is_some_and(|at| at.elapsed() > RECONNECT_MAX_DELAY) {
+                            tab.reconnect_attempts = 0;
+                        }
+                        if tab.auto_reconnect && tab.reconnect_attempts < max_attempts {
+                            let delay = Duration::from_secs(1 << tab.reconnect_attempts.min(5))
+                                .min(RECONNECT_MAX_DELAY);
+                            tab.reconnect_attempts += 1;
+                            tab.reconnect_at = Some(std::time::Instant::now() + delay);
+                        }
+                    }
+                }
+
+                for (id, failure) in exits {
+                    if let Some(host) = self.terminal_tabs.iter().find(|t| t.id == id).map(|t| t.host.clone()) {
+                        let needs_passphrase = failure
+                            .as_deref()
+                            .is_some_and(|f| f.contains(ssh::PASSPHRASE_REQUIRED));
+                        let unknown_key = failure.as_deref().and_then(ssh::unknown_host_key);
+                        // Retrying cannot fix a missing passphrase or an untrusted key.
+                        if needs_passphrase || unknown_key.is_some() {
+                            if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == id) {
+                                tab.reconnect_at = None;
+                            }
+                        }
+                        self.record_host_error(&host, failure);
+                        if let (Some((entry, fingerprint)), None) = (unknown_key, &self.dialog) {
+                            if host.adhoc || self.saved_host_index(&host).is_some() {
+                                self.dialog = Some(dialogs::DialogState::ConfirmHostKey(
+                                    Box::new(host.clone()),
+                                    dialogs::HostKeyPrompt {
+                                        alias: host.alias.clone(),
+                                        entry,
+                                        fingerprint,
+                                    },
+                                ));
+                            }
+                        } else if needs_passphrase && self.dialog.is_none() {
+                            if let Some(idx) = self.saved_host_index(&host) {
+                                self.dialog = Some(dialogs::DialogState::KeyPassphrase(
+                                    idx,
+                                    dialogs::PassphraseForm {
+                                        alias: host.alias.clone(),
+                                        key_path: host.identity_file.clone().unwrap_or_default(),
+                                        passphrase: String::new(),
+                                    },
+                                ));
+                                return text_input::focus(dialogs::passphrase_input_id());
+                            }
+                        }
                     }
                 }
 
@@ -1560,8 +3826,13 @@ impl App {
                     );
                 }
 
-                // Only auto-snap to bottom when NOT in scroll mode
-                if should_snap_bottom && !self.scroll_mode {
+                // Only auto-snap to bottom when NOT in scroll mode, and not
+                // while search is holding a match in view
+                let searching = self
+                    .active_tab
+                    .and_then(|i| self.terminal_tabs.get(i))
+                    .is_some_and(|t| t.search_active && !t.search_query.is_empty());
+                if should_snap_bottom && !self.scroll_mode && !searching {
                     self.scroll_position = 1.0;
                     return scrollable::snap_to(
                         self.terminal_scroll_id.clone(),
@@ -1585,12 +3856,22 @@ impl App {
                 if let Some(tab) = self.terminal_tabs.get(i) {
                     if let Some(runtime) = self.terminal_runtime.get(&tab.id) {
                         if let Ok(mut stdin) = runtime.stdin.lock() {
-                            let _ = stdin.write_all(&bytes);
-                            let _ = stdin.flush();
+                            let _ = protocol::write_data(&mut *stdin, &bytes);
                         }
                     }
                 }
             }
+            Message::TerminalHistorySearch => {
+                let Some(i) = self.active_tab else { return Task::none(); };
+                let tab = &mut self.terminal_tabs[i];
+                match tab.history_search.as_mut() {
+                    Some(search) => search.skip += 1,
+                    None => {
+                        tab.history_search = Some(HistorySearch::default());
+                        tab.suggestion_index = None;
+                    }
+                }
+            }
             Message::TerminalSuggestionMove(delta) => {
                 let Some(i) = self.active_tab else { return Task::none(); };
                 let triggers: Vec<String> = self.config.custom_commands.iter().map(|c| c.trigger.clone()).collect();
@@ -1614,16 +3895,278 @@ impl App {
                 };
                 self.terminal_tabs[i].suggestion_index = new_idx;
             }
+            Message::TerminalSaveLog => {
+                let Some(tab) = self.active_tab.and_then(|i| self.terminal_tabs.get(i)) else {
+                    return Task::none();
+                };
+                let tab_id = tab.id;
+                let file_name = format!(
+                    "{}-{}.log",
+                    session_log::file_stem(&tab.label),
+                    session_log::timestamp()
+                );
+                return Task::perform(
+                    async move {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Save Session Log")
+                            .set_file_name(file_name)
+                            .add_filter("Log", &["log", "txt"])
+                            .save_file()
+                            .await
+                            .map(|handle| handle.path().to_path_buf())
+                    },
+                    move |path| Message::TerminalSaveLogTo(tab_id, path),
+                );
+            }
+            Message::TerminalSaveLogTo(tab_id, Some(path)) => {
+                let keep_ansi = self.config.log_keep_ansi;
+                let parser = match self.terminal_runtime.get_mut(&tab_id) {
+                    Some(runtime) => &mut runtime.parser,
+                    None => match self.ended_sessions.get_mut(&tab_id) {
+                        Some(parser) => parser,
+                        None => {
+                            self.notification = Some(("Tab was closed; nothing to save".to_string(), true));
+                            return Task::none();
+                        }
+                    },
+                };
+                let contents = session_log::scrollback_bytes(parser, keep_ansi);
+                self.notification = Some(match std::fs::write(&path, contents) {
+                    Ok(()) => (format!("Saved log to {}", path.display()), false),
+                    Err(e) => (format!("Cannot write {}: {}", path.display(), e), true),
+                });
+            }
+            Message::TerminalSaveLogTo(_, None) => {}
             Message::TerminalCopyOutput => {
                 let Some(i) = self.active_tab else { return Task::none(); };
-                let content = self.terminal_tabs[i].output.clone();
+                // A selection, if there is one; the whole output otherwise.
+                let content = self
+                    .selected_text()
+                    .unwrap_or_else(|| self.terminal_tabs[i].output.clone());
                 return iced::clipboard::write::<Message>(content);
             }
+            Message::TerminalPointerMoved(point) => {
+                self.terminal_pointer = Some(point);
+                let cell = self.active_tab.map(|i| terminal_cell_at(point, self.tab_font_size(i)));
+                if let (Some(selection), Some(cell)) = (self.terminal_selection.as_mut(), cell) {
+                    if selection.dragging {
+                        selection.head = cell;
+                    }
+                }
+            }
+            Message::TerminalSelectStart => {
+                let (Some(i), Some(point)) = (self.active_tab, self.terminal_pointer) else { return Task::none(); };
+                let cell = terminal_cell_at(point, self.tab_font_size(i));
+                self.terminal_selection = Some(TermSelection { anchor: cell, head: cell, dragging: true });
+            }
+            Message::TerminalSelectEnd => {
+                let Some(selection) = self.terminal_selection.as_mut().filter(|s| s.dragging) else {
+                    return Task::none();
+                };
+                selection.dragging = false;
+                if selection.anchor == selection.head {
+                    // A plain click, not a drag.
+                    self.terminal_selection = None;
+                    return Task::none();
+                }
+                if self.config.copy_on_select {
+                    if let Some(content) = self.selected_text() {
+                        return Task::batch([
+                            iced::clipboard::write_primary::<Message>(content.clone()),
+                            iced::clipboard::write::<Message>(content),
+                        ]);
+                    }
+                }
+            }
 
             // ── Security audit ────────────────────────────────────────────
             Message::OpenSecurityAudit => {
-                let findings = run_security_audit(&self.config, &self.api_url);
-                self.dialog = Some(dialogs::DialogState::SecurityAudit(findings));
+                let findings = self.audit_findings();
+                self.dialog = Some(dialogs::DialogState::SecurityAudit {
+                    findings,
+                    redact: false,
+                    shown: self.audit_shown_severities(),
+                    notice: None,
+                    scan: self.deep_scan,
+                });
+            }
+            Message::SecurityAuditDeepScan => {
+                if self.deep_scan.is_some() || self.config.hosts.is_empty() {
+                    return Task::none();
+                }
+                self.remote_findings.clear();
+                self.deep_scan = Some((0, self.config.hosts.len()));
+                self.deep_scan_run += 1;
+                let run = self.deep_scan_run;
+                self.refresh_security_audit(None);
+                let scans = self.config.hosts.iter().cloned().map(|host| {
+                    Task::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || crate::remote_audit::scan_host(&host))
+                                .await
+                                .unwrap_or_default()
+                        },
+                        move |findings| Message::SecurityAuditHostScanned(run, findings),
+                    )
+                });
+                return Task::batch(scans);
+            }
+            Message::SecurityAuditCancelScan => {
+                if self.deep_scan.take().is_some() {
+                    // Hosts still being scanned finish (or time out) in the background.
+                    self.deep_scan_run += 1;
+                    self.refresh_security_audit(Some(("Deep scan cancelled".into(), false)));
+                }
+            }
+            Message::SecurityAuditHostScanned(run, findings) => {
+                if run != self.deep_scan_run {
+                    return Task::none();
+                }
+                self.remote_findings.extend(findings);
+                let notice = match &mut self.deep_scan {
+                    Some((done, total)) => {
+                        *done += 1;
+                        (*done >= *total).then(|| (format!("Deep scan finished: {} host(s) checked", total), false))
+                    }
+                    None => None,
+                };
+                if notice.is_some() {
+                    self.deep_scan = None;
+                }
+                if matches!(self.dialog, Some(dialogs::DialogState::SecurityAudit { .. })) {
+                    self.refresh_security_audit(notice);
+                }
+            }
+            Message::SecurityAuditRedactChanged(on) => {
+                if let Some(dialogs::DialogState::SecurityAudit { redact, .. }) = &mut self.dialog {
+                    *redact = on;
+                }
+            }
+            Message::SecurityAuditToggleSeverity(severity) => {
+                if let Some(dialogs::DialogState::SecurityAudit { shown, .. }) = &mut self.dialog {
+                    if let Some(pos) = shown.iter().position(|s| *s == severity) {
+                        shown.remove(pos);
+                    } else {
+                        shown.push(severity);
+                    }
+                    self.config.audit_hidden_severities = SecuritySeverity::ALL
+                        .iter()
+                        .filter(|s| !shown.contains(s))
+                        .map(|s| s.label().to_string())
+                        .collect();
+                    let _ = config::save_config(&self.config);
+                }
+            }
+            Message::SecurityAuditFix(action) => match action {
+                AuditAction::InstallKey(idx) => {
+                    let Some(host) = self.config.hosts.get(idx).cloned() else {
+                        return Task::none();
+                    };
+                    let Some(pubkey) = local_public_key() else {
+                        self.refresh_security_audit(Some((
+                            "No public key found in ~/.ssh — generate one with ssh-keygen first".into(),
+                            true,
+                        )));
+                        return Task::none();
+                    };
+                    self.refresh_security_audit(Some((
+                        format!("Installing public key on {}...", host.alias),
+                        false,
+                    )));
+                    return Task::perform(
+                        async move {
+                            let res = tokio::task::spawn_blocking({
+                                let host = host.clone();
+                                move || {
+                                    crate::syspanel::install_public_key(host.clone(), pubkey)?;
+                                    // Only a working key-only login makes the password redundant.
+                                    let key_only = Host { password: None, ..host };
+                                    Ok(ssh::open_session(&key_only).is_ok())
+                                }
+                            })
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()));
+                            (host, res)
+                        },
+                        |(host, res)| Message::SecurityAuditKeyInstalled(Box::new(host), res),
+                    );
+                }
+                AuditAction::EditHost(idx) => {
+                    self.selected_host = Some(idx);
+                    return self.update(Message::OpenEditDialog(idx));
+                }
+                AuditAction::UseHttps => {
+                    if let Some(rest) = self.api_url.strip_prefix("http://") {
+                        self.api_url = format!("https://{rest}");
+                        self.config.api_url = Some(self.api_url.clone());
+                        let _ = config::save_config(&self.config);
+                    }
+                    self.refresh_security_audit(Some(("API URL switched to HTTPS".into(), false)));
+                }
+                AuditAction::OpenSettings => return self.update(Message::OpenSettings),
+                AuditAction::OpenCustomCommands => return self.update(Message::OpenCustomCommands),
+            },
+            Message::SecurityAuditKeyInstalled(host, res) => {
+                // The list may have changed while the key was installed.
+                let idx = match &host.id {
+                    Some(id) => self.config.hosts.iter().position(|h| h.id.as_ref() == Some(id)),
+                    None => self.saved_host_index(&host),
+                };
+                let mut api_task = Task::none();
+                let notice = match res {
+                    Ok(true) => {
+                        if let Some(idx) = idx {
+                            self.config.hosts[idx].password = None;
+                            if let Some(id) = self.config.hosts[idx].id.clone() {
+                                api_task = self.send_host_change(PendingApiOp::Update { id });
+                            }
+                            let _ = config::save_config(&self.config);
+                        }
+                        (format!("Key installed on {} and saved password removed", host.alias), false)
+                    }
+                    Ok(false) => (
+                        format!(
+                            "Key installed on {}, but logging in without the password failed, so the password was kept — \
+                             load the key in ssh-agent or set it as the host's identity file",
+                            host.alias
+                        ),
+                        true,
+                    ),
+                    Err(e) => (format!("Key install failed: {e}"), true),
+                };
+                if matches!(self.dialog, Some(dialogs::DialogState::SecurityAudit { .. })) {
+                    self.refresh_security_audit(Some(notice));
+                }
+                return api_task;
+            }
+
+            Message::SecurityAuditExport => {
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Export Audit Report")
+                            .set_file_name(format!("termissh-audit-{}.md", session_log::timestamp()))
+                            .add_filter("Markdown", &["md"])
+                            .add_filter("JSON", &["json"])
+                            .save_file()
+                            .await
+                            .map(|handle| handle.path().to_path_buf())
+                    },
+                    Message::SecurityAuditExportTo,
+                );
+            }
+            Message::SecurityAuditExportTo(path) => {
+                let (Some(path), Some(dialogs::DialogState::SecurityAudit { findings, redact, notice, .. })) =
+                    (path, self.dialog.as_mut())
+                else {
+                    return Task::none();
+                };
+                let json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+                let report = audit_report(findings, !json, *redact);
+                *notice = Some(match std::fs::write(&path, report) {
+                    Ok(()) => (format!("Report saved to {}", path.display()), false),
+                    Err(e) => (format!("Cannot write {}: {}", path.display(), e), true),
+                });
             }
 
             // ── Custom commands (aliases) ─────────────────────────────────
@@ -1674,6 +4217,15 @@ impl App {
                     tab.sys_open = true;
                     tab.sys_state = crate::syspanel::SysState::new();
                     let host = tab.host.clone();
+                    if self.config.sys_prefetch {
+                        // The pooled session runs these side by side; the tabs not
+                        // shown keep their result until they are opened.
+                        return Task::batch([
+                            crate::syspanel::fetch_overview(host.clone(), tab_id),
+                            crate::syspanel::fetch_firewall(host.clone(), tab_id),
+                            crate::syspanel::fetch_logins(host, tab_id),
+                        ]);
+                    }
                     return crate::syspanel::fetch_overview(host, tab_id);
                 }
             }
@@ -1687,8 +4239,13 @@ impl App {
                     let new_tab = crate::syspanel::SysTab::from_str(&tab_name);
                     tab.sys_state.tab = new_tab.clone();
                     tab.sys_state.loading = true;
+                    tab.sys_state.showing_log = false;
                     tab.sys_state.output.clear();
                     tab.sys_state.action_result = None;
+                    tab.sys_state.stderr.clear();
+                    if let Some(result) = tab.sys_state.prefetched.remove(new_tab.fetch_kind()) {
+                        return self.update(Message::SysPanelFetched(tab_id, new_tab.fetch_kind().to_string(), result));
+                    }
                     let host = tab.host.clone();
                     return match new_tab {
                         crate::syspanel::SysTab::Overview => crate::syspanel::fetch_overview(host, tab_id),
@@ -1696,6 +4253,10 @@ impl App {
                         crate::syspanel::SysTab::Packages => crate::syspanel::fetch_packages(host, tab_id),
                         crate::syspanel::SysTab::Logins => crate::syspanel::fetch_logins(host, tab_id),
                         crate::syspanel::SysTab::SshKeys => crate::syspanel::fetch_ssh_keys(host, tab_id),
+                        crate::syspanel::SysTab::Cron => crate::syspanel::fetch_cron(host, tab_id),
+                        crate::syspanel::SysTab::Services => crate::syspanel::fetch_services(host, tab_id),
+                        crate::syspanel::SysTab::Users => crate::syspanel::fetch_users(host, tab_id),
+                        crate::syspanel::SysTab::Processes => crate::syspanel::fetch_processes(host, tab_id),
                         crate::syspanel::SysTab::Extension(ref id) => {
                             crate::syspanel::fetch_extension(host, tab_id, id.clone())
                         }
@@ -1709,8 +4270,36 @@ impl App {
                         "fw_proto"  => tab.sys_state.fw_proto = value,
                         "fw_action" => tab.sys_state.fw_action = value,
                         "pkg_search" => tab.sys_state.pkg_search = value,
+                        "pkg_install" => tab.sys_state.pkg_install = value,
                         "key_name"  => tab.sys_state.key_name = value,
                         "key_type"  => tab.sys_state.key_type = value,
+                        "auth_key_input" => tab.sys_state.auth_key_input = value,
+                        "cron_line" => tab.sys_state.cron_line = value,
+                        "svc_search" => tab.sys_state.svc_search = value,
+                        "show_raw" => tab.sys_state.show_raw = value == "open",
+                        "proc_auto" => tab.sys_state.proc_auto = value == "on",
+                        "user_name" => tab.sys_state.user_name = value,
+                        "user_group" => tab.sys_state.user_group = value,
+                        "svc_sort" => {
+                            let col = value.parse().unwrap_or(0);
+                            let state = &mut tab.sys_state;
+                            state.svc_sort_desc = state.svc_sort == col && !state.svc_sort_desc;
+                            state.svc_sort = col;
+                        }
+                        "pkg_sort" => {
+                            let col = value.parse().ok();
+                            let state = &mut tab.sys_state;
+                            state.pkg_sort_desc = state.pkg_sort == col && !state.pkg_sort_desc;
+                            state.pkg_sort = col;
+                        }
+                        "svc_selected" => {
+                            let state = &mut tab.sys_state;
+                            state.svc_selected = if state.svc_selected.as_deref() == Some(value.as_str()) {
+                                None
+                            } else {
+                                Some(value)
+                            };
+                        }
                         _ => {}
                     }
                 }
@@ -1727,45 +4316,819 @@ impl App {
                         "packages"  => crate::syspanel::fetch_packages(host, tab_id),
                         "logins"    => crate::syspanel::fetch_logins(host, tab_id),
                         "sshkeys"   => crate::syspanel::fetch_ssh_keys(host, tab_id),
+                        "cron"      => crate::syspanel::fetch_cron(host, tab_id),
+                        "services"  => crate::syspanel::fetch_services(host, tab_id),
+                        "users"     => crate::syspanel::fetch_users(host, tab_id),
+                        "processes" => crate::syspanel::fetch_processes(host, tab_id),
                         ext_id      => crate::syspanel::fetch_extension(host, tab_id, ext_id.to_string()),
                     };
                 }
             }
             Message::SysPanelAction(tab_id, cmd) => {
                 if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == tab_id) {
+                    if tab.sys_state.tab == crate::syspanel::SysTab::Packages {
+                        // Package operations can take minutes; show output as it arrives.
+                        if tab.sys_state.streaming {
+                            return Task::none();
+                        }
+                        tab.sys_state.streaming = true;
+                        tab.sys_state.showing_log = true;
+                        tab.sys_state.action_result = None;
+                        tab.sys_state.output = format!("$ {cmd}\n");
+                        let host = tab.host.clone();
+                        return crate::syspanel::run_streaming(host, tab_id, cmd);
+                    }
                     tab.sys_state.loading = true;
                     tab.sys_state.action_result = None;
                     let host = tab.host.clone();
                     return crate::syspanel::run_action(host, tab_id, cmd);
                 }
             }
-            Message::SysPanelFetched(tab_id, kind, output) => {
+            Message::SysPanelConfirm(tab_id, prompt, label, cmd) => {
+                self.dialog = Some(dialogs::DialogState::ConfirmSysAction { tab_id, prompt, label, cmd });
+            }
+            Message::SysPanelConfirmed(tab_id, cmd) => {
+                self.dialog = None;
+                return self.update(Message::SysPanelAction(tab_id, cmd));
+            }
+            Message::SysPanelOutput(tab_id, chunk) => {
+                if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == tab_id) {
+                    // Ignore chunks after the user has moved on to another view.
+                    if tab.sys_state.showing_log {
+                        tab.sys_state.output.push_str(&chunk);
+                    }
+                }
+            }
+            Message::SysPanelCopyPublicKey(tab_id) => {
+                if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == tab_id) {
+                    if let Some(key) = tab.sys_state.generated_pubkey.clone() {
+                        tab.sys_state.action_result = Some("Public key copied to clipboard".to_string());
+                        return iced::clipboard::write::<Message>(key);
+                    }
+                }
+            }
+            Message::SysPanelAuthorizeLocally(tab_id) => {
+                if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == tab_id) {
+                    if let Some(key) = &tab.sys_state.generated_pubkey {
+                        tab.sys_state.action_result = Some(match crate::syspanel::append_local_authorized_key(key) {
+                            Ok(path) => format!("Added key to {}", path.display()),
+                            Err(e) => e,
+                        });
+                    }
+                }
+            }
+            Message::SysPanelResolveSources(tab_id) => {
+                if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == tab_id) {
+                    let state = &mut tab.sys_state;
+                    let ips: Vec<_> = crate::syspanel::failed_login_ips(&state.output)
+                        .into_iter()
+                        .filter(|ip| !state.source_names.contains_key(&ip.to_string()))
+                        .collect();
+                    if !ips.is_empty() {
+                        state.resolving = true;
+                        return crate::syspanel::resolve_sources(tab_id, ips);
+                    }
+                }
+            }
+            Message::SysPanelSourcesResolved(tab_id, names) => {
+                if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == tab_id) {
+                    tab.sys_state.resolving = false;
+                    tab.sys_state.source_names.extend(names);
+                }
+            }
+            Message::SysPanelTick => {
+                let mut tasks = Vec::new();
+                for tab in self.terminal_tabs.iter_mut() {
+                    let state = &mut tab.sys_state;
+                    if tab.sys_open
+                        && state.proc_auto
+                        && state.tab == crate::syspanel::SysTab::Processes
+                        && !state.loading
+                        && !state.refreshing
+                    {
+                        // Refresh in place; the loading screen would flicker every tick.
+                        state.refreshing = true;
+                        tasks.push(crate::syspanel::fetch_processes(tab.host.clone(), tab.id));
+                    }
+                }
+                return Task::batch(tasks);
+            }
+            Message::SysPanelFetched(tab_id, kind, result) => {
                 if let Some(tab) = self.terminal_tabs.iter_mut().find(|t| t.id == tab_id) {
+                    tab.sys_state.refreshing = false;
+                    // A fetch that lands after switching tabs would overwrite the new view;
+                    // keep it for when its tab is opened (prefetch), unless it is stale by then.
+                    if !matches!(kind.as_str(), "action" | "stream") && kind != tab.sys_state.tab.fetch_kind() {
+                        if kind != "processes" && kind != "extension" {
+                            tab.sys_state.prefetched.insert(kind, result);
+                        }
+                        return Task::none();
+                    }
                     tab.sys_state.loading = false;
+                    if kind != "action" {
+                        tab.sys_state.stderr = result.stderr.clone();
+                    }
+                    let output = result.stdout.clone();
                     match kind.as_str() {
                         "action" => {
-                            tab.sys_state.action_result = Some(output.lines().last().unwrap_or("Done").to_string());
+                            tab.sys_state.action_result = Some(result.summary().to_string());
+                            if tab.sys_state.tab == crate::syspanel::SysTab::SshKeys {
+                                if let Some(key) = crate::syspanel::last_public_key(&output) {
+                                    tab.sys_state.generated_pubkey = Some(key);
+                                }
+                            }
                             // Refresh current panel after action
                             let host = tab.host.clone();
                             let current_tab = tab.sys_state.tab.clone();
                             return match current_tab {
                                 crate::syspanel::SysTab::Firewall => crate::syspanel::fetch_firewall(host, tab_id),
+                                crate::syspanel::SysTab::Services => crate::syspanel::fetch_services(host, tab_id),
+                                crate::syspanel::SysTab::Users => crate::syspanel::fetch_users(host, tab_id),
+                                crate::syspanel::SysTab::Processes => crate::syspanel::fetch_processes(host, tab_id),
+                                crate::syspanel::SysTab::SshKeys => {
+                                    tab.sys_state.auth_key_input.clear();
+                                    crate::syspanel::fetch_ssh_keys(host, tab_id)
+                                }
+                                crate::syspanel::SysTab::Cron => {
+                                    tab.sys_state.cron_line.clear();
+                                    crate::syspanel::fetch_cron(host, tab_id)
+                                }
                                 crate::syspanel::SysTab::Extension(ref id) => crate::syspanel::fetch_extension(host, tab_id, id.clone()),
                                 _ => { tab.sys_state.output = output; Task::none() }
                             };
                         }
+                        "stream" => {
+                            tab.sys_state.streaming = false;
+                            tab.sys_state.pkg_install.clear();
+                            tab.sys_state.action_result = Some(result.summary().to_string());
+                        }
+                        "packages" => {
+                            tab.sys_state.showing_log = false;
+                            tab.sys_state.output = output;
+                        }
                         "overview" => {
                             tab.sys_state.extensions = crate::syspanel::parse_extensions(&output);
+                            tab.sys_state.pkg_manager = crate::syspanel::parse_package_manager(&output);
+                            tab.sys_state.output = output;
+                        }
+                        "extension" => {
+                            // Keep the running indicator in step after start/stop.
+                            if let (crate::syspanel::SysTab::Extension(id), Some(active)) =
+                                (&tab.sys_state.tab, crate::syspanel::parse_service_active(&output))
+                            {
+                                if let Some(ext) = tab.sys_state.extensions.iter_mut().find(|e| &e.id == id) {
+                                    ext.active = active;
+                                }
+                            }
                             tab.sys_state.output = output;
                         }
                         _ => {
                             tab.sys_state.output = output;
                         }
-                    }
+                    }
+                }
+            }
+        }
+        Task::none()
+    }
+
+    /// Local host a queued API change refers to, if it still exists.
+    fn pending_op_host(&self, op: &PendingApiOp) -> Option<&Host> {
+        match op {
+            PendingApiOp::Create { alias } => {
+                self.config.hosts.iter().find(|h| h.id.is_none() && h.alias == *alias)
+            }
+            PendingApiOp::Update { id } | PendingApiOp::Delete { id } => {
+                self.config.hosts.iter().find(|h| h.id.as_deref() == Some(id.as_str()))
+            }
+        }
+    }
+
+    /// Keys typed while the Ctrl+R search is open: Enter inserts the match,
+    /// Esc or Ctrl+C cancels, anything printable extends the query.
+    fn history_search_input(&mut self, i: usize, bytes: &[u8]) -> Task<Message> {
+        let tab = &mut self.terminal_tabs[i];
+        match bytes {
+            [b'\r'] => {
+                let chosen = tab.history_search_match().map(str::to_string);
+                tab.history_search = None;
+                if let Some(cmd) = chosen {
+                    return self.update(Message::TerminalSuggestionAccept(cmd));
+                }
+            }
+            [27] | [3] | [7] => tab.history_search = None,
+            [127] | [8] => {
+                if let Some(search) = tab.history_search.as_mut() {
+                    search.query.pop();
+                    search.skip = 0;
+                }
+            }
+            _ if bytes.iter().all(|&b| b >= 32) => {
+                if let (Some(search), Ok(s)) = (tab.history_search.as_mut(), std::str::from_utf8(bytes)) {
+                    search.query.push_str(s);
+                    search.skip = 0;
+                }
+            }
+            _ => {}
+        }
+        Task::none()
+    }
+
+    /// Removes a host and tells the API about it. The caller saves the config.
+    fn remove_host(&mut self, idx: usize) -> Task<Message> {
+        self.stop_host_forwards(&self.config.hosts[idx].clone());
+        let host = &self.config.hosts[idx];
+        let task = match host.id.clone() {
+            Some(id) => self.send_host_change(PendingApiOp::Delete { id }),
+            None => {
+                // Never reached the API; just forget the queued create. One
+                // already on its way is deleted again once it has an id.
+                let alias = host.alias.clone();
+                self.config
+                    .pending_api_ops
+                    .retain(|op| *op != PendingApiOp::Create { alias: alias.clone() });
+                Task::none()
+            }
+        };
+        self.config.hosts.remove(idx);
+        self.shift_host_indices(idx);
+        task
+    }
+
+    /// Drops per-host state keyed by `idx` and moves everything above it down
+    /// one, after the host at `idx` was removed.
+    fn shift_host_indices(&mut self, idx: usize) {
+        self.selected_host = shift_index(self.selected_host, idx);
+        self.latency_monitor = shift_index(self.latency_monitor, idx);
+        shift_index_keys(&mut self.ping_results, idx);
+        shift_index_keys(&mut self.latency_history, idx);
+        self.checked_hosts = self.checked_hosts.iter().filter_map(|&i| shift_index(Some(i), idx)).collect();
+    }
+
+    /// Queues a host change and sends it to the API in the background, unless
+    /// offline or another change is still on its way. Call after updating
+    /// `config.hosts`; the caller saves the config.
+    fn send_host_change(&mut self, op: PendingApiOp) -> Task<Message> {
+        if self.config.api_key.is_none() {
+            return Task::none();
+        }
+        if !self.config.pending_api_ops.contains(&op) {
+            self.config.pending_api_ops.push(op);
+        }
+        self.send_next_host_change()
+    }
+
+    /// Sends the oldest queued host change, one at a time so they keep their
+    /// order. A sync replays the queue itself, so nothing is sent meanwhile.
+    fn send_next_host_change(&mut self) -> Task<Message> {
+        let Some(key) = self.config.api_key.clone() else { return Task::none(); };
+        if self.offline || self.syncing || self.api_in_flight.is_some() || self.config.pending_api_ops.is_empty() {
+            return Task::none();
+        }
+        let op = self.config.pending_api_ops.remove(0);
+        let host = self.pending_op_host(&op).cloned();
+        let api_url = self.api_url.clone();
+        self.api_in_flight = Some(op.clone());
+        Task::perform(
+            async move {
+                let res = tokio::task::spawn_blocking({
+                    let op = op.clone();
+                    move || {
+                        api::apply_op(&api_url, &key, &op, host.as_ref())
+                            .map_err(|e| (api::is_transient(&e), format!("{:#}", e)))
+                    }
+                })
+                .await
+                .unwrap_or_else(|e| Err((true, e.to_string())));
+                (op, res)
+            },
+            |(op, res)| Message::HostChangeSent(op, res),
+        )
+    }
+
+    /// Opt-in passive audit: counts Critical/High findings that were not
+    /// present the last time the audit dialog was opened.
+    fn run_background_audit(&mut self) {
+        if !self.config.background_audit {
+            self.audit_alerts = 0;
+            return;
+        }
+        let findings = run_security_audit(&self.config, &self.api_url);
+        self.audit_alerts = serious_finding_keys(&findings)
+            .iter()
+            .filter(|key| !self.config.audit_baseline.contains(key))
+            .count();
+    }
+
+    /// Marks the current serious findings as seen and clears the toolbar badge.
+    fn acknowledge_audit(&mut self, findings: &[SecurityFinding]) {
+        self.audit_alerts = 0;
+        let keys = serious_finding_keys(findings);
+        if keys != self.config.audit_baseline {
+            self.config.audit_baseline = keys;
+            let _ = config::save_config(&self.config);
+        }
+    }
+
+    /// Severities the audit dialog starts with, from the saved filter.
+    fn audit_shown_severities(&self) -> Vec<SecuritySeverity> {
+        SecuritySeverity::ALL
+            .into_iter()
+            .filter(|s| !self.config.audit_hidden_severities.iter().any(|h| h == s.label()))
+            .collect()
+    }
+
+    /// Local audit findings followed by those of the last deep scan. Only the
+    /// local ones feed the background-audit baseline.
+    fn audit_findings(&mut self) -> Vec<SecurityFinding> {
+        let mut findings = run_security_audit(&self.config, &self.api_url);
+        self.acknowledge_audit(&findings);
+        findings.extend(self.remote_findings.iter().cloned());
+        findings
+    }
+
+    /// Re-runs the audit in place, keeping the dialog's view options.
+    fn refresh_security_audit(&mut self, notice: Option<(String, bool)>) {
+        let findings = self.audit_findings();
+        let deep_scan = self.deep_scan;
+        match &mut self.dialog {
+            Some(dialogs::DialogState::SecurityAudit { findings: current, notice: current_notice, scan, .. }) => {
+                *current = findings;
+                *current_notice = notice;
+                *scan = deep_scan;
+            }
+            _ => {
+                self.dialog = Some(dialogs::DialogState::SecurityAudit {
+                    findings,
+                    redact: false,
+                    shown: self.audit_shown_severities(),
+                    notice,
+                    scan: deep_scan,
+                });
+            }
+        }
+    }
+
+    /// Launches the relay child for `host` and wires its output into a fresh runtime.
+    fn spawn_runtime(&mut self, relay_path: &str, host: &Host) -> Result<TerminalRuntime, String> {
+        let mut child = bridge::spawn_relay_child(relay_path, host).map_err(|e| e.to_string())?;
+        let (Some(stdin), Some(stdout), Some(stderr)) =
+            (child.stdin.take(), child.stdout.take(), child.stderr.take())
+        else {
+            return Err("Relay started but stdio pipes are unavailable.".to_string());
+        };
+
+        let (tx, rx) = mpsc::channel::<Vec<u8>>();
+        // Quick Connect sessions leave nothing behind, logs included.
+        let log = if self.config.auto_log && !host.adhoc {
+            match SessionLog::create(&host.alias, self.config.log_keep_ansi) {
+                Ok(log) => Some(Arc::new(Mutex::new(log))),
+                Err(e) => {
+                    self.notification = Some((format!("Session log not started for {}: {}", host.alias, e), true));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        spawn_reader_thread(stdout, tx.clone(), log.clone());
+        spawn_reader_thread(stderr, tx, log);
+
+        Ok(TerminalRuntime {
+            child,
+            stdin: Arc::new(Mutex::new(stdin)),
+            rx,
+            parser: Parser::new(TERMINAL_ROWS, TERMINAL_COLS, 10_000),
+            links: LinkTracker::default(),
+            on_connect: host.on_connect.clone(),
+            on_connect_at: None,
+        })
+    }
+
+    /// Font size of tab `idx`; tabs that never changed theirs follow the setting.
+    fn tab_font_size(&self, idx: usize) -> f32 {
+        let size = self.terminal_tabs.get(idx).map_or(13.0, |t| t.font_size);
+        if (size - 13.0).abs() < 0.1 { self.config.terminal_font_size } else { size }
+    }
+
+    /// Text of the active tab's selection, if any.
+    fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.terminal_selection?.range();
+        let tab = self.terminal_tabs.get(self.active_tab?)?;
+        let screen = self.terminal_runtime.get(&tab.id)?.parser.screen();
+        let (_, cols) = screen.size();
+        let text = screen.contents_between(start.0, start.1, end.0, (end.1 + 1).min(cols));
+        (!text.is_empty()).then_some(text)
+    }
+
+    /// Takes over a config loaded after startup (unlocked, or its key found).
+    fn apply_loaded_config(&mut self, mut cfg: AppConfig) -> Task<Message> {
+        if cfg.terminal_font_size < 8.0 {
+            cfg.terminal_font_size = 13.0;
+        }
+        self.theme = cfg.theme;
+        theme::set_custom_palette(&cfg.custom_palette);
+        ssh::set_connect_timeout(cfg.connect_timeout_secs);
+        ssh::set_keepalive(cfg.keepalive_secs);
+        ssh::set_term_type(&cfg.term_type);
+        api::set_timeout(cfg.api_timeout_secs);
+        if let Some(url) = cfg.api_url.as_deref().map(normalize_api_url).filter(|u| !u.is_empty()) {
+            self.api_url = url;
+        }
+        self.config = cfg;
+        self.run_background_audit();
+        let mut tasks = Vec::new();
+        if self.theme == AppTheme::Auto {
+            tasks.push(Task::done(Message::SystemThemeCheck));
+        }
+        if self.config.api_key.is_some() {
+            tasks.push(Task::done(Message::SyncFromApi));
+        }
+        Task::batch(tasks)
+    }
+
+    /// Resizes the parser and remote PTY of the active tab, and of the split
+    /// tab next to it, to the grids measured for their panes.
+    fn apply_terminal_grid(&mut self) {
+        let split = self.split_tab.filter(|&s| Some(s) != self.active_tab);
+        for (idx, grid) in [(self.active_tab, self.terminal_grid.get()), (split, self.split_grid.get())] {
+            let (Some(idx), Some((rows, cols))) = (idx, grid) else { continue; };
+            let Some(tab) = self.terminal_tabs.get(idx) else { continue; };
+            let Some(runtime) = self.terminal_runtime.get_mut(&tab.id) else { continue; };
+            if runtime.parser.screen().size() == (rows, cols) {
+                continue;
+            }
+            runtime.parser.set_size(rows, cols);
+            if let Ok(mut stdin) = runtime.stdin.lock() {
+                let _ = protocol::write_frame(&mut *stdin, &protocol::Frame::Resize { cols, rows });
+            }
+        }
+    }
+
+    /// Host index and tunnel `i` of the open Tunnels dialog.
+    fn dialog_tunnel(&self, i: usize) -> Option<(usize, config::Tunnel)> {
+        let Some(dialogs::DialogState::Tunnels { host_idx, .. }) = &self.dialog else { return None; };
+        let tunnel = self.config.hosts.get(*host_idx)?.tunnels.get(i)?.clone();
+        Some((*host_idx, tunnel))
+    }
+
+    /// Stops the forward on `local_port` if it belongs to host `host_idx`.
+    fn stop_forward(&mut self, host_idx: usize, local_port: u16) {
+        let host = &self.config.hosts[host_idx];
+        if self.forwards.get(&local_port).is_some_and(|f| f.belongs_to(host)) {
+            self.forwards.remove(&local_port);
+        }
+    }
+
+    /// Stops every forward of `host`, e.g. before it is edited or removed.
+    fn stop_host_forwards(&mut self, host: &Host) {
+        self.forwards.retain(|_, f| !f.belongs_to(host));
+    }
+
+    /// Rebuilds the status rows of the open Tunnels dialog.
+    fn refresh_tunnels_dialog(&mut self) {
+        let Some(dialogs::DialogState::Tunnels { host_idx, rows, .. }) = self.dialog.as_mut() else { return; };
+        let Some(host) = self.config.hosts.get(*host_idx) else { return; };
+        *rows = host
+            .tunnels
+            .iter()
+            .map(|t| {
+                let running = self.forwards.get(&t.local_port).filter(|f| f.belongs_to(host));
+                let status = match running {
+                    Some(handle) => {
+                        let mut status = format!("listening · {} connection(s)", handle.connections());
+                        if let Some(e) = handle.last_error() {
+                            status.push_str(&format!(" · last error: {}", e));
+                        }
+                        status
+                    }
+                    None => "stopped".to_string(),
+                };
+                dialogs::TunnelRow {
+                    tunnel: t.clone(),
+                    running: running.is_some(),
+                    status,
+                }
+            })
+            .collect();
+    }
+
+    /// Starts the next queued upload of tab `active`, if any.
+    fn next_upload(&mut self, active: usize) -> Task<Message> {
+        let ftp_state = &mut self.terminal_tabs[active].ftp;
+        let Some(host) = ftp_state.connected_host.clone() else {
+            ftp_state.upload_queue.clear();
+            return Task::none();
+        };
+        let Some(local) = ftp_state.upload_queue.pop_front() else { return Task::none(); };
+        let local_str = local.to_string_lossy().to_string();
+        let file_name = local
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "upload".to_string());
+        let remote_path = format!("{}/{}", ftp_state.current_path.trim_end_matches('/'), file_name);
+        let position = ftp_state.uploads_total - ftp_state.upload_queue.len();
+        ftp_state.notification = Some((
+            format!("Uploading {}/{}: {}", position, ftp_state.uploads_total, file_name),
+            false,
+        ));
+        ftp_state.current_upload = Some(file_name);
+        let tab_id = self.terminal_tabs[active].id;
+        let tx = self.start_ftp_transfer(active);
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    ftp::upload_file(&host, &local_str, &remote_path, |done, total| {
+                        let _ = tx.send((done, total));
+                    })
+                })
+                .await
+                .unwrap_or_else(|e| Err(e.to_string()))
+            },
+            move |result| Message::FtpUploadResult(tab_id, result),
+        )
+    }
+
+    /// Registers a progress channel for a transfer started from tab `active`.
+    fn start_ftp_transfer(&mut self, active: usize) -> mpsc::Sender<(u64, u64)> {
+        let (tx, rx) = mpsc::channel();
+        let tab = &mut self.terminal_tabs[active];
+        tab.ftp.transfer = Some((0, 0));
+        self.ftp_transfers.insert(tab.id, rx);
+        tx
+    }
+
+    /// Swaps a host with its nearest neighbour in the same sidebar group.
+    /// Selection and ping results follow the moved entries.
+    fn move_host(&mut self, idx: usize, up: bool) {
+        let Some(group) = self.config.hosts.get(idx).map(|h| h.group.clone()) else {
+            return;
+        };
+        let same_group = |h: &Host| h.group == group;
+        let neighbour = if up {
+            self.config.hosts[..idx].iter().rposition(same_group)
+        } else {
+            self.config.hosts[idx + 1..]
+                .iter()
+                .position(same_group)
+                .map(|offset| idx + 1 + offset)
+        };
+        let Some(other) = neighbour else {
+            return;
+        };
+
+        self.config.hosts.swap(idx, other);
+        let ping_idx = self.ping_results.remove(&idx);
+        let ping_other = self.ping_results.remove(&other);
+        if let Some(ping) = ping_idx {
+            self.ping_results.insert(other, ping);
+        }
+        if let Some(ping) = ping_other {
+            self.ping_results.insert(idx, ping);
+        }
+        let history_idx = self.latency_history.remove(&idx);
+        let history_other = self.latency_history.remove(&other);
+        if let Some(history) = history_idx {
+            self.latency_history.insert(other, history);
+        }
+        if let Some(history) = history_other {
+            self.latency_history.insert(idx, history);
+        }
+        if self.latency_monitor == Some(idx) {
+            self.latency_monitor = Some(other);
+        } else if self.latency_monitor == Some(other) {
+            self.latency_monitor = Some(idx);
+        }
+        if self.selected_host == Some(idx) {
+            self.selected_host = Some(other);
+        } else if self.selected_host == Some(other) {
+            self.selected_host = Some(idx);
+        }
+        let _ = config::save_config(&self.config);
+    }
+
+    /// Appends hosts whose alias is not taken yet and queues them for the API;
+    /// returns (added, skipped) and the task sending them.
+    fn merge_hosts(&mut self, hosts: Vec<Host>) -> (usize, usize, Task<Message>) {
+        let mut added = 0;
+        let mut skipped = 0;
+        let mut api_tasks = Vec::new();
+        for host in hosts {
+            if self.config.hosts.iter().any(|h| h.alias == host.alias) {
+                skipped += 1;
+            } else {
+                let alias = host.alias.clone();
+                self.config.hosts.push(host);
+                api_tasks.push(self.send_host_change(PendingApiOp::Create { alias }));
+                added += 1;
+            }
+        }
+        if added > 0 {
+            let _ = config::save_config(&self.config);
+            self.run_background_audit();
+        }
+        (added, skipped, Task::batch(api_tasks))
+    }
+
+    /// Distinct group names across saved hosts, sorted.
+    fn group_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .config
+            .hosts
+            .iter()
+            .filter_map(|h| h.group.clone())
+            .filter(|g| !g.is_empty())
+            .collect();
+        names.sort_by_key(|g| g.to_lowercase());
+        names.dedup();
+        names
+    }
+
+    /// Index of the saved host a tab was opened from.
+    fn saved_host_index(&self, host: &Host) -> Option<usize> {
+        if host.adhoc {
+            return None;
+        }
+        self.config.hosts.iter().position(|h| {
+            h.alias == host.alias
+                && h.hostname == host.hostname
+                && h.port == host.port
+                && h.username == host.username
+        })
+    }
+
+    /// Whether the sidebar gauges may poll `tab`'s host; the saved entry wins
+    /// so turning it off applies to tabs that are already open.
+    fn collects_stats(&self, tab: &TerminalTab) -> bool {
+        self.saved_host_index(&tab.host)
+            .map_or(tab.host.collect_stats, |i| self.config.hosts[i].collect_stats)
+    }
+
+    /// Opens a terminal tab for `host` and starts its relay.
+    fn open_terminal(&mut self, host: Host) {
+        // Resolve relay launcher path (single-binary internal relay mode)
+        match bridge::find_relay_binary() {
+            Ok(relay_path) => {
+                self.tab_counter += 1;
+                let tab_id = self.tab_counter;
+
+                let (connected, relay_error, output, structure) =
+                    match self.spawn_runtime(&relay_path, &host) {
+                        Ok(runtime) => {
+                            self.terminal_runtime.insert(tab_id, runtime);
+                            (
+                                true,
+                                None,
+                                format!(
+                                    "Connected to {}@{}\n",
+                                    host.username,
+                                    ssh::socket_addr(&host.hostname, host.port)
+                                ),
+                                fetch_remote_structure(&host),
+                            )
+                        }
+                        Err(err) => (false, Some(err), String::new(), Vec::new()),
+                    };
+                let tab = TerminalTab {
+                    id: tab_id,
+                    label: host.alias.clone(),
+                    host: host.clone(),
+                    connected,
+                    ssh_process: Some(SshProcessInfo {
+                        relay_path: relay_path.clone(),
+                    }),
+                    relay_error,
+                    output,
+                    structure,
+                    structure_expanded: HashSet::new(),
+                    ftp: FtpState::default(),
+                    font_size: 13.0,
+                    search_active: false,
+                    search_query: String::new(),
+                    search_current: 0,
+                    search_mode: SearchMode::Text,
+                    search_case_sensitive: false,
+                    quick_cmds_visible: false,
+                    input_buffer: String::new(),
+                    input_dirty: false,
+                    command_history: if host.adhoc { VecDeque::new() } else { history::load(&host.alias) },
+                    command_counts: if host.adhoc { history::Counts::new() } else { history::load_counts(&host.alias) },
+                    suggestion_index: None,
+                    history_search: None,
+                    sys_open: false,
+                    sys_state: crate::syspanel::SysState::new(),
+                    auto_reconnect: false,
+                    reconnect_attempts: 0,
+                    reconnect_at: None,
+                    has_activity: false,
+                    remote_info: None,
+                    remote_info_pending: false,
+                    remote_os: None,
+                    remote_history: VecDeque::new(),
+                    connected_at: connected.then(std::time::Instant::now),
+                };
+
+                let spawn_error = tab.relay_error.clone();
+                self.terminal_tabs.push(tab);
+                self.active_tab = Some(self.terminal_tabs.len() - 1);
+                if spawn_error.is_some() {
+                    self.record_host_error(&host, spawn_error);
                 }
             }
+            Err(err) => {
+                // Relay not found - show connection info instead
+                self.tab_counter += 1;
+                let tab = TerminalTab {
+                    id: self.tab_counter,
+                    label: host.alias.clone(),
+                    host: host.clone(),
+                    connected: false,
+                    ssh_process: None,
+                    relay_error: Some(err.to_string()),
+                    output: String::new(),
+                    structure: Vec::new(),
+                    structure_expanded: HashSet::new(),
+                    ftp: FtpState::default(),
+                    font_size: 13.0,
+                    search_active: false,
+                    search_query: String::new(),
+                    search_current: 0,
+                    search_mode: SearchMode::Text,
+                    search_case_sensitive: false,
+                    quick_cmds_visible: false,
+                    input_buffer: String::new(),
+                    input_dirty: false,
+                    command_history: if host.adhoc { VecDeque::new() } else { history::load(&host.alias) },
+                    command_counts: if host.adhoc { history::Counts::new() } else { history::load_counts(&host.alias) },
+                    suggestion_index: None,
+                    history_search: None,
+                    sys_open: false,
+                    sys_state: crate::syspanel::SysState::new(),
+                    auto_reconnect: false,
+                    reconnect_attempts: 0,
+                    reconnect_at: None,
+                    has_activity: false,
+                    remote_info: None,
+                    remote_info_pending: false,
+                    remote_os: None,
+                    remote_history: VecDeque::new(),
+                    connected_at: None,
+                };
+                self.terminal_tabs.push(tab);
+                self.active_tab = Some(self.terminal_tabs.len() - 1);
+                self.record_host_error(&host, Some(err.to_string()));
+            }
         }
-        Task::none()
+    }
+
+    /// Saved hosts by last connection, newest first, for the Recent menu.
+    fn recent_hosts(&self) -> Vec<toolbar::RecentHost> {
+        let mut recent: Vec<toolbar::RecentHost> = self
+            .config
+            .hosts
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, h)| {
+                h.last_connected.map(|at| toolbar::RecentHost { idx, alias: h.alias.clone(), at })
+            })
+            .collect();
+        recent.sort_by_key(|r| std::cmp::Reverse(r.at));
+        recent.truncate(RECENT_HOSTS);
+        recent
+    }
+
+    fn schedule_clipboard_clear(&mut self, content: &str, secs: u64) {
+        let at = std::time::Instant::now() + Duration::from_secs(secs);
+        self.clipboard_clear_at = Some((at, content.to_string()));
+        self.notification = Some((format!("Pasted text looks secret; clipboard clears in {}s", secs), false));
+    }
+
+    /// Text left of the cursor on the active terminal, e.g. `Password: `.
+    fn active_prompt_line(&self) -> String {
+        let Some(rt) = self
+            .active_tab
+            .and_then(|i| self.terminal_tabs.get(i))
+            .and_then(|tab| self.terminal_runtime.get(&tab.id))
+        else {
+            return String::new();
+        };
+        let screen = rt.parser.screen();
+        let (row, col) = screen.cursor_position();
+        screen.contents_between(row, 0, row, col)
+    }
+
+    /// Stores (or clears, with `None`) the last connection error on the saved host.
+    fn record_host_error(&mut self, host: &Host, error: Option<String>) {
+        let Some(idx) = self.saved_host_index(host) else {
+            return;
+        };
+        let saved = &mut self.config.hosts[idx];
+        if saved.last_error.is_none() && error.is_none() {
+            return;
+        }
+        saved.last_error_at = error.as_ref().map(|_| unix_now());
+        saved.last_error = error.map(|e| host.redact(&e));
+        let _ = config::save_config(&self.config);
     }
 
     pub fn view(&self) -> Element<'_, Message> {
@@ -1773,8 +5136,16 @@ impl App {
         let p = theme::palette(self.theme);
         let lc = theme::layout(self.config.layout);
 
-        let toolbar_view = toolbar::view(&texts, self.theme, lc);
-        let tab_bar_view = tab_bar::view(&self.terminal_tabs, self.active_tab, self.theme, lc);
+        let toolbar_view = toolbar::view(
+            &texts,
+            self.audit_alerts,
+            self.broadcast_mode,
+            self.recent_hosts(),
+            self.theme,
+            lc,
+        );
+        let titles: Vec<String> = self.terminal_tabs.iter().map(|t| self.tab_title(t)).collect();
+        let tab_bar_view = tab_bar::view(&self.terminal_tabs, &titles, self.active_tab, self.theme, lc);
         let structure: &[String] = self
             .active_tab
             .and_then(|i| self.terminal_tabs.get(i))
@@ -1782,18 +5153,50 @@ impl App {
             .unwrap_or(&[]);
         let sidebar_view = sidebar::view(
             &texts,
-            &self.config.hosts,
-            &self.search_query,
-            self.selected_host,
-            &self.ping_results,
-            &self.system_info,
+            sidebar::HostListView {
+                hosts: &self.config.hosts,
+                search_query: &self.search_query,
+                selected_host: self.selected_host,
+                checked_hosts: self.multi_select.then_some(&self.checked_hosts),
+                sort: self.config.host_sort,
+                collapsed_groups: &self.collapsed_groups,
+            },
+            sidebar::PingView {
+                results: &self.ping_results,
+                history: &self.latency_history,
+                monitored: self.latency_monitor,
+                scans: &self.port_scans,
+            },
+            sidebar::MonitorView {
+                local: &self.system_info,
+                remote: self
+                    .active_tab
+                    .and_then(|i| self.terminal_tabs.get(i))
+                    .filter(|tab| tab.connected && self.collects_stats(tab))
+                    .and_then(|tab| Some((tab.label.as_str(), tab.remote_info.as_ref()?, &tab.remote_history))),
+            },
             structure,
             self.theme,
             lc,
         );
+        let sync = if self.config.api_key.is_none() {
+            status_bar::SyncStatus::Local
+        } else if self.syncing {
+            status_bar::SyncStatus::Syncing
+        } else {
+            match &self.last_sync {
+                None => status_bar::SyncStatus::Idle,
+                Some((at, Ok(count))) => status_bar::SyncStatus::Synced(*count, at.elapsed().as_secs()),
+                Some((_, Err(_))) if self.offline => {
+                    status_bar::SyncStatus::Offline(self.config.pending_api_ops.len())
+                }
+                Some((_, Err(_))) => status_bar::SyncStatus::Idle,
+            }
+        };
         let status_view = status_bar::view(
             &texts,
-            self.config.api_key.is_some(),
+            sync,
+            self.notification.as_ref(),
             self.config.language,
             self.theme,
             lc,
@@ -1803,14 +5206,13 @@ impl App {
 
         let pg = lc.panel_gap;
         let cp = lc.container_padding;
-        let content = column![
-            toolbar_view,
-            tab_bar_view,
-            row![sidebar_view, main_area].spacing(pg).height(Length::Fill),
-            status_view,
-        ]
-        .spacing(pg)
-        .padding(cp);
+        let mut content = column![toolbar_view, tab_bar_view].spacing(pg).padding(cp);
+        if self.broadcast_mode {
+            content = content.push(self.view_broadcast_banner(lc));
+        }
+        let content = content
+            .push(row![sidebar_view, main_area].spacing(pg).height(Length::Fill))
+            .push(status_view);
 
         let base: Element<'_, Message> = container(content)
             .width(Length::Fill)
@@ -1829,6 +5231,46 @@ impl App {
         }
     }
 
+    /// Warning strip shown while keystrokes are mirrored to every tab.
+    fn view_broadcast_banner(&self, lc: theme::LayoutConfig) -> Element<'_, Message> {
+        let p = theme::palette(self.theme);
+        let cr = lc.corner_radius;
+        let targets = self.terminal_tabs.iter().filter(|t| t.connected).count();
+        let label = format!(
+            "BROADCAST  ·  input is sent to all {} connected tab{}",
+            targets,
+            if targets == 1 { "" } else { "s" }
+        );
+        let stop = button(text("Stop").size(11).color(iced::Color::WHITE))
+            .on_press(Message::TerminalToggleBroadcast)
+            .padding([2, 10])
+            .style(move |_: &iced::Theme, status: button::Status| button::Style {
+                background: Some(iced::Background::Color(match status {
+                    button::Status::Hovered | button::Status::Pressed => iced::Color::from_rgba(0.0, 0.0, 0.0, 0.25),
+                    _ => iced::Color::TRANSPARENT,
+                })),
+                text_color: iced::Color::WHITE,
+                border: iced::Border { color: iced::Color::WHITE, width: 1.0, radius: cr.into() },
+                ..Default::default()
+            });
+        container(
+            row![
+                text(label).size(12).color(iced::Color::WHITE),
+                iced::widget::horizontal_space(),
+                stop,
+            ]
+            .align_y(Alignment::Center),
+        )
+        .width(Length::Fill)
+        .padding([4, 10])
+        .style(move |_: &iced::Theme| container::Style {
+            background: Some(iced::Background::Color(p.danger)),
+            border: iced::Border { radius: cr.into(), ..Default::default() },
+            ..Default::default()
+        })
+        .into()
+    }
+
     fn view_main_area(&self, texts: &Texts, lc: theme::LayoutConfig) -> Element<'_, Message> {
         let p = theme::palette(self.theme);
         let cr = lc.corner_radius;
@@ -1846,7 +5288,16 @@ impl App {
                     );
                 }
 
-                let status_text = if tab.connected { "connected" } else { "disconnected" };
+                let status_text = if tab.connected {
+                    "connected".to_string()
+                } else if tab.reconnect_at.is_some() {
+                    format!(
+                        "reconnecting (attempt {}/{})",
+                        tab.reconnect_attempts, self.config.max_reconnect_attempts
+                    )
+                } else {
+                    "disconnected".to_string()
+                };
                 let status_color = if tab.connected { p.success } else { p.danger };
 
                 // Build top_bar with optional layout-toggle button (top-right)
@@ -1857,8 +5308,9 @@ impl App {
                 top_bar_row = top_bar_row
                     .push(
                         text(format!(
-                            "{}@{}:{}",
-                            tab.host.username, tab.host.hostname, tab.host.port
+                            "{}@{}",
+                            tab.host.username,
+                            ssh::socket_addr(&tab.host.hostname, tab.host.port)
                         ))
                         .size(11)
                         .color(p.text_muted),
@@ -1905,7 +5357,16 @@ impl App {
                     .push(terminal_action_button("A+", Message::TerminalFontSizeInc, p))
                     .push(terminal_action_button("^C", Message::TerminalSendCtrlC, p))
                     .push(terminal_action_button("Copy", Message::TerminalCopyOutput, p))
+                    .push(terminal_action_button("Save Log", Message::TerminalSaveLog, p))
+                    .push(terminal_action_button(
+                        if tab.auto_reconnect { "Auto ●" } else { "Auto" },
+                        Message::TerminalAutoReconnectToggle, p,
+                    ))
                     .push(terminal_action_button("Clear", Message::TerminalClear, p))
+                    .push(terminal_action_button(
+                        if self.split_tab.is_some() { "Split ●" } else { "Split" },
+                        Message::TerminalSplitToggle, p,
+                    ))
                     .push(terminal_action_button("⚙ System", Message::SysPanelOpen(tab.id), p));
                 let top_bar = top_bar_row;
 
@@ -1913,7 +5374,10 @@ impl App {
                 let raw_spans = self
                     .terminal_runtime
                     .get(&tab.id)
-                    .map(|rt| build_terminal_spans(rt, p.text_primary))
+                    .map(|rt| {
+                        let selection = self.terminal_selection.map(|s| (s, iced::Color { a: 0.35, ..p.accent }));
+                        build_terminal_spans(rt, p.text_primary, self.config.linkify_urls, selection)
+                    })
                     .unwrap_or_else(|| {
                         let fallback = if tab.output.is_empty() {
                             " ".to_string()
@@ -1923,17 +5387,21 @@ impl App {
                         vec![iced::widget::text::Span::new(fallback)]
                     });
 
-                let (terminal_spans, match_count) = if tab.search_active
-                    && !tab.search_query.is_empty()
-                {
-                    apply_search_highlight(
+                let matcher = tab.search_matcher().filter(|_| tab.search_active);
+                let (terminal_spans, match_count) = match &matcher {
+                    Some(Ok(matcher)) => apply_search_highlight(
                         raw_spans,
-                        &tab.search_query,
+                        matcher,
+                        tab.search_current,
                         iced::Color::from_rgb(1.0, 0.85, 0.0),
+                        iced::Color::from_rgb(1.0, 0.5, 0.0),
                         p.text_primary,
-                    )
-                } else {
-                    (raw_spans, 0)
+                    ),
+                    _ => (raw_spans, 0),
+                };
+                let search_error = match matcher {
+                    Some(Err(e)) => Some(e),
+                    _ => None,
                 };
 
                 let in_alternate_screen = self
@@ -1948,7 +5416,12 @@ impl App {
                 } else {
                     tab.font_size
                 };
-                let terminal_view = container(
+                // Measure the area the output gets so the remote PTY can follow it
+                let grid = &self.terminal_grid;
+                let measure = measure::measure(move |size| {
+                    grid.set(Some(terminal_grid_size(size, font_sz)));
+                });
+                let terminal_output = container(
                     scrollable(
                         rich_text(terminal_spans)
                             .size(font_sz)
@@ -2065,16 +5538,20 @@ impl App {
                 if tab.search_active && !in_alternate_screen {
                     let sq = tab.search_query.clone();
                     let mc = match_count;
-                    let match_text = if sq.is_empty() {
+                    let match_text = if let Some(err) = &search_error {
+                        err.clone()
+                    } else if sq.is_empty() {
                         "type to search".to_string()
+                    } else if mc == 0 {
+                        "no matches".to_string()
                     } else {
-                        format!("{} match{}", mc, if mc == 1 { "" } else { "es" })
+                        format!("{} / {}", tab.search_current % mc + 1, mc)
                     };
                     let search_bar = container(
                         row![
-                            text_input("Search terminal... (Ctrl+F, Esc)", &sq)
+                            text_input("Search terminal... (Enter next, Shift+Enter previous, Esc)", &sq)
                                 .on_input(Message::TerminalSearchChanged)
-                                .on_submit(Message::TerminalSearchClose)
+                                .on_submit(Message::TerminalSearchSubmit)
                                 .padding([3, 6])
                                 .size(11)
                                 .width(Length::Fill)
@@ -2095,7 +5572,19 @@ impl App {
                                         selection: p.accent,
                                     }
                                 }),
-                            text(match_text).size(10).color(p.text_muted),
+                            text(match_text)
+                                .size(10)
+                                .color(if search_error.is_some() { p.danger } else { p.text_muted }),
+                            terminal_action_button(
+                                if tab.search_case_sensitive { "Aa ●" } else { "Aa" },
+                                Message::TerminalSearchToggleCase, p,
+                            ),
+                            terminal_action_button(
+                                if tab.search_mode == SearchMode::Regex { ".* ●" } else { ".*" },
+                                Message::TerminalSearchToggleRegex, p,
+                            ),
+                            terminal_action_button("▲", Message::TerminalSearchStep(-1), p),
+                            terminal_action_button("▼", Message::TerminalSearchStep(1), p),
                             terminal_action_button("✕", Message::TerminalSearchClose, p),
                         ]
                         .spacing(6)
@@ -2111,11 +5600,60 @@ impl App {
                     panel = panel.push(search_bar);
                 }
 
+                let terminal_view = stack![
+                    iced::widget::mouse_area(terminal_output)
+                        .on_middle_press(Message::TerminalMiddlePaste)
+                        .on_move(Message::TerminalPointerMoved)
+                        .on_press(Message::TerminalSelectStart)
+                        .on_release(Message::TerminalSelectEnd)
+                        .on_exit(Message::TerminalSelectEnd),
+                    measure,
+                ];
                 panel = panel.push(terminal_view);
 
                 if let Some(err) = &tab.relay_error {
                     panel = panel.push(text(format!("⚠ {}", err)).size(10).color(p.danger));
                 }
+                if !tab.connected {
+                    panel = panel.push(
+                        row![
+                            text("Session disconnected").size(11).color(p.text_muted),
+                            terminal_action_button("Reconnect", Message::ReconnectTab(active), p),
+                        ]
+                        .spacing(8)
+                        .align_y(Alignment::Center),
+                    );
+                }
+
+                // Reverse history search (Ctrl+R)
+                if let Some(search) = &tab.history_search {
+                    let found = tab.history_search_match();
+                    let (found_text, found_color) = match found {
+                        Some(cmd) => (cmd.to_string(), p.text_primary),
+                        None if search.query.is_empty() => ("type to search history".to_string(), p.text_muted),
+                        None => ("no match".to_string(), p.danger),
+                    };
+                    panel = panel.push(
+                        container(
+                            row![
+                                text("(reverse-i-search)").size(10).color(p.accent),
+                                text(format!("`{}'", search.query)).size(11).color(p.text_secondary).font(iced::Font::MONOSPACE),
+                                text(found_text).size(11).color(found_color).font(iced::Font::MONOSPACE),
+                                iced::widget::horizontal_space(),
+                                text("Ctrl+R older · Enter insert · Esc cancel").size(9).color(p.text_muted),
+                            ]
+                            .spacing(8)
+                            .align_y(Alignment::Center),
+                        )
+                        .width(Length::Fill)
+                        .padding([3, 6])
+                        .style(move |_: &iced::Theme| container::Style {
+                            background: Some(iced::Background::Color(p.bg_tertiary)),
+                            border: iced::Border { color: p.accent, width: 1.0, radius: cr.into() },
+                            ..Default::default()
+                        }),
+                    );
+                }
 
                 // Autocomplete panel — shown BELOW the terminal while user is typing
                 if !tab.input_buffer.is_empty() && !in_alternate_screen && self.config.suggestions_enabled {
@@ -2138,6 +5676,7 @@ impl App {
                                     text("  Click or Ctrl+Space to select").size(9).color(p.text_muted),
                                     text("  ↑↓ navigate").size(9).color(p.text_muted),
                                     text("  Tab accept").size(9).color(p.text_muted),
+                                    text("  Shift+Tab shell completion").size(9).color(p.text_muted),
                                     text("  Esc close").size(9).color(p.text_muted),
                                 ]
                                 .spacing(4)
@@ -2213,6 +5752,7 @@ impl App {
                 // Terminal container block
                 let ftp_theme = self.theme;
                 let borders = self.config.show_borders;
+                let split = self.split_tab.filter(|&s| s != active && s < self.terminal_tabs.len());
                 let terminal_block = container(panel)
                     .padding([8, 10])
                     .width(Length::Fill)
@@ -2220,15 +5760,30 @@ impl App {
                     .style(move |_: &iced::Theme| container::Style {
                         background: Some(iced::Background::Color(p.bg_secondary)),
                         border: iced::Border {
-                            color: p.border,
-                            width: if borders { 1.0 } else { 0.0 },
+                            // Mark which pane receives keystrokes.
+                            color: if split.is_some() { p.accent } else { p.border },
+                            width: if borders || split.is_some() { 1.0 } else { 0.0 },
                             radius: cr.into(),
                         },
                         ..Default::default()
                     });
 
-                // Attach FTP panel — position depends on tab.ftp.layout
                 let pg = lc.panel_gap;
+                let terminal_block: Element<'_, Message> = match split {
+                    Some(split) => {
+                        let other = self.view_split_pane(split, lc);
+                        match self.focused_pane {
+                            SplitPane::Left => row![terminal_block, other],
+                            SplitPane::Right => row![other, terminal_block],
+                        }
+                        .spacing(pg)
+                        .height(Length::Fill)
+                        .into()
+                    }
+                    None => terminal_block.into(),
+                };
+
+                // Attach FTP panel — position depends on tab.ftp.layout
                 let main_content: Element<'_, Message> = if tab.ftp.visible {
                     let ftp_view = ftp_panel::view(&tab.ftp, ftp_theme, lc);
                     match tab.ftp.layout {
@@ -2242,7 +5797,7 @@ impl App {
                             .into(),
                     }
                 } else {
-                    terminal_block.into()
+                    terminal_block
                 };
                 return main_content;
             }
@@ -2251,6 +5806,70 @@ impl App {
         self.view_welcome(texts)
     }
 
+    /// Read-only view of the split tab; clicking it moves focus there.
+    fn view_split_pane(&self, idx: usize, lc: theme::LayoutConfig) -> Element<'_, Message> {
+        let p = theme::palette(self.theme);
+        let cr = lc.corner_radius;
+        let tab = &self.terminal_tabs[idx];
+
+        let spans = self
+            .terminal_runtime
+            .get(&tab.id)
+            .map(|rt| build_terminal_spans(rt, p.text_primary, self.config.linkify_urls, None))
+            .unwrap_or_else(|| vec![iced::widget::text::Span::new(tab.output.clone())]);
+        let font_sz = if (tab.font_size - 13.0).abs() < 0.1 {
+            self.config.terminal_font_size
+        } else {
+            tab.font_size
+        };
+        let header = row![
+            text(format!("{}@{}", tab.host.username, ssh::socket_addr(&tab.host.hostname, tab.host.port)))
+                .size(11)
+                .color(p.text_muted),
+            text(if tab.connected { "  ·  connected" } else { "  ·  disconnected" })
+                .size(11)
+                .color(if tab.connected { p.success } else { p.danger }),
+            iced::widget::horizontal_space(),
+            text("click to focus").size(10).color(p.text_muted),
+            terminal_action_button("✕", Message::TerminalSplitToggle, p),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center);
+        let output = scrollable(
+            rich_text(spans)
+                .size(font_sz)
+                .font(Font::MONOSPACE)
+                .wrapping(iced::widget::text::Wrapping::None)
+                .width(Length::Fill),
+        )
+        .id(self.split_scroll_id.clone())
+        .anchor_bottom()
+        .style(hidden_scrollbar_style)
+        .height(Length::Fill);
+        let grid = &self.split_grid;
+        let measure = measure::measure(move |size| {
+            grid.set(Some(terminal_grid_size(size, font_sz)));
+        });
+
+        let borders = self.config.show_borders;
+        let pane = container(column![header, stack![output, measure]].spacing(4))
+            .padding([8, 10])
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(move |_: &iced::Theme| container::Style {
+                background: Some(iced::Background::Color(p.bg_secondary)),
+                border: iced::Border {
+                    color: p.border,
+                    width: if borders { 1.0 } else { 0.0 },
+                    radius: cr.into(),
+                },
+                ..Default::default()
+            });
+        iced::widget::mouse_area(pane)
+            .on_press(Message::TerminalSplitFocus)
+            .into()
+    }
+
     fn view_welcome(&self, texts: &Texts) -> Element<'_, Message> {
         let p = theme::palette(self.theme);
         container(
@@ -2290,15 +5909,75 @@ impl App {
             iced::time::every(Duration::from_secs(2)).map(|_| Message::SystemInfoTick),
             iced::time::every(Duration::from_millis(50)).map(|_| Message::TerminalPoll),
             event::listen_with(runtime_event_to_message),
+            if matches!(self.dialog, Some(dialogs::DialogState::Tunnels { .. })) {
+                iced::time::every(Duration::from_secs(1)).map(|_| Message::TunnelsTick)
+            } else {
+                Subscription::none()
+            },
+            if self.ftp_transfers.is_empty() {
+                Subscription::none()
+            } else {
+                iced::time::every(Duration::from_millis(200)).map(|_| Message::FtpTransferPoll)
+            },
+            if self.theme == AppTheme::Auto {
+                // Pick up OS light/dark switches while running.
+                iced::time::every(Duration::from_secs(10)).map(|_| Message::SystemThemeCheck)
+            } else {
+                Subscription::none()
+            },
+            if self.clipboard_clear_at.is_some() {
+                iced::time::every(Duration::from_secs(1)).map(|_| Message::ClipboardClearTick)
+            } else {
+                Subscription::none()
+            },
+            if self.latency_monitor.is_some() {
+                iced::time::every(Duration::from_secs(2)).map(|_| Message::LatencyTick)
+            } else {
+                Subscription::none()
+            },
+            if self.terminal_tabs.iter().any(|t| {
+                t.sys_open
+                    && t.sys_state.proc_auto
+                    && t.sys_state.tab == crate::syspanel::SysTab::Processes
+            }) {
+                iced::time::every(Duration::from_secs(3)).map(|_| Message::SysPanelTick)
+            } else {
+                Subscription::none()
+            },
         ])
     }
 }
 
+/// Width and height of a monospace cell, in multiples of the font size.
+const CELL_WIDTH_EM: f32 = 0.6;
+const CELL_HEIGHT_EM: f32 = 1.3;
+
+/// Rows and columns of monospace text at `font_size` that fit in `size`,
+/// after the output container's padding.
+fn terminal_grid_size(size: iced::Size, font_size: f32) -> (u16, u16) {
+    let cell_width = font_size * CELL_WIDTH_EM;
+    let cell_height = font_size * CELL_HEIGHT_EM;
+    let cols = ((size.width - 20.0) / cell_width).floor().clamp(20.0, 500.0) as u16;
+    let rows = ((size.height - 16.0) / cell_height).floor().clamp(5.0, 200.0) as u16;
+    (rows, cols)
+}
+
+/// (row, column) of the cell under `point`, measured from the output
+/// container's top left like `terminal_grid_size`.
+fn terminal_cell_at(point: iced::Point, font_size: f32) -> (u16, u16) {
+    let col = ((point.x - 10.0) / (font_size * CELL_WIDTH_EM)).floor().clamp(0.0, 499.0) as u16;
+    let row = ((point.y - 8.0) / (font_size * CELL_HEIGHT_EM)).floor().clamp(0.0, 199.0) as u16;
+    (row, col)
+}
+
 fn runtime_event_to_message(
     event: iced::Event,
     status: iced::event::Status,
     _window: iced::window::Id,
 ) -> Option<Message> {
+    if let iced::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) = event {
+        return Some(Message::ModifiersChanged(modifiers));
+    }
     if matches!(status, iced::event::Status::Captured) {
         return None;
     }
@@ -2381,6 +6060,27 @@ fn map_key_to_bytes(key: Key, modifiers: Modifiers) -> Option<Vec<u8>> {
     }
 }
 
+/// Cells dragged over in a terminal's output, as (row, column).
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TermSelection {
+    anchor: (u16, u16),
+    head: (u16, u16),
+    /// The button is still down.
+    dragging: bool,
+}
+
+impl TermSelection {
+    /// First and last selected cell, in reading order.
+    fn range(self) -> ((u16, u16), (u16, u16)) {
+        if self.anchor <= self.head { (self.anchor, self.head) } else { (self.head, self.anchor) }
+    }
+
+    fn contains(self, row: u16, col: u16) -> bool {
+        let (start, end) = self.range();
+        start <= (row, col) && (row, col) <= end
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 struct TermSpanStyle {
     fg: iced::Color,
@@ -2388,13 +6088,35 @@ struct TermSpanStyle {
     bold: bool,
     italic: bool,
     underline: bool,
+    /// Index into the link targets of this render.
+    link: Option<usize>,
+}
+
+/// Desktop notification for a bell on a background tab. Sent from a thread;
+/// some notification daemons take a moment to answer.
+fn notify_bell(label: &str) {
+    let body = format!("Bell in {}", label);
+    thread::spawn(move || {
+        let _ = notify_rust::Notification::new()
+            .summary("TermiSSH")
+            .body(&body)
+            .show();
+    });
 }
 
-fn build_terminal_spans(runtime: &TerminalRuntime, default_color: iced::Color) -> Vec<iced::widget::text::Span<'static, Message>> {
+/// Spans of the visible screen. Cells showing an OSC 8 link (and, with
+/// `plain_urls`, a bare URL) become clickable spans sending `OpenUrl`.
+fn build_terminal_spans(
+    runtime: &TerminalRuntime,
+    default_color: iced::Color,
+    plain_urls: bool,
+    selection: Option<(TermSelection, iced::Color)>,
+) -> Vec<iced::widget::text::Span<'static, Message>> {
     let screen = runtime.parser.screen();
     let (rows, cols) = screen.size();
 
     let mut spans: Vec<iced::widget::text::Span<'static, Message>> = Vec::new();
+    let mut urls: Vec<String> = Vec::new();
     let mut current_text = String::new();
     let mut current_style = TermSpanStyle {
         fg: default_color,
@@ -2402,9 +6124,13 @@ fn build_terminal_spans(runtime: &TerminalRuntime, default_color: iced::Color) -
         bold: false,
         italic: false,
         underline: false,
+        link: None,
     };
 
     for row in 0..rows {
+        // Row text with each cell's byte offset, to map found links back to cells
+        let mut cells = Vec::with_capacity(cols as usize);
+        let mut line = String::new();
         for col in 0..cols {
             let Some(cell) = screen.cell(row, col) else {
                 continue;
@@ -2412,15 +6138,42 @@ fn build_terminal_spans(runtime: &TerminalRuntime, default_color: iced::Color) -
             if cell.is_wide_continuation() {
                 continue;
             }
-
             let content = {
                 let raw = cell.contents();
                 if raw.is_empty() { " ".to_string() } else { raw }
             };
-
-            let bg = match cell.bgcolor() {
-                vt100::Color::Default => None,
-                c => Some(vt_color_to_iced(c, default_color)),
+            cells.push((line.len(), col, cell, content.clone()));
+            line.push_str(&content);
+        }
+        // OSC 8 cells as byte ranges of `line`
+        let osc_links = runtime
+            .links
+            .links_on_row(screen, row)
+            .into_iter()
+            .filter_map(|(start, end, uri)| {
+                let from = cells.iter().find(|c| c.1 >= start)?.0;
+                let to = cells.iter().find(|c| c.1 >= end).map_or(line.len(), |c| c.0);
+                Some((from, to, uri.to_string()))
+            })
+            .collect();
+        let row_links: Vec<(usize, usize, usize)> = hyperlink::find_links(&line, osc_links, plain_urls)
+            .into_iter()
+            .map(|(start, end, url)| {
+                urls.push(url);
+                (start, end, urls.len() - 1)
+            })
+            .collect();
+
+        for (offset, col, cell, content) in cells {
+            let link = row_links
+                .iter()
+                .find(|&&(start, end, _)| offset >= start && offset < end)
+                .map(|&(_, _, i)| i);
+
+            let bg = match (selection, cell.bgcolor()) {
+                (Some((sel, color)), _) if sel.contains(row, col) => Some(color),
+                (_, vt100::Color::Default) => None,
+                (_, c) => Some(vt_color_to_iced(c, default_color)),
             };
             let style = TermSpanStyle {
                 fg: vt_color_to_iced(cell.fgcolor(), default_color),
@@ -2428,10 +6181,11 @@ fn build_terminal_spans(runtime: &TerminalRuntime, default_color: iced::Color) -
                 bold: cell.bold(),
                 italic: cell.italic(),
                 underline: cell.underline(),
+                link,
             };
 
             if style != current_style && !current_text.is_empty() {
-                spans.push(span_from_style(&current_text, current_style));
+                spans.push(span_from_style(&current_text, current_style, &urls));
                 current_text.clear();
             }
 
@@ -2445,7 +6199,7 @@ fn build_terminal_spans(runtime: &TerminalRuntime, default_color: iced::Color) -
     }
 
     if !current_text.is_empty() {
-        spans.push(span_from_style(&current_text, current_style));
+        spans.push(span_from_style(&current_text, current_style, &urls));
     }
 
     if spans.is_empty() {
@@ -2455,7 +6209,7 @@ fn build_terminal_spans(runtime: &TerminalRuntime, default_color: iced::Color) -
     spans
 }
 
-fn span_from_style(text_value: &str, style: TermSpanStyle) -> iced::widget::text::Span<'static, Message> {
+fn span_from_style(text_value: &str, style: TermSpanStyle, urls: &[String]) -> iced::widget::text::Span<'static, Message> {
     let mut font = Font::MONOSPACE;
     if style.bold {
         font.weight = iced::font::Weight::Bold;
@@ -2472,6 +6226,9 @@ fn span_from_style(text_value: &str, style: TermSpanStyle) -> iced::widget::text
     if style.underline {
         s = s.underline(true);
     }
+    if let Some(url) = style.link.and_then(|i| urls.get(i)) {
+        s = s.underline(true).link(Message::OpenUrl(url.clone()));
+    }
     s
 }
 
@@ -2540,21 +6297,64 @@ const QUICK_CMDS: &[(&str, &str)] = &[
 // ─── Suggestion helpers ────────────────────────────────────────────────────
 
 fn compute_suggestions(tab: &TerminalTab, alias_triggers: &[String]) -> Vec<String> {
-    if tab.input_buffer.is_empty() {
+    if tab.input_dirty {
         return vec![];
     }
-    let buf_lower = tab.input_buffer.to_lowercase();
-    let mut suggestions: Vec<String> = tab
-        .command_history
-        .iter()
-        .rev()
-        .filter(|cmd| {
-            let cl = cmd.to_lowercase();
-            cl.starts_with(&buf_lower) && cl != buf_lower
-        })
-        .take(4)
-        .cloned()
-        .collect();
+    suggestions_for(&tab.input_buffer, alias_triggers, &tab.command_history, &tab.command_counts)
+}
+
+/// Follows what has been typed on the shell's current line. Keys that edit
+/// the line in ways the buffer can't mirror clear it and set `dirty`;
+/// without that, a stale buffer could match an alias trigger and run its
+/// script unexpectedly.
+fn track_input(buffer: &mut String, dirty: &mut bool, bytes: &[u8]) {
+    match bytes {
+        [] => {}
+        [127] => {
+            buffer.pop();
+        }
+        // Ctrl+C abandons the line
+        [3] => {
+            buffer.clear();
+            *dirty = false;
+        }
+        // Ctrl+U kills what was typed
+        [21] => buffer.clear(),
+        // Printable text, one key or many (IME, paste)
+        _ if bytes.iter().all(|&b| b >= 32 && b != 127) => match std::str::from_utf8(bytes) {
+            Ok(s) => buffer.push_str(s),
+            Err(_) => {
+                buffer.clear();
+                *dirty = true;
+            }
+        },
+        // Escape sequences (arrows, Home/End, history), Tab (remote completion),
+        // Ctrl+A/E/W/K/Y, pasted newlines, … move the cursor or rewrite the line.
+        _ => {
+            buffer.clear();
+            *dirty = true;
+        }
+    }
+}
+
+/// The line to match against alias triggers at Enter; `None` once the
+/// buffer has lost track of it.
+fn typed_line(buffer: &str, dirty: bool) -> Option<&str> {
+    (!dirty).then(|| buffer.trim())
+}
+
+/// Alias triggers first, then the most run past commands, then built-ins.
+fn suggestions_for(
+    input: &str,
+    alias_triggers: &[String],
+    command_history: &VecDeque<String>,
+    command_counts: &history::Counts,
+) -> Vec<String> {
+    if input.is_empty() {
+        return vec![];
+    }
+    let buf_lower = input.to_lowercase();
+    let mut suggestions: Vec<String> = Vec::new();
     // Custom alias triggers — shown first so users can discover them
     for trigger in alias_triggers {
         if suggestions.len() >= 8 {
@@ -2562,18 +6362,24 @@ fn compute_suggestions(tab: &TerminalTab, alias_triggers: &[String]) -> Vec<Stri
         }
         let tl = trigger.to_lowercase();
         if tl.starts_with(&buf_lower)
-            && trigger.as_str() != tab.input_buffer.as_str()
+            && trigger.as_str() != input
             && !suggestions.iter().any(|s| s == trigger)
         {
             suggestions.push(trigger.clone());
         }
     }
+    let past = history::ranked(command_history, command_counts, input);
+    for cmd in past.into_iter().take(4) {
+        if !suggestions.iter().any(|s| s == cmd) {
+            suggestions.push(cmd.to_string());
+        }
+    }
     for &builtin in BUILT_IN_SUGGESTIONS {
         if suggestions.len() >= 8 {
             break;
         }
         if builtin.to_lowercase().starts_with(&buf_lower)
-            && builtin != tab.input_buffer.as_str()
+            && builtin != input
             && !suggestions.iter().any(|s| s == builtin)
         {
             suggestions.push(builtin.to_string());
@@ -2600,54 +6406,90 @@ const BUILT_IN_SUGGESTIONS: &[&str] = &[
 ];
 
 // ─── Search highlight ──────────────────────────────────────────────────────
+/// Byte ranges of `query` in `text`, ignoring case. Stops at a match whose
+/// lowercase form doesn't line up with the original's char boundaries.
+fn find_matches(text: &str, query: &str) -> Vec<(usize, usize)> {
+    let ql = query.to_lowercase();
+    let tl = text.to_lowercase();
+    let mut found = Vec::new();
+    let mut pos = 0;
+    while let Some(rel) = tl.get(pos..).and_then(|rest| rest.find(ql.as_str())) {
+        let abs = pos + rel;
+        let end = abs + ql.len();
+        // Safety: only slice on valid char boundaries
+        if end > text.len() || !text.is_char_boundary(abs) || !text.is_char_boundary(end) {
+            break;
+        }
+        found.push((abs, end));
+        pos = end;
+    }
+    found
+}
+
+/// Output line of every search match, in the order they are highlighted.
+fn search_match_lines(spans: &[iced::widget::text::Span<'static, Message>], matcher: &SearchMatcher) -> Vec<usize> {
+    let mut lines = Vec::new();
+    let mut line = 0;
+    for span in spans {
+        let text = span.text.as_ref();
+        let mut counted = 0;
+        for (start, _) in matcher.find(text) {
+            line += text[counted..start].matches('\n').count();
+            counted = start;
+            lines.push(line);
+        }
+        line += text[counted..].matches('\n').count();
+    }
+    lines
+}
+
+/// Colors every match; the `current`-th one (wrapping) stands out.
 fn apply_search_highlight(
     spans: Vec<iced::widget::text::Span<'static, Message>>,
-    query: &str,
+    matcher: &SearchMatcher,
+    current: usize,
     highlight_color: iced::Color,
+    current_color: iced::Color,
     default_color: iced::Color,
 ) -> (Vec<iced::widget::text::Span<'static, Message>>, usize) {
-    if query.is_empty() {
-        return (spans, 0);
-    }
-    let ql = query.to_lowercase();
+    let total: usize = spans.iter().map(|s| matcher.find(s.text.as_ref()).len()).sum();
+    let current = if total == 0 { 0 } else { current % total };
     let mut result = Vec::new();
     let mut count = 0;
 
     for span in spans {
         let text = span.text.as_ref().to_string();
-        let tl = text.to_lowercase();
-        let base_color = span.color.unwrap_or(default_color);
-
-        if !tl.contains(ql.as_str()) {
+        let matches = matcher.find(&text);
+        if matches.is_empty() {
             result.push(span);
             continue;
         }
+        let base_color = span.color.unwrap_or(default_color);
 
         let mut pos = 0;
-        while pos < text.len() {
-            match tl[pos..].find(ql.as_str()) {
-                Some(rel) => {
-                    let abs = pos + rel;
-                    let end = abs + ql.len();
-                    // Safety: only slice on valid char boundaries
-                    if !text.is_char_boundary(abs) || !text.is_char_boundary(end) || end > text.len() {
-                        result.push(iced::widget::text::Span::new(text[pos..].to_string()).color(base_color));
-                        break;
-                    }
-                    if abs > pos {
-                        result.push(iced::widget::text::Span::new(text[pos..abs].to_string()).color(base_color));
-                    }
-                    result.push(iced::widget::text::Span::new(text[abs..end].to_string()).color(highlight_color));
-                    count += 1;
-                    pos = end;
-                }
-                None => {
-                    if pos < text.len() {
-                        result.push(iced::widget::text::Span::new(text[pos..].to_string()).color(base_color));
-                    }
-                    break;
-                }
+        for (start, end) in matches {
+            if start > pos {
+                result.push(
+                    iced::widget::text::Span::new(text[pos..start].to_string())
+                        .color(base_color)
+                        .link_maybe(span.link.clone()),
+                );
             }
+            let color = if count == current { current_color } else { highlight_color };
+            result.push(
+                iced::widget::text::Span::new(text[start..end].to_string())
+                    .color(color)
+                    .link_maybe(span.link.clone()),
+            );
+            count += 1;
+            pos = end;
+        }
+        if pos < text.len() {
+            result.push(
+                iced::widget::text::Span::new(text[pos..].to_string())
+                    .color(base_color)
+                    .link_maybe(span.link.clone()),
+            );
         }
     }
 
@@ -2714,65 +6556,79 @@ fn hidden_scrollbar_style(theme: &iced::Theme, status: scrollable::Status) -> sc
     style
 }
 
-fn fetch_remote_structure(host: &Host) -> Vec<String> {
-    let mut structure: Vec<String> = Vec::new();
-
-    let tcp = match TcpStream::connect(format!("{}:{}", host.hostname, host.port)) {
-        Ok(tcp) => tcp,
-        Err(err) => return vec![format!("FTP connection failed: {}", err)],
-    };
+/// Indentation per tree level in `TerminalTab::structure`.
+const STRUCTURE_INDENT: &str = "  ";
 
-    let mut sess = match ssh2::Session::new() {
-        Ok(s) => s,
-        Err(err) => return vec![format!("FTP session error: {}", err)],
-    };
-    sess.set_tcp_stream(tcp);
-    if let Err(err) = sess.handshake() {
-        return vec![format!("FTP handshake failed: {}", err)];
+/// Tree depth of a structure line and the entry after its indentation.
+pub fn structure_depth(line: &str) -> (usize, &str) {
+    let mut depth = 0;
+    let mut rest = line;
+    while let Some(r) = rest.strip_prefix(STRUCTURE_INDENT) {
+        depth += 1;
+        rest = r;
     }
+    (depth, rest)
+}
 
-    let mut authenticated = false;
-    if sess.userauth_agent(&host.username).is_ok() {
-        authenticated = true;
-    } else if let Some(ref pwd) = host.password {
-        if sess.userauth_password(&host.username, pwd).is_ok() {
-            authenticated = true;
+/// Path of the `[D]` line at `i` relative to the root, e.g. `src/ui`.
+fn structure_dir_path(structure: &[String], i: usize) -> Option<String> {
+    let (mut depth, entry) = structure_depth(structure.get(i)?);
+    let mut parts = vec![entry.strip_prefix("[D] ")?];
+    for line in structure[..i].iter().rev() {
+        if depth == 0 {
+            break;
+        }
+        let (d, entry) = structure_depth(line);
+        if d < depth {
+            parts.push(entry.strip_prefix("[D] ")?);
+            depth = d;
         }
     }
+    parts.reverse();
+    Some(parts.join("/"))
+}
 
-    if !authenticated {
-        return vec!["FTP auth failed".to_string()];
-    }
-
-    let mut channel = match sess.channel_session() {
-        Ok(ch) => ch,
-        Err(err) => return vec![format!("FTP channel failed: {}", err)],
-    };
+/// Runs a listing command on `host` and returns its output.
+fn run_remote_listing(host: &Host, cmd: &str) -> Result<String, String> {
+    let sess = crate::ssh::open_session(host).map_err(|err| format!("FTP {}", err))?;
+    let mut channel = sess
+        .channel_session()
+        .map_err(|err| format!("FTP channel failed: {}", err))?;
+    channel
+        .exec(cmd)
+        .map_err(|err| format!("FTP structure command failed: {}", err))?;
+    let mut output = String::new();
+    channel
+        .read_to_string(&mut output)
+        .map_err(|_| "FTP structure read failed".to_string())?;
+    Ok(output)
+}
 
-    if let Err(err) = channel.exec("pwd && ls -1p 2>/dev/null | head -n 80") {
-        return vec![format!("FTP structure command failed: {}", err)];
-    }
+/// Turns `ls -1p` output into `[D]` / `[F]` lines at `depth`.
+fn structure_lines<'a>(listing: impl Iterator<Item = &'a str>, depth: usize) -> Vec<String> {
+    let indent = STRUCTURE_INDENT.repeat(depth);
+    listing
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.strip_suffix('/') {
+            Some(dir) => format!("{}[D] {}", indent, dir),
+            None => format!("{}[F] {}", indent, entry),
+        })
+        .collect()
+}
 
-    let mut output = String::new();
-    if channel.read_to_string(&mut output).is_err() {
-        return vec!["FTP structure read failed".to_string()];
-    }
+fn fetch_remote_structure(host: &Host) -> Vec<String> {
+    let output = match run_remote_listing(host, "pwd && ls -1p 2>/dev/null | head -n 80") {
+        Ok(output) => output,
+        Err(err) => return vec![host.redact(&err)],
+    };
 
+    let mut structure: Vec<String> = Vec::new();
     let mut lines = output.lines();
     if let Some(root) = lines.next() {
         structure.push(format!("Root: {}", root.trim()));
     }
-    for line in lines {
-        let entry = line.trim();
-        if entry.is_empty() {
-            continue;
-        }
-        if entry.ends_with('/') {
-            structure.push(format!("[D] {}", entry.trim_end_matches('/')));
-        } else {
-            structure.push(format!("[F] {}", entry));
-        }
-    }
+    structure.extend(structure_lines(lines, 0));
 
     if structure.is_empty() {
         structure.push("No structure data".to_string());
@@ -2781,7 +6637,41 @@ fn fetch_remote_structure(host: &Host) -> Vec<String> {
     structure
 }
 
-fn spawn_reader_thread<R>(mut reader: R, tx: mpsc::Sender<Vec<u8>>)
+/// Lists `dir` (relative to `root`) as structure lines at `depth`.
+fn fetch_remote_children(host: &Host, root: &str, dir: &str, depth: usize) -> Vec<String> {
+    let path = crate::syspanel::shell_quote(&format!("{}/{}", root.trim_end_matches('/'), dir));
+    let cmd = format!("cd {} && ls -1p 2>/dev/null | head -n 80", path);
+    let indent = STRUCTURE_INDENT.repeat(depth);
+    match run_remote_listing(host, &cmd) {
+        Ok(output) => {
+            let lines = structure_lines(output.lines(), depth);
+            if lines.is_empty() {
+                vec![format!("{}(empty)", indent)]
+            } else {
+                lines
+            }
+        }
+        Err(err) => vec![format!("{}{}", indent, host.redact(&err))],
+    }
+}
+
+/// `index` after the entry at `removed` was taken out of its list.
+fn shift_index(index: Option<usize>, removed: usize) -> Option<usize> {
+    match index? {
+        i if i == removed => None,
+        i if i > removed => Some(i - 1),
+        i => Some(i),
+    }
+}
+
+fn shift_index_keys<V>(map: &mut HashMap<usize, V>, removed: usize) {
+    *map = std::mem::take(map)
+        .into_iter()
+        .filter_map(|(i, v)| Some((shift_index(Some(i), removed)?, v)))
+        .collect();
+}
+
+fn spawn_reader_thread<R>(mut reader: R, tx: mpsc::Sender<Vec<u8>>, log: Option<Arc<Mutex<SessionLog>>>)
 where
     R: Read + Send + 'static,
 {
@@ -2791,6 +6681,9 @@ where
             match reader.read(&mut buf) {
                 Ok(0) => break,
                 Ok(n) => {
+                    if let Some(Ok(mut log)) = log.as_ref().map(|l| l.lock()) {
+                        log.write(&buf[..n]);
+                    }
                     if tx.send(buf[..n].to_vec()).is_err() {
                         break;
                     }
@@ -2853,3 +6746,115 @@ fn collect_system_info(sys: &System, disks: &Disks) -> LocalSystemInfo {
         uptime_secs,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `typed_line` after sending each of `keys`.
+    fn line_after(keys: &[&[u8]]) -> Option<String> {
+        let (mut buffer, mut dirty) = (String::new(), false);
+        for key in keys {
+            track_input(&mut buffer, &mut dirty, key);
+        }
+        typed_line(&buffer, dirty).map(str::to_string)
+    }
+
+    #[test]
+    fn clean_typing_is_tracked() {
+        assert_eq!(line_after(&[b"d", b"e", b"x", &[127], b"p", b"loy"]).as_deref(), Some("deploy"));
+        assert_eq!(line_after(&[b"rm -rf /tmp/x", &[21], b"deploy"]).as_deref(), Some("deploy"));
+    }
+
+    #[test]
+    fn ctrl_w_desyncs_the_buffer() {
+        // "deploy now", Ctrl+W leaves "deploy " on the remote line
+        assert_eq!(line_after(&[b"deploy now", &[23]]), None);
+        assert_eq!(line_after(&[b"deploy now", &[23], b"deploy"]), None);
+    }
+
+    #[test]
+    fn cursor_moves_desync_the_buffer() {
+        for key in [&[1u8][..], &[5], b"\x1b[D", b"\x1b[A", b"\x1b[H", &[27]] {
+            assert_eq!(line_after(&[b"ploy", key, b"de"]), None, "{:?}", key);
+        }
+    }
+
+    #[test]
+    fn remote_completion_desyncs_the_buffer() {
+        // "dep<Tab>" may become "deploy" or anything else on the remote side
+        assert_eq!(line_after(&[b"dep", b"\t"]), None);
+        assert_eq!(line_after(&[b"ls\r", b"deploy"]), None);
+    }
+
+    #[test]
+    fn ctrl_c_starts_a_clean_line() {
+        assert_eq!(line_after(&[b"x", &[23], &[3], b"deploy"]).as_deref(), Some("deploy"));
+    }
+
+    #[test]
+    fn removing_a_host_shifts_the_indices_above_it() {
+        assert_eq!(shift_index(Some(1), 2), Some(1));
+        assert_eq!(shift_index(Some(2), 2), None);
+        assert_eq!(shift_index(Some(3), 2), Some(2));
+        let mut pings = HashMap::from([(0, Some(5)), (2, None), (4, Some(9))]);
+        shift_index_keys(&mut pings, 2);
+        assert_eq!(pings, HashMap::from([(0, Some(5)), (3, Some(9))]));
+    }
+
+    #[test]
+    fn destructive_commands_are_found_however_written() {
+        for script in [
+            "rm -rf /var/www",
+            "rm -fr build",
+            "rm -r -f build",
+            "rm --recursive --force build",
+            "cd /tmp && sudo rm -R old",
+            "/bin/rm -rf x",
+            "find . -name '*.o' | xargs rm -r",
+            "mkfs.ext4 /dev/sdb1",
+            "dd if=/dev/zero of=/dev/sda bs=1M",
+            ":(){ :|:& };:",
+        ] {
+            assert!(is_destructive(script), "{script}");
+        }
+        for script in ["rm -f build.log", "ls -R /etc", "echo rm -rf", "grep -r mkfs docs", "dd if=a of=b"] {
+            assert!(!is_destructive(script), "{script}");
+        }
+    }
+
+    #[test]
+    fn selections_cover_cells_in_reading_order() {
+        // Dragged up and to the left from (2, 5) to (1, 8).
+        let sel = TermSelection { anchor: (2, 5), head: (1, 8), dragging: false };
+        assert_eq!(sel.range(), ((1, 8), (2, 5)));
+        assert!(sel.contains(1, 8) && sel.contains(1, 79) && sel.contains(2, 0) && sel.contains(2, 5));
+        assert!(!sel.contains(1, 7) && !sel.contains(2, 6) && !sel.contains(0, 10));
+        assert_eq!(terminal_cell_at(iced::Point::new(10.0 + 7.8 * 3.5, 8.0 + 16.9 * 2.5), 13.0), (2, 3));
+        assert_eq!(terminal_cell_at(iced::Point::new(0.0, 0.0), 13.0), (0, 0));
+    }
+
+    #[test]
+    fn alias_triggers_come_before_ranked_history() {
+        let mut command_history = VecDeque::new();
+        let mut counts = history::Counts::new();
+        for _ in 0..10 {
+            history::push(&mut command_history, "deploy web");
+            history::count(&mut counts, "deploy web");
+        }
+        history::push(&mut command_history, "deploy db");
+        history::count(&mut counts, "deploy db");
+        let triggers = vec!["deploy-all".to_string()];
+        let suggestions = suggestions_for("dep", &triggers, &command_history, &counts);
+        assert_eq!(suggestions[..3], ["deploy-all", "deploy web", "deploy db"]);
+    }
+
+    #[test]
+    fn secrets_stay_out_of_history() {
+        assert!(!is_unechoed_input("ann@web:~$ deploy now", "deploy now"));
+        assert!(is_unechoed_input("[sudo] password for ann: ", "hunter2"));
+        assert!(is_unechoed_input("Enter passphrase for key: ", "hunter2"));
+        // Echo off without a recognisable prompt: nothing typed shows up.
+        assert!(is_unechoed_input("Verification code ", "123456"));
+    }
+}
@@ -1,15 +1,21 @@
 use aes_gcm::{
-    aead::{Aead, KeyInit},
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
     Aes256Gcm, Nonce,
 };
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::fmt;
 use std::fs;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::UNIX_EPOCH;
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+use crate::theme::PaletteSpec;
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Host {
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub id: Option<String>,
@@ -18,6 +24,94 @@ pub struct Host {
     pub port: u16,
     pub username: String,
     pub password: Option<String>,
+    /// Private key file tried after the agent and before the password.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub identity_file: Option<String>,
+    /// Passphrase for `identity_file`; held in memory for the session only.
+    #[serde(skip)]
+    pub key_passphrase: Option<String>,
+    /// Bastion to tunnel through, as `[user@]host[:port]` (ProxyJump).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub jump_host: Option<String>,
+    /// Sidebar folder this host is listed under.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub group: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tags: Vec<String>,
+    /// Local port forwards (`ssh -L`) configured for this host.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tunnels: Vec<Tunnel>,
+    /// Typed into the shell once it is ready, e.g. `cd /var/www && source .env`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub on_connect: Option<String>,
+    /// Variables set in the remote shell (`TERM` picks the PTY type).
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub env: Vec<(String, String)>,
+    /// Last connection error seen for this host, kept until the next clean session.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_error: Option<String>,
+    /// Unix timestamp (seconds) of `last_error`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_error_at: Option<u64>,
+    /// Unix timestamp (seconds) of the last connection attempt.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub last_connected: Option<u64>,
+    /// Poll the host for the sidebar CPU/RAM/disk gauges while a tab is open.
+    #[serde(default = "default_true")]
+    pub collect_stats: bool,
+    /// Opened with Quick Connect: never saved, and leaves no history behind.
+    #[serde(skip)]
+    pub adhoc: bool,
+}
+
+/// Secrets print as `***` so a `{:?}` in a log or a pasted trace can't leak them.
+impl fmt::Debug for Host {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mask = |secret: &Option<String>| secret.as_ref().map(|_| "***");
+        f.debug_struct("Host")
+            .field("id", &self.id)
+            .field("alias", &self.alias)
+            .field("hostname", &self.hostname)
+            .field("port", &self.port)
+            .field("username", &self.username)
+            .field("password", &mask(&self.password))
+            .field("identity_file", &self.identity_file)
+            .field("key_passphrase", &mask(&self.key_passphrase))
+            .field("jump_host", &self.jump_host)
+            .field("group", &self.group)
+            .field("tags", &self.tags)
+            .field("tunnels", &self.tunnels)
+            .field("on_connect", &self.on_connect)
+            .field("env", &self.env.iter().map(|(name, _)| (name, "***")).collect::<Vec<_>>())
+            .field("last_error", &self.last_error)
+            .field("last_error_at", &self.last_error_at)
+            .field("last_connected", &self.last_connected)
+            .field("collect_stats", &self.collect_stats)
+            .field("adhoc", &self.adhoc)
+            .finish()
+    }
+}
+
+impl Host {
+    /// Whether `name` may be used as an environment variable name.
+    pub fn is_env_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+
+    /// `text` with this host's password and key passphrase replaced by `***`,
+    /// for errors that quote server or API output back to the user.
+    pub fn redact(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        for secret in [&self.password, &self.key_passphrase].into_iter().flatten() {
+            // Very short secrets would mask ordinary words; they aren't worth protecting this way.
+            if secret.len() >= 4 {
+                out = out.replace(secret.as_str(), "***");
+            }
+        }
+        out
+    }
 }
 
 impl Default for Host {
@@ -29,15 +123,63 @@ impl Default for Host {
             port: 22,
             username: String::new(),
             password: None,
+            identity_file: None,
+            key_passphrase: None,
+            jump_host: None,
+            group: None,
+            tags: Vec::new(),
+            tunnels: Vec::new(),
+            on_connect: None,
+            env: Vec::new(),
+            last_error: None,
+            last_error_at: None,
+            last_connected: None,
+            collect_stats: true,
+            adhoc: false,
         }
     }
 }
 
+/// Host change made while the API was unreachable, replayed in order on
+/// the next sync.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PendingApiOp {
+    /// Local host without an API id yet, found by alias when replayed.
+    Create { alias: String },
+    Update { id: String },
+    Delete { id: String },
+}
+
+/// Forwards `127.0.0.1:local_port` to `remote_host:remote_port` as seen from the server.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Tunnel {
+    pub local_port: u16,
+    pub remote_host: String,
+    pub remote_port: u16,
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
 pub enum Language {
     Turkish,
     #[default]
     English,
+    German,
+}
+
+impl Language {
+    /// Short code shown in the status bar and language picker.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Turkish => "TR",
+            Self::English => "EN",
+            Self::German => "DE",
+        }
+    }
+
+    pub fn all() -> &'static [Language] {
+        &[Self::English, Self::Turkish, Self::German]
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
@@ -66,6 +208,10 @@ pub enum AppTheme {
     Kanagawa,
     Everforest,
     Midnight,
+    /// Colors from `AppConfig::custom_palette`.
+    Custom,
+    /// Light or Dark, following the OS preference.
+    Auto,
 }
 
 impl AppTheme {
@@ -93,6 +239,8 @@ impl AppTheme {
             Self::Kanagawa => "Kanagawa",
             Self::Everforest => "Everforest",
             Self::Midnight => "Midnight",
+            Self::Custom => "Custom",
+            Self::Auto => "Auto (System)",
         }
     }
 
@@ -120,11 +268,20 @@ impl AppTheme {
             Self::Kanagawa,
             Self::Everforest,
             Self::Midnight,
+            Self::Custom,
+            Self::Auto,
         ]
     }
 
     pub fn is_light(self) -> bool {
-        matches!(self, Self::Light | Self::MonoLight)
+        match self {
+            Self::Light | Self::MonoLight => true,
+            Self::Custom | Self::Auto => {
+                let bg = crate::theme::palette(self).bg_primary;
+                0.299 * bg.r + 0.587 * bg.g + 0.114 * bg.b > 0.5
+            }
+            _ => false,
+        }
     }
 }
 
@@ -134,6 +291,38 @@ impl std::fmt::Display for AppTheme {
     }
 }
 
+/// Order of the sidebar host list.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum HostSort {
+    /// As saved, rearranged with the move arrows.
+    #[default]
+    Manual,
+    Alias,
+    LastConnected,
+    Latency,
+}
+
+impl HostSort {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Manual => "Custom order",
+            Self::Alias => "Name (A→Z)",
+            Self::LastConnected => "Recently used",
+            Self::Latency => "Fastest ping",
+        }
+    }
+
+    pub fn all() -> &'static [HostSort] {
+        &[Self::Manual, Self::Alias, Self::LastConnected, Self::Latency]
+    }
+}
+
+impl std::fmt::Display for HostSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
 pub enum LayoutPreset {
     #[default]
@@ -207,8 +396,13 @@ pub struct CustomCommand {
 fn default_font_size() -> f32 { 13.0 }
 fn default_true() -> bool { true }
 fn default_suggestions() -> bool { true }
+fn default_connect_timeout() -> u64 { crate::ssh::DEFAULT_CONNECT_TIMEOUT_SECS }
+fn default_reconnect_attempts() -> u32 { 5 }
+fn default_title_template() -> String { "{title}".to_string() }
+fn default_term_type() -> String { crate::ssh::DEFAULT_TERM_TYPE.to_string() }
+fn default_api_timeout() -> u64 { crate::api::DEFAULT_TIMEOUT_SECS }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct AppConfig {
     pub hosts: Vec<Host>,
     pub api_key: Option<String>,
@@ -221,6 +415,11 @@ pub struct AppConfig {
     #[serde(default)]
     pub layout: LayoutPreset,
     #[serde(default)]
+    pub host_sort: HostSort,
+    /// Colors used when `theme` is `AppTheme::Custom`.
+    #[serde(default)]
+    pub custom_palette: PaletteSpec,
+    #[serde(default)]
     pub custom_commands: Vec<CustomCommand>,
     // Terminal appearance
     #[serde(default = "default_font_size")]
@@ -229,6 +428,95 @@ pub struct AppConfig {
     pub show_borders: bool,
     #[serde(default = "default_suggestions")]
     pub suggestions_enabled: bool,
+    #[serde(default = "default_connect_timeout")]
+    pub connect_timeout_secs: u64,
+    /// Seconds between SSH keepalives on idle sessions; 0 turns them off.
+    #[serde(default)]
+    pub keepalive_secs: u64,
+    /// `TERM` requested for the remote PTY unless a host sets its own.
+    #[serde(default = "default_term_type")]
+    pub term_type: String,
+    /// Seconds after pasting something secret-like before the clipboard is
+    /// cleared; 0 turns it off (Ctrl+Shift+V still clears).
+    #[serde(default)]
+    pub clipboard_clear_secs: u64,
+    /// Seconds an API request may take to connect or respond.
+    #[serde(default = "default_api_timeout")]
+    pub api_timeout_secs: u64,
+    /// Re-run the security audit on startup and host changes (opt-in).
+    #[serde(default)]
+    pub background_audit: bool,
+    /// Load the overview, firewall and login tabs together when the system panel opens.
+    #[serde(default)]
+    pub sys_prefetch: bool,
+    /// Critical/High findings already acknowledged by opening the audit dialog.
+    #[serde(default)]
+    pub audit_baseline: Vec<String>,
+    /// Severity labels (e.g. `INFO`) filtered out of the audit dialog.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub audit_hidden_severities: Vec<String>,
+    /// Stream everything each tab receives to a file under the logs directory.
+    #[serde(default)]
+    pub auto_log: bool,
+    /// Keep ANSI escape sequences in session logs instead of plain text.
+    #[serde(default)]
+    pub log_keep_ansi: bool,
+    /// Make bare `http(s)://` URLs in terminal output clickable (OSC 8 links always are).
+    #[serde(default)]
+    pub linkify_urls: bool,
+    /// Put text selected in the terminal on the clipboard as soon as the drag ends.
+    #[serde(default)]
+    pub copy_on_select: bool,
+    /// Desktop notification when a background tab rings the bell.
+    #[serde(default)]
+    pub bell_notify: bool,
+    /// Attempts an auto-reconnecting tab makes before giving up.
+    #[serde(default = "default_reconnect_attempts")]
+    pub max_reconnect_attempts: u32,
+    /// Tab label and window title: `{alias}`, `{user}`, `{host}`, `{port}`,
+    /// `{title}` (set by the remote, else the alias) and `{path}`.
+    #[serde(default = "default_title_template")]
+    pub title_template: String,
+    /// Host changes waiting for the API to be reachable again.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending_api_ops: Vec<PendingApiOp>,
+}
+
+/// The API key prints as `***`; hosts mask their own secrets.
+impl fmt::Debug for AppConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppConfig")
+            .field("hosts", &self.hosts)
+            .field("api_key", &self.api_key.as_ref().map(|_| "***"))
+            .field("api_url", &self.api_url)
+            .field("language", &self.language)
+            .field("theme", &self.theme)
+            .field("layout", &self.layout)
+            .field("host_sort", &self.host_sort)
+            .field("custom_palette", &self.custom_palette)
+            .field("custom_commands", &self.custom_commands)
+            .field("terminal_font_size", &self.terminal_font_size)
+            .field("show_borders", &self.show_borders)
+            .field("suggestions_enabled", &self.suggestions_enabled)
+            .field("connect_timeout_secs", &self.connect_timeout_secs)
+            .field("keepalive_secs", &self.keepalive_secs)
+            .field("term_type", &self.term_type)
+            .field("clipboard_clear_secs", &self.clipboard_clear_secs)
+            .field("api_timeout_secs", &self.api_timeout_secs)
+            .field("background_audit", &self.background_audit)
+            .field("sys_prefetch", &self.sys_prefetch)
+            .field("audit_baseline", &self.audit_baseline)
+            .field("audit_hidden_severities", &self.audit_hidden_severities)
+            .field("auto_log", &self.auto_log)
+            .field("log_keep_ansi", &self.log_keep_ansi)
+            .field("linkify_urls", &self.linkify_urls)
+            .field("copy_on_select", &self.copy_on_select)
+            .field("bell_notify", &self.bell_notify)
+            .field("max_reconnect_attempts", &self.max_reconnect_attempts)
+            .field("title_template", &self.title_template)
+            .field("pending_api_ops", &self.pending_api_ops)
+            .finish()
+    }
 }
 
 impl Default for AppConfig {
@@ -240,17 +528,375 @@ impl Default for AppConfig {
             language: Language::default(),
             theme: AppTheme::default(),
             layout: LayoutPreset::default(),
+            host_sort: HostSort::default(),
+            custom_palette: PaletteSpec::default(),
             custom_commands: Vec::new(),
             terminal_font_size: default_font_size(),
             show_borders: default_true(),
             suggestions_enabled: default_suggestions(),
+            connect_timeout_secs: default_connect_timeout(),
+            keepalive_secs: 0,
+            term_type: default_term_type(),
+            clipboard_clear_secs: 0,
+            api_timeout_secs: default_api_timeout(),
+            background_audit: false,
+            sys_prefetch: false,
+            audit_baseline: Vec::new(),
+            audit_hidden_severities: Vec::new(),
+            auto_log: false,
+            linkify_urls: false,
+            copy_on_select: false,
+            bell_notify: false,
+            log_keep_ansi: false,
+            max_reconnect_attempts: default_reconnect_attempts(),
+            title_template: default_title_template(),
+            pending_api_ops: Vec::new(),
         }
     }
 }
 
 // --- Encryption helpers ---
+//
+// `config.enc` is `v2:` followed by hex(nonce ‖ AES-256-GCM ciphertext). The
+// key is 32 random bytes kept in the OS keyring (Keychain, Credential
+// Manager, Secret Service); where no keyring is reachable it lives in
+// `config.key` next to the config, readable only by the user. Files without
+// the prefix use the old key derived from the machine name and are
+// re-encrypted on load. A config under `--config` always uses `config.key`
+// so the directory can be carried to another machine.
+//
+// With a master password the file is `v3:` hex(salt) `:` hex(nonce ‖
+// ciphertext) instead, keyed by Argon2id(password, salt). Nothing on disk
+// can open it; "remember on this device" stores the derived key in the
+// keyring to skip the unlock prompt.
+
+const FORMAT_V2: &str = "v2:";
+const FORMAT_MASTER: &str = "v3:";
+const KEYRING_SERVICE: &str = "termissh";
+const KEYRING_USER: &str = "config-key";
+const KEYRING_MASTER_USER: &str = "master-key";
+
+/// Salt and derived key of the master password, held for the session once
+/// the config is unlocked.
+static MASTER_KEY: Mutex<Option<([u8; 16], [u8; 32])>> = Mutex::new(None);
+
+/// Set while the config awaits its master password. Saving is refused so the
+/// empty startup config can't replace the locked file.
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+/// Set when the key of an existing config can't be had. The file is left
+/// alone and saves are refused until `retry_config_key` finds the key or
+/// `replace_config_key` gives up on it.
+static KEY_UNAVAILABLE: Mutex<Option<KeyProblem>> = Mutex::new(None);
+
+/// Why the key of an existing config can't be had.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeyProblem {
+    /// The keyring has no entry for it; it won't come back by itself.
+    Missing,
+    /// The keyring failed (locked, access denied, …); it may work later.
+    Unreadable,
+}
+
+/// The config key exists somewhere but can't be read right now.
+#[derive(Debug)]
+struct KeyUnavailable(KeyProblem, String);
+
+impl fmt::Display for KeyUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.1)
+    }
+}
+
+impl std::error::Error for KeyUnavailable {}
+
+/// Why the keyring gave no key.
+enum KeyringFailure {
+    /// No usable keyring on this system; a key file can stand in.
+    NoBackend(keyring::Error),
+    /// The keyring exists but failed (locked, access denied, …).
+    Failed(anyhow::Error),
+    /// The keyring works but has no config key.
+    Missing,
+}
+
+/// Key of this process, resolved once: keyring lookups can prompt.
+/// Cleared when a master password replaces it.
+static CONFIG_KEY: Mutex<Option<([u8; 32], KeyStore)>> = Mutex::new(None);
+
+/// Where the config key is kept, as reported by the security audit.
+#[derive(Clone, Debug)]
+pub enum KeyStore {
+    MasterPassword,
+    Keyring,
+    File(PathBuf),
+}
+
+fn key_file_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("config.key"))
+}
+
+fn config_key() -> Result<([u8; 32], KeyStore)> {
+    let mut cached = CONFIG_KEY.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(key) = cached.as_ref() {
+        return Ok(key.clone());
+    }
+    let key = load_or_create_key()?;
+    *cached = Some(key.clone());
+    Ok(key)
+}
+
+/// Deletes the key file and keyring entry once nothing is encrypted with
+/// them any more, so they can't open anything left behind.
+fn forget_config_key() -> Result<()> {
+    *CONFIG_KEY.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    let file = key_file_path()?;
+    if file.exists() {
+        fs::remove_file(file)?;
+    }
+    if let Ok(entry) = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER) {
+        match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) | Err(keyring::Error::PlatformFailure(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
 
-fn derive_key() -> [u8; 32] {
+/// A key file, once written, wins over the keyring so a config saved
+/// without keyring access stays readable when the keyring shows up later.
+/// A new key file is only made when there is no keyring at all, or for a
+/// `--config` directory; a keyring that merely fails is reported instead,
+/// since a fresh key could never open the existing file.
+fn load_or_create_key() -> Result<([u8; 32], KeyStore)> {
+    let file = key_file_path()?;
+    if file.exists() {
+        let hex = fs::read_to_string(&file)?;
+        return Ok((parse_key(&hex).context("config.key is malformed")?, KeyStore::File(file)));
+    }
+    let existing = keyring_config_exists();
+    if CONFIG_DIR_OVERRIDE.get().is_some() {
+        // A portable config written before it had its own key file takes
+        // the keyring key along; it opens the same file either way.
+        let key = match existing.then(|| keyring_key(false)) {
+            Some(Ok(key)) => key,
+            Some(Err(KeyringFailure::Failed(e))) => return Err(unavailable(KeyProblem::Unreadable, e)),
+            Some(Err(KeyringFailure::Missing)) => return Err(missing()),
+            _ => Aes256Gcm::generate_key(&mut OsRng).into(),
+        };
+        write_private(&file, &bytes_to_hex(&key))?;
+        return Ok((key, KeyStore::File(file)));
+    }
+    match keyring_key(!existing) {
+        Ok(key) => Ok((key, KeyStore::Keyring)),
+        Err(KeyringFailure::NoBackend(e)) if !existing => {
+            eprintln!("termissh: no OS keyring ({}); keeping the config key in {}", e, file.display());
+            let key: [u8; 32] = Aes256Gcm::generate_key(&mut OsRng).into();
+            write_private(&file, &bytes_to_hex(&key))?;
+            Ok((key, KeyStore::File(file)))
+        }
+        Err(KeyringFailure::NoBackend(e)) => Err(unavailable(KeyProblem::Unreadable, e.into())),
+        Err(KeyringFailure::Failed(e)) => Err(unavailable(KeyProblem::Unreadable, e)),
+        Err(KeyringFailure::Missing) => Err(missing()),
+    }
+}
+
+fn unavailable(problem: KeyProblem, e: anyhow::Error) -> anyhow::Error {
+    anyhow::Error::new(KeyUnavailable(problem, format!("the config key in the OS keyring can't be read: {:#}", e)))
+}
+
+fn missing() -> anyhow::Error {
+    anyhow::Error::new(KeyUnavailable(KeyProblem::Missing, "the config key is missing from the OS keyring".into()))
+}
+
+/// Whether `config.enc` is encrypted with a key that isn't in a key file.
+fn keyring_config_exists() -> bool {
+    config_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .is_some_and(|data| data.trim_start().starts_with(FORMAT_V2))
+}
+
+/// The key from the keyring; with `create`, a missing entry gets a new key.
+fn keyring_key(create: bool) -> std::result::Result<[u8; 32], KeyringFailure> {
+    let classify = |e: keyring::Error| match e {
+        keyring::Error::PlatformFailure(_) => KeyringFailure::NoBackend(e),
+        e => KeyringFailure::Failed(e.into()),
+    };
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER).map_err(classify)?;
+    match entry.get_password() {
+        Ok(hex) => parse_key(&hex)
+            .context("keyring entry is malformed")
+            .map_err(KeyringFailure::Failed),
+        Err(keyring::Error::NoEntry) if !create => Err(KeyringFailure::Missing),
+        Err(keyring::Error::NoEntry) => {
+            let key: [u8; 32] = Aes256Gcm::generate_key(&mut OsRng).into();
+            entry.set_password(&bytes_to_hex(&key)).map_err(classify)?;
+            // Some stores accept a write they can't return; don't trust it blindly.
+            if entry.get_password().ok().and_then(|h| parse_key(&h)) != Some(key) {
+                return Err(KeyringFailure::NoBackend(keyring::Error::NoStorageAccess(
+                    "keyring did not keep the key".into(),
+                )));
+            }
+            Ok(key)
+        }
+        Err(e) => Err(classify(e)),
+    }
+}
+
+fn parse_key(hex: &str) -> Option<[u8; 32]> {
+    hex_to_bytes(hex.trim())?.try_into().ok()
+}
+
+/// Creates `path` with owner-only permissions where the platform has them.
+fn write_private(path: &Path, data: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    std::io::Write::write_all(&mut options.open(path)?, data.as_bytes())?;
+    Ok(())
+}
+
+/// Where the config key lives, or why there is none.
+pub fn key_store() -> Result<KeyStore> {
+    if has_master_password() {
+        return Ok(KeyStore::MasterPassword);
+    }
+    config_key().map(|(_, store)| store)
+}
+
+// --- Master password ---
+
+fn master_key() -> Option<([u8; 16], [u8; 32])> {
+    *MASTER_KEY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn set_master_key(key: Option<([u8; 16], [u8; 32])>) {
+    *MASTER_KEY.lock().unwrap_or_else(|e| e.into_inner()) = key;
+}
+
+pub fn has_master_password() -> bool {
+    master_key().is_some()
+}
+
+pub fn is_locked() -> bool {
+    LOCKED.load(Ordering::Relaxed)
+}
+
+fn derive_master_key(password: &str, salt: &[u8; 16]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+/// Salt and ciphertext of a master-password file.
+fn split_master(data: &str) -> Option<([u8; 16], &str)> {
+    let (salt, hex) = data.trim().strip_prefix(FORMAT_MASTER)?.split_once(':')?;
+    Some((hex_to_bytes(salt)?.try_into().ok()?, hex))
+}
+
+/// Derived key saved by "remember on this device", if it matches `salt`.
+fn remembered_master_key(salt: &[u8; 16]) -> Option<[u8; 32]> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_MASTER_USER).ok()?;
+    let stored = entry.get_password().ok()?;
+    let (stored_salt, key) = stored.split_once(':')?;
+    (hex_to_bytes(stored_salt)? == salt).then_some(())?;
+    parse_key(key)
+}
+
+/// Stores the unlocked key in the OS keyring, or removes it, so later
+/// launches skip (or show again) the unlock prompt.
+pub fn remember_master_key(remember: bool) -> Result<()> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_MASTER_USER)?;
+    match (remember, master_key()) {
+        (true, Some((salt, key))) => {
+            entry.set_password(&format!("{}:{}", bytes_to_hex(&salt), bytes_to_hex(&key)))?;
+        }
+        _ => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(e.into()),
+        },
+    }
+    Ok(())
+}
+
+/// Opens a locked config with its master password.
+pub fn unlock(password: &str) -> Result<AppConfig> {
+    let data = fs::read_to_string(config_path()?)?;
+    let (salt, hex) = split_master(&data).context("config is not protected by a master password")?;
+    let key = derive_master_key(password, &salt)?;
+    let cfg = open(&key, hex).map_err(|_| anyhow::anyhow!("wrong master password"))?;
+    set_master_key(Some((salt, key)));
+    LOCKED.store(false, Ordering::Relaxed);
+    Ok(cfg)
+}
+
+/// Sets, changes or (with `None`) removes the master password and rewrites
+/// the config and its backups under the new key; backups that can't be read
+/// any more are deleted. Setting a password also deletes the random config
+/// key, which could otherwise still open old copies. A remembered key is
+/// forgotten either way.
+pub fn set_master_password(config: &AppConfig, password: Option<&str>) -> Result<()> {
+    anyhow::ensure!(!is_locked(), "config is locked");
+    anyhow::ensure!(key_problem().is_none(), "config key is unavailable");
+    let path = config_path()?;
+    if path.exists() {
+        rotate_backups(&path)?;
+    }
+    // Read under the old key, before it goes away.
+    let backups: Vec<(PathBuf, Option<AppConfig>)> = (1..=CONFIG_BACKUPS)
+        .map(|n| backup_path(&path, n))
+        .filter(|file| file.exists())
+        .map(|file| {
+            let cfg = fs::read_to_string(&file).ok().and_then(|d| decrypt_config(&d).ok());
+            (file, cfg)
+        })
+        .collect();
+    let previous = master_key();
+    let next = match password {
+        Some(password) => {
+            let mut salt = [0u8; 16];
+            OsRng.fill_bytes(&mut salt);
+            Some((salt, derive_master_key(password, &salt)?))
+        }
+        None => None,
+    };
+    set_master_key(next);
+    if let Err(e) = encrypt_config(config).and_then(|data| Ok(fs::write(&path, data)?)) {
+        set_master_key(previous);
+        return Err(e);
+    }
+    for (file, cfg) in backups {
+        let modified = fs::metadata(&file).and_then(|m| m.modified()).ok();
+        match cfg.map(|cfg| encrypt_config(&cfg)) {
+            Some(Ok(data)) => {
+                fs::write(&file, data)?;
+                // The restore dialog dates backups by modification time.
+                if let (Some(time), Ok(f)) = (modified, fs::File::options().write(true).open(&file)) {
+                    let _ = f.set_modified(time);
+                }
+            }
+            _ => fs::remove_file(&file)?,
+        }
+    }
+    if password.is_some() {
+        forget_config_key()?;
+    }
+    let _ = remember_master_key(false);
+    Ok(())
+}
+
+/// Key of configs written before `FORMAT_V2`; only used to read them.
+fn legacy_key() -> [u8; 32] {
     let machine_id = std::env::var("COMPUTERNAME")
         .or_else(|_| std::env::var("HOSTNAME"))
         .unwrap_or_else(|_| "termissh-default".to_string());
@@ -259,14 +905,6 @@ fn derive_key() -> [u8; 32] {
     hash.into()
 }
 
-fn nonce_from_time() -> [u8; 12] {
-    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
-    let mut n = [0u8; 12];
-    n[0..8].copy_from_slice(&now.as_secs().to_le_bytes());
-    n[8..12].copy_from_slice(&now.subsec_nanos().to_le_bytes());
-    n
-}
-
 fn bytes_to_hex(b: &[u8]) -> String {
     b.iter().map(|x| format!("{:02x}", x)).collect()
 }
@@ -282,25 +920,55 @@ fn hex_to_bytes(s: &str) -> Option<Vec<u8>> {
 }
 
 fn encrypt_config(config: &AppConfig) -> Result<String> {
-    let key_bytes = derive_key();
-    let cipher = Aes256Gcm::new_from_slice(&key_bytes)?;
-    let nonce_bytes = nonce_from_time();
-    let nonce = Nonce::from_slice(&nonce_bytes);
+    if let Some((salt, key)) = master_key() {
+        return Ok(format!("{}{}:{}", FORMAT_MASTER, bytes_to_hex(&salt), seal(&key, config)?));
+    }
+    let (key_bytes, _) = config_key()?;
+    Ok(format!("{}{}", FORMAT_V2, seal(&key_bytes, config)?))
+}
+
+/// hex(nonce ‖ ciphertext) of `config` under `key`.
+fn seal(key: &[u8; 32], config: &AppConfig) -> Result<String> {
+    let cipher = Aes256Gcm::new_from_slice(key)?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
     let plaintext = serde_json::to_vec(config)?;
     let ciphertext = cipher
-        .encrypt(nonce, plaintext.as_ref())
+        .encrypt(&nonce, plaintext.as_ref())
         .map_err(|e| anyhow::anyhow!("encryption failed: {}", e))?;
-    let mut combined = nonce_bytes.to_vec();
+    let mut combined = nonce.to_vec();
     combined.extend_from_slice(&ciphertext);
     Ok(bytes_to_hex(&combined))
 }
 
-fn decrypt_config(hex: &str) -> Result<AppConfig> {
-    let bytes = hex_to_bytes(hex.trim()).context("invalid hex in config")?;
+/// Whether `data` was written with the legacy key and should be re-encrypted.
+fn is_legacy(data: &str) -> bool {
+    let data = data.trim_start();
+    !data.starts_with(FORMAT_V2) && !data.starts_with(FORMAT_MASTER)
+}
+
+fn decrypt_config(data: &str) -> Result<AppConfig> {
+    let data = data.trim();
+    if data.starts_with(FORMAT_MASTER) {
+        let (salt, hex) = split_master(data).context("malformed config header")?;
+        return match master_key() {
+            Some((current, key)) if current == salt => open(&key, hex),
+            _ => anyhow::bail!("saved under a different master password"),
+        };
+    }
+    match data.strip_prefix(FORMAT_V2) {
+        Some(hex) => {
+            let (key, _) = config_key()?;
+            open(&key, hex).map_err(|e| e.context("was the config key in the OS keyring removed?"))
+        }
+        None => open(&legacy_key(), data),
+    }
+}
+
+fn open(key: &[u8; 32], hex: &str) -> Result<AppConfig> {
+    let bytes = hex_to_bytes(hex).context("invalid hex in config")?;
     anyhow::ensure!(bytes.len() > 12, "config data too short");
     let (nonce_bytes, ciphertext) = bytes.split_at(12);
-    let key_bytes = derive_key();
-    let cipher = Aes256Gcm::new_from_slice(&key_bytes)?;
+    let cipher = Aes256Gcm::new_from_slice(key)?;
     let nonce = Nonce::from_slice(nonce_bytes);
     let plaintext = cipher
         .decrypt(nonce, ciphertext)
@@ -310,51 +978,392 @@ fn decrypt_config(hex: &str) -> Result<AppConfig> {
 
 // --- Config path ---
 
-fn config_path() -> Result<std::path::PathBuf> {
+/// Directory given with `--config <dir>`; takes precedence over
+/// `TERMISSH_CONFIG_DIR`.
+static CONFIG_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+pub fn set_config_dir(dir: PathBuf) {
+    let _ = CONFIG_DIR_OVERRIDE.set(dir);
+}
+
+/// Directory from `--config` or `TERMISSH_CONFIG_DIR`, if either is set.
+pub fn config_dir_override() -> Option<PathBuf> {
+    CONFIG_DIR_OVERRIDE.get().cloned().or_else(|| {
+        std::env::var_os("TERMISSH_CONFIG_DIR")
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+/// `--config`, then `TERMISSH_CONFIG_DIR`, then the platform config dir.
+fn config_dir() -> Result<PathBuf> {
+    if let Some(dir) = config_dir_override() {
+        return Ok(dir);
+    }
     let proj = ProjectDirs::from("com", "termissh", "manager")
         .context("Could not determine config directory")?;
-    let dir = proj.config_dir();
+    Ok(proj.config_dir().to_path_buf())
+}
+
+fn config_path() -> Result<PathBuf> {
+    let dir = config_dir()?;
     if !dir.exists() {
-        fs::create_dir_all(dir)?;
+        fs::create_dir_all(&dir)?;
     }
     Ok(dir.join("config.enc"))
 }
 
-fn legacy_config_path() -> Option<std::path::PathBuf> {
-    let proj = ProjectDirs::from("com", "termissh", "manager")?;
-    let path = proj.config_dir().join("config.json");
+fn legacy_config_path() -> Option<PathBuf> {
+    let path = config_dir().ok()?.join("config.json");
     if path.exists() { Some(path) } else { None }
 }
 
 // --- Public API ---
 
-pub fn load_config() -> AppConfig {
+/// Loads the config. A file that exists but can't be read is moved aside
+/// rather than overwritten, and the second value explains what happened.
+/// A config behind a master password that isn't remembered comes back as
+/// the default with `is_locked()` set; see `unlock`.
+pub fn load_config() -> (AppConfig, Option<String>) {
     // 1. Try encrypted file
     if let Ok(path) = config_path() {
         if path.exists() {
-            if let Ok(data) = fs::read_to_string(&path) {
-                if let Ok(cfg) = decrypt_config(&data) {
-                    return cfg;
+            if let Some((salt, _)) = fs::read_to_string(&path).ok().as_deref().and_then(split_master) {
+                match remembered_master_key(&salt) {
+                    Some(key) => set_master_key(Some((salt, key))),
+                    None => {
+                        LOCKED.store(true, Ordering::Relaxed);
+                        return (AppConfig::default(), None);
+                    }
                 }
             }
+            let loaded = fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|data| Ok((decrypt_config(&data)?, is_legacy(&data))));
+            return match loaded {
+                Ok((cfg, legacy)) => {
+                    if legacy {
+                        // Re-encrypt under the random key; the old file stays as backup 1.
+                        let _ = rotate_backups(&path)
+                            .and_then(|()| encrypt_config(&cfg))
+                            .and_then(|data| Ok(fs::write(&path, data)?));
+                    }
+                    (cfg, None)
+                }
+                Err(e) if e.is::<KeyUnavailable>() => {
+                    let problem = e.downcast_ref::<KeyUnavailable>().map_or(KeyProblem::Unreadable, |k| k.0);
+                    *KEY_UNAVAILABLE.lock().unwrap_or_else(|e| e.into_inner()) = Some(problem);
+                    (
+                        AppConfig::default(),
+                        Some(format!("{:#}. The config was left untouched; changes won't be saved until the key is found or replaced.", e)),
+                    )
+                }
+                Err(e) => (AppConfig::default(), Some(quarantine(&path, &e))),
+            };
         }
     }
     // 2. Migrate from legacy plain-text JSON
     if let Some(legacy) = legacy_config_path() {
         if let Ok(data) = fs::read_to_string(&legacy) {
-            let cfg: AppConfig = serde_json::from_str(&data).unwrap_or_default();
+            let cfg: AppConfig = match serde_json::from_str(&data) {
+                Ok(cfg) => cfg,
+                Err(e) => return (AppConfig::default(), Some(quarantine(&legacy, &e.into()))),
+            };
             // Save encrypted version and remove legacy file
             let _ = save_config(&cfg);
             let _ = fs::remove_file(legacy);
-            return cfg;
+            return (cfg, None);
         }
     }
-    AppConfig::default()
+    (AppConfig::default(), None)
+}
+
+/// Why the config key couldn't be had at load, if it couldn't.
+pub fn key_problem() -> Option<KeyProblem> {
+    *KEY_UNAVAILABLE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Loads the config again after its key couldn't be had, e.g. once the
+/// keyring is unlocked or a `config.key` from another copy is in place
+/// (see `key_file_hint`).
+pub fn retry_config_key() -> (AppConfig, Option<String>) {
+    *KEY_UNAVAILABLE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    *CONFIG_KEY.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    load_config()
+}
+
+/// Gives up on a config whose key is lost: the file is moved aside like a
+/// corrupt one and the next save starts over under a new key.
+pub fn replace_config_key() -> Result<String> {
+    anyhow::ensure!(key_problem() == Some(KeyProblem::Missing), "the config key may still turn up");
+    let path = config_path()?;
+    let notice = quarantine(&path, &anyhow::anyhow!("its key is lost"));
+    *KEY_UNAVAILABLE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    *CONFIG_KEY.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    Ok(notice)
+}
+
+/// Where a `config.key` is looked for before the keyring.
+pub fn key_file_hint() -> Option<PathBuf> {
+    key_file_path().ok()
+}
+
+/// Renames an unreadable config to `<file>.corrupt-<timestamp>` so the next
+/// save can't destroy it, and returns a notice for the user.
+fn quarantine(path: &Path, err: &anyhow::Error) -> String {
+    eprintln!("termissh: cannot load {}: {:#}", path.display(), err);
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".corrupt-{}", crate::session_log::timestamp()));
+    let target = PathBuf::from(name);
+    let mut notice = match fs::rename(path, &target) {
+        Ok(()) => format!("Config could not be read ({:#}); the file was kept as {}", err, target.display()),
+        Err(e) => format!("Config could not be read ({:#}) or moved aside: {}", err, e),
+    };
+    if !list_backups().is_empty() {
+        notice.push_str(" — earlier versions are under Settings › Restore previous config");
+    }
+    notice
+}
+
+/// Previous versions kept next to the config as `config.enc.1` (newest)
+/// through `config.enc.N`.
+pub const CONFIG_BACKUPS: usize = 5;
+
+fn backup_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    name.into()
+}
+
+/// Shifts `config.enc` into `config.enc.1`, `.1` into `.2` and so on,
+/// dropping the oldest.
+fn rotate_backups(path: &Path) -> Result<()> {
+    for n in (1..CONFIG_BACKUPS).rev() {
+        let from = backup_path(path, n);
+        if from.exists() {
+            fs::rename(&from, backup_path(path, n + 1))?;
+        }
+    }
+    fs::copy(path, backup_path(path, 1))?;
+    Ok(())
 }
 
 pub fn save_config(config: &AppConfig) -> Result<()> {
+    anyhow::ensure!(!is_locked(), "config is locked");
+    let path = config_path()?;
+    if path.exists() {
+        // Many UI toggles save; only keep a backup when the content changed.
+        let current = fs::read_to_string(&path).ok().and_then(|d| decrypt_config(&d).ok());
+        let unchanged = current.is_some_and(|c| {
+            serde_json::to_vec(&c).ok() == serde_json::to_vec(config).ok()
+        });
+        if unchanged {
+            return Ok(());
+        }
+    }
+    write_config(config)
+}
+
+/// Writes `config` under the current key, keeping the previous file as a backup.
+fn write_config(config: &AppConfig) -> Result<()> {
+    anyhow::ensure!(key_problem().is_none(), "config key is unavailable");
     let path = config_path()?;
+    if path.exists() {
+        rotate_backups(&path)?;
+    }
     let encrypted = encrypt_config(config)?;
     fs::write(path, encrypted)?;
     Ok(())
 }
+
+/// A readable config backup, as listed in the restore dialog.
+#[derive(Clone, Debug)]
+pub struct ConfigBackup {
+    /// `n` of `config.enc.n`; 1 is the most recent.
+    pub index: usize,
+    /// Unix time the backup was written.
+    pub saved_at: u64,
+    pub host_count: usize,
+}
+
+/// Existing backups, newest first. Unreadable files are skipped.
+pub fn list_backups() -> Vec<ConfigBackup> {
+    let Ok(path) = config_path() else { return Vec::new(); };
+    (1..=CONFIG_BACKUPS)
+        .filter_map(|index| {
+            let file = backup_path(&path, index);
+            let cfg = decrypt_config(&fs::read_to_string(&file).ok()?).ok()?;
+            let saved_at = fs::metadata(&file)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            Some(ConfigBackup { index, saved_at, host_count: cfg.hosts.len() })
+        })
+        .collect()
+}
+
+/// Makes backup `index` the current config. The config being replaced is
+/// rotated into the backups, so a restore can itself be undone.
+pub fn restore_backup(index: usize) -> Result<AppConfig> {
+    let path = config_path()?;
+    let data = fs::read_to_string(backup_path(&path, index))
+        .with_context(|| format!("backup #{} not found", index))?;
+    let cfg = decrypt_config(&data)?;
+    save_config(&cfg)?;
+    Ok(cfg)
+}
+
+// --- Host export / import ---
+
+/// Writes `hosts` as a portable JSON array, or as `alias,hostname,port,username`
+/// rows to a `.csv` path. Machine-local state (API ids, last errors, last use)
+/// is dropped; passwords only when `include_passwords` is set. The file is
+/// readable by the owner only.
+pub fn export_hosts(path: &Path, hosts: &[Host], include_passwords: bool) -> Result<usize> {
+    let portable: Vec<Host> = hosts
+        .iter()
+        .map(|h| Host {
+            id: None,
+            password: if include_passwords { h.password.clone() } else { None },
+            last_error: None,
+            last_error_at: None,
+            last_connected: None,
+            ..h.clone()
+        })
+        .collect();
+    let data = if is_csv_path(path) {
+        let mut out = String::from("alias,hostname,port,username\n");
+        for h in &portable {
+            let row = [h.alias.clone(), h.hostname.clone(), h.port.to_string(), h.username.clone()];
+            out.push_str(&row.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+            out.push('\n');
+        }
+        out
+    } else {
+        serde_json::to_string_pretty(&portable)?
+    };
+    // `write_private` never overwrites; the save dialog already confirmed it.
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    write_private(path, &data)?;
+    Ok(portable.len())
+}
+
+fn is_csv_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+}
+
+/// `field` quoted for CSV when it holds a comma, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) || field.trim() != field {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Records of `data` as CSV: fields may be quoted, with `""` for a quote and
+/// commas or line breaks inside the quotes.
+fn parse_csv(data: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// Reads hosts from a JSON export or a CSV file with the columns
+/// `alias,hostname,port,username` (header row optional).
+pub fn import_hosts(path: &Path) -> Result<Vec<Host>> {
+    let data = fs::read_to_string(path)?;
+    if !is_csv_path(path) {
+        let hosts: Vec<Host> = serde_json::from_str(&data).context("not a termissh host export")?;
+        return Ok(hosts
+            .into_iter()
+            .map(|h| Host { id: None, last_error: None, last_error_at: None, last_connected: None, ..h })
+            .collect());
+    }
+
+    let mut hosts = Vec::new();
+    for (line_no, record) in parse_csv(&data).iter().enumerate() {
+        let cols: Vec<&str> = record.iter().map(|c| c.trim()).collect();
+        if cols.iter().all(|c| c.is_empty()) || (line_no == 0 && cols[0].eq_ignore_ascii_case("alias")) {
+            continue;
+        }
+        anyhow::ensure!(
+            cols.len() >= 4,
+            "line {}: expected alias,hostname,port,username",
+            line_no + 1
+        );
+        let port = cols[2]
+            .parse::<u16>()
+            .with_context(|| format!("line {}: invalid port '{}'", line_no + 1, cols[2]))?;
+        hosts.push(Host {
+            alias: cols[0].to_string(),
+            hostname: cols[1].to_string(),
+            port,
+            username: cols[3].to_string(),
+            ..Default::default()
+        });
+    }
+    Ok(hosts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_output_masks_secrets() {
+        let host = Host {
+            password: Some("hunter2".into()),
+            env: vec![("DB_PASSWORD".into(), "s3cret".into())],
+            ..Default::default()
+        };
+        let config = AppConfig { hosts: vec![host], api_key: Some("sk-live-123".into()), ..Default::default() };
+        let debug = format!("{:?}", config);
+        for secret in ["hunter2", "s3cret", "sk-live-123"] {
+            assert!(!debug.contains(secret), "{secret} in {debug}");
+        }
+        assert!(debug.contains("DB_PASSWORD"));
+    }
+
+    #[test]
+    fn csv_fields_round_trip() {
+        let fields = ["web, eu", "say \"hi\"", "plain", " padded"];
+        let line = fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(",");
+        assert_eq!(parse_csv(&format!("{}\r\nnext,row\n", line)), vec![
+            fields.iter().map(|f| f.to_string()).collect::<Vec<_>>(),
+            vec!["next".to_string(), "row".to_string()],
+        ]);
+        assert_eq!(parse_csv("\"multi\nline\",x"), vec![vec!["multi\nline".to_string(), "x".to_string()]]);
+    }
+}
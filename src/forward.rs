@@ -0,0 +1,167 @@
+//! Local port forwarding (`ssh -L`).
+//!
+//! A forward listens on `127.0.0.1:local_port` in a background thread. The
+//! first accepted connection opens an SSH session that all later ones share;
+//! each gets a `direct-tcpip` channel to the tunnel target, and the thread
+//! pumps them all with the same step used for jump hosts.
+
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use ssh2::{Channel, Session};
+
+use crate::config::{Host, Tunnel};
+use crate::ssh;
+
+/// Shared state of a running forward, read by the UI for live status.
+#[derive(Debug, Default)]
+struct Shared {
+    stop: AtomicBool,
+    connections: AtomicUsize,
+    last_error: Mutex<Option<String>>,
+}
+
+/// Handle to a running forward; the listener stops when this is dropped.
+#[derive(Debug)]
+pub struct ForwardHandle {
+    shared: Arc<Shared>,
+    /// The host as it was when the forward started.
+    host: Host,
+}
+
+impl ForwardHandle {
+    /// Whether this forward was started for `host`: same API id, or for
+    /// hosts without one, the same alias and address.
+    pub fn belongs_to(&self, host: &Host) -> bool {
+        match (&self.host.id, &host.id) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) => {
+                self.host.alias == host.alias
+                    && self.host.hostname == host.hostname
+                    && self.host.port == host.port
+                    && self.host.username == host.username
+            }
+            _ => false,
+        }
+    }
+
+    pub fn connections(&self) -> usize {
+        self.shared.connections.load(Ordering::Relaxed)
+    }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.shared.last_error.lock().ok().and_then(|e| e.clone())
+    }
+}
+
+impl Drop for ForwardHandle {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Binds the local port and starts accepting connections for `tunnel`.
+pub fn start(host: &Host, tunnel: &Tunnel) -> Result<ForwardHandle, String> {
+    let listener = TcpListener::bind(("127.0.0.1", tunnel.local_port))
+        .map_err(|e| format!("Cannot listen on 127.0.0.1:{}: {}", tunnel.local_port, e))?;
+    listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+
+    let shared = Arc::new(Shared::default());
+    let owner = host.clone();
+    let host = host.clone();
+    let tunnel = tunnel.clone();
+    let state = shared.clone();
+    thread::spawn(move || accept_loop(listener, host, tunnel, state));
+    Ok(ForwardHandle { shared, host: owner })
+}
+
+fn accept_loop(listener: TcpListener, host: Host, tunnel: Tunnel, shared: Arc<Shared>) {
+    let mut sess: Option<Session> = None;
+    let mut open: Vec<(Channel, TcpStream)> = Vec::new();
+    let mut buf = [0u8; 16384];
+    while !shared.stop.load(Ordering::Relaxed) {
+        let mut active = false;
+        match listener.accept() {
+            Ok((stream, _)) => {
+                active = true;
+                match open_channel(&mut sess, stream, &host, &tunnel) {
+                    Ok(conn) => open.push(conn),
+                    Err(e) => set_error(&shared, e),
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+            Err(e) => {
+                set_error(&shared, e.to_string());
+                break;
+            }
+        }
+
+        open.retain_mut(|(channel, stream)| match ssh::pump_step(channel, stream, &mut buf) {
+            Some(moved) => {
+                active |= moved;
+                true
+            }
+            None => {
+                let _ = channel.close();
+                false
+            }
+        });
+        shared.connections.store(open.len(), Ordering::Relaxed);
+        // Nothing left to keep the session for; the next connection opens a fresh one.
+        if open.is_empty() {
+            sess = None;
+        }
+
+        if !active {
+            let idle = if open.is_empty() { 100 } else { 2 };
+            thread::sleep(Duration::from_millis(idle));
+        }
+    }
+}
+
+fn set_error(shared: &Shared, error: String) {
+    if let Ok(mut last) = shared.last_error.lock() {
+        *last = Some(error);
+    }
+}
+
+/// Opens a channel for `stream` on the forward's session, logging in first
+/// if there is none yet. A session that fails to open a channel is dropped
+/// so the next connection starts over.
+fn open_channel(
+    sess: &mut Option<Session>,
+    stream: TcpStream,
+    host: &Host,
+    tunnel: &Tunnel,
+) -> Result<(Channel, TcpStream), String> {
+    stream.set_nonblocking(true).map_err(|e| e.to_string())?;
+    if sess.is_none() {
+        *sess = Some(ssh::open_session(host)?);
+    }
+    let session = sess.as_ref().expect("session opened above");
+    // Blocking, but bounded, while the channel opens; pumping is non-blocking.
+    session.set_blocking(true);
+    session.set_timeout((ssh::connect_timeout() * 1000) as u32);
+    let channel = session.channel_direct_tcpip(ssh::bare_host(&tunnel.remote_host), tunnel.remote_port, None);
+    let alive = channel.is_ok() || session.keepalive_send().is_ok();
+    session.set_timeout(0);
+    session.set_blocking(false);
+    match channel {
+        Ok(channel) => Ok((channel, stream)),
+        Err(e) => {
+            if !alive {
+                *sess = None;
+            }
+            Err(format!(
+                "{} cannot reach {}: {}",
+                host.alias,
+                ssh::socket_addr(&tunnel.remote_host, tunnel.remote_port),
+                e
+            ))
+        }
+    }
+}
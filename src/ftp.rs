@@ -1,9 +1,7 @@
 use crate::config::Host;
-use ssh2::Session;
+use crate::ssh::open_session;
 use std::io::{Read, Write};
-use std::net::TcpStream;
 use std::path::Path;
-use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct FtpEntry {
@@ -11,31 +9,8 @@ pub struct FtpEntry {
     pub path: String,
     pub is_dir: bool,
     pub size: u64,
-}
-
-fn open_session(host: &Host) -> Result<Session, String> {
-    let addr = format!("{}:{}", host.hostname, host.port);
-    let tcp = TcpStream::connect_timeout(
-        &addr.parse().map_err(|e: std::net::AddrParseError| e.to_string())?,
-        Duration::from_secs(10),
-    )
-    .map_err(|e| format!("Connection failed: {}", e))?;
-
-    let mut sess = Session::new().map_err(|e| e.to_string())?;
-    sess.set_tcp_stream(tcp);
-    sess.handshake().map_err(|e| format!("Handshake failed: {}", e))?;
-
-    let authed = sess.userauth_agent(&host.username).is_ok()
-        || host
-            .password
-            .as_ref()
-            .map(|pw| sess.userauth_password(&host.username, pw).is_ok())
-            .unwrap_or(false);
-
-    if !authed || !sess.authenticated() {
-        return Err("Authentication failed".to_string());
-    }
-    Ok(sess)
+    /// Unix mode bits as reported by the server, if any.
+    pub perm: Option<u32>,
 }
 
 pub fn list_directory(host: &Host, path: &str) -> Result<Vec<FtpEntry>, String> {
@@ -58,6 +33,7 @@ pub fn list_directory(host: &Host, path: &str) -> Result<Vec<FtpEntry>, String>
                 path: pb.to_string_lossy().replace('\\', "/"),
                 is_dir: stat.is_dir(),
                 size: stat.size.unwrap_or(0),
+                perm: stat.perm,
             })
         })
         .collect();
@@ -71,36 +47,80 @@ pub fn list_directory(host: &Host, path: &str) -> Result<Vec<FtpEntry>, String>
     Ok(result)
 }
 
-pub fn download_file(host: &Host, remote_path: &str, local_path: &str) -> Result<(), String> {
+/// Chunk size for streamed transfers; progress is reported once per chunk.
+const TRANSFER_CHUNK: usize = 32 * 1024;
+
+/// Streams `from` into `to`, calling `progress(done, total)` after each chunk.
+fn copy_with_progress(
+    from: &mut impl Read,
+    to: &mut impl Write,
+    total: u64,
+    progress: &mut impl FnMut(u64, u64),
+) -> std::io::Result<u64> {
+    let mut buf = vec![0u8; TRANSFER_CHUNK];
+    let mut done = 0u64;
+    progress(0, total);
+    loop {
+        let n = from.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        to.write_all(&buf[..n])?;
+        done += n as u64;
+        progress(done, total);
+    }
+    Ok(done)
+}
+
+pub fn download_file(
+    host: &Host,
+    remote_path: &str,
+    local_path: &str,
+    mut progress: impl FnMut(u64, u64),
+) -> Result<(), String> {
     let sess = open_session(host)?;
     let sftp = sess.sftp().map_err(|e| e.to_string())?;
 
     let mut remote = sftp
         .open(Path::new(remote_path))
         .map_err(|e| format!("Cannot open remote file: {}", e))?;
+    let total = remote.stat().ok().and_then(|s| s.size).unwrap_or(0);
 
-    let mut buf = Vec::new();
-    remote
-        .read_to_end(&mut buf)
-        .map_err(|e| format!("Read error: {}", e))?;
-
-    std::fs::write(local_path, &buf).map_err(|e| format!("Write error: {}", e))?;
-    Ok(())
+    // Written beside the target and renamed into place once complete, so a
+    // failed transfer never leaves a truncated file under the real name.
+    let part_path = format!("{}.part", local_path);
+    let mut local =
+        std::fs::File::create(&part_path).map_err(|e| format!("Write error: {}", e))?;
+    let result = copy_with_progress(&mut remote, &mut local, total, &mut progress)
+        .map_err(|e| format!("Transfer error: {}", e))
+        .and_then(|_| local.sync_all().map_err(|e| format!("Write error: {}", e)))
+        .and_then(|()| std::fs::rename(&part_path, local_path).map_err(|e| format!("Write error: {}", e)));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&part_path);
+    }
+    result
 }
 
-pub fn upload_file(host: &Host, local_path: &str, remote_path: &str) -> Result<(), String> {
+pub fn upload_file(
+    host: &Host,
+    local_path: &str,
+    remote_path: &str,
+    mut progress: impl FnMut(u64, u64),
+) -> Result<(), String> {
     let sess = open_session(host)?;
     let sftp = sess.sftp().map_err(|e| e.to_string())?;
 
-    let buf = std::fs::read(local_path).map_err(|e| format!("Cannot read file: {}", e))?;
-    let size = buf.len() as u64;
+    let mut local = std::fs::File::open(local_path).map_err(|e| format!("Cannot read file: {}", e))?;
+    let size = local
+        .metadata()
+        .map_err(|e| format!("Cannot read file: {}", e))?
+        .len();
 
     let mut remote = sftp
         .create(Path::new(remote_path))
         .map_err(|e| format!("Cannot create remote file: {}", e))?;
 
-    remote
-        .write_all(&buf)
+    copy_with_progress(&mut local, &mut remote, size, &mut progress)
         .map_err(|e| format!("Upload error: {}", e))?;
 
     drop(remote);
@@ -113,6 +133,81 @@ pub fn upload_file(host: &Host, local_path: &str, remote_path: &str) -> Result<(
     Ok(())
 }
 
+pub fn delete_file(host: &Host, path: &str) -> Result<(), String> {
+    let sess = open_session(host)?;
+    let sftp = sess.sftp().map_err(|e| e.to_string())?;
+    sftp.unlink(Path::new(path))
+        .map_err(|e| format!("Cannot delete {}: {}", path, e))
+}
+
+/// Removes a directory and everything below it. Refuses `/` and the empty
+/// path, and removes symlinks rather than following them.
+pub fn delete_dir(host: &Host, path: &str) -> Result<(), String> {
+    let trimmed = path.trim_end_matches('/');
+    if trimmed.is_empty() {
+        return Err("Refusing to delete the root directory".to_string());
+    }
+    let sess = open_session(host)?;
+    let sftp = sess.sftp().map_err(|e| e.to_string())?;
+    remove_tree(&sftp, Path::new(trimmed))
+}
+
+fn remove_tree(sftp: &ssh2::Sftp, dir: &Path) -> Result<(), String> {
+    let entries = sftp
+        .readdir(dir)
+        .map_err(|e| format!("Cannot list {}: {}", dir.display(), e))?;
+    for (child, _) in entries {
+        let name = child.file_name().map(|n| n.to_string_lossy().to_string());
+        if matches!(name.as_deref(), None | Some(".") | Some("..")) {
+            continue;
+        }
+        let stat = sftp
+            .lstat(&child)
+            .map_err(|e| format!("Cannot stat {}: {}", child.display(), e))?;
+        if stat.is_dir() {
+            remove_tree(sftp, &child)?;
+        } else {
+            sftp.unlink(&child)
+                .map_err(|e| format!("Cannot delete {}: {}", child.display(), e))?;
+        }
+    }
+    sftp.rmdir(dir)
+        .map_err(|e| format!("Cannot remove {}: {}", dir.display(), e))
+}
+
+pub fn make_dir(host: &Host, path: &str) -> Result<(), String> {
+    let sess = open_session(host)?;
+    let sftp = sess.sftp().map_err(|e| e.to_string())?;
+    if sftp.lstat(Path::new(path)).is_ok() {
+        return Err(format!("{} already exists", path));
+    }
+    sftp.mkdir(Path::new(path), 0o755)
+        .map_err(|e| format!("Cannot create {}: {}", path, e))
+}
+
+/// Sets the permission bits (e.g. `0o644`) of a remote file or directory.
+pub fn set_permissions(host: &Host, path: &str, mode: u32) -> Result<(), String> {
+    let sess = open_session(host)?;
+    let sftp = sess.sftp().map_err(|e| e.to_string())?;
+    let stat = ssh2::FileStat {
+        size: None,
+        uid: None,
+        gid: None,
+        perm: Some(mode & 0o7777),
+        atime: None,
+        mtime: None,
+    };
+    sftp.setstat(Path::new(path), stat)
+        .map_err(|e| format!("Cannot chmod {}: {}", path, e))
+}
+
+pub fn rename(host: &Host, from: &str, to: &str) -> Result<(), String> {
+    let sess = open_session(host)?;
+    let sftp = sess.sftp().map_err(|e| e.to_string())?;
+    sftp.rename(Path::new(from), Path::new(to), None)
+        .map_err(|e| format!("Cannot rename {}: {}", from, e))
+}
+
 pub fn search_files(host: &Host, start_path: &str, query: &str) -> Result<Vec<FtpEntry>, String> {
     let sess = open_session(host)?;
     let mut channel = sess.channel_session().map_err(|e| e.to_string())?;
@@ -157,6 +252,7 @@ pub fn search_files(host: &Host, start_path: &str, query: &str) -> Result<Vec<Ft
                 path: path.to_string(),
                 is_dir,
                 size: 0,
+                perm: None,
             })
         })
         .collect();
@@ -187,3 +283,35 @@ pub fn format_size(bytes: u64) -> String {
         format!("{:.2} GB", bytes as f64 / 1024.0 / 1024.0 / 1024.0)
     }
 }
+
+/// Renders the permission bits as `rwxr-xr-x`, with setuid/setgid shown as
+/// `s`/`S` and the sticky bit as `t`/`T` in place of the execute bit, like `ls -l`.
+pub fn format_mode(mode: u32) -> String {
+    let mut out = String::with_capacity(9);
+    for (shift, special, mark) in [(6, 0o4000, 's'), (3, 0o2000, 's'), (0, 0o1000, 't')] {
+        let bits = (mode >> shift) & 0o7;
+        out.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        out.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        out.push(match (bits & 0o1 != 0, mode & special != 0) {
+            (true, true) => mark,
+            (false, true) => mark.to_ascii_uppercase(),
+            (true, false) => 'x',
+            (false, false) => '-',
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modes_render_like_ls() {
+        assert_eq!(format_mode(0o755), "rwxr-xr-x");
+        assert_eq!(format_mode(0o4755), "rwsr-xr-x");
+        assert_eq!(format_mode(0o2750), "rwxr-s---");
+        assert_eq!(format_mode(0o1777), "rwxrwxrwt");
+        assert_eq!(format_mode(0o7644), "rwSr-Sr-T");
+    }
+}
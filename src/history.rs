@@ -0,0 +1,199 @@
+//! Per-host command history, kept under the data directory (or the
+//! `--config` directory) so suggestions and the history bar survive restarts. One command per line, oldest first;
+//! run counts live next to it in `<alias>.counts` as `count<TAB>command`.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::{config, session_log};
+
+/// Commands kept per host.
+pub const MAX_ENTRIES: usize = 50;
+
+/// Distinct commands whose run count is remembered per host.
+const MAX_COUNTED: usize = 500;
+
+/// How often each command was run on a host.
+pub type Counts = HashMap<String, u32>;
+
+/// `<data dir>/termissh/history`, next to the session logs, or `history`
+/// inside the config directory when one was given.
+pub fn history_dir() -> Option<PathBuf> {
+    if let Some(dir) = config::config_dir_override() {
+        return Some(dir.join("history"));
+    }
+    directories::BaseDirs::new().map(|d| d.data_dir().join("termissh").join("history"))
+}
+
+/// Writes `data` readable by the owner only, in a directory only the owner
+/// can enter; history can hold hostnames, paths and arguments worth hiding.
+fn write_private(path: &Path, data: &str) {
+    let Some(dir) = path.parent() else { return; };
+    if fs::create_dir_all(dir).is_err() {
+        return;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        let _ = fs::set_permissions(dir, fs::Permissions::from_mode(0o700));
+        options.mode(0o600);
+        // `mode` only applies to new files; tighten ones left by older versions.
+        if path.exists() {
+            let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o600));
+        }
+    }
+    if let Ok(mut file) = options.open(path) {
+        let _ = file.write_all(data.as_bytes());
+    }
+}
+
+fn history_path(alias: &str) -> Option<PathBuf> {
+    history_dir().map(|dir| dir.join(session_log::file_stem(alias)))
+}
+
+fn counts_path(alias: &str) -> Option<PathBuf> {
+    history_dir().map(|dir| dir.join(format!("{}.counts", session_log::file_stem(alias))))
+}
+
+/// Saved history of `alias`; empty when there is none yet.
+pub fn load(alias: &str) -> VecDeque<String> {
+    let Some(data) = history_path(alias).and_then(|p| fs::read_to_string(p).ok()) else {
+        return VecDeque::new();
+    };
+    let mut history: VecDeque<String> = data
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(str::to_string)
+        .collect();
+    while history.len() > MAX_ENTRIES {
+        history.pop_front();
+    }
+    history
+}
+
+/// Appends `cmd` unless it repeats the last entry, dropping the oldest
+/// beyond `MAX_ENTRIES`. Returns whether anything changed.
+pub fn push(history: &mut VecDeque<String>, cmd: &str) -> bool {
+    if history.back().map(String::as_str) == Some(cmd) {
+        return false;
+    }
+    history.push_back(cmd.to_string());
+    if history.len() > MAX_ENTRIES {
+        history.pop_front();
+    }
+    true
+}
+
+/// Writes the history of `alias`. Failures are ignored; history is a convenience.
+pub fn save(alias: &str, history: &VecDeque<String>) {
+    let Some(path) = history_path(alias) else { return; };
+    let mut data = String::new();
+    for cmd in history {
+        data.push_str(cmd);
+        data.push('\n');
+    }
+    write_private(&path, &data);
+}
+
+/// Saved run counts of `alias`; malformed lines are skipped.
+pub fn load_counts(alias: &str) -> Counts {
+    let Some(data) = counts_path(alias).and_then(|p| fs::read_to_string(p).ok()) else {
+        return Counts::new();
+    };
+    data.lines()
+        .filter_map(|l| {
+            let (count, cmd) = l.split_once('\t')?;
+            Some((cmd.to_string(), count.parse().ok()?))
+        })
+        .collect()
+}
+
+/// Counts one more run of `cmd`. Past `MAX_COUNTED` commands the least
+/// used one is forgotten.
+pub fn count(counts: &mut Counts, cmd: &str) {
+    *counts.entry(cmd.to_string()).or_insert(0) += 1;
+    if counts.len() > MAX_COUNTED {
+        let rarest = counts
+            .iter()
+            .filter(|(c, _)| c.as_str() != cmd)
+            .min_by_key(|(_, n)| **n)
+            .map(|(c, _)| c.clone());
+        if let Some(rarest) = rarest {
+            counts.remove(&rarest);
+        }
+    }
+}
+
+/// Past commands starting with `prefix` (case-insensitive, the prefix
+/// itself excluded), most run first; equal counts go by recency.
+pub fn ranked<'a>(history: &'a VecDeque<String>, counts: &'a Counts, prefix: &str) -> Vec<&'a str> {
+    let prefix = prefix.to_lowercase();
+    let matches = |cmd: &str| {
+        let cl = cmd.to_lowercase();
+        cl.starts_with(&prefix) && cl != prefix
+    };
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut past: Vec<(&str, u32, usize)> = Vec::new();
+    for (recency, cmd) in history.iter().rev().enumerate() {
+        if matches(cmd) && seen.insert(cmd) {
+            past.push((cmd, counts.get(cmd).copied().unwrap_or(1), recency));
+        }
+    }
+    for (cmd, n) in counts {
+        if matches(cmd) && seen.insert(cmd) {
+            past.push((cmd, *n, usize::MAX));
+        }
+    }
+    past.sort_by(|a, b| b.1.cmp(&a.1).then(a.2.cmp(&b.2)));
+    past.into_iter().map(|(cmd, _, _)| cmd).collect()
+}
+
+pub fn save_counts(alias: &str, counts: &Counts) {
+    let Some(path) = counts_path(alias) else { return; };
+    let mut data = String::new();
+    for (cmd, n) in counts {
+        data.push_str(&format!("{}\t{}\n", n, cmd));
+    }
+    write_private(&path, &data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frequent_commands_outrank_recent_ones() {
+        let mut history = VecDeque::new();
+        let mut counts = Counts::new();
+        for _ in 0..10 {
+            push(&mut history, "git status");
+            count(&mut counts, "git status");
+        }
+        push(&mut history, "git stash");
+        count(&mut counts, "git stash");
+        assert_eq!(ranked(&history, &counts, "git st"), ["git status", "git stash"]);
+    }
+
+    #[test]
+    fn equal_counts_go_by_recency() {
+        let mut history = VecDeque::new();
+        let mut counts = Counts::new();
+        for cmd in ["ls -l", "ls -a"] {
+            push(&mut history, cmd);
+            count(&mut counts, cmd);
+        }
+        assert_eq!(ranked(&history, &counts, "LS"), ["ls -a", "ls -l"]);
+    }
+
+    #[test]
+    fn counted_commands_beyond_history_are_included() {
+        let history = VecDeque::new();
+        let counts = Counts::from([("make test".to_string(), 3)]);
+        assert_eq!(ranked(&history, &counts, "make"), ["make test"]);
+        assert!(ranked(&history, &counts, "make test").is_empty());
+    }
+}
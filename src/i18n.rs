@@ -1,3 +1,7 @@
+//! UI strings. Each language is a key/value table; a key missing from a
+//! table falls back to English, so a new language only needs its table and
+//! a `Language` variant.
+
 use crate::config::Language;
 
 pub struct Texts {
@@ -26,6 +30,13 @@ pub struct Texts {
     pub port: &'static str,
     pub username: &'static str,
     pub password: &'static str,
+    pub identity_file: &'static str,
+    pub jump_host: &'static str,
+    pub group: &'static str,
+    pub tags: &'static str,
+    pub on_connect: &'static str,
+    pub collect_stats: &'static str,
+    pub environment: &'static str,
     pub api_key: &'static str,
     pub api_url: &'static str,
     pub sync_status_connected: &'static str,
@@ -33,73 +44,179 @@ pub struct Texts {
     pub welcome_msg: &'static str,
 }
 
+const EN: &[(&str, &str)] = &[
+    ("title", "Servers"),
+    ("new_server", "New Server"),
+    ("edit_server", "Edit Server"),
+    ("delete_confirm", "Are you sure you want to delete this server?"),
+    ("search_placeholder", "Search..."),
+    ("connection", "Connection"),
+    ("no_commands", "No commands yet"),
+    ("command_history", "Command History"),
+    ("quick_commands", "Quick Commands"),
+    ("api_key_settings", "API Settings"),
+    ("ssh_exit", "[ESC] Exit"),
+    ("system", "System"),
+    ("macros", "Macros"),
+    ("connect", "Connect"),
+    ("cancel", "Cancel"),
+    ("save", "Save"),
+    ("delete", "Delete"),
+    ("settings", "Settings"),
+    ("ping_all", "Ping"),
+    ("new_connection", "New"),
+    ("alias", "Alias"),
+    ("hostname", "Hostname"),
+    ("port", "Port"),
+    ("username", "Username"),
+    ("password", "Password"),
+    ("identity_file", "Key File (optional)"),
+    ("jump_host", "Jump Host (user@host:port)"),
+    ("group", "Group"),
+    ("tags", "Tags (comma separated)"),
+    ("on_connect", "Run on Connect (optional)"),
+    ("collect_stats", "Show remote CPU/RAM/disk usage (polls the server)"),
+    ("environment", "Environment Variables"),
+    ("api_key", "API Key"),
+    ("api_url", "API URL"),
+    ("sync_status_connected", "synced"),
+    ("sync_status_local", "local"),
+    ("welcome_msg", "Select a server to connect"),
+];
+
+const TR: &[(&str, &str)] = &[
+    ("title", "Sunucular"),
+    ("new_server", "Yeni Sunucu"),
+    ("edit_server", "Sunucu Düzenle"),
+    ("delete_confirm", "Bu sunucuyu silmek istediğinize emin misiniz?"),
+    ("search_placeholder", "Ara..."),
+    ("connection", "Bağlantı"),
+    ("no_commands", "Henüz komut yok"),
+    ("command_history", "Komut Geçmişi"),
+    ("quick_commands", "Hızlı Komutlar"),
+    ("api_key_settings", "API Ayarları"),
+    ("ssh_exit", "[ESC]"),
+    ("system", "Sistem"),
+    ("macros", "Makrolar"),
+    ("connect", "Bağlan"),
+    ("cancel", "İptal"),
+    ("save", "Kaydet"),
+    ("delete", "Sil"),
+    ("settings", "Ayarlar"),
+    ("ping_all", "Ping"),
+    ("new_connection", "Yeni"),
+    ("alias", "Takma Ad"),
+    ("hostname", "Sunucu"),
+    ("port", "Port"),
+    ("username", "Kullanıcı"),
+    ("password", "Şifre"),
+    ("identity_file", "Anahtar Dosyası (isteğe bağlı)"),
+    ("jump_host", "Atlama Sunucusu (kullanıcı@sunucu:port)"),
+    ("group", "Grup"),
+    ("tags", "Etiketler (virgülle ayrılmış)"),
+    ("on_connect", "Bağlanınca Çalıştır (isteğe bağlı)"),
+    ("collect_stats", "Uzak CPU/RAM/disk kullanımını göster (sunucuyu yoklar)"),
+    ("environment", "Ortam Değişkenleri"),
+    ("api_key", "API Anahtarı"),
+    ("api_url", "API URL"),
+    ("sync_status_connected", "senkron"),
+    ("sync_status_local", "yerel"),
+    ("welcome_msg", "Bağlanmak için bir sunucu seçin"),
+];
+
+const DE: &[(&str, &str)] = &[
+    ("title", "Server"),
+    ("new_server", "Neuer Server"),
+    ("edit_server", "Server bearbeiten"),
+    ("delete_confirm", "Möchten Sie diesen Server wirklich löschen?"),
+    ("search_placeholder", "Suchen..."),
+    ("connection", "Verbindung"),
+    ("no_commands", "Noch keine Befehle"),
+    ("command_history", "Befehlsverlauf"),
+    ("quick_commands", "Schnellbefehle"),
+    ("api_key_settings", "API-Einstellungen"),
+    ("ssh_exit", "[ESC] Beenden"),
+    ("system", "System"),
+    ("macros", "Makros"),
+    ("connect", "Verbinden"),
+    ("cancel", "Abbrechen"),
+    ("save", "Speichern"),
+    ("delete", "Löschen"),
+    ("settings", "Einstellungen"),
+    ("ping_all", "Ping"),
+    ("new_connection", "Neu"),
+    ("alias", "Alias"),
+    ("hostname", "Hostname"),
+    ("port", "Port"),
+    ("username", "Benutzername"),
+    ("password", "Passwort"),
+    ("identity_file", "Schlüsseldatei (optional)"),
+    ("jump_host", "Jump-Host (benutzer@host:port)"),
+    ("group", "Gruppe"),
+    ("tags", "Tags (durch Komma getrennt)"),
+    ("on_connect", "Beim Verbinden ausführen (optional)"),
+    ("collect_stats", "CPU/RAM/Disk des Servers anzeigen (fragt den Server ab)"),
+    ("environment", "Umgebungsvariablen"),
+    ("api_key", "API-Schlüssel"),
+    ("api_url", "API-URL"),
+    ("sync_status_connected", "synchronisiert"),
+    ("sync_status_local", "lokal"),
+    ("welcome_msg", "Wählen Sie einen Server zum Verbinden"),
+];
+
+fn table(lang: Language) -> &'static [(&'static str, &'static str)] {
+    match lang {
+        Language::English => EN,
+        Language::Turkish => TR,
+        Language::German => DE,
+    }
+}
+
+fn lookup(table: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    table.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
 impl Texts {
     pub fn get(lang: Language) -> Self {
-        match lang {
-            Language::Turkish => Self {
-                title: "Sunucular",
-                new_server: "Yeni Sunucu",
-                edit_server: "Sunucu Düzenle",
-                delete_confirm: "Bu sunucuyu silmek istediğinize emin misiniz?",
-                search_placeholder: "Ara...",
-                connection: "Bağlantı",
-                no_commands: "Henüz komut yok",
-                command_history: "Komut Geçmişi",
-                quick_commands: "Hızlı Komutlar",
-                api_key_settings: "API Ayarları",
-                ssh_exit: "[ESC]",
-                system: "Sistem",
-                macros: "Makrolar",
-                connect: "Bağlan",
-                cancel: "İptal",
-                save: "Kaydet",
-                delete: "Sil",
-                settings: "Ayarlar",
-                ping_all: "Ping",
-                new_connection: "Yeni",
-                alias: "Takma Ad",
-                hostname: "Sunucu",
-                port: "Port",
-                username: "Kullanıcı",
-                password: "Şifre",
-                api_key: "API Anahtarı",
-                api_url: "API URL",
-                sync_status_connected: "senkron",
-                sync_status_local: "yerel",
-                welcome_msg: "Bağlanmak için bir sunucu seçin",
-            },
-            Language::English => Self {
-                title: "Servers",
-                new_server: "New Server",
-                edit_server: "Edit Server",
-                delete_confirm: "Are you sure you want to delete this server?",
-                search_placeholder: "Search...",
-                connection: "Connection",
-                no_commands: "No commands yet",
-                command_history: "Command History",
-                quick_commands: "Quick Commands",
-                api_key_settings: "API Settings",
-                ssh_exit: "[ESC] Exit",
-                system: "System",
-                macros: "Macros",
-                connect: "Connect",
-                cancel: "Cancel",
-                save: "Save",
-                delete: "Delete",
-                settings: "Settings",
-                ping_all: "Ping",
-                new_connection: "New",
-                alias: "Alias",
-                hostname: "Hostname",
-                port: "Port",
-                username: "Username",
-                password: "Password",
-                api_key: "API Key",
-                api_url: "API URL",
-                sync_status_connected: "synced",
-                sync_status_local: "local",
-                welcome_msg: "Select a server to connect",
-            },
+        let t = |key: &'static str| lookup(table(lang), key).or_else(|| lookup(EN, key)).unwrap_or(key);
+        Self {
+            title: t("title"),
+            new_server: t("new_server"),
+            edit_server: t("edit_server"),
+            delete_confirm: t("delete_confirm"),
+            search_placeholder: t("search_placeholder"),
+            connection: t("connection"),
+            no_commands: t("no_commands"),
+            command_history: t("command_history"),
+            quick_commands: t("quick_commands"),
+            api_key_settings: t("api_key_settings"),
+            ssh_exit: t("ssh_exit"),
+            system: t("system"),
+            macros: t("macros"),
+            connect: t("connect"),
+            cancel: t("cancel"),
+            save: t("save"),
+            delete: t("delete"),
+            settings: t("settings"),
+            ping_all: t("ping_all"),
+            new_connection: t("new_connection"),
+            alias: t("alias"),
+            hostname: t("hostname"),
+            port: t("port"),
+            username: t("username"),
+            password: t("password"),
+            identity_file: t("identity_file"),
+            jump_host: t("jump_host"),
+            group: t("group"),
+            tags: t("tags"),
+            on_connect: t("on_connect"),
+            collect_stats: t("collect_stats"),
+            environment: t("environment"),
+            api_key: t("api_key"),
+            api_url: t("api_url"),
+            sync_status_connected: t("sync_status_connected"),
+            sync_status_local: t("sync_status_local"),
+            welcome_msg: t("welcome_msg"),
         }
     }
 }
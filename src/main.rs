@@ -3,8 +3,15 @@
 mod api;
 mod app;
 mod config;
+mod forward;
 mod ftp;
+mod history;
 mod i18n;
+mod placeholders;
+mod remote_audit;
+mod session_log;
+mod ssh;
+mod ssh_config;
 mod syspanel;
 mod terminal;
 mod theme;
@@ -25,11 +32,32 @@ fn app_icon() -> Option<iced::window::Icon> {
     iced::window::icon::from_file_data(include_bytes!("icons/mini-icon.png"), None).ok()
 }
 
+/// Value of `--config <dir>` / `--config=<dir>`, if given.
+fn config_dir_arg() -> Option<std::path::PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args.next().map(Into::into);
+        }
+        if let Some(dir) = arg.strip_prefix("--config=") {
+            return Some(dir.into());
+        }
+    }
+    None
+}
+
 fn main() -> iced::Result {
+    if terminal::relay_mode::is_version_query() {
+        println!("{}", terminal::relay_mode::relay_version());
+        return Ok(());
+    }
     if terminal::relay_mode::is_internal_relay_mode() {
         terminal::relay_mode::run_from_env();
         return Ok(());
     }
+    if let Some(dir) = config_dir_arg() {
+        config::set_config_dir(dir);
+    }
 
     iced::application(App::title, App::update, App::view)
         .window(iced::window::Settings {
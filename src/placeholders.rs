@@ -0,0 +1,171 @@
+//! `{name}` placeholders in custom command scripts.
+//!
+//! `{host}`, `{user}` and `{port}` come from the tab's host; any other
+//! `{name}` is asked for before the script is sent. `\{name}` produces a
+//! literal `{name}`. Shell syntax such as `${VAR}`, `{a,b}` or `{1..3}` is
+//! left alone because it isn't a plain identifier in braces, and so are
+//! doubled braces as in Go templates (`{{.Names}}`) or Jinja (`{{name}}`).
+
+use std::collections::HashMap;
+
+use crate::config::Host;
+
+const HOST_VARS: &[&str] = &["host", "user", "port"];
+
+enum Piece<'a> {
+    Text(&'a str),
+    Var(&'a str),
+}
+
+/// Splits `script` into literal text and placeholder names.
+fn pieces(script: &str) -> Vec<Piece<'_>> {
+    let mut out = Vec::new();
+    let bytes = script.as_bytes();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'{' || (i > 0 && matches!(bytes[i - 1], b'$' | b'{')) {
+            i += 1;
+            continue;
+        }
+        let name_len = script[i + 1..]
+            .bytes()
+            .take_while(|b| b.is_ascii_alphanumeric() || *b == b'_')
+            .count();
+        let end = i + 1 + name_len;
+        if name_len == 0 || bytes.get(end) != Some(&b'}') || bytes.get(end + 1) == Some(&b'}') {
+            i += 1;
+            continue;
+        }
+        if i > 0 && bytes[i - 1] == b'\\' {
+            // Escaped: drop the backslash, keep `{name}` as written.
+            out.push(Piece::Text(&script[start..i - 1]));
+            start = i;
+        } else {
+            out.push(Piece::Text(&script[start..i]));
+            out.push(Piece::Var(&script[i + 1..end]));
+            start = end + 1;
+        }
+        i = end + 1;
+    }
+    out.push(Piece::Text(&script[start..]));
+    out
+}
+
+/// Placeholder names in `script` that the host can't fill in, in order of
+/// first appearance.
+pub fn prompt_names(script: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for piece in pieces(script) {
+        if let Piece::Var(name) = piece {
+            if !HOST_VARS.contains(&name) && !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Substitutes host variables and `prompts` into `script`. Unknown
+/// placeholders without a prompt value are kept as written.
+pub fn expand_command(script: &str, host: &Host, prompts: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(script.len());
+    for piece in pieces(script) {
+        match piece {
+            Piece::Text(t) => out.push_str(t),
+            Piece::Var("host") => out.push_str(&host.hostname),
+            Piece::Var("user") => out.push_str(&host.username),
+            Piece::Var("port") => out.push_str(&host.port.to_string()),
+            Piece::Var(name) => match prompts.get(name) {
+                Some(value) => out.push_str(value),
+                None => {
+                    out.push('{');
+                    out.push_str(name);
+                    out.push('}');
+                }
+            },
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host() -> Host {
+        Host {
+            hostname: "web1.example.com".to_string(),
+            username: "deploy".to_string(),
+            port: 2222,
+            ..Default::default()
+        }
+    }
+
+    fn expand(script: &str, prompts: &[(&str, &str)]) -> String {
+        let prompts = prompts.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        expand_command(script, &host(), &prompts)
+    }
+
+    #[test]
+    fn host_placeholders_are_filled_in() {
+        assert_eq!(expand("ssh -p {port} {user}@{host}", &[]), "ssh -p 2222 deploy@web1.example.com");
+        assert!(prompt_names("echo {host} {user} {port}").is_empty());
+    }
+
+    #[test]
+    fn unknown_placeholders_are_prompted_once_in_order() {
+        assert_eq!(prompt_names("cp {src} {dest} && ls {src}"), ["src", "dest"]);
+    }
+
+    #[test]
+    fn prompt_values_are_substituted() {
+        assert_eq!(expand("tail -n {lines} {file}", &[("lines", "50"), ("file", "/var/log/syslog")]),
+            "tail -n 50 /var/log/syslog");
+    }
+
+    #[test]
+    fn missing_prompt_values_are_kept_as_written() {
+        assert_eq!(expand("restart {service}", &[]), "restart {service}");
+    }
+
+    #[test]
+    fn unterminated_and_non_identifier_braces_are_literal() {
+        for script in ["echo {host", "echo {}", "echo {a b}", "echo {a-b}", "trailing {"] {
+            assert!(prompt_names(script).is_empty(), "{script}");
+            assert_eq!(expand(script, &[]), script);
+        }
+    }
+
+    #[test]
+    fn shell_syntax_is_left_alone() {
+        for script in ["echo ${HOME}", "echo {a,b}", "echo {1..3}"] {
+            assert!(prompt_names(script).is_empty(), "{script}");
+            assert_eq!(expand(script, &[]), script);
+        }
+    }
+
+    #[test]
+    fn backslash_escapes_a_placeholder() {
+        assert_eq!(expand(r"echo \{host} {host}", &[]), "echo {host} web1.example.com");
+        assert!(prompt_names(r"echo \{name}").is_empty());
+        // Only in front of a placeholder; other backslashes are the shell's.
+        assert_eq!(expand(r"echo \{a,b\} \\n", &[]), r"echo \{a,b\} \\n");
+    }
+
+    #[test]
+    fn nested_braces_are_left_alone() {
+        for script in [
+            "docker ps --format '{{.Names}}'",
+            "docker inspect -f '{{ .State.Status }}' {{name}}",
+            "echo '{{host}}'",
+            "awk '{print $1}'",
+            "awk '{{print $1}}'",
+            "jq '{a: {b: .c}}'",
+        ] {
+            assert!(prompt_names(script).is_empty(), "{script}");
+            assert_eq!(expand(script, &[]), script);
+        }
+        assert_eq!(expand("docker logs {{.ID}} {host}", &[]), "docker logs {{.ID}} web1.example.com");
+    }
+}
@@ -0,0 +1,192 @@
+//! Opt-in "deep scan" for the security audit: logs into a saved host and
+//! checks its sshd settings, exposed ports, pending updates and
+//! world-writable files under `/etc`.
+
+use std::io::Read;
+
+use crate::app::{SecurityFinding, SecuritySeverity};
+use crate::config::Host;
+
+/// Ports that should rarely be reachable from outside the host.
+const SENSITIVE_PORTS: &[(u16, &str)] = &[
+    (2375, "Docker API"),
+    (3306, "MySQL"),
+    (5432, "PostgreSQL"),
+    (6379, "Redis"),
+    (9200, "Elasticsearch"),
+    (11211, "Memcached"),
+    (27017, "MongoDB"),
+];
+
+const SCAN_CMD: &str = r#"echo "=== SSHD ==="; \
+(sudo -n sshd -T 2>/dev/null || cat /etc/ssh/sshd_config 2>/dev/null) | grep -iE '^[[:space:]]*(permitrootlogin|passwordauthentication)[[:space:]]'; \
+echo "=== PORTS ==="; \
+if command -v ss >/dev/null 2>&1; then ss -tln | awk 'NR>1 {print $4}'; \
+else netstat -tln 2>/dev/null | awk 'NR>2 {print $4}'; fi; \
+echo "=== UPDATES ==="; \
+if command -v apt-get >/dev/null 2>&1; then apt-get -s upgrade 2>/dev/null | grep -c '^Inst'; \
+elif command -v dnf >/dev/null 2>&1; then dnf -q check-update 2>/dev/null | grep -c '^[^[:space:]]'; \
+elif command -v yum >/dev/null 2>&1; then yum -q check-update 2>/dev/null | grep -c '^[^[:space:]]'; \
+else echo "?"; fi; \
+echo "=== WORLD_WRITABLE ==="; \
+find /etc -xdev -type f -perm -0002 2>/dev/null | head -20"#;
+
+/// Runs every remote check against `host`. Connection problems come back as
+/// a single Info finding so one unreachable host doesn't hide the rest.
+pub fn scan_host(host: &Host) -> Vec<SecurityFinding> {
+    match run(host) {
+        Ok(output) => parse(host, &output),
+        Err(e) => vec![finding(
+            SecuritySeverity::Info,
+            "Deep Scan",
+            format!("Could not scan '{}': {}", host.alias, e),
+            "A host could not be scanned".into(),
+        )],
+    }
+}
+
+/// How long the scan may go without output before the host is given up on.
+/// The update check prints nothing until it is done, so this is generous.
+const SCAN_TIMEOUT_SECS: u32 = 120;
+
+fn run(host: &Host) -> Result<String, String> {
+    let sess = crate::ssh::open_session(host)?;
+    sess.set_timeout(SCAN_TIMEOUT_SECS * 1000);
+    let mut ch = sess.channel_session().map_err(|e| e.to_string())?;
+    ch.exec(SCAN_CMD).map_err(|e| e.to_string())?;
+    let mut out = String::new();
+    ch.read_to_string(&mut out).map_err(|e| match e.kind() {
+        std::io::ErrorKind::TimedOut => format!("no answer within {SCAN_TIMEOUT_SECS}s"),
+        _ => e.to_string(),
+    })?;
+    ch.wait_close().ok();
+    Ok(out)
+}
+
+fn finding(severity: SecuritySeverity, category: &str, message: String, redacted: String) -> SecurityFinding {
+    SecurityFinding {
+        severity,
+        category: category.to_string(),
+        message,
+        redacted,
+        action: None,
+    }
+}
+
+fn parse(host: &Host, output: &str) -> Vec<SecurityFinding> {
+    let alias = &host.alias;
+    let mut findings = Vec::new();
+    let mut section = "";
+    let mut root_login: Option<String> = None;
+    let mut password_auth: Option<String> = None;
+    let mut exposed: Vec<u16> = Vec::new();
+    let mut updates: Option<u32> = None;
+    let mut writable: Vec<String> = Vec::new();
+
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix("=== ").and_then(|l| l.strip_suffix(" ===")) {
+            section = name;
+            continue;
+        }
+        if line.is_empty() {
+            continue;
+        }
+        match section {
+            "SSHD" => {
+                let mut parts = line.split_whitespace();
+                let (Some(key), Some(value)) = (parts.next(), parts.next()) else { continue; };
+                // Like sshd itself, the first occurrence wins.
+                let slot = match key.to_lowercase().as_str() {
+                    "permitrootlogin" => &mut root_login,
+                    "passwordauthentication" => &mut password_auth,
+                    _ => continue,
+                };
+                slot.get_or_insert_with(|| value.to_lowercase());
+            }
+            "PORTS" => {
+                // `0.0.0.0:3306`, `[::]:80`, `*:22`, `127.0.0.1:5432`
+                let Some((addr, port)) = line.rsplit_once(':') else { continue; };
+                let Ok(port) = port.parse::<u16>() else { continue; };
+                let loopback = addr.starts_with("127.") || addr == "[::1]" || addr == "::1";
+                if !loopback && !exposed.contains(&port) {
+                    exposed.push(port);
+                }
+            }
+            "UPDATES" => updates = line.parse().ok(),
+            "WORLD_WRITABLE" => writable.push(line.to_string()),
+            _ => {}
+        }
+    }
+
+    if root_login.as_deref() == Some("yes") {
+        findings.push(finding(
+            SecuritySeverity::High,
+            "SSH Daemon",
+            format!("'{alias}' allows root login with a password (PermitRootLogin yes)"),
+            "A host allows root login with a password".into(),
+        ));
+    }
+    if password_auth.as_deref() == Some("yes") {
+        findings.push(finding(
+            SecuritySeverity::Medium,
+            "SSH Daemon",
+            format!("'{alias}' accepts password authentication (PasswordAuthentication yes)"),
+            "A host accepts password authentication".into(),
+        ));
+    }
+
+    exposed.sort_unstable();
+    for &(port, service) in SENSITIVE_PORTS {
+        if exposed.contains(&port) {
+            findings.push(finding(
+                SecuritySeverity::High,
+                "Network",
+                format!("'{alias}' exposes {service} on port {port} to all interfaces"),
+                format!("A host exposes {service} to all interfaces"),
+            ));
+        }
+    }
+    // Anything besides SSH itself is worth a look, even if intended.
+    let unexpected: Vec<String> = exposed
+        .iter()
+        .filter(|p| **p != host.port && **p != 22 && !SENSITIVE_PORTS.iter().any(|(s, _)| s == *p))
+        .map(u16::to_string)
+        .collect();
+    if !unexpected.is_empty() {
+        findings.push(finding(
+            SecuritySeverity::Low,
+            "Network",
+            format!("'{alias}' listens publicly on port(s) {}", unexpected.join(", ")),
+            "A host listens on additional public ports".into(),
+        ));
+    }
+
+    if let Some(n) = updates.filter(|n| *n > 0) {
+        findings.push(finding(
+            SecuritySeverity::Medium,
+            "Updates",
+            format!("'{alias}' has {n} pending package update(s)"),
+            "A host has pending package updates".into(),
+        ));
+    }
+
+    if !writable.is_empty() {
+        findings.push(finding(
+            SecuritySeverity::High,
+            "Filesystem",
+            format!("'{alias}' has world-writable files in /etc: {}", writable.join(", ")),
+            "A host has world-writable files in /etc".into(),
+        ));
+    }
+
+    if findings.is_empty() {
+        findings.push(finding(
+            SecuritySeverity::Info,
+            "Deep Scan",
+            format!("'{alias}' passed all remote checks"),
+            "A host passed all remote checks".into(),
+        ));
+    }
+    findings
+}
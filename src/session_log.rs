@@ -0,0 +1,174 @@
+//! Terminal session logs: on-demand scrollback export and opt-in streaming
+//! of everything a tab receives to `<data dir>/termissh/logs` (or `logs` in
+//! the `--config` directory).
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use vt100::Parser;
+
+/// `~/.local/share/termissh/logs` on Linux, the platform data dir elsewhere;
+/// next to the config when its directory was overridden.
+pub fn logs_dir() -> Option<PathBuf> {
+    if let Some(dir) = crate::config::config_dir_override() {
+        return Some(dir.join("logs"));
+    }
+    directories::BaseDirs::new().map(|d| d.data_dir().join("termissh").join("logs"))
+}
+
+/// Current UTC time as `YYYYMMDD-HHMMSS`, for log file names.
+pub fn timestamp() -> String {
+    let (year, month, day, h, m, s) = utc_now();
+    format!("{:04}{:02}{:02}-{:02}{:02}{:02}", year, month, day, h, m, s)
+}
+
+/// Current UTC time in RFC 3339 form (`2024-05-01T12:00:00Z`), for reports.
+pub fn iso_timestamp() -> String {
+    let (year, month, day, h, m, s) = utc_now();
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, h, m, s)
+}
+
+/// Unix time `secs` as `YYYY-MM-DD HH:MM UTC`, for listings.
+pub fn format_utc(secs: u64) -> String {
+    let (year, month, day, h, m, _) = utc_parts(secs);
+    format!("{:04}-{:02}-{:02} {:02}:{:02} UTC", year, month, day, h, m)
+}
+
+fn utc_now() -> (i64, i64, i64, u64, u64, u64) {
+    utc_parts(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    )
+}
+
+/// `(year, month, day, hour, minute, second)` in UTC.
+fn utc_parts(secs: u64) -> (i64, i64, i64, u64, u64, u64) {
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+/// File-name-safe version of a host alias.
+pub fn file_stem(alias: &str) -> String {
+    let stem: String = alias
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect();
+    if stem.is_empty() { "session".to_string() } else { stem }
+}
+
+/// Full scrollback plus the visible screen, oldest line first.
+pub fn scrollback_bytes(parser: &mut Parser, keep_ansi: bool) -> Vec<u8> {
+    let previous = parser.screen().scrollback();
+    parser.set_scrollback(usize::MAX);
+    let depth = parser.screen().scrollback();
+    let (rows, cols) = parser.screen().size();
+
+    let mut out = Vec::new();
+    let mut push_rows = |parser: &Parser, count: usize| {
+        let screen = parser.screen();
+        if keep_ansi {
+            for line in screen.rows_formatted(0, cols).take(count) {
+                out.extend_from_slice(&line);
+                out.extend_from_slice(b"\x1b[0m\n");
+            }
+        } else {
+            for line in screen.rows(0, cols).take(count) {
+                out.extend_from_slice(line.trim_end().as_bytes());
+                out.push(b'\n');
+            }
+        }
+    };
+    // Scrolling back by `offset` puts scrollback line `depth - offset` on row 0.
+    for offset in (1..=depth).rev() {
+        parser.set_scrollback(offset);
+        push_rows(parser, 1);
+    }
+    parser.set_scrollback(0);
+    push_rows(parser, rows as usize);
+
+    parser.set_scrollback(previous);
+    out
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum Escape {
+    #[default]
+    None,
+    Start,
+    Csi,
+    Osc,
+    OscEsc,
+}
+
+/// Appends received bytes to a log file, optionally stripping escape sequences.
+pub struct SessionLog {
+    file: File,
+    keep_ansi: bool,
+    escape: Escape,
+}
+
+impl SessionLog {
+    /// Opens a new log for `alias`, readable by the owner only: it holds
+    /// whatever the session printed, secrets included.
+    pub fn create(alias: &str, keep_ansi: bool) -> io::Result<Self> {
+        let dir = logs_dir().ok_or_else(|| io::Error::other("Cannot locate data directory"))?;
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}-{}.log", file_stem(alias), timestamp()));
+        let mut options = fs::OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            fs::set_permissions(&dir, fs::Permissions::from_mode(0o700))?;
+            options.mode(0o600);
+        }
+        Ok(Self {
+            file: options.open(path)?,
+            keep_ansi,
+            escape: Escape::None,
+        })
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        if self.keep_ansi {
+            let _ = self.file.write_all(bytes);
+            return;
+        }
+        let mut plain = Vec::with_capacity(bytes.len());
+        for &b in bytes {
+            // Sequences can straddle reads, so the parser state carries over.
+            self.escape = match (self.escape, b) {
+                (Escape::None, 0x1b) => Escape::Start,
+                (Escape::None, b'\r') => Escape::None,
+                (Escape::None, _) => {
+                    plain.push(b);
+                    Escape::None
+                }
+                (Escape::Start, b'[') => Escape::Csi,
+                (Escape::Start, b']') => Escape::Osc,
+                (Escape::Start, _) => Escape::None,
+                (Escape::Csi, 0x40..=0x7e) => Escape::None,
+                (Escape::Csi, _) => Escape::Csi,
+                (Escape::Osc, 0x07) => Escape::None,
+                (Escape::Osc, 0x1b) => Escape::OscEsc,
+                (Escape::Osc, _) => Escape::Osc,
+                (Escape::OscEsc, _) => Escape::None,
+            };
+        }
+        let _ = self.file.write_all(&plain);
+    }
+}
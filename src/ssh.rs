@@ -0,0 +1,585 @@
+//! Shared SSH session setup.
+//!
+//! The relay, SFTP panel, system panel and structure fetch all open their own
+//! `ssh2::Session`; this module owns the connect / handshake / auth ladder so
+//! they behave the same way.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::net::{Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
+
+use ssh2::{CheckResult, HashType, KnownHostFileKind, Session};
+
+use crate::config::Host;
+
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Seconds allowed for TCP connect and the SSH handshake/auth, from
+/// `AppConfig::connect_timeout_secs` (or `TERMISSH_TIMEOUT` in the relay).
+static CONNECT_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(DEFAULT_CONNECT_TIMEOUT_SECS);
+
+pub fn set_connect_timeout(secs: u64) {
+    CONNECT_TIMEOUT_SECS.store(secs.max(1), Ordering::Relaxed);
+}
+
+pub fn connect_timeout() -> u64 {
+    CONNECT_TIMEOUT_SECS.load(Ordering::Relaxed)
+}
+
+/// Seconds between keepalives on idle sessions, 0 for none, from
+/// `AppConfig::keepalive_secs` (or `TERMISSH_KEEPALIVE` in the relay).
+static KEEPALIVE_SECS: AtomicU64 = AtomicU64::new(0);
+
+pub fn set_keepalive(secs: u64) {
+    KEEPALIVE_SECS.store(secs, Ordering::Relaxed);
+}
+
+pub fn keepalive() -> u64 {
+    KEEPALIVE_SECS.load(Ordering::Relaxed)
+}
+
+pub const DEFAULT_TERM_TYPE: &str = "xterm-256color";
+
+/// PTY type requested for shells, from `AppConfig::term_type`. A host's own
+/// `TERM` variable takes precedence.
+static TERM_TYPE: RwLock<String> = RwLock::new(String::new());
+
+pub fn set_term_type(term: &str) {
+    if let Ok(mut current) = TERM_TYPE.write() {
+        *current = term.trim().to_string();
+    }
+}
+
+pub fn term_type() -> String {
+    TERM_TYPE
+        .read()
+        .ok()
+        .map(|t| t.clone())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| DEFAULT_TERM_TYPE.to_string())
+}
+
+/// Appears in the auth error when an encrypted key needs a (correct)
+/// passphrase; the GUI watches for it to prompt and retry.
+pub const PASSPHRASE_REQUIRED: &str = "key passphrase required";
+
+/// Prefix of the error for a host missing from `known_hosts`; the message
+/// ends with the key fingerprint so the GUI can ask the user to confirm it.
+pub const HOST_KEY_UNKNOWN: &str = "unknown host key";
+
+// libssh2 codes returned when a key file cannot be decrypted.
+const LIBSSH2_ERROR_FILE: i32 = -16;
+const LIBSSH2_ERROR_PUBLICKEY_UNVERIFIED: i32 = -19;
+
+/// Connects, handshakes and authenticates against `host`, going through
+/// its jump host when one is configured.
+pub fn open_session(host: &Host) -> Result<Session, String> {
+    let sess = handshake(host)?;
+    verify_host_key(&sess, &host.hostname, host.port)?;
+    authenticate(&sess, host)?;
+    // The timeout only guards setup; interactive use must be able to idle.
+    sess.set_timeout(0);
+    // libssh2 only sends them from `keepalive_send`, which the relay calls while idle.
+    if keepalive() > 0 {
+        sess.set_keepalive(false, keepalive() as u32);
+    }
+    Ok(sess)
+}
+
+fn handshake(host: &Host) -> Result<Session, String> {
+    let tcp = match jump_host(host)? {
+        Some(jump) => tunnel_through(&jump, &host.hostname, host.port)?,
+        None => connect_tcp(&host.hostname, host.port)?,
+    };
+
+    let mut sess = Session::new().map_err(|e| format!("Session error: {}", e))?;
+    sess.set_timeout((connect_timeout() * 1000) as u32);
+    sess.set_tcp_stream(tcp);
+    sess.handshake()
+        .map_err(|e| format!("SSH handshake failed: {}", e))?;
+    Ok(sess)
+}
+
+/// Splits `[user@]host[:port]` into user (`default_user` if absent), validated
+/// hostname and port (22 if absent). IPv6 literals take brackets when a port
+/// follows: `[::1]:2222`.
+pub fn parse_target(spec: &str, default_user: &str) -> Result<(String, String, u16), String> {
+    let spec = spec.trim();
+    let (username, rest) = match spec.rsplit_once('@') {
+        Some((user, rest)) if !user.is_empty() => (user.to_string(), rest),
+        Some(_) => return Err("user name before '@' is empty".to_string()),
+        None => (default_user.to_string(), spec),
+    };
+    // `[v6]:port`, `v6` (several colons, no port), or `host[:port]`.
+    let (hostname, port) = match rest.strip_prefix('[').and_then(|r| r.split_once(']')) {
+        Some((h, "")) => (h, None),
+        Some((h, p)) => (h, Some(p.strip_prefix(':').unwrap_or(p))),
+        None if rest.matches(':').count() > 1 => (rest, None),
+        None => match rest.rsplit_once(':') {
+            Some((h, p)) => (h, Some(p)),
+            None => (rest, None),
+        },
+    };
+    let port = match port {
+        Some(p) => p.parse::<u16>().map_err(|_| format!("invalid port '{}'", p))?,
+        None => 22,
+    };
+    Ok((username, normalize_hostname(hostname)?, port))
+}
+
+/// Parses `host.jump_host` (`[user@]host[:port]`) into a bastion `Host`.
+///
+/// The bastion authenticates with the agent or the target's key file; the
+/// target's password is never sent to it.
+fn jump_host(host: &Host) -> Result<Option<Host>, String> {
+    let Some(spec) = host.jump_host.as_deref().map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    let (username, hostname, port) =
+        parse_target(spec, &host.username).map_err(|e| format!("Jump host '{}': {}", spec, e))?;
+    Ok(Some(Host {
+        alias: format!("jump {}", spec),
+        hostname,
+        port,
+        username,
+        identity_file: host.identity_file.clone(),
+        key_passphrase: host.key_passphrase.clone(),
+        ..Default::default()
+    }))
+}
+
+/// Opens a direct-tcpip channel from `jump` to the target and exposes it as
+/// a local `TcpStream`, since `Session::set_tcp_stream` needs a real socket.
+fn tunnel_through(jump: &Host, target_host: &str, target_port: u16) -> Result<TcpStream, String> {
+    let bastion = open_session(jump).map_err(|e| format!("Jump host {}: {}", jump.hostname, e))?;
+    let channel = bastion
+        .channel_direct_tcpip(target_host, target_port, None)
+        .map_err(|e| {
+            format!("Connection failed: jump host cannot reach {}: {}", socket_addr(target_host, target_port), e)
+        })?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").map_err(|e| e.to_string())?;
+    let client = TcpStream::connect(listener.local_addr().map_err(|e| e.to_string())?)
+        .map_err(|e| e.to_string())?;
+    let (local, peer) = listener.accept().map_err(|e| e.to_string())?;
+    // Only our own client may hold the tunnel end.
+    if Some(peer) != client.local_addr().ok() {
+        return Err("Jump tunnel was claimed by another local process".to_string());
+    }
+
+    thread::spawn(move || pump_tunnel(bastion, channel, local));
+    Ok(client)
+}
+
+/// Copies bytes between the local socket and the bastion channel until
+/// either side closes.
+pub(crate) fn pump_tunnel(bastion: Session, mut channel: ssh2::Channel, mut local: TcpStream) {
+    if local.set_nonblocking(true).is_err() {
+        return;
+    }
+    bastion.set_blocking(false);
+
+    let mut buf = [0u8; 16384];
+    while let Some(active) = pump_step(&mut channel, &mut local, &mut buf) {
+        if !active {
+            thread::sleep(Duration::from_millis(2));
+        }
+    }
+    let _ = channel.close();
+}
+
+/// One round of copying in each direction between a non-blocking socket and
+/// channel. `Some(true)` if bytes moved, `Some(false)` if idle, `None` once
+/// either side has closed.
+pub(crate) fn pump_step(channel: &mut ssh2::Channel, local: &mut TcpStream, buf: &mut [u8]) -> Option<bool> {
+    let mut active = false;
+
+    match local.read(buf) {
+        Ok(0) => return None,
+        Ok(n) => {
+            active = true;
+            write_all_retry(channel, &buf[..n]).ok()?;
+        }
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+        Err(_) => return None,
+    }
+
+    match channel.read(buf) {
+        Ok(0) if channel.eof() => return None,
+        Ok(0) => {}
+        Ok(n) => {
+            active = true;
+            write_all_retry(local, &buf[..n]).ok()?;
+        }
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+        Err(_) => return None,
+    }
+    Some(active)
+}
+
+pub fn write_all_retry(w: &mut impl Write, mut data: &[u8]) -> io::Result<()> {
+    while !data.is_empty() {
+        match w.write(data) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) => data = &data[n..],
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(Duration::from_millis(1)),
+            Err(e) => return Err(e),
+        }
+    }
+    loop {
+        match w.flush() {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(Duration::from_millis(1)),
+            done => return done,
+        }
+    }
+}
+
+/// `write_all_retry` for a channel shared with a reader thread. The lock is
+/// taken for each attempt only, never while waiting for window space, so the
+/// reader can keep draining the channel meanwhile.
+pub fn write_all_shared<W: Write>(w: &Mutex<W>, mut data: &[u8]) -> io::Result<()> {
+    let lock = || w.lock().map_err(|_| io::Error::other("channel lock poisoned"));
+    while !data.is_empty() {
+        let written = lock()?.write(data);
+        match written {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) => data = &data[n..],
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(Duration::from_millis(1)),
+            Err(e) => return Err(e),
+        }
+    }
+    loop {
+        let flushed = lock()?.flush();
+        match flushed {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(Duration::from_millis(1)),
+            done => return done,
+        }
+    }
+}
+
+/// Checks the server key against `~/.ssh/known_hosts`.
+///
+/// A changed or `@revoked` key always fails; an unknown one fails with
+/// `HOST_KEY_UNKNOWN` and the fingerprint, to be accepted through `trust_host_key`.
+fn verify_host_key(sess: &Session, hostname: &str, port: u16) -> Result<(), String> {
+    let hostname = bare_host(hostname);
+    let (key, _) = sess
+        .host_key()
+        .ok_or_else(|| "Host key verification failed: server sent no host key".to_string())?;
+    let mut known = sess
+        .known_hosts()
+        .map_err(|e| format!("Host key verification failed: {}", e))?;
+    if let Some(path) = known_hosts_path().filter(|p| p.is_file()) {
+        // Feed entries one at a time: libssh2 rejects the whole file on a single
+        // line it cannot parse (e.g. `@cert-authority`), so such lines are skipped.
+        let bytes = std::fs::read(&path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        let text = String::from_utf8_lossy(&bytes);
+        if is_revoked(&text, key) {
+            return Err(format!(
+                "Host key verification failed: the key of {} ({}) is marked @revoked in ~/.ssh/known_hosts. \
+                 Refusing to connect.",
+                known_hosts_entry(hostname, port),
+                fingerprint(sess)
+            ));
+        }
+        for line in text.lines().map(str::trim) {
+            if !line.is_empty() && !line.starts_with('#') {
+                let _ = known.read_str(line, KnownHostFileKind::OpenSSH);
+            }
+        }
+    }
+
+    match known.check_port(hostname, port, key) {
+        CheckResult::Match => Ok(()),
+        CheckResult::NotFound => Err(format!(
+            "{} for {}: {}",
+            HOST_KEY_UNKNOWN,
+            known_hosts_entry(hostname, port),
+            fingerprint(sess)
+        )),
+        CheckResult::Mismatch => Err(format!(
+            "Host key verification failed: the key for {} has CHANGED (now {}). \
+             Refusing to connect; remove the old entry from ~/.ssh/known_hosts if the change is expected.",
+            known_hosts_entry(hostname, port),
+            fingerprint(sess)
+        )),
+        CheckResult::Failure => Err("Host key verification failed".to_string()),
+    }
+}
+
+/// Whether `key` appears on a `@revoked` line of `known_hosts`. Such a key
+/// is refused for every host, whatever the line's host patterns say.
+fn is_revoked(known_hosts: &str, key: &[u8]) -> bool {
+    let key = base64_unpadded(key);
+    known_hosts.lines().any(|line| {
+        let mut fields = line.split_whitespace();
+        fields.next() == Some("@revoked") && fields.nth(2).is_some_and(|k| k.trim_end_matches('=') == key)
+    })
+}
+
+/// `(known_hosts entry, fingerprint)` carried by a `HOST_KEY_UNKNOWN` error.
+pub fn unknown_host_key(error: &str) -> Option<(String, String)> {
+    let rest = &error[error.find(HOST_KEY_UNKNOWN)? + HOST_KEY_UNKNOWN.len()..];
+    let (entry, fp) = rest.strip_prefix(" for ")?.split_once(": SHA256:")?;
+    let fp = fp.split_whitespace().next()?;
+    Some((entry.to_string(), format!("SHA256:{}", fp)))
+}
+
+/// Reconnects, checks the server still presents `fingerprint_seen`, and
+/// appends its key to `~/.ssh/known_hosts`. With a jump host, the prompt may
+/// have been for the bastion itself, so that is checked first.
+pub fn trust_host_key(host: &Host, fingerprint_seen: &str) -> Result<(), String> {
+    if let Some(jump) = jump_host(host)? {
+        let tcp = connect_tcp(&jump.hostname, jump.port)?;
+        let mut sess = Session::new().map_err(|e| format!("Session error: {}", e))?;
+        sess.set_timeout((connect_timeout() * 1000) as u32);
+        sess.set_tcp_stream(tcp);
+        sess.handshake()
+            .map_err(|e| format!("SSH handshake failed: {}", e))?;
+        if fingerprint(&sess) == fingerprint_seen {
+            return append_known_host(&sess, &jump.hostname, jump.port);
+        }
+    }
+
+    let sess = handshake(host)?;
+    if fingerprint(&sess) != fingerprint_seen {
+        return Err("Host key changed while confirming; not trusting it".to_string());
+    }
+    append_known_host(&sess, &host.hostname, host.port)
+}
+
+fn append_known_host(sess: &Session, hostname: &str, port: u16) -> Result<(), String> {
+    let (key, key_type) = sess
+        .host_key()
+        .ok_or_else(|| "Server sent no host key".to_string())?;
+
+    // Render the line through libssh2, then append it so existing entries
+    // libssh2 cannot parse are left untouched.
+    let mut known = sess.known_hosts().map_err(|e| e.to_string())?;
+    known
+        .add(&known_hosts_entry(hostname, port), key, "added by termissh", key_type.into())
+        .map_err(|e| e.to_string())?;
+    let entry = known
+        .hosts()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Cannot encode host key".to_string())?;
+    let line = known
+        .write_string(&entry, KnownHostFileKind::OpenSSH)
+        .map_err(|e| e.to_string())?;
+
+    let path = known_hosts_path().ok_or_else(|| "Cannot locate home directory".to_string())?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    writeln!(file, "{}", line.trim_end()).map_err(|e| e.to_string())
+}
+
+fn known_hosts_path() -> Option<PathBuf> {
+    directories::BaseDirs::new().map(|d| d.home_dir().join(".ssh").join("known_hosts"))
+}
+
+fn known_hosts_entry(hostname: &str, port: u16) -> String {
+    let hostname = bare_host(hostname);
+    if port == 22 {
+        hostname.to_string()
+    } else {
+        format!("[{}]:{}", hostname, port)
+    }
+}
+
+/// OpenSSH-style `SHA256:<base64>` fingerprint of the server key.
+fn fingerprint(sess: &Session) -> String {
+    match sess.host_key_hash(HashType::Sha256) {
+        Some(hash) => format!("SHA256:{}", base64_unpadded(hash)),
+        None => "SHA256:?".to_string(),
+    }
+}
+
+fn base64_unpadded(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+/// `host:port` as written in URLs and OpenSSH: IPv6 literals are bracketed
+/// (`[::1]:22`) so the port stays unambiguous.
+pub fn socket_addr(hostname: &str, port: u16) -> String {
+    let hostname = bare_host(hostname);
+    if hostname.contains(':') {
+        format!("[{}]:{}", hostname, port)
+    } else {
+        format!("{}:{}", hostname, port)
+    }
+}
+
+/// `hostname` without the brackets users may type around an IPv6 literal.
+pub fn bare_host(hostname: &str) -> &str {
+    let hostname = hostname.trim();
+    hostname
+        .strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(hostname)
+}
+
+/// Validates a hostname as entered in the connection dialog and returns the
+/// form to store: IPv6 literals without brackets, DNS names and IPv4 as-is,
+/// a fully qualified name without its trailing dot.
+pub fn normalize_hostname(input: &str) -> Result<String, String> {
+    let hostname = bare_host(input);
+    let hostname = hostname.strip_suffix('.').filter(|h| !h.contains(':')).unwrap_or(hostname);
+    if hostname.is_empty() {
+        return Err("Hostname is empty".to_string());
+    }
+    if hostname.contains(':') {
+        // A zone (`fe80::1%eth0`) is allowed after the address.
+        let addr = hostname.split('%').next().unwrap_or_default();
+        return match addr.parse::<Ipv6Addr>() {
+            Ok(_) => Ok(hostname.to_string()),
+            Err(_) => Err(format!("'{}' is not a valid IPv6 address", hostname)),
+        };
+    }
+    let valid = hostname
+        .split('.')
+        .all(|label| !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    if !valid || hostname.starts_with('-') {
+        return Err(format!("'{}' is not a valid hostname", hostname));
+    }
+    Ok(hostname.to_string())
+}
+
+/// Resolves `hostname` before connecting, so a DNS failure is reported as
+/// "Could not resolve" rather than as a refused or timed-out connection.
+pub fn resolve(hostname: &str, port: u16) -> Result<Vec<SocketAddr>, String> {
+    let hostname = bare_host(hostname);
+    let addrs: Vec<SocketAddr> = (hostname, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("Could not resolve {}: {}", hostname, e))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(format!("Could not resolve {}: no addresses", hostname));
+    }
+    Ok(addrs)
+}
+
+fn connect_tcp(hostname: &str, port: u16) -> Result<TcpStream, String> {
+    let addrs = resolve(hostname, port)?;
+    let timeout = Duration::from_secs(connect_timeout());
+
+    let mut last_err = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(tcp) => return Ok(tcp),
+            Err(e) => last_err = Some((addr, e)),
+        }
+    }
+    let (addr, e) = last_err.expect("resolve returns at least one address");
+    Err(match e.kind() {
+        io::ErrorKind::ConnectionRefused => {
+            format!("Connection refused by {}: nothing listens on port {}", addr, port)
+        }
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => {
+            format!("Connection to {} timed out after {}s", addr, timeout.as_secs())
+        }
+        _ => format!("Connection failed: {}: {}", addr, e),
+    })
+}
+
+/// Agent, then identity file, then password.
+///
+/// When a key file is configured and rejected, its ssh2 error is reported
+/// instead of a generic message so a wrong path or bad key is visible.
+fn authenticate(sess: &Session, host: &Host) -> Result<(), String> {
+    let user = host.username.as_str();
+
+    if sess.userauth_agent(user).is_ok() && sess.authenticated() {
+        return Ok(());
+    }
+
+    let mut key_error = None;
+    if let Some(key) = host.identity_file.as_deref().filter(|k| !k.is_empty()) {
+        let path = expand_home(key);
+        let passphrase = host.key_passphrase.as_deref();
+        match sess.userauth_pubkey_file(user, None, &path, passphrase) {
+            Ok(()) if sess.authenticated() => return Ok(()),
+            Ok(()) => key_error = Some(format!("Key auth failed ({}): key rejected", key)),
+            Err(e) if path.is_file() && is_decrypt_error(&e) => {
+                key_error = Some(format!("Key auth failed ({}): {} — {}", key, PASSPHRASE_REQUIRED, e))
+            }
+            Err(e) => key_error = Some(format!("Key auth failed ({}): {}", key, e)),
+        }
+    }
+
+    match host.password.as_deref().filter(|p| !p.is_empty()) {
+        Some(pass) => match sess.userauth_password(user, pass) {
+            Ok(()) if sess.authenticated() => Ok(()),
+            Ok(()) => Err("Password auth failed".to_string()),
+            Err(e) => Err(match key_error {
+                Some(key_err) => format!("{}; password auth failed: {}", key_err, e),
+                None => format!("Password auth failed: {}", e),
+            }),
+        },
+        None => Err(key_error.unwrap_or_else(|| {
+            "Authentication failed: no password and agent auth failed".to_string()
+        })),
+    }
+}
+
+fn is_decrypt_error(e: &ssh2::Error) -> bool {
+    matches!(
+        e.code(),
+        ssh2::ErrorCode::Session(LIBSSH2_ERROR_FILE | LIBSSH2_ERROR_PUBLICKEY_UNVERIFIED)
+    )
+}
+
+/// Expands a leading `~/` so key paths can be written the way ssh accepts them.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), directories::BaseDirs::new()) {
+        (Some(rest), Some(dirs)) => dirs.home_dir().join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hostnames_are_normalized() {
+        assert_eq!(normalize_hostname("web.example.com.").as_deref(), Ok("web.example.com"));
+        assert_eq!(normalize_hostname("[::1]").as_deref(), Ok("::1"));
+        assert_eq!(normalize_hostname("10.0.0.1").as_deref(), Ok("10.0.0.1"));
+        for bad in ["", ".", "web..example.com", "web.example.com..", "-web", "we b"] {
+            assert!(normalize_hostname(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn revoked_keys_are_found() {
+        let key = b"\x00\x00\x00\x0bssh-ed25519 revoked";
+        let line = format!("@revoked * ssh-ed25519 {}=", base64_unpadded(key));
+        let other = format!("web.example.com ssh-ed25519 {}", base64_unpadded(key));
+        assert!(is_revoked(&format!("# comment\n{}\n", line), key));
+        assert!(!is_revoked(&other, key));
+        assert!(!is_revoked(&line, b"another key"));
+        assert!(!is_revoked("@revoked *", key));
+    }
+}
@@ -0,0 +1,84 @@
+//! Reads concrete host entries out of an OpenSSH client config (`~/.ssh/config`).
+//!
+//! Only `Host`, `HostName`, `Port`, `User`, `IdentityFile` and `ProxyJump` are
+//! mapped. Wildcard / negated patterns and `Match` blocks describe defaults
+//! rather than connectable hosts, so they are skipped.
+
+use std::collections::HashSet;
+use std::fs;
+
+use crate::config::Host;
+
+/// Parses `~/.ssh/config` of the current user.
+pub fn load_default() -> Result<Vec<Host>, String> {
+    let dirs = directories::BaseDirs::new().ok_or("Cannot locate home directory")?;
+    let path = dirs.home_dir().join(".ssh").join("config");
+    let contents =
+        fs::read_to_string(&path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    Ok(parse(&contents))
+}
+
+pub fn parse(contents: &str) -> Vec<Host> {
+    let default_user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+
+    let mut hosts: Vec<Host> = Vec::new();
+    // Keywords already set per host, and the hosts the current stanza applies to.
+    let mut seen: Vec<HashSet<String>> = Vec::new();
+    let mut current: Vec<usize> = Vec::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+            Some((k, v)) => (k, v.trim_start_matches(|c: char| c.is_whitespace() || c == '=').trim()),
+            None => continue,
+        };
+        let value = value.trim_matches('"');
+
+        let key = key.to_lowercase();
+        if key == "host" {
+            current.clear();
+            for pattern in value.split_whitespace() {
+                if pattern.contains(['*', '?', '!']) || hosts.iter().any(|h| h.alias == pattern) {
+                    continue;
+                }
+                current.push(hosts.len());
+                seen.push(HashSet::new());
+                hosts.push(Host {
+                    alias: pattern.to_string(),
+                    hostname: pattern.to_string(),
+                    username: default_user.clone(),
+                    ..Default::default()
+                });
+            }
+            continue;
+        }
+        if key == "match" {
+            current.clear();
+            continue;
+        }
+
+        for &i in &current {
+            // Like ssh, the first value given for a keyword wins.
+            if !seen[i].insert(key.clone()) {
+                continue;
+            }
+            let host = &mut hosts[i];
+            match key.as_str() {
+                "hostname" => host.hostname = value.to_string(),
+                "port" => host.port = value.parse().unwrap_or(host.port),
+                "user" => host.username = value.to_string(),
+                "identityfile" => host.identity_file = Some(value.to_string()),
+                "proxyjump" if !value.eq_ignore_ascii_case("none") => {
+                    host.jump_host = Some(value.to_string())
+                }
+                _ => {}
+            }
+        }
+    }
+    hosts
+}
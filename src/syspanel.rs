@@ -1,11 +1,16 @@
 //! System Management Panel
 //!
 //! Provides firewall manager, package manager, login history, SSH key manager,
-//! system settings editor, and an auto-detecting extension system (nginx, apache, mysql, etc.)
+//! cron jobs, a systemd services browser, user accounts, a process list,
+//! system settings editor, and an
+//! auto-detecting extension system (nginx, apache, mysql, etc.)
 
 use std::io::Read;
+use std::sync::{Condvar, Mutex};
 
-use iced::widget::{button, column, container, row, scrollable, text, text_input, Column, Row};
+use iced::widget::{
+    button, column, container, progress_bar, row, scrollable, text, text_input, Column, Row,
+};
 use iced::{Alignment, Element, Length};
 
 use crate::app::Message;
@@ -22,6 +27,10 @@ pub enum SysTab {
     Packages,
     Logins,
     SshKeys,
+    Cron,
+    Services,
+    Users,
+    Processes,
     Extension(String), // service id: "nginx", "mysql", etc.
 }
 
@@ -33,9 +42,28 @@ impl SysTab {
             SysTab::Packages => "Packages",
             SysTab::Logins => "Login History",
             SysTab::SshKeys => "SSH Keys",
+            SysTab::Cron => "Cron",
+            SysTab::Services => "Services",
+            SysTab::Users => "Users",
+            SysTab::Processes => "Processes",
             SysTab::Extension(n) => n.as_str(),
         }
     }
+    /// `kind` of the `SysPanelFetched` result that fills this tab.
+    pub fn fetch_kind(&self) -> &'static str {
+        match self {
+            SysTab::Overview => "overview",
+            SysTab::Firewall => "firewall",
+            SysTab::Packages => "packages",
+            SysTab::Logins => "logins",
+            SysTab::SshKeys => "sshkeys",
+            SysTab::Cron => "cron",
+            SysTab::Services => "services",
+            SysTab::Users => "users",
+            SysTab::Processes => "processes",
+            SysTab::Extension(_) => "extension",
+        }
+    }
     pub fn from_str(s: &str) -> Self {
         match s {
             "Overview" => SysTab::Overview,
@@ -43,6 +71,10 @@ impl SysTab {
             "Packages" => SysTab::Packages,
             "Login History" => SysTab::Logins,
             "SSH Keys" => SysTab::SshKeys,
+            "Cron" => SysTab::Cron,
+            "Services" => SysTab::Services,
+            "Users" => SysTab::Users,
+            "Processes" => SysTab::Processes,
             other => SysTab::Extension(other.to_string()),
         }
     }
@@ -54,16 +86,51 @@ pub struct SysState {
     pub output: String,
     pub loading: bool,
     pub action_result: Option<String>,
+    /// Stderr of the last fetch, shown apart from the parsed output.
+    pub stderr: String,
+    /// Results fetched ahead for tabs not shown yet, by fetch kind.
+    pub prefetched: std::collections::HashMap<String, ExecOutput>,
     pub extensions: Vec<ExtensionInfo>,
+    // Overview: raw command output expanded below the gauges
+    pub show_raw: bool,
     // Firewall form
     pub fw_port: String,
     pub fw_proto: String,
     pub fw_action: String,
     // Package search
     pub pkg_search: String,
+    // Package list sort column (none: install order) and direction
+    pub pkg_sort: Option<usize>,
+    pub pkg_sort_desc: bool,
+    // Reverse DNS of failed login sources (None: no name), and whether a lookup runs
+    pub source_names: std::collections::HashMap<String, Option<String>>,
+    pub resolving: bool,
+    // Package manager detected from the overview (`apt-get`, `dnf`, ...)
+    pub pkg_manager: Option<String>,
+    pub pkg_install: String,
+    // A streamed package command is running, or its log is being shown
+    pub streaming: bool,
+    pub showing_log: bool,
     // SSH Key gen
     pub key_name: String,
     pub key_type: String,
+    // Public key pasted for authorized_keys
+    pub auth_key_input: String,
+    // Public key printed by the last "Generate" action
+    pub generated_pubkey: Option<String>,
+    // Cron form
+    pub cron_line: String,
+    // Services browser: filter, sort column/direction, and the clicked unit
+    pub svc_search: String,
+    pub svc_sort: usize,
+    pub svc_sort_desc: bool,
+    pub svc_selected: Option<String>,
+    // User accounts form
+    pub user_name: String,
+    pub user_group: String,
+    // Process list: periodic refresh while the tab is open, and whether one is in flight
+    pub proc_auto: bool,
+    pub refreshing: bool,
 }
 
 impl SysState {
@@ -88,55 +155,204 @@ pub struct ExtensionInfo {
 
 // ─── SSH Execution ───────────────────────────────────────────────────────────
 
-fn ssh_exec_sync(host: Host, cmd: String) -> String {
-    use ssh2::Session;
-    use std::net::TcpStream;
+/// What a remote command printed, kept apart so parsers only see stdout.
+#[derive(Debug, Clone, Default)]
+pub struct ExecOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// `None` when the command never ran (connection or exec failure).
+    pub exit_code: Option<i32>,
+}
 
-    let tcp = match TcpStream::connect(format!("{}:{}", host.hostname, host.port)) {
-        Ok(t) => t,
-        Err(e) => return format!("[Connection Error] {e}"),
-    };
-    let mut sess = match Session::new() {
-        Ok(s) => s,
-        Err(e) => return format!("[Session Error] {e}"),
-    };
-    sess.set_tcp_stream(tcp);
-    if sess.handshake().is_err() {
-        return "[Handshake failed — check host/port]".into();
+impl ExecOutput {
+    fn failed(msg: String) -> Self {
+        Self { stderr: msg, ..Default::default() }
     }
 
-    // Try SSH agent, then password
-    let authed = sess.userauth_agent(&host.username).is_ok() && sess.authenticated();
-    if !authed {
-        let pass = host.password.as_deref().unwrap_or("");
-        if pass.is_empty() {
-            return "[Auth failed: no password and agent auth failed]".into();
+    /// Last line worth showing in a banner: stderr when the command failed
+    /// or printed nothing else, stdout otherwise.
+    pub fn summary(&self) -> &str {
+        let failed = self.exit_code != Some(0) || self.stdout.trim().is_empty();
+        let source = if failed && !self.stderr.trim().is_empty() { &self.stderr } else { &self.stdout };
+        source.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("Done")
+    }
+}
+
+// ─── Session Pool ────────────────────────────────────────────────────────────
+
+/// Sessions per host opened for fetches and actions. libssh2 serialises
+/// channels on one session, so prefetches run in parallel only over separate
+/// sessions; servers cap logins per user, so this stays small.
+const MAX_SESSIONS: usize = 3;
+
+/// Sessions of one host (see `pool_key`): idle ones ready for reuse, and how
+/// many exist in total, checked out or not.
+struct HostPool {
+    key: String,
+    idle: Vec<ssh2::Session>,
+    open: usize,
+}
+
+/// A Vec keeps the static const-constructible; there are only a few hosts.
+static SESSIONS: Mutex<Vec<HostPool>> = Mutex::new(Vec::new());
+static RETURNED: Condvar = Condvar::new();
+
+fn pool_key(host: &Host) -> String {
+    format!(
+        "{}@{} via {}",
+        host.username,
+        crate::ssh::socket_addr(&host.hostname, host.port),
+        host.jump_host.as_deref().unwrap_or("-")
+    )
+}
+
+/// How long a panel command may go without output before it is abandoned,
+/// so a hung command can't hold its session forever.
+const EXEC_TIMEOUT_SECS: u32 = 60;
+
+fn open_pooled(host: &Host) -> Result<ssh2::Session, String> {
+    let sess = crate::ssh::open_session(host)?;
+    sess.set_timeout(EXEC_TIMEOUT_SECS * 1000);
+    Ok(sess)
+}
+
+/// A session of `host` used by one command at a time; it goes back to the
+/// pool on drop unless `evict`ed.
+struct PooledSession {
+    key: String,
+    sess: Option<ssh2::Session>,
+}
+
+impl PooledSession {
+    /// Takes an idle session of `host`, opens one if the host has fewer than
+    /// `MAX_SESSIONS`, or waits for one to come back.
+    fn checkout(host: &Host) -> Result<Self, String> {
+        let key = pool_key(host);
+        let mut pool = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            let idx = match pool.iter().position(|p| p.key == key) {
+                Some(idx) => idx,
+                None => {
+                    pool.push(HostPool { key: key.clone(), idle: Vec::new(), open: 0 });
+                    pool.len() - 1
+                }
+            };
+            let entry = &mut pool[idx];
+            if let Some(sess) = entry.idle.pop() {
+                return Ok(PooledSession { key, sess: Some(sess) });
+            }
+            if entry.open < MAX_SESSIONS {
+                entry.open += 1;
+                break;
+            }
+            pool = RETURNED.wait(pool).unwrap_or_else(|e| e.into_inner());
         }
-        if sess.userauth_password(&host.username, pass).is_err() {
-            return "[Auth failed: wrong password]".into();
+        // Handshake without holding the lock; the slot is already counted.
+        drop(pool);
+        let mut slot = PooledSession { key, sess: None };
+        slot.sess = Some(open_pooled(host)?);
+        Ok(slot)
+    }
+
+    /// Opens a channel. A session that went stale (server restart, network
+    /// change) is replaced once.
+    fn channel(&mut self, host: &Host) -> Result<ssh2::Channel, String> {
+        if let Some(ch) = self.sess.as_ref().and_then(|s| s.channel_session().ok()) {
+            return Ok(ch);
         }
+        self.sess = Some(open_pooled(host).map_err(|e| format!("[{e}]"))?);
+        self.sess
+            .as_ref()
+            .expect("session opened above")
+            .channel_session()
+            .map_err(|e| format!("[Channel Error] {e}"))
     }
 
-    let mut ch = match sess.channel_session() {
+    /// Drops the session instead of returning it to the pool.
+    fn evict(&mut self) {
+        self.sess = None;
+    }
+}
+
+impl Drop for PooledSession {
+    fn drop(&mut self) {
+        let mut pool = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+        // Gone when `release_session` ran meanwhile; the session just closes.
+        if let Some(entry) = pool.iter_mut().find(|p| p.key == self.key) {
+            match self.sess.take() {
+                Some(sess) => entry.idle.push(sess),
+                None => entry.open -= 1,
+            }
+        }
+        RETURNED.notify_all();
+    }
+}
+
+/// Whether `a` and `b` use the same pooled sessions.
+pub fn shares_session(a: &Host, b: &Host) -> bool {
+    pool_key(a) == pool_key(b)
+}
+
+/// Disconnects the pooled sessions of `host`; call when no tab uses them anymore.
+pub fn release_session(host: &Host) {
+    let key = pool_key(host);
+    let mut pool = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(idx) = pool.iter().position(|p| p.key == key) {
+        for sess in pool.remove(idx).idle {
+            let _ = sess.disconnect(None, "closed", None);
+        }
+    }
+    RETURNED.notify_all();
+}
+
+fn ssh_exec_sync(host: Host, cmd: String) -> ExecOutput {
+    let mut slot = match PooledSession::checkout(&host) {
+        Ok(slot) => slot,
+        Err(e) => return ExecOutput::failed(format!("[{e}]")),
+    };
+    let mut ch = match slot.channel(&host) {
         Ok(c) => c,
-        Err(e) => return format!("[Channel Error] {e}"),
+        Err(e) => {
+            slot.evict();
+            return ExecOutput::failed(e);
+        }
     };
     if ch.exec(&cmd).is_err() {
-        return "[Exec failed]".into();
+        return ExecOutput::failed("[Exec failed]".into());
     }
 
-    let mut out = String::new();
-    ch.read_to_string(&mut out).ok();
-    let mut err_buf = String::new();
-    ch.stderr().read_to_string(&mut err_buf).ok();
+    let mut out = ExecOutput::default();
+    let read = ch
+        .read_to_string(&mut out.stdout)
+        .and_then(|_| ch.stderr().read_to_string(&mut out.stderr));
+    if read.is_err_and(|e| e.kind() == std::io::ErrorKind::TimedOut) {
+        // The session may be wedged on this command; don't hand it out again.
+        slot.evict();
+        out.stderr.push_str(&format!("[Timed out: no output for {EXEC_TIMEOUT_SECS}s]"));
+        return out;
+    }
     ch.wait_close().ok();
+    out.exit_code = ch.exit_status().ok();
+    out
+}
 
-    if out.is_empty() && !err_buf.is_empty() {
-        err_buf
-    } else if !err_buf.is_empty() {
-        format!("{out}\n--- stderr ---\n{err_buf}")
+/// Appends `pubkey` to the remote `~/.ssh/authorized_keys`, like `ssh-copy-id`.
+pub fn install_public_key(host: Host, pubkey: String) -> Result<(), String> {
+    if pubkey.contains('\'') || pubkey.contains('\n') {
+        return Err("Public key contains unexpected characters".into());
+    }
+    let cmd = format!(
+        "umask 077 && mkdir -p ~/.ssh && touch ~/.ssh/authorized_keys && \
+grep -qxF '{pubkey}' ~/.ssh/authorized_keys || echo '{pubkey}' >> ~/.ssh/authorized_keys; \
+grep -qxF '{pubkey}' ~/.ssh/authorized_keys && echo __termissh_key_ok__"
+    );
+    let out = ssh_exec_sync(host, cmd);
+    if out.stdout.contains("__termissh_key_ok__") {
+        Ok(())
+    } else if !out.stderr.trim().is_empty() {
+        Err(out.stderr.trim().to_string())
     } else {
-        out
+        Err(out.stdout.trim().to_string())
     }
 }
 
@@ -146,7 +362,7 @@ fn task_fetch(host: Host, tab_id: u64, kind: &'static str, cmd: String) -> iced:
         move |res| {
             let output = match res {
                 Ok(o) => o,
-                Err(e) => format!("[Task Error] {e}"),
+                Err(e) => ExecOutput::failed(format!("[Task Error] {e}")),
             };
             Message::SysPanelFetched(tab_id, kind.to_string(), output)
         },
@@ -161,15 +377,19 @@ pub fn fetch_overview(host: Host, tab_id: u64) -> iced::Task<Message> {
         tab_id,
         "overview",
         r#"echo "=== HOSTNAME ===" && hostname && \
-echo "" && echo "=== OS ===" && (cat /etc/os-release 2>/dev/null | grep -E "PRETTY_NAME|VERSION_ID" || uname -a) && \
+echo "" && echo "=== OS ===" && (cat /etc/os-release 2>/dev/null | grep -E "PRETTY_NAME|VERSION_ID" || sw_vers 2>/dev/null || uname -a) && \
 echo "" && echo "=== UPTIME ===" && uptime && \
-echo "" && echo "=== MEMORY ===" && free -h 2>/dev/null || vm_stat 2>/dev/null | head -10 && \
+echo "" && echo "=== MEMORY ===" && (free -h 2>/dev/null || (MEM_CMD) | awk '/MemTotal/ {t=$2} /MemAvailable/ {a=$2} END {if (t) printf "Mem: %.1fGi %.1fGi\n", t/1048576, (t-a)/1048576}') && \
 echo "" && echo "=== DISK ===" && df -h / && \
 echo "" && echo "=== EXTENSIONS ===" && \
 for s in nginx apache2 httpd mysql mariadb postgresql redis docker pm2 php-fpm; do \
   st=$(systemctl is-active $s 2>/dev/null || echo "inactive"); echo "$s:$st"; \
+done; \
+echo "" && echo "=== PACKAGE MANAGER ===" && \
+for m in apt-get dnf yum zypper apk pacman brew; do \
+  command -v $m >/dev/null 2>&1 && echo $m && break; \
 done"#
-            .to_string(),
+            .replace("MEM_CMD", &format!("case \"$(uname -s)\" in Darwin) {MAC_MEM_CMD} ;; *) {BSD_MEM_CMD} ;; esac")),
     )
 }
 
@@ -178,7 +398,7 @@ pub fn fetch_firewall(host: Host, tab_id: u64) -> iced::Task<Message> {
         host,
         tab_id,
         "firewall",
-        r#"echo "=== UFW Status ===" && sudo -n ufw status verbose 2>/dev/null && echo "[ok]" || \
+        r#"echo "=== UFW Status ===" && sudo -n ufw status numbered 2>/dev/null && echo "[ok]" || \
 echo "=== IPTables ===" && sudo -n iptables -L -n --line-numbers 2>/dev/null || \
 echo "[Info] No accessible firewall tool found. Ensure the user has passwordless sudo for ufw/iptables.""#
             .to_string(),
@@ -198,7 +418,7 @@ elif command -v rpm >/dev/null 2>&1; then \
   rpm -qa --qf "%-40{NAME} %-20{VERSION}\n" | sort | head -400; \
 elif command -v apk >/dev/null 2>&1; then \
   echo "=== Installed Packages (apk) ===" && \
-  apk list --installed 2>/dev/null | head -400; \
+  apk list --installed 2>/dev/null | awk '{print $1}' | sed -E 's/-([0-9][^-]*-r[0-9]+)$/ \1/' | head -400; \
 elif command -v brew >/dev/null 2>&1; then \
   echo "=== Installed Packages (brew) ===" && \
   brew list --versions 2>/dev/null | head -400; \
@@ -215,9 +435,9 @@ pub fn fetch_logins(host: Host, tab_id: u64) -> iced::Task<Message> {
         r#"echo "=== Currently Logged In ===" && w 2>/dev/null || who && \
 echo "" && echo "=== Login History (last 30) ===" && \
 last -n 30 2>/dev/null || echo "[last not available]" && \
-echo "" && echo "=== Failed Logins (last 10) ===" && \
-sudo -n lastb -n 10 2>/dev/null || \
-grep "Failed password" /var/log/auth.log 2>/dev/null | tail -10 || \
+echo "" && echo "=== Failed Logins (last 100) ===" && \
+sudo -n lastb -n 100 2>/dev/null || \
+grep "Failed password" /var/log/auth.log 2>/dev/null | tail -100 || \
 echo "[no failed login data]""#
             .to_string(),
     )
@@ -233,6 +453,12 @@ echo "" && echo "=== Key Fingerprints ===" && \
 for f in ~/.ssh/*.pub; do [ -f "$f" ] && echo "--- $f ---" && ssh-keygen -lf "$f" 2>/dev/null; done || echo "(no .pub files)" && \
 echo "" && echo "=== Authorized Keys ===" && \
 cat ~/.ssh/authorized_keys 2>/dev/null | head -15 || echo "(none)" && \
+echo "" && echo "=== Authorized Key Entries ===" && \
+(while IFS= read -r line; do \
+  case "$line" in ''|'#'*) continue;; esac; \
+  fp=$(printf '%s\n' "$line" | ssh-keygen -lf - 2>/dev/null | awk '{print $2}'); \
+  printf '%s\t%s\n' "${fp:--}" "$line"; \
+done < ~/.ssh/authorized_keys) 2>/dev/null; \
 echo "" && echo "=== SSH Client Config ===" && \
 cat ~/.ssh/config 2>/dev/null | head -30 || echo "(no config)" && \
 echo "" && echo "=== Host Key (server) ===" && \
@@ -242,15 +468,350 @@ cat /etc/ssh/ssh_host_rsa_key.pub 2>/dev/null || echo "(no server keys readable)
     )
 }
 
+pub fn fetch_cron(host: Host, tab_id: u64) -> iced::Task<Message> {
+    task_fetch(
+        host,
+        tab_id,
+        "cron",
+        r#"echo "=== User Crontab ===" && (crontab -l 2>/dev/null || echo "[no crontab]"); \
+echo "" && echo "=== Root Crontab ===" && (sudo -n crontab -l -u root 2>/dev/null || echo "[no access]")"#
+            .to_string(),
+    )
+}
+
+pub fn fetch_services(host: Host, tab_id: u64) -> iced::Task<Message> {
+    task_fetch(
+        host,
+        tab_id,
+        "services",
+        "systemctl list-units --type=service --all --no-pager --plain --no-legend 2>/dev/null \
+|| echo '[systemctl not available]'"
+            .to_string(),
+    )
+}
+
+pub fn fetch_users(host: Host, tab_id: u64) -> iced::Task<Message> {
+    task_fetch(
+        host,
+        tab_id,
+        "users",
+        r#"awk -F: '$3 >= 1000 && $3 < 65534 {print $1":"$3}' /etc/passwd | while IFS=: read -r u uid; do \
+  groups=$(id -nG "$u" 2>/dev/null | tr ' ' ','); \
+  status=$(sudo -n passwd -S "$u" 2>/dev/null | awk '{print $2}'); \
+  last=$(lastlog -u "$u" 2>/dev/null | awk 'NR==2 { if ($0 ~ /Never logged in/) print "never"; else print $(NF-4)" "$(NF-3)" "$(NF-2)" "$NF }'); \
+  echo "$u|$uid|$groups|${status:-?}|${last:--}"; \
+done"#
+            .to_string(),
+    )
+}
+
+pub fn fetch_processes(host: Host, tab_id: u64) -> iced::Task<Message> {
+    task_fetch(
+        host,
+        tab_id,
+        "processes",
+        // Grouped so the fallback runs when `ps` rejects `--sort` (BSD,
+        // macOS); after a pipe only `head`'s exit status would count.
+        "(ps aux --sort=-%cpu 2>/dev/null || ps aux -r) | head -30".to_string(),
+    )
+}
+
 pub fn fetch_extension(host: Host, tab_id: u64, ext_id: String) -> iced::Task<Message> {
     let cmd = extension_fetch_cmd(&ext_id);
     task_fetch(host, tab_id, "extension", cmd)
 }
 
+// ─── Remote Gauges ───────────────────────────────────────────────────────────
+
+/// Live usage of a connected host, shown in the sidebar next to the local one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RemoteInfo {
+    pub cpu_usage: f32,
+    pub memory_usage: f32,
+    pub disk_usage_percent: f32,
+}
+
+/// Kernel family of a remote host, from `uname -s`. Decides which tools the
+/// gauges are read with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RemoteOs {
+    Linux,
+    MacOs,
+    Bsd,
+    Other,
+}
+
+impl RemoteOs {
+    pub fn from_uname(uname: &str) -> Self {
+        match uname.trim() {
+            "Linux" => Self::Linux,
+            "Darwin" => Self::MacOs,
+            s if s.ends_with("BSD") || s == "DragonFly" => Self::Bsd,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// `None` when the command could not run, so detection is retried later.
+pub fn detect_os(host: Host, tab_id: u64) -> iced::Task<Message> {
+    iced::Task::perform(
+        tokio::task::spawn_blocking(move || ssh_exec_sync(host, "uname -s".to_string())),
+        move |res| {
+            let os = res
+                .ok()
+                .filter(|o| o.exit_code == Some(0))
+                .map(|o| RemoteOs::from_uname(&o.stdout));
+            Message::RemoteOsDetected(tab_id, os)
+        },
+    )
+}
+
+/// CPU is measured over two `/proc/stat` samples half a second apart.
+const REMOTE_INFO_CMD: &str = r#"echo "=== CPU ===" && head -1 /proc/stat && sleep 0.5 && head -1 /proc/stat; \
+echo "=== MEM ===" && grep -E "^(MemTotal|MemAvailable):" /proc/meminfo; \
+echo "=== DISK ===" && df -P / | tail -1"#;
+
+/// `/proc/meminfo`-style lines from `sysctl`; free + inactive pages count as available.
+const BSD_MEM_CMD: &str = r#"sysctl -n hw.physmem hw.pagesize vm.stats.vm.v_free_count vm.stats.vm.v_inactive_count | awk 'NR == 1 {t = $1} NR == 2 {p = $1} NR > 2 {a += $1} END {printf "MemTotal: %d kB\nMemAvailable: %d kB\n", t / 1024, a * p / 1024}'"#;
+
+/// The same from `vm_stat`, whose header carries the page size.
+const MAC_MEM_CMD: &str = r#"(sysctl -n hw.memsize; vm_stat) | awk 'NR == 1 {t = $1} /page size of/ {p = $8} /^Pages (free|inactive|speculative):/ {gsub(/\./, "", $NF); a += $NF} END {printf "MemTotal: %d kB\nMemAvailable: %d kB\n", t / 1024, a * p / 1024}'"#;
+
+/// The gauge command for `os`, printing what `parse_remote_info` reads on Linux.
+/// BSD `kern.cp_time` (user nice sys intr idle) is reordered into `/proc/stat`
+/// columns; macOS `top` only reports percentages, which are scaled into a
+/// second sample after an all-zero first one.
+fn remote_info_cmd(os: RemoteOs) -> String {
+    let (cpu, mem) = match os {
+        RemoteOs::Linux | RemoteOs::Other => return REMOTE_INFO_CMD.to_string(),
+        RemoteOs::Bsd => (
+            r#"(sysctl -n kern.cp_time; sleep 0.5; sysctl -n kern.cp_time) | awk '{print "cpu", $1, $2, $3, $5, 0, $4}'"#,
+            BSD_MEM_CMD,
+        ),
+        RemoteOs::MacOs => (
+            r#"top -l 2 -n 0 -s 1 | awk '/^CPU usage/ {u = $3; s = $5; i = $7} END {print "cpu 0 0 0 0"; print "cpu", u * 100, 0, s * 100, i * 100}'"#,
+            MAC_MEM_CMD,
+        ),
+    };
+    format!("echo \"=== CPU ===\" && {cpu}; echo \"=== MEM ===\" && {mem}; echo \"=== DISK ===\" && df -P / | tail -1")
+}
+
+pub fn fetch_remote_info(host: Host, tab_id: u64, os: RemoteOs) -> iced::Task<Message> {
+    iced::Task::perform(
+        tokio::task::spawn_blocking(move || ssh_exec_sync(host, remote_info_cmd(os))),
+        move |res| Message::RemoteInfoFetched(tab_id, res.ok().and_then(|o| parse_remote_info(&o.stdout))),
+    )
+}
+
+/// `None` when no section could be read (no `/proc`, command failed, garbage).
+/// Sections may be missing, empty or in any order; a gauge whose section is
+/// unusable reads 0 instead of borrowing a neighbour's values.
+fn parse_remote_info(output: &str) -> Option<RemoteInfo> {
+    let mut section = "";
+    let mut cpu: Vec<(f64, f64)> = Vec::new();
+    let (mut mem_total, mut mem_avail) = (None::<f64>, None::<f64>);
+    let mut disk = None::<f32>;
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(name) = trimmed.strip_prefix("=== ").and_then(|l| l.strip_suffix(" ===")) {
+            section = match name {
+                "CPU" => "cpu",
+                "MEM" => "mem",
+                "DISK" => "disk",
+                _ => "",
+            };
+            continue;
+        }
+        let parts: Vec<&str> = trimmed.split_whitespace().collect();
+        match section {
+            // cpu  user nice system idle iowait irq softirq …
+            "cpu" if parts.first() == Some(&"cpu") => {
+                let fields: Option<Vec<f64>> = parts[1..].iter().map(|v| v.parse().ok()).collect();
+                if let Some(fields) = fields.filter(|f| f.len() >= 4) {
+                    let idle = fields[3] + fields.get(4).copied().unwrap_or(0.0);
+                    cpu.push((fields.iter().sum(), idle));
+                }
+            }
+            "mem" => {
+                let kb = parts.get(1).and_then(|v| v.parse::<f64>().ok());
+                match parts.first() {
+                    Some(&"MemTotal:") => mem_total = kb,
+                    Some(&"MemAvailable:") => mem_avail = kb,
+                    _ => {}
+                }
+            }
+            // Filesystem 1024-blocks Used Available Capacity Mounted-on
+            "disk" if parts.len() >= 6 => {
+                if let Some(pct) = parts[parts.len() - 2].strip_suffix('%').and_then(|v| v.parse().ok()) {
+                    disk = Some(pct);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let cpu_usage = match cpu[..] {
+        [(total0, idle0), (total1, idle1), ..] if total1 > total0 => {
+            let busy = (total1 - total0) - (idle1 - idle0);
+            Some((busy / (total1 - total0) * 100.0) as f32)
+        }
+        [_, _, ..] => Some(0.0),
+        _ => None,
+    };
+    let memory_usage = match (mem_total, mem_avail) {
+        (Some(total), Some(avail)) if total > 0.0 => Some(((1.0 - avail / total) * 100.0) as f32),
+        _ => None,
+    };
+    if cpu_usage.is_none() && memory_usage.is_none() && disk.is_none() {
+        return None;
+    }
+    let gauge = |v: Option<f32>| v.filter(|v| v.is_finite()).unwrap_or(0.0).clamp(0.0, 100.0);
+    Some(RemoteInfo {
+        cpu_usage: gauge(cpu_usage),
+        memory_usage: gauge(memory_usage),
+        disk_usage_percent: gauge(disk),
+    })
+}
+
 pub fn run_action(host: Host, tab_id: u64, cmd: String) -> iced::Task<Message> {
     task_fetch(host, tab_id, "action", cmd)
 }
 
+/// Runs `cmd` and emits its combined stdout/stderr as `SysPanelOutput` chunks
+/// while it runs, followed by a `SysPanelFetched(.., "stream", ..)` summary.
+pub fn run_streaming(host: Host, tab_id: u64, cmd: String) -> iced::Task<Message> {
+    use iced::futures::SinkExt;
+
+    iced::Task::run(
+        iced::stream::channel(64, move |mut out| async move {
+            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+            let job = tokio::task::spawn_blocking(move || ssh_exec_streaming(host, cmd, tx));
+            while let Some(chunk) = rx.recv().await {
+                let _ = out.send(Message::SysPanelOutput(tab_id, chunk)).await;
+            }
+            let summary = match job.await {
+                Ok(s) => ExecOutput { stdout: s, exit_code: Some(0), ..Default::default() },
+                Err(e) => ExecOutput::failed(format!("[Task Error] {e}")),
+            };
+            let _ = out
+                .send(Message::SysPanelFetched(tab_id, "stream".into(), summary))
+                .await;
+        }),
+        |msg| msg,
+    )
+}
+
+fn ssh_exec_streaming(
+    host: Host,
+    cmd: String,
+    tx: tokio::sync::mpsc::UnboundedSender<String>,
+) -> String {
+    // A dedicated session: a blocking read on a minutes-long install would
+    // otherwise hold up every fetch sharing the pooled one.
+    let sess = match crate::ssh::open_session(&host) {
+        Ok(s) => s,
+        Err(e) => return format!("[{e}]"),
+    };
+    let mut ch = match sess.channel_session() {
+        Ok(c) => c,
+        Err(e) => return format!("[Channel Error] {e}"),
+    };
+    if ch.exec(&format!("{cmd} 2>&1")).is_err() {
+        return "[Exec failed]".into();
+    }
+
+    let mut buf = [0u8; 4096];
+    // Bytes of a UTF-8 sequence split across reads.
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        match ch.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                pending.extend_from_slice(&buf[..n]);
+                let valid = match std::str::from_utf8(&pending) {
+                    Ok(_) => pending.len(),
+                    Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                    Err(_) => pending.len(),
+                };
+                let rest = pending.split_off(valid);
+                let _ = tx.send(String::from_utf8_lossy(&pending).into_owned());
+                pending = rest;
+            }
+        }
+    }
+    if !pending.is_empty() {
+        let _ = tx.send(String::from_utf8_lossy(&pending).into_owned());
+    }
+    ch.wait_close().ok();
+
+    match ch.exit_status() {
+        Ok(0) => "Finished successfully".into(),
+        Ok(code) => format!("Failed (exit code {code})"),
+        Err(e) => format!("Finished ({e})"),
+    }
+}
+
+// ─── Package Commands ────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PkgOp {
+    Install,
+    Remove,
+    UpdateAll,
+}
+
+pub fn parse_package_manager(output: &str) -> Option<String> {
+    output
+        .lines()
+        .skip_while(|l| !l.contains("=== PACKAGE MANAGER ==="))
+        .nth(1)
+        .map(str::trim)
+        .filter(|m| !m.is_empty() && !m.starts_with("==="))
+        .map(str::to_string)
+}
+
+/// Package names go straight into a shell command, so only accept the
+/// characters real package names use.
+fn valid_package_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._+:@/-".contains(c))
+}
+
+/// Builds the shell command for `op` with the given package manager. Returns
+/// `None` for unknown managers or unsafe package names.
+pub fn package_command(manager: &str, op: PkgOp, pkg: &str) -> Option<String> {
+    let pkg = pkg.trim();
+    if op != PkgOp::UpdateAll && !valid_package_name(pkg) {
+        return None;
+    }
+    let cmd = match (manager, op) {
+        ("apt-get", PkgOp::Install) => format!("sudo -n env DEBIAN_FRONTEND=noninteractive apt-get install -y {pkg}"),
+        ("apt-get", PkgOp::Remove) => format!("sudo -n env DEBIAN_FRONTEND=noninteractive apt-get remove -y {pkg}"),
+        ("apt-get", PkgOp::UpdateAll) => "sudo -n apt-get update && sudo -n env DEBIAN_FRONTEND=noninteractive apt-get upgrade -y".into(),
+        ("dnf" | "yum", PkgOp::Install) => format!("sudo -n {manager} install -y {pkg}"),
+        ("dnf" | "yum", PkgOp::Remove) => format!("sudo -n {manager} remove -y {pkg}"),
+        ("dnf" | "yum", PkgOp::UpdateAll) => format!("sudo -n {manager} upgrade -y"),
+        ("zypper", PkgOp::Install) => format!("sudo -n zypper --non-interactive install {pkg}"),
+        ("zypper", PkgOp::Remove) => format!("sudo -n zypper --non-interactive remove {pkg}"),
+        ("zypper", PkgOp::UpdateAll) => "sudo -n zypper --non-interactive update".into(),
+        ("apk", PkgOp::Install) => format!("sudo -n apk add {pkg}"),
+        ("apk", PkgOp::Remove) => format!("sudo -n apk del {pkg}"),
+        ("apk", PkgOp::UpdateAll) => "sudo -n apk update && sudo -n apk upgrade".into(),
+        ("pacman", PkgOp::Install) => format!("sudo -n pacman -S --noconfirm {pkg}"),
+        ("pacman", PkgOp::Remove) => format!("sudo -n pacman -R --noconfirm {pkg}"),
+        ("pacman", PkgOp::UpdateAll) => "sudo -n pacman -Syu --noconfirm".into(),
+        // Homebrew refuses to run as root.
+        ("brew", PkgOp::Install) => format!("brew install {pkg}"),
+        ("brew", PkgOp::Remove) => format!("brew uninstall {pkg}"),
+        ("brew", PkgOp::UpdateAll) => "brew update && brew upgrade".into(),
+        _ => return None,
+    };
+    Some(cmd)
+}
+
 fn extension_fetch_cmd(id: &str) -> String {
     match id {
         "nginx" => r#"echo "=== Nginx Status ===" && systemctl status nginx 2>/dev/null | head -20 && \
@@ -282,6 +843,187 @@ echo "" && echo "=== PM2 Info ===" && pm2 info 2>/dev/null | head -20 || echo "(
     }
 }
 
+// ─── Cron Commands ───────────────────────────────────────────────────────────
+
+/// Quotes `s` as a single POSIX shell word.
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Splits a crontab entry into `(schedule, command)`. Comments, blank lines
+/// and environment assignments (`MAILTO=...`) yield `None`.
+fn split_cron_line(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let sched_len = if fields[0].starts_with('@') { 1 } else { 5 };
+    if fields.len() <= sched_len {
+        return None;
+    }
+    if sched_len == 5 && fields[0].contains('=') {
+        return None;
+    }
+    Some((fields[..sched_len].join(" "), fields[sched_len..].join(" ")))
+}
+
+/// Command appending `line` to the current user's crontab, or `None` when
+/// the line isn't a valid single cron entry.
+pub fn cron_add_command(line: &str) -> Option<String> {
+    let line = line.trim();
+    if line.contains('\n') || split_cron_line(line).is_none() {
+        return None;
+    }
+    Some(format!(
+        "(crontab -l 2>/dev/null; printf '%s\\n' {}) | crontab -",
+        shell_quote(line)
+    ))
+}
+
+/// Command removing the exact `line` from the user's (or root's) crontab.
+/// The current crontab is read first, so a failed `crontab -l` leaves it
+/// alone instead of installing empty output over it.
+fn cron_delete_command(line: &str, root: bool) -> String {
+    let line = shell_quote(line);
+    let (list, install) = if root {
+        ("sudo -n crontab -l -u root", "sudo -n crontab -u root -")
+    } else {
+        ("crontab -l", "crontab -")
+    };
+    format!("c=$({list}) && printf '%s\\n' \"$c\" | grep -vxF -- {line} | {install}")
+}
+
+// ─── Authorized Keys ─────────────────────────────────────────────────────────
+
+const KEY_TYPE_PREFIXES: &[&str] = &["ssh-", "ecdsa-", "sk-"];
+
+/// One `authorized_keys` line: the raw line plus its key type, comment and
+/// fingerprint as reported by `ssh-keygen -lf`.
+struct AuthorizedKey {
+    line: String,
+    key_type: String,
+    comment: String,
+    fingerprint: String,
+}
+
+/// Parses the `=== Authorized Key Entries ===` section (`fingerprint\tline`).
+fn parse_authorized_keys(output: &str) -> Vec<AuthorizedKey> {
+    output
+        .lines()
+        .skip_while(|l| !l.starts_with("=== Authorized Key Entries"))
+        .skip(1)
+        .take_while(|l| !l.starts_with("==="))
+        .filter_map(|l| {
+            let (fingerprint, line) = l.split_once('\t')?;
+            // Options such as `command="..."` may precede the key type.
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let type_idx = fields
+                .iter()
+                .position(|f| KEY_TYPE_PREFIXES.iter().any(|p| f.starts_with(p)))?;
+            Some(AuthorizedKey {
+                line: line.to_string(),
+                key_type: fields[type_idx].to_string(),
+                comment: fields.get(type_idx + 2..).map(|c| c.join(" ")).unwrap_or_default(),
+                fingerprint: fingerprint.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Command appending `key` to `~/.ssh/authorized_keys` (creating it with
+/// 0600 / 0700 permissions), or `None` if it isn't a single public key line.
+pub fn authorized_key_add_command(key: &str) -> Option<String> {
+    let key = key.trim();
+    let mut fields = key.split_whitespace();
+    let is_key = matches!(
+        (fields.next(), fields.next()),
+        (Some(t), Some(_)) if KEY_TYPE_PREFIXES.iter().any(|p| t.starts_with(p))
+    );
+    if !is_key || key.contains('\n') {
+        return None;
+    }
+    let q = shell_quote(key);
+    Some(format!(
+        "umask 077 && mkdir -p ~/.ssh && chmod 700 ~/.ssh && f=~/.ssh/authorized_keys && touch \"$f\" && chmod 600 \"$f\" && \
+if grep -qxF -- {q} \"$f\"; then echo 'Key already present'; else \
+[ -s \"$f\" ] && [ -n \"$(tail -c1 \"$f\")\" ] && echo >> \"$f\"; printf '%s\\n' {q} >> \"$f\" && echo 'Key added'; fi"
+    ))
+}
+
+/// Last public key line (`ssh-ed25519 AAAA... comment`) in command output.
+pub fn last_public_key(output: &str) -> Option<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .rfind(|l| KEY_TYPE_PREFIXES.iter().any(|p| l.starts_with(p)) && l.split_whitespace().count() >= 2)
+        .map(str::to_string)
+}
+
+/// Appends `key` to the local user's `~/.ssh/authorized_keys` unless it is
+/// already there, returning the file path.
+pub fn append_local_authorized_key(key: &str) -> Result<std::path::PathBuf, String> {
+    use std::io::Write;
+
+    let dirs = directories::BaseDirs::new().ok_or("Cannot locate home directory")?;
+    let ssh_dir = dirs.home_dir().join(".ssh");
+    let path = ssh_dir.join("authorized_keys");
+    std::fs::create_dir_all(&ssh_dir).map_err(|e| format!("Cannot create {}: {}", ssh_dir.display(), e))?;
+
+    let existing = std::fs::read_to_string(&path).unwrap_or_default();
+    if existing.lines().any(|l| l.trim() == key) {
+        return Err(format!("Key is already in {}", path.display()));
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    let sep = if existing.is_empty() || existing.ends_with('\n') { "" } else { "\n" };
+    writeln!(file, "{sep}{key}").map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+
+    // sshd ignores authorized_keys that are group/world writable.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&ssh_dir, std::fs::Permissions::from_mode(0o700));
+        let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+    }
+    Ok(path)
+}
+
+/// Command rewriting `~/.ssh/authorized_keys` without the exact `line`.
+/// The file is only replaced once the copy was fully written; grep exits 1
+/// when no line is left, which is still a success.
+fn authorized_key_remove_command(line: &str) -> String {
+    let q = shell_quote(line);
+    format!(
+        "f=~/.ssh/authorized_keys && {{ grep -vxF -- {q} \"$f\" > \"$f.tmp\"; [ $? -le 1 ] && [ -f \"$f.tmp\" ]; }} && \
+chmod 600 \"$f.tmp\" && mv \"$f.tmp\" \"$f\" && echo 'Key removed' || {{ rm -f \"$f.tmp\"; echo 'Key not removed' >&2; false; }}"
+    )
+}
+
+// ─── User Commands ───────────────────────────────────────────────────────────
+
+/// Mirrors the default `useradd` NAME_REGEX; also keeps names shell-safe.
+fn valid_account_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_lowercase() || c == '_')
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+        && name.len() <= 32
+}
+
+pub fn user_add_command(name: &str) -> Option<String> {
+    let name = name.trim();
+    valid_account_name(name).then(|| format!("sudo -n useradd -m {name} 2>&1"))
+}
+
+pub fn user_add_group_command(name: &str, group: &str) -> Option<String> {
+    let (name, group) = (name.trim(), group.trim());
+    (valid_account_name(name) && valid_account_name(group))
+        .then(|| format!("sudo -n usermod -aG {group} {name} 2>&1"))
+}
+
 // ─── Parse Extensions ────────────────────────────────────────────────────────
 
 const KNOWN_EXTENSIONS: &[(&str, &str)] = &[
@@ -327,8 +1069,18 @@ pub fn parse_extensions(output: &str) -> Vec<ExtensionInfo> {
     exts
 }
 
+/// Reads the `Active:` line of `systemctl status` output, if present.
+pub fn parse_service_active(output: &str) -> Option<bool> {
+    output
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("Active:"))
+        .map(|state| state.trim().starts_with("active"))
+}
+
 // ─── Table Helpers ───────────────────────────────────────────────────────────
 
+const ACTION_COL_WIDTH: f32 = 36.0;
+
 fn action_color(value: &str, p: theme::Palette) -> iced::Color {
     let v = value.to_uppercase();
     if v.contains("ALLOW") || v.contains("ACCEPT") {
@@ -349,9 +1101,20 @@ fn render_table(
     p: theme::Palette,
     cr: f32,
 ) -> Element<'static, Message> {
-    let mut col: Column<'static, Message> = Column::new().spacing(0).width(Length::Fill);
+    render_table_with_actions(headers, rows, accent_col, "", Vec::new(), p, cr)
+}
 
-    // Header row
+/// Like [`render_table`], but appends a small `action_label` button to each
+/// row whose entry in `actions` is `Some`, sending that message when pressed.
+fn render_table_with_actions(
+    headers: &[(&'static str, u16)],
+    rows: Vec<Vec<String>>,
+    accent_col: Option<usize>,
+    action_label: &'static str,
+    actions: Vec<Option<Message>>,
+    p: theme::Palette,
+    cr: f32,
+) -> Element<'static, Message> {
     let mut header_row: Row<'static, Message> = Row::new();
     for &(label, portion) in headers {
         header_row = header_row.push(
@@ -360,6 +1123,64 @@ fn render_table(
                 .padding([4, 8]),
         );
     }
+    render_table_under(header_row, headers, rows, accent_col, action_label, actions, p, cr)
+}
+
+/// Column headers that sort the table when clicked: `sort_col` is marked
+/// with ▲ or ▼ (`desc`), and pressing column `i` sends `on_sort(i)`.
+fn sort_header(
+    headers: &[(&'static str, u16)],
+    sort_col: usize,
+    desc: bool,
+    on_sort: impl Fn(usize) -> Message,
+    p: theme::Palette,
+) -> Row<'static, Message> {
+    let mut header_row: Row<'static, Message> = Row::new();
+    for (i, &(label, portion)) in headers.iter().enumerate() {
+        let arrow = match (i == sort_col, desc) {
+            (true, false) => " ▲",
+            (true, true) => " ▼",
+            _ => "",
+        };
+        header_row = header_row.push(
+            button(text(format!("{label}{arrow}")).size(10).color(p.text_muted))
+                .on_press(on_sort(i))
+                .width(Length::FillPortion(portion))
+                .padding([4, 8])
+                .style(move |_: &iced::Theme, s: button::Status| button::Style {
+                    background: Some(iced::Background::Color(match s {
+                        button::Status::Hovered | button::Status::Pressed => p.bg_hover,
+                        _ => p.bg_primary,
+                    })),
+                    text_color: p.text_muted,
+                    border: iced::Border::default(),
+                    ..Default::default()
+                }),
+        );
+    }
+    header_row
+}
+
+/// Body of [`render_table_with_actions`] below an already built header row.
+#[allow(clippy::too_many_arguments)]
+fn render_table_under(
+    mut header_row: Row<'static, Message>,
+    headers: &[(&'static str, u16)],
+    rows: Vec<Vec<String>>,
+    accent_col: Option<usize>,
+    action_label: &'static str,
+    actions: Vec<Option<Message>>,
+    p: theme::Palette,
+    cr: f32,
+) -> Element<'static, Message> {
+    let has_actions = actions.iter().any(Option::is_some);
+    let action_width = (action_label.chars().count() as f32 * 7.0 + 22.0).max(ACTION_COL_WIDTH);
+    let mut actions = actions.into_iter();
+    let mut col: Column<'static, Message> = Column::new().spacing(0).width(Length::Fill);
+
+    if has_actions {
+        header_row = header_row.push(container(text("")).width(Length::Fixed(action_width)));
+    }
     col = col.push(
         container(header_row)
             .width(Length::Fill)
@@ -403,6 +1224,21 @@ fn render_table(
                         .padding([3, 8]),
                 );
             }
+            if has_actions {
+                let cell: Element<'static, Message> = match actions.next().flatten() {
+                    Some(msg) => button(text(action_label).size(10).color(p.danger))
+                        .on_press(msg)
+                        .padding([1, 6])
+                        .style(btn_style(p, false, cr))
+                        .into(),
+                    None => text("").into(),
+                };
+                data_row = data_row.push(
+                    container(cell)
+                        .width(Length::Fixed(action_width))
+                        .padding([2, 4]),
+                );
+            }
             col = col.push(
                 container(data_row)
                     .width(Length::Fill)
@@ -423,6 +1259,9 @@ fn render_table(
         .into()
 }
 
+/// Parses `ufw status numbered` or `iptables -L` output. UFW rows are
+/// `[number, to, action, from]`; iptables rows are
+/// `[target, protocol, source, destination]`.
 fn parse_firewall_rules(output: &str) -> (bool, Vec<Vec<String>>) {
     // Detect by whether the UFW rules header ("To  Action  From") is actually
     // present in the output — NOT just by the "=== UFW Status ===" echo which is
@@ -449,14 +1288,20 @@ fn parse_firewall_rules(output: &str) -> (bool, Vec<Vec<String>>) {
             if trimmed.starts_with("--") { continue; }
             // Skip the trailing "[ok]" marker
             if trimmed == "[ok]" { continue; }
+            // Numbered mode prefixes each rule with "[ n]".
+            let (number, rest) = match trimmed.strip_prefix('[').and_then(|r| r.split_once(']')) {
+                Some((n, rest)) if n.trim().parse::<u32>().is_ok() => (n.trim().to_string(), rest),
+                _ => (String::new(), line),
+            };
             // UFW columns are separated by 2+ spaces; single spaces inside a
             // column value (e.g. "ALLOW IN", "Anywhere (v6)") are preserved.
-            let parts: Vec<&str> = line.split("  ")
+            let parts: Vec<&str> = rest.split("  ")
                 .map(str::trim)
                 .filter(|s| !s.is_empty())
                 .collect();
             if parts.len() >= 2 {
                 rows.push(vec![
+                    number,
                     parts[0].to_string(),
                     parts.get(1).unwrap_or(&"").to_string(),
                     parts.get(2).unwrap_or(&"*").to_string(),
@@ -484,6 +1329,8 @@ fn parse_firewall_rules(output: &str) -> (bool, Vec<Vec<String>>) {
     (is_ufw, rows)
 }
 
+const PACKAGE_COLUMNS: [(&str, u16); 2] = [("PACKAGE", 3), ("VERSION", 2)];
+
 fn parse_packages(output: &str) -> Vec<Vec<String>> {
     let mut rows: Vec<Vec<String>> = Vec::new();
     let mut in_data = false;
@@ -503,12 +1350,155 @@ fn parse_packages(output: &str) -> Vec<Vec<String>> {
     rows
 }
 
-fn parse_logins(output: &str) -> (Vec<Vec<String>>, Vec<Vec<String>>, Vec<Vec<String>>) {
-    let mut current: Vec<Vec<String>> = Vec::new();
-    let mut history: Vec<Vec<String>> = Vec::new();
-    let mut failed: Vec<Vec<String>> = Vec::new();
-    let mut section: u8 = 0;
-
+/// Returns `(is_root, schedule, command, raw line)` for each cron entry.
+fn parse_cron(output: &str) -> Vec<(bool, String, String, String)> {
+    let mut entries = Vec::new();
+    let mut root = false;
+    for line in output.lines() {
+        if line.starts_with("=== User Crontab") { root = false; continue; }
+        if line.starts_with("=== Root Crontab") { root = true; continue; }
+        if line.starts_with('[') { continue; }
+        if let Some((schedule, command)) = split_cron_line(line) {
+            entries.push((root, schedule, command, line.to_string()));
+        }
+    }
+    entries
+}
+
+/// Parses `systemctl list-units --plain --no-legend` into
+/// `[unit, load, active, sub, description]` rows.
+fn parse_services(output: &str) -> Vec<Vec<String>> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 4 || !parts[0].ends_with(".service") {
+                return None;
+            }
+            Some(vec![
+                parts[0].to_string(),
+                parts[1].to_string(),
+                parts[2].to_string(),
+                parts[3].to_string(),
+                parts[4..].join(" "),
+            ])
+        })
+        .collect()
+}
+
+/// Parses the `user|uid|groups|status|last login` lines of [`fetch_users`].
+fn parse_users(output: &str) -> Vec<Vec<String>> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('|').collect();
+            if parts.len() != 5 {
+                return None;
+            }
+            let status = match parts[3] {
+                "L" | "LK" => "locked",
+                "P" | "PS" => "active",
+                "NP" => "no password",
+                _ => "?",
+            };
+            Some(vec![
+                parts[0].to_string(),
+                parts[1].to_string(),
+                parts[2].replace(',', ", "),
+                status.to_string(),
+                parts[4].to_string(),
+            ])
+        })
+        .collect()
+}
+
+/// One usage bar on the overview: label, used fraction (0–1) and "used / total".
+struct Gauge {
+    label: String,
+    fraction: f32,
+    detail: String,
+}
+
+/// Parses a `free -h` / `df -h` size such as `7.7Gi`, `512M` or `0B` into bytes.
+fn parse_size(s: &str) -> Option<f64> {
+    let split = s.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ',')).unwrap_or(s.len());
+    let value: f64 = s[..split].replace(',', ".").parse().ok()?;
+    let exp = match s[split..].trim_end_matches(['i', 'B']) {
+        "" => 0,
+        "K" | "k" => 1,
+        "M" => 2,
+        "G" => 3,
+        "T" => 4,
+        "P" => 5,
+        _ => return None,
+    };
+    Some(value * 1024f64.powi(exp))
+}
+
+/// Pulls memory/swap usage out of the `=== MEMORY ===` section and per-mount
+/// disk usage out of `=== DISK ===`.
+fn parse_overview_gauges(output: &str) -> Vec<Gauge> {
+    let mut gauges = Vec::new();
+    let mut section = "";
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(name) = trimmed.strip_prefix("=== ").and_then(|t| t.strip_suffix(" ===")) {
+            section = if name == "MEMORY" || name == "DISK" { name } else { "" };
+            continue;
+        }
+        let parts: Vec<&str> = trimmed.split_whitespace().collect();
+        match section {
+            "MEMORY" if matches!(parts.first(), Some(&"Mem:") | Some(&"Swap:")) && parts.len() >= 3 => {
+                let (Some(total), Some(used)) = (parse_size(parts[1]), parse_size(parts[2])) else { continue; };
+                if total > 0.0 {
+                    gauges.push(Gauge {
+                        label: if parts[0] == "Mem:" { "Memory".into() } else { "Swap".into() },
+                        fraction: (used / total).clamp(0.0, 1.0) as f32,
+                        detail: format!("{} / {}", parts[2], parts[1]),
+                    });
+                }
+            }
+            // Filesystem Size Used Avail Use% Mounted-on
+            "DISK" if parts.len() >= 6 => {
+                let Some(pct) = parts[4].strip_suffix('%').and_then(|v| v.parse::<f32>().ok()) else { continue; };
+                gauges.push(Gauge {
+                    label: format!("Disk {}", parts[5..].join(" ")),
+                    fraction: (pct / 100.0).clamp(0.0, 1.0),
+                    detail: format!("{} / {}", parts[2], parts[1]),
+                });
+            }
+            _ => {}
+        }
+    }
+    gauges
+}
+
+/// Parses `ps aux` into `[user, pid, %cpu, %mem, command]` rows.
+fn parse_processes(output: &str) -> Vec<Vec<String>> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 11 || parts[1].parse::<u32>().is_err() {
+                return None;
+            }
+            Some(vec![
+                parts[0].to_string(),
+                parts[1].to_string(),
+                parts[2].to_string(),
+                parts[3].to_string(),
+                parts[10..].join(" "),
+            ])
+        })
+        .collect()
+}
+
+fn parse_logins(output: &str) -> (Vec<Vec<String>>, Vec<Vec<String>>, Vec<Vec<String>>) {
+    let mut current: Vec<Vec<String>> = Vec::new();
+    let mut history: Vec<Vec<String>> = Vec::new();
+    let mut failed: Vec<Vec<String>> = Vec::new();
+    let mut section: u8 = 0;
+
     for line in output.lines() {
         let trimmed = line.trim();
         if trimmed.contains("Currently Logged In") { section = 1; continue; }
@@ -538,6 +1528,52 @@ fn parse_logins(output: &str) -> (Vec<Vec<String>>, Vec<Vec<String>>, Vec<Vec<St
     (current, history, failed)
 }
 
+/// Failed login sources with their attempt counts, most attempts first.
+/// `lastb` rows carry the source in FROM; auth.log lines in "from <ip>".
+fn failed_login_sources(failed: &[Vec<String>]) -> Vec<(String, usize)> {
+    let mut sources: Vec<(String, usize)> = Vec::new();
+    for row in failed {
+        let line = row.join(" ");
+        let source = match line.split_once(" from ") {
+            Some((_, rest)) => rest.split_whitespace().next().unwrap_or_default(),
+            None => row.get(2).map(String::as_str).unwrap_or_default(),
+        };
+        if source.is_empty() {
+            continue;
+        }
+        match sources.iter_mut().find(|(s, _)| s == source) {
+            Some((_, n)) => *n += 1,
+            None => sources.push((source.to_string(), 1)),
+        }
+    }
+    sources.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    sources
+}
+
+/// IP addresses among the failed login sources of a logins `output`.
+pub fn failed_login_ips(output: &str) -> Vec<std::net::IpAddr> {
+    let (_, _, failed) = parse_logins(output);
+    failed_login_sources(&failed)
+        .into_iter()
+        .filter_map(|(s, _)| s.parse().ok())
+        .collect()
+}
+
+/// Looks up the reverse DNS name of each of `ips` with the local resolver.
+pub fn resolve_sources(tab_id: u64, ips: Vec<std::net::IpAddr>) -> iced::Task<Message> {
+    iced::Task::perform(
+        tokio::task::spawn_blocking(move || {
+            ips.into_iter()
+                .map(|ip| {
+                    let name = dns_lookup::lookup_addr(&ip).ok().filter(|n| *n != ip.to_string());
+                    (ip.to_string(), name)
+                })
+                .collect()
+        }),
+        move |res| Message::SysPanelSourcesResolved(tab_id, res.unwrap_or_default()),
+    )
+}
+
 // ─── View ────────────────────────────────────────────────────────────────────
 
 fn btn_style(p: theme::Palette, accent: bool, cr: f32) -> impl Fn(&iced::Theme, button::Status) -> button::Style {
@@ -594,6 +1630,10 @@ pub fn view_sys_panel(
         SysTab::Packages,
         SysTab::Logins,
         SysTab::SshKeys,
+        SysTab::Cron,
+        SysTab::Services,
+        SysTab::Users,
+        SysTab::Processes,
     ];
     for ext in &state.extensions {
         tabs.push(SysTab::Extension(ext.id.clone()));
@@ -677,6 +1717,23 @@ pub fn view_sys_panel(
         container(text("").size(1)).height(Length::Fixed(0.0)).into()
     };
 
+    // ── Stderr banner: warnings stay out of the parsed tables ────────────────
+    let stderr_banner: Element<'static, Message> = if state.stderr.trim().is_empty() || state.loading {
+        container(text("").size(1)).height(Length::Fixed(0.0)).into()
+    } else {
+        let lines: Vec<&str> = state.stderr.trim().lines().collect();
+        let shown = lines[lines.len().saturating_sub(4)..].join("\n");
+        container(text(shown).size(10).color(p.danger).font(iced::Font::MONOSPACE))
+            .padding([3, 12])
+            .width(Length::Fill)
+            .style(move |_: &iced::Theme| container::Style {
+                background: Some(iced::Background::Color(p.bg_tertiary)),
+                border: iced::Border { color: p.danger, width: 1.0, radius: cr.into() },
+                ..Default::default()
+            })
+            .into()
+    };
+
     // ── Content ──────────────────────────────────────────────────────────────
     let content: Element<'static, Message> = if state.loading {
         container(
@@ -699,12 +1756,16 @@ pub fn view_sys_panel(
             SysTab::Packages => view_packages(tab_id, state, p, cr),
             SysTab::Logins => view_logins(tab_id, state, p, cr),
             SysTab::SshKeys => view_ssh_keys(tab_id, state, p, cr),
+            SysTab::Cron => view_cron(tab_id, state, p, cr),
+            SysTab::Services => view_services(tab_id, state, p, cr),
+            SysTab::Users => view_users(tab_id, state, p, cr),
+            SysTab::Processes => view_processes(tab_id, state, p, cr),
             SysTab::Extension(id) => view_extension(tab_id, id.clone(), state, p, cr),
         }
     };
 
     container(
-        column![tab_bar, action_banner, content].spacing(0).height(Length::Fill),
+        column![tab_bar, action_banner, stderr_banner, content].spacing(0).height(Length::Fill),
     )
     .width(Length::Fill)
     .height(Length::Fill)
@@ -768,6 +1829,71 @@ fn view_overview(
         );
     }
 
+    let mut gauges: Column<'static, Message> = Column::new().spacing(6);
+    for g in parse_overview_gauges(&output) {
+        let bar_color = if g.fraction >= 0.9 {
+            p.danger
+        } else if g.fraction >= 0.7 {
+            p.warning
+        } else {
+            p.success
+        };
+        gauges = gauges.push(
+            row![
+                text(g.label).size(11).color(p.text_secondary).width(Length::Fixed(110.0)),
+                progress_bar(0.0..=1.0, g.fraction)
+                    .height(Length::Fixed(8.0))
+                    .style(move |_: &iced::Theme| progress_bar::Style {
+                        background: iced::Background::Color(p.bg_tertiary),
+                        bar: iced::Background::Color(bar_color),
+                        border: iced::Border { radius: cr.into(), ..Default::default() },
+                    }),
+                text(format!("{:>3.0}%", g.fraction * 100.0))
+                    .size(11)
+                    .color(p.text_primary)
+                    .width(Length::Fixed(40.0)),
+                text(g.detail).size(10).color(p.text_muted).width(Length::Fixed(110.0)),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    let show_raw = state.show_raw;
+    let raw_toggle = button(
+        text(if show_raw { "▾ Raw output" } else { "▸ Raw output" })
+            .size(11)
+            .color(p.text_secondary),
+    )
+    .on_press(Message::SysPanelInput(
+        tab_id,
+        "show_raw".into(),
+        if show_raw { "closed".into() } else { "open".into() },
+    ))
+    .padding([2, 4])
+    .style(move |_: &iced::Theme, s: button::Status| button::Style {
+        background: Some(iced::Background::Color(match s {
+            button::Status::Hovered => p.bg_hover,
+            _ => iced::Color::TRANSPARENT,
+        })),
+        text_color: p.text_secondary,
+        border: iced::Border::default(),
+        ..Default::default()
+    });
+    let raw: Element<'static, Message> = if show_raw {
+        scrollable(
+            text(output)
+                .size(11)
+                .color(p.text_primary)
+                .font(iced::Font::MONOSPACE),
+        )
+        .height(Length::Fill)
+        .style(hidden_scrollbar_style)
+        .into()
+    } else {
+        iced::widget::Space::new(0.0, 0.0).into()
+    };
+
     column![
         row![
             text("System Overview").size(14).color(p.text_primary),
@@ -778,6 +1904,7 @@ fn view_overview(
         ]
         .spacing(10)
         .align_y(Alignment::Center),
+        gauges,
         if !state.extensions.is_empty() {
             let ext_section: Element<'static, Message> = column![
                 text("Detected Services").size(11).color(p.text_secondary),
@@ -795,14 +1922,8 @@ fn view_overview(
             .into();
             no_ext
         },
-        scrollable(
-            text(output)
-                .size(11)
-                .color(p.text_primary)
-                .font(iced::Font::MONOSPACE),
-        )
-        .height(Length::Fill)
-        .style(hidden_scrollbar_style),
+        raw_toggle,
+        raw,
     ]
     .spacing(10)
     .padding([8, 12])
@@ -934,10 +2055,19 @@ fn view_firewall(
         .padding([8, 8])
         .into()
     } else if is_ufw {
-        render_table(
-            &[("PORT / SERVICE", 3), ("ACTION", 2), ("FROM / SOURCE", 3)],
+        let deletes = rules
+            .iter()
+            .map(|r| {
+                let n = r.first().filter(|n| !n.is_empty())?;
+                Some(Message::SysPanelAction(tab_id, format!("sudo -n ufw --force delete {n}")))
+            })
+            .collect();
+        render_table_with_actions(
+            &[("#", 1), ("PORT / SERVICE", 3), ("ACTION", 2), ("FROM / SOURCE", 3)],
             rules,
-            Some(1), // color the Action column
+            Some(2), // color the Action column
+            "✕",
+            deletes,
             p,
             cr,
         )
@@ -1018,9 +2148,47 @@ fn view_packages(
 ) -> Element<'static, Message> {
     let pkg_search = state.pkg_search.clone();
     let output = state.output.clone();
+    let busy = state.streaming;
+    let manager = state.pkg_manager.clone();
+
+    let header = row![
+        text("Package Manager").size(14).color(p.text_primary),
+        text(manager.clone().unwrap_or_else(|| "not detected".into()))
+            .size(11)
+            .color(p.text_muted),
+        button(text("↻ Refresh").size(11).color(p.text_primary))
+            .on_press_maybe((!busy).then(|| Message::SysPanelFetch(tab_id, "packages".into())))
+            .padding([3, 10])
+            .style(btn_style(p, true, cr)),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center);
+
+    // While a package command runs (and until the list is refreshed), show its log.
+    if state.showing_log {
+        let status = if busy { "⟳  Running..." } else { "Done — refresh to return to the package list" };
+        return column![
+            header,
+            text(status).size(11).color(p.text_muted),
+            scrollable(
+                text(output)
+                    .size(11)
+                    .color(p.text_primary)
+                    .font(iced::Font::MONOSPACE),
+            )
+            .anchor_bottom()
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .style(hidden_scrollbar_style),
+        ]
+        .spacing(8)
+        .padding([8, 12])
+        .height(Length::Fill)
+        .into();
+    }
 
     let all_rows = parse_packages(&output);
-    let rows: Vec<Vec<String>> = if pkg_search.is_empty() {
+    let mut rows: Vec<Vec<String>> = if pkg_search.is_empty() {
         all_rows
     } else {
         let lower = pkg_search.to_lowercase();
@@ -1029,34 +2197,81 @@ fn view_packages(
             .filter(|r| r.iter().any(|c| c.to_lowercase().contains(&lower)))
             .collect()
     };
+    // No sort column keeps the order the package manager listed them in.
+    if let Some(col) = state.pkg_sort {
+        rows.sort_by(|a, b| a[col].to_lowercase().cmp(&b[col].to_lowercase()));
+        if state.pkg_sort_desc {
+            rows.reverse();
+        }
+    }
     let row_count = rows.len();
 
+    // Removing and upgrading change the system, so both ask first.
+    let removes = rows
+        .iter()
+        .map(|r| {
+            let name = r.first()?;
+            let cmd = package_command(manager.as_deref()?, PkgOp::Remove, name)?;
+            Some(Message::SysPanelConfirm(tab_id, format!("Remove the package {name}?"), "Remove", cmd))
+        })
+        .collect();
+
     let search_input = text_input("Search packages...", &pkg_search)
         .on_input(move |v| Message::SysPanelInput(tab_id, "pkg_search".into(), v))
         .padding(6)
         .size(12)
         .style(input_style(p, cr));
 
+    let install_cmd = manager
+        .as_deref()
+        .and_then(|m| package_command(m, PkgOp::Install, &state.pkg_install));
+    let install_input = text_input("Package to install", &state.pkg_install)
+        .on_input(move |v| Message::SysPanelInput(tab_id, "pkg_install".into(), v))
+        .on_submit_maybe(install_cmd.clone().map(|c| Message::SysPanelAction(tab_id, c)))
+        .padding(6)
+        .size(12)
+        .width(Length::Fixed(220.0))
+        .style(input_style(p, cr));
+    let install_btn = button(text("Install").size(11).color(p.text_primary))
+        .on_press_maybe(install_cmd.map(|c| Message::SysPanelAction(tab_id, c)))
+        .padding([4, 14])
+        .style(btn_style(p, true, cr));
+    let update_btn = button(text("Update all").size(11).color(p.text_primary))
+        .on_press_maybe(
+            manager
+                .as_deref()
+                .and_then(|m| package_command(m, PkgOp::UpdateAll, ""))
+                .map(|c| {
+                    Message::SysPanelConfirm(tab_id, "Upgrade every installed package?".into(), "Update all", c)
+                }),
+        )
+        .padding([4, 14])
+        .style(btn_style(p, false, cr));
+
     column![
-        row![
-            text("Package Manager").size(14).color(p.text_primary),
-            button(text("↻ Refresh").size(11).color(p.text_primary))
-                .on_press(Message::SysPanelFetch(tab_id, "packages".into()))
-                .padding([3, 10])
-                .style(btn_style(p, true, cr)),
-        ]
-        .spacing(10)
-        .align_y(Alignment::Center),
+        header,
+        row![install_input, install_btn, update_btn]
+            .spacing(8)
+            .align_y(Alignment::Center),
         row![
             search_input,
             text(format!("{row_count} package(s)")).size(11).color(p.text_muted),
         ]
         .spacing(10)
         .align_y(Alignment::Center),
-        scrollable(render_table(
-            &[("PACKAGE", 3), ("VERSION", 2)],
+        scrollable(render_table_under(
+            sort_header(
+                &PACKAGE_COLUMNS,
+                state.pkg_sort.unwrap_or(usize::MAX),
+                state.pkg_sort_desc,
+                |i| Message::SysPanelInput(tab_id, "pkg_sort".into(), i.to_string()),
+                p,
+            ),
+            &PACKAGE_COLUMNS,
             rows,
             None,
+            "Remove",
+            removes,
             p,
             cr,
         ))
@@ -1079,6 +2294,7 @@ fn view_logins(
 ) -> Element<'static, Message> {
     let output = state.output.clone();
     let (current, history, failed) = parse_logins(&output);
+    let sources = failed_login_sources(&failed);
 
     let section_header = |label: &'static str, color: iced::Color| -> Element<'static, Message> {
         text(label).size(12).color(color).into()
@@ -1112,6 +2328,23 @@ fn view_logins(
                     p,
                     cr,
                 ),
+                row![
+                    section_header("Failed Logins by Source", p.danger),
+                    button(
+                        text(if state.resolving { "Resolving..." } else { "Resolve names" })
+                            .size(11)
+                            .color(p.text_primary),
+                    )
+                    .on_press_maybe(
+                        (!state.resolving && !sources.is_empty())
+                            .then_some(Message::SysPanelResolveSources(tab_id)),
+                    )
+                    .padding([3, 10])
+                    .style(btn_style(p, false, cr)),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+                view_login_sources(sources, &state.source_names, p, cr),
                 section_header("Failed Login Attempts", p.danger),
                 render_table(
                     &[("USER", 2), ("TTY", 1), ("FROM", 3), ("DATE", 4)],
@@ -1132,6 +2365,70 @@ fn view_logins(
     .into()
 }
 
+/// Sources with the most failed logins, shown in red.
+const TOP_OFFENDERS: usize = 3;
+
+/// Failed login counts per source; the top offenders with more than one
+/// attempt are highlighted.
+fn view_login_sources(
+    sources: Vec<(String, usize)>,
+    names: &std::collections::HashMap<String, Option<String>>,
+    p: theme::Palette,
+    cr: f32,
+) -> Element<'static, Message> {
+    let headers = [("SOURCE", 3), ("ATTEMPTS", 1), ("NAME", 4)];
+    let mut col: Column<'static, Message> = Column::new().spacing(0).width(Length::Fill);
+    let mut header_row: Row<'static, Message> = Row::new();
+    for (label, portion) in headers {
+        header_row = header_row.push(
+            container(text(label).size(10).color(p.text_muted))
+                .width(Length::FillPortion(portion))
+                .padding([4, 8]),
+        );
+    }
+    col = col.push(header_row);
+    if sources.is_empty() {
+        col = col.push(
+            container(text("No failed logins").size(11).color(p.text_muted))
+                .padding([6, 8])
+                .width(Length::Fill),
+        );
+    }
+    for (i, (source, count)) in sources.into_iter().enumerate() {
+        let bg = if i % 2 == 0 { p.bg_secondary } else { p.bg_tertiary };
+        let color = if i < TOP_OFFENDERS && count > 1 { p.danger } else { p.text_primary };
+        let name = match names.get(&source) {
+            Some(Some(name)) => name.clone(),
+            Some(None) => "—".to_string(),
+            None => String::new(),
+        };
+        let cells = [source, count.to_string(), name];
+        let mut data_row: Row<'static, Message> = Row::new();
+        for (j, cell) in cells.into_iter().enumerate() {
+            data_row = data_row.push(
+                container(text(cell).size(11).color(if j == 2 { p.text_secondary } else { color }))
+                    .width(Length::FillPortion(headers[j].1))
+                    .padding([3, 8]),
+            );
+        }
+        col = col.push(
+            container(data_row)
+                .width(Length::Fill)
+                .style(move |_: &iced::Theme| container::Style {
+                    background: Some(iced::Background::Color(bg)),
+                    ..Default::default()
+                }),
+        );
+    }
+    container(col)
+        .width(Length::Fill)
+        .style(move |_: &iced::Theme| container::Style {
+            border: iced::Border { color: p.border, width: 1.0, radius: cr.into() },
+            ..Default::default()
+        })
+        .into()
+}
+
 // ─── SSH Keys ────────────────────────────────────────────────────────────────
 
 fn view_ssh_keys(
@@ -1151,6 +2448,34 @@ fn view_ssh_keys(
         r#"ssh-keygen -t {kt} -N "" -f ~/.ssh/{kn} && echo "Key generated: ~/.ssh/{kn}" && cat ~/.ssh/{kn}.pub"#
     );
 
+    let keys = parse_authorized_keys(&output);
+    let key_count = keys.len();
+    let mut key_rows = Vec::with_capacity(key_count);
+    let mut key_removes = Vec::with_capacity(key_count);
+    for key in keys {
+        let label = if key.comment.is_empty() { key.key_type.clone() } else { key.comment.clone() };
+        key_removes.push(Some(Message::SysPanelConfirm(
+            tab_id,
+            format!("Remove the authorized key \"{label}\"? Its owner will no longer be able to log in with it."),
+            "Remove",
+            authorized_key_remove_command(&key.line),
+        )));
+        key_rows.push(vec![key.key_type, key.comment, key.fingerprint]);
+    }
+
+    let add_key_cmd = authorized_key_add_command(&state.auth_key_input);
+    let auth_key_input = text_input("Paste a public key (ssh-ed25519 AAAA... user@host)", &state.auth_key_input)
+        .on_input(move |v| Message::SysPanelInput(tab_id, "auth_key_input".into(), v))
+        .on_submit_maybe(add_key_cmd.clone().map(|c| Message::SysPanelAction(tab_id, c)))
+        .padding(6)
+        .size(12)
+        .font(iced::Font::MONOSPACE)
+        .style(input_style(p, cr));
+    let add_key_btn = button(text("Add key").size(11).color(p.text_primary))
+        .on_press_maybe(add_key_cmd.map(|c| Message::SysPanelAction(tab_id, c)))
+        .padding([4, 14])
+        .style(btn_style(p, true, cr));
+
     let name_input = text_input("Key filename (e.g. id_termissh)", &key_name)
         .on_input(move |v| Message::SysPanelInput(tab_id, "key_name".into(), v))
         .padding(6)
@@ -1158,6 +2483,30 @@ fn view_ssh_keys(
         .width(Length::Fixed(200.0))
         .style(input_style(p, cr));
 
+    let generated: Element<'static, Message> = match &state.generated_pubkey {
+        Some(key) => {
+            let mut preview = key.clone();
+            if preview.chars().count() > 60 {
+                preview = format!("{}…", preview.chars().take(60).collect::<String>());
+            }
+            row![
+                text(preview).size(11).color(p.text_secondary).font(iced::Font::MONOSPACE),
+                button(text("Copy Public Key").size(11).color(p.text_primary))
+                    .on_press(Message::SysPanelCopyPublicKey(tab_id))
+                    .padding([4, 10])
+                    .style(btn_style(p, true, cr)),
+                button(text("Copy to local authorized_keys").size(11).color(p.text_primary))
+                    .on_press(Message::SysPanelAuthorizeLocally(tab_id))
+                    .padding([4, 10])
+                    .style(btn_style(p, false, cr)),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center)
+            .into()
+        }
+        None => iced::widget::Space::new(0.0, 0.0).into(),
+    };
+
     column![
         row![
             text("SSH Key Manager").size(14).color(p.text_primary),
@@ -1217,6 +2566,7 @@ fn view_ssh_keys(
                 text("Keys are generated on the REMOTE machine (the SSH server). Files are saved to ~/.ssh/")
                     .size(10)
                     .color(p.text_muted),
+                generated,
             ]
             .spacing(8),
         )
@@ -1228,10 +2578,274 @@ fn view_ssh_keys(
             ..Default::default()
         }),
         scrollable(
-            text(output)
-                .size(11)
-                .color(p.text_primary)
-                .font(iced::Font::MONOSPACE),
+            column![
+                text(format!("Authorized Keys ({key_count})")).size(12).color(p.text_secondary),
+                row![auth_key_input, add_key_btn].spacing(8).align_y(Alignment::Center),
+                render_table_with_actions(
+                    &[("TYPE", 2), ("COMMENT", 3), ("FINGERPRINT", 5)],
+                    key_rows,
+                    None,
+                    "Remove",
+                    key_removes,
+                    p,
+                    cr,
+                ),
+                text(output)
+                    .size(11)
+                    .color(p.text_primary)
+                    .font(iced::Font::MONOSPACE),
+            ]
+            .spacing(8),
+        )
+        .height(Length::Fill)
+        .style(hidden_scrollbar_style),
+    ]
+    .spacing(8)
+    .padding([8, 12])
+    .height(Length::Fill)
+    .into()
+}
+
+// ─── Cron ────────────────────────────────────────────────────────────────────
+
+fn view_cron(
+    tab_id: u64,
+    state: &SysState,
+    p: theme::Palette,
+    cr: f32,
+) -> Element<'static, Message> {
+    let entries = parse_cron(&state.output);
+    let entry_count = entries.len();
+    let no_root_access = state.output.contains("[no access]");
+
+    let mut rows = Vec::with_capacity(entry_count);
+    let mut deletes = Vec::with_capacity(entry_count);
+    for (root, schedule, command, line) in entries {
+        rows.push(vec![
+            if root { "root".to_string() } else { "user".to_string() },
+            schedule,
+            command,
+        ]);
+        deletes.push(Some(Message::SysPanelAction(tab_id, cron_delete_command(&line, root))));
+    }
+
+    let add_cmd = cron_add_command(&state.cron_line);
+    let line_input = text_input("*/5 * * * * /path/to/script.sh", &state.cron_line)
+        .on_input(move |v| Message::SysPanelInput(tab_id, "cron_line".into(), v))
+        .on_submit_maybe(add_cmd.clone().map(|c| Message::SysPanelAction(tab_id, c)))
+        .padding(6)
+        .size(12)
+        .font(iced::Font::MONOSPACE)
+        .style(input_style(p, cr));
+    let add_btn = button(text("Add Job").size(11).color(p.text_primary))
+        .on_press_maybe(add_cmd.map(|c| Message::SysPanelAction(tab_id, c)))
+        .padding([4, 14])
+        .style(btn_style(p, true, cr));
+
+    column![
+        row![
+            text("Cron Jobs").size(14).color(p.text_primary),
+            button(text("↻ Refresh").size(11).color(p.text_primary))
+                .on_press(Message::SysPanelFetch(tab_id, "cron".into()))
+                .padding([3, 10])
+                .style(btn_style(p, true, cr)),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+        container(
+            column![
+                text("Add to your crontab (schedule followed by command)")
+                    .size(12)
+                    .color(p.text_secondary),
+                row![line_input, add_btn].spacing(8).align_y(Alignment::Center),
+            ]
+            .spacing(8),
+        )
+        .padding([10, 12])
+        .width(Length::Fill)
+        .style(move |_: &iced::Theme| container::Style {
+            background: Some(iced::Background::Color(p.bg_tertiary)),
+            border: iced::Border { color: p.border, width: 1.0, radius: cr.into() },
+            ..Default::default()
+        }),
+        text(if no_root_access {
+            format!("{entry_count} job(s) — root crontab needs passwordless sudo")
+        } else {
+            format!("{entry_count} job(s)")
+        })
+        .size(10)
+        .color(p.text_muted),
+        scrollable(render_table_with_actions(
+            &[("OWNER", 1), ("SCHEDULE", 2), ("COMMAND", 5)],
+            rows,
+            None,
+            "✕",
+            deletes,
+            p,
+            cr,
+        ))
+        .height(Length::Fill)
+        .style(hidden_scrollbar_style),
+    ]
+    .spacing(8)
+    .padding([8, 12])
+    .height(Length::Fill)
+    .into()
+}
+
+// ─── Services ────────────────────────────────────────────────────────────────
+
+const SERVICE_COLUMNS: [(&str, u16); 5] = [
+    ("UNIT", 3),
+    ("LOAD", 1),
+    ("ACTIVE", 1),
+    ("SUB", 1),
+    ("DESCRIPTION", 4),
+];
+
+fn view_services(
+    tab_id: u64,
+    state: &SysState,
+    p: theme::Palette,
+    cr: f32,
+) -> Element<'static, Message> {
+    let mut rows = parse_services(&state.output);
+    if !state.svc_search.is_empty() {
+        let lower = state.svc_search.to_lowercase();
+        rows.retain(|r| r.iter().any(|c| c.to_lowercase().contains(&lower)));
+    }
+    let sort_col = state.svc_sort.min(SERVICE_COLUMNS.len() - 1);
+    rows.sort_by(|a, b| a[sort_col].to_lowercase().cmp(&b[sort_col].to_lowercase()));
+    if state.svc_sort_desc {
+        rows.reverse();
+    }
+    let row_count = rows.len();
+    let loading = state.loading;
+
+    let search_input = text_input("Search services...", &state.svc_search)
+        .on_input(move |v| Message::SysPanelInput(tab_id, "svc_search".into(), v))
+        .padding(6)
+        .size(12)
+        .style(input_style(p, cr));
+
+    // Actions for the clicked unit
+    let actions: Element<'static, Message> = match &state.svc_selected {
+        Some(unit) => {
+            let mut bar = Row::new()
+                .spacing(6)
+                .align_y(Alignment::Center)
+                .push(text(unit.clone()).size(12).color(p.text_primary));
+            for action in ["start", "stop", "restart", "enable", "disable"] {
+                let mut label = action.to_string();
+                label[..1].make_ascii_uppercase();
+                bar = bar.push(
+                    button(text(label).size(11).color(p.text_primary))
+                        .on_press_maybe((!loading).then(|| {
+                            Message::SysPanelAction(
+                                tab_id,
+                                format!("sudo -n systemctl {action} {} 2>&1", shell_quote(unit)),
+                            )
+                        }))
+                        .padding([3, 10])
+                        .style(btn_style(p, false, cr)),
+                );
+            }
+            container(bar)
+                .padding([6, 10])
+                .width(Length::Fill)
+                .style(move |_: &iced::Theme| container::Style {
+                    background: Some(iced::Background::Color(p.bg_tertiary)),
+                    border: iced::Border { color: p.border, width: 1.0, radius: cr.into() },
+                    ..Default::default()
+                })
+                .into()
+        }
+        None => text("Click a service to manage it.").size(10).color(p.text_muted).into(),
+    };
+
+    // Header: clicking a column sorts by it; clicking again reverses the order.
+    let header_row = sort_header(
+        &SERVICE_COLUMNS,
+        sort_col,
+        state.svc_sort_desc,
+        |i| Message::SysPanelInput(tab_id, "svc_sort".into(), i.to_string()),
+        p,
+    );
+
+    let mut table: Column<'static, Message> = Column::new().spacing(0).width(Length::Fill);
+    table = table.push(header_row);
+    if rows.is_empty() {
+        table = table.push(
+            container(text("No data available").size(11).color(p.text_muted))
+                .padding([6, 8])
+                .width(Length::Fill),
+        );
+    }
+    for (i, row_data) in rows.into_iter().enumerate() {
+        let selected = state.svc_selected.as_deref() == Some(row_data[0].as_str());
+        let bg = if selected {
+            p.accent
+        } else if i % 2 == 0 {
+            p.bg_secondary
+        } else {
+            p.bg_tertiary
+        };
+        let unit = row_data[0].clone();
+        let mut data_row: Row<'static, Message> = Row::new();
+        for (j, cell) in row_data.into_iter().enumerate() {
+            let color = match (j, cell.as_str()) {
+                (2, "active") => p.success,
+                (2, "failed") => p.danger,
+                (2, _) => p.text_muted,
+                _ => p.text_primary,
+            };
+            data_row = data_row.push(
+                container(text(cell).size(11).color(color))
+                    .width(Length::FillPortion(SERVICE_COLUMNS[j].1))
+                    .padding([3, 8]),
+            );
+        }
+        table = table.push(
+            button(data_row)
+                .on_press(Message::SysPanelInput(tab_id, "svc_selected".into(), unit))
+                .width(Length::Fill)
+                .padding(0)
+                .style(move |_: &iced::Theme, s: button::Status| button::Style {
+                    background: Some(iced::Background::Color(match s {
+                        button::Status::Hovered if !selected => p.bg_hover,
+                        _ => bg,
+                    })),
+                    text_color: p.text_primary,
+                    border: iced::Border::default(),
+                    ..Default::default()
+                }),
+        );
+    }
+
+    column![
+        row![
+            text("Services").size(14).color(p.text_primary),
+            button(text("↻ Refresh").size(11).color(p.text_primary))
+                .on_press_maybe((!loading).then(|| Message::SysPanelFetch(tab_id, "services".into())))
+                .padding([3, 10])
+                .style(btn_style(p, true, cr)),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+        row![
+            search_input,
+            text(format!("{row_count} service(s)")).size(11).color(p.text_muted),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+        actions,
+        scrollable(
+            container(table)
+                .width(Length::Fill)
+                .style(move |_: &iced::Theme| container::Style {
+                    border: iced::Border { color: p.border, width: 1.0, radius: cr.into() },
+                    ..Default::default()
+                }),
         )
         .height(Length::Fill)
         .style(hidden_scrollbar_style),
@@ -1242,6 +2856,219 @@ fn view_ssh_keys(
     .into()
 }
 
+// ─── Users ───────────────────────────────────────────────────────────────────
+
+fn view_users(
+    tab_id: u64,
+    state: &SysState,
+    p: theme::Palette,
+    cr: f32,
+) -> Element<'static, Message> {
+    const COLUMNS: [(&str, u16); 5] = [
+        ("USER", 2),
+        ("UID", 1),
+        ("GROUPS", 4),
+        ("STATUS", 1),
+        ("LAST LOGIN", 2),
+    ];
+    let users = parse_users(&state.output);
+    let user_count = users.len();
+
+    let name_input = text_input("username", &state.user_name)
+        .on_input(move |v| Message::SysPanelInput(tab_id, "user_name".into(), v))
+        .padding(6)
+        .size(12)
+        .width(Length::Fixed(160.0))
+        .style(input_style(p, cr));
+    let group_input = text_input("group", &state.user_group)
+        .on_input(move |v| Message::SysPanelInput(tab_id, "user_group".into(), v))
+        .padding(6)
+        .size(12)
+        .width(Length::Fixed(120.0))
+        .style(input_style(p, cr));
+    let add_btn = button(text("Add User").size(11).color(p.text_primary))
+        .on_press_maybe(user_add_command(&state.user_name).map(|c| Message::SysPanelAction(tab_id, c)))
+        .padding([4, 14])
+        .style(btn_style(p, true, cr));
+    let group_btn = button(text("Add to Group").size(11).color(p.text_primary))
+        .on_press_maybe(
+            user_add_group_command(&state.user_name, &state.user_group)
+                .map(|c| Message::SysPanelAction(tab_id, c)),
+        )
+        .padding([4, 14])
+        .style(btn_style(p, false, cr));
+
+    let mut table: Column<'static, Message> = Column::new().spacing(0).width(Length::Fill);
+    let mut header_row: Row<'static, Message> = Row::new();
+    for &(label, portion) in &COLUMNS {
+        header_row = header_row.push(
+            container(text(label).size(10).color(p.text_muted))
+                .width(Length::FillPortion(portion))
+                .padding([4, 8]),
+        );
+    }
+    header_row = header_row.push(container(text("")).width(Length::Fixed(70.0)));
+    table = table.push(container(header_row).width(Length::Fill).style(move |_: &iced::Theme| {
+        container::Style {
+            background: Some(iced::Background::Color(p.bg_primary)),
+            ..Default::default()
+        }
+    }));
+    if users.is_empty() {
+        table = table.push(
+            container(text("No data available").size(11).color(p.text_muted))
+                .padding([6, 8])
+                .width(Length::Fill),
+        );
+    }
+    for (i, user) in users.into_iter().enumerate() {
+        let bg = if i % 2 == 0 { p.bg_secondary } else { p.bg_tertiary };
+        let display_name = user[0].clone();
+        let name = shell_quote(&user[0]);
+        let locked = user[3] == "locked";
+        let mut data_row: Row<'static, Message> = Row::new().align_y(Alignment::Center);
+        for (j, cell) in user.into_iter().enumerate() {
+            let color = match (j, cell.as_str()) {
+                (3, "locked") => p.danger,
+                (3, "active") => p.success,
+                (3, _) => p.text_muted,
+                _ => p.text_primary,
+            };
+            data_row = data_row.push(
+                container(text(cell).size(11).color(color))
+                    .width(Length::FillPortion(COLUMNS[j].1))
+                    .padding([3, 8]),
+            );
+        }
+        // Locking cuts the user off, so it goes through a confirmation first.
+        let toggle = if locked {
+            Message::SysPanelAction(tab_id, format!("sudo -n usermod -U {name} 2>&1"))
+        } else {
+            Message::SysPanelConfirm(
+                tab_id,
+                format!("Lock the account {display_name}? Password logins will be refused."),
+                "Lock",
+                format!("sudo -n usermod -L {name} 2>&1"),
+            )
+        };
+        data_row = data_row.push(
+            container(
+                button(text(if locked { "Unlock" } else { "Lock" }).size(10).color(p.text_primary))
+                    .on_press(toggle)
+                    .padding([1, 6])
+                    .style(btn_style(p, false, cr)),
+            )
+            .width(Length::Fixed(70.0))
+            .padding([2, 4]),
+        );
+        table = table.push(container(data_row).width(Length::Fill).style(move |_: &iced::Theme| {
+            container::Style {
+                background: Some(iced::Background::Color(bg)),
+                ..Default::default()
+            }
+        }));
+    }
+
+    column![
+        row![
+            text("User Accounts").size(14).color(p.text_primary),
+            button(text("↻ Refresh").size(11).color(p.text_primary))
+                .on_press(Message::SysPanelFetch(tab_id, "users".into()))
+                .padding([3, 10])
+                .style(btn_style(p, true, cr)),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+        text("Requires passwordless sudo for useradd/usermod; status needs sudo for passwd -S.")
+            .size(10)
+            .color(p.text_muted),
+        row![name_input, add_btn, group_input, group_btn]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        text(format!("{user_count} user(s) with UID ≥ 1000")).size(10).color(p.text_muted),
+        scrollable(
+            container(table)
+                .width(Length::Fill)
+                .style(move |_: &iced::Theme| container::Style {
+                    border: iced::Border { color: p.border, width: 1.0, radius: cr.into() },
+                    ..Default::default()
+                }),
+        )
+        .height(Length::Fill)
+        .style(hidden_scrollbar_style),
+    ]
+    .spacing(8)
+    .padding([8, 12])
+    .height(Length::Fill)
+    .into()
+}
+
+// ─── Processes ───────────────────────────────────────────────────────────────
+
+fn view_processes(
+    tab_id: u64,
+    state: &SysState,
+    p: theme::Palette,
+    cr: f32,
+) -> Element<'static, Message> {
+    let rows = parse_processes(&state.output);
+    let kills = rows
+        .iter()
+        .map(|r| {
+            let pid = &r[1];
+            Some(Message::SysPanelConfirm(
+                tab_id,
+                format!("Send SIGTERM to process {pid} ({})?", r[4]),
+                "Kill",
+                format!("kill {pid} 2>/dev/null || sudo -n kill {pid} 2>&1"),
+            ))
+        })
+        .collect();
+
+    let auto = state.proc_auto;
+    let auto_btn = button(
+        text(if auto { "Auto-refresh ●" } else { "Auto-refresh" })
+            .size(11)
+            .color(p.text_primary),
+    )
+    .on_press(Message::SysPanelInput(
+        tab_id,
+        "proc_auto".into(),
+        if auto { "off".into() } else { "on".into() },
+    ))
+    .padding([3, 10])
+    .style(btn_style(p, auto, cr));
+
+    column![
+        row![
+            text("Processes").size(14).color(p.text_primary),
+            button(text("↻ Refresh").size(11).color(p.text_primary))
+                .on_press(Message::SysPanelFetch(tab_id, "processes".into()))
+                .padding([3, 10])
+                .style(btn_style(p, true, cr)),
+            auto_btn,
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+        text("Top processes by CPU usage").size(10).color(p.text_muted),
+        scrollable(render_table_with_actions(
+            &[("USER", 2), ("PID", 1), ("%CPU", 1), ("%MEM", 1), ("COMMAND", 6)],
+            rows,
+            None,
+            "Kill",
+            kills,
+            p,
+            cr,
+        ))
+        .height(Length::Fill)
+        .style(hidden_scrollbar_style),
+    ]
+    .spacing(8)
+    .padding([8, 12])
+    .height(Length::Fill)
+    .into()
+}
+
 // ─── Extension ───────────────────────────────────────────────────────────────
 
 fn view_extension(
@@ -1268,12 +3095,12 @@ fn view_extension(
     let id3 = ext_id.clone();
     let id4 = ext_id.clone();
 
+    let loading = state.loading;
     let make_svc_btn = |label: &'static str, action: String| {
         button(text(label).size(11).color(p.text_primary))
-            .on_press(Message::SysPanelAction(
-                tab_id,
-                format!("sudo -n systemctl {action}"),
-            ))
+            .on_press_maybe((!loading).then(|| {
+                Message::SysPanelAction(tab_id, format!("sudo -n systemctl {action}"))
+            }))
             .padding([3, 10])
             .style(btn_style(p, false, cr))
     };
@@ -1416,3 +3243,149 @@ fn hidden_scrollbar_style(theme: &iced::Theme, status: scrollable::Status) -> sc
     style.gap = None;
     style
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINUX: &str = "=== CPU ===
+cpu  100 0 100 800 0 0 0
+cpu  120 0 120 960 0 0 0
+=== MEM ===
+MemTotal:        2000000 kB
+MemAvailable:     500000 kB
+=== DISK ===
+/dev/sda1 41152736 17224380 21814820 45% /
+";
+
+    fn gauges(output: &str) -> Option<(f32, f32, f32)> {
+        parse_remote_info(output).map(|i| (i.cpu_usage, i.memory_usage, i.disk_usage_percent))
+    }
+
+    #[test]
+    fn reads_linux_output() {
+        assert_eq!(gauges(LINUX), Some((20.0, 75.0, 45.0)));
+    }
+
+    #[test]
+    fn sections_may_come_in_any_order() {
+        let sections: Vec<&str> = LINUX.split_inclusive('\n').collect();
+        let reordered = [&sections[6..8], &sections[3..6], &sections[0..3]].concat().concat();
+        assert_eq!(gauges(&reordered), Some((20.0, 75.0, 45.0)));
+    }
+
+    #[test]
+    fn missing_sections_read_zero_without_shifting() {
+        // A minimal container: no /proc/meminfo and no df
+        let output = "=== CPU ===\ncpu 1 0 1 8\ncpu 3 0 3 14\n=== MEM ===\n=== DISK ===\nsh: df: not found\n";
+        assert_eq!(gauges(output), Some((40.0, 0.0, 0.0)));
+        let output = "=== MEM ===\nMemTotal: 1000 kB\nMemAvailable: 1000 kB\n";
+        assert_eq!(gauges(output), Some((0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn garbage_is_ignored() {
+        assert_eq!(gauges(""), None);
+        assert_eq!(gauges("Welcome to the server!\n=== FOO ===\n1 2 3 4 5 6\n"), None);
+        let noisy = LINUX.replace("=== MEM ===\n", "=== MEM ===\nmotd: have a nice day\nMemTotal: lots\n=== NOISE ===\n/dev/x 1 1 1 99% /\n=== MEM ===\n");
+        assert_eq!(gauges(&noisy), Some((20.0, 75.0, 45.0)));
+        // A single CPU sample gives no usage; a truncated stat line is skipped
+        assert_eq!(gauges("=== CPU ===\ncpu 1 2 3 4\ncpu 1 2\n"), None);
+    }
+
+    #[test]
+    fn reads_bsd_output() {
+        // What remote_info_cmd(Bsd) prints: kern.cp_time reordered, sysctl memory
+        let output = "=== CPU ===
+cpu 100 0 50 850 0 0
+cpu 130 0 70 900 0 0
+=== MEM ===
+MemTotal: 8388608 kB
+MemAvailable: 2097152 kB
+=== DISK ===
+/dev/ada0p2 101000000 30300000 62620000 33% /
+";
+        assert_eq!(gauges(output), Some((50.0, 75.0, 33.0)));
+    }
+
+    #[test]
+    fn reads_macos_output() {
+        // What remote_info_cmd(MacOs) prints: top percentages after a zero sample
+        let output = "=== CPU ===
+cpu 0 0 0 0
+cpu 1250 0 1250 7500
+=== MEM ===
+MemTotal: 16777216 kB
+MemAvailable: 8388608 kB
+=== DISK ===
+/dev/disk3s1s1 965595304 19425472 480542440 4% /
+";
+        assert_eq!(gauges(output), Some((25.0, 50.0, 4.0)));
+    }
+
+    #[test]
+    fn uname_maps_to_os() {
+        assert_eq!(RemoteOs::from_uname("Linux\n"), RemoteOs::Linux);
+        assert_eq!(RemoteOs::from_uname("Darwin"), RemoteOs::MacOs);
+        assert_eq!(RemoteOs::from_uname("FreeBSD"), RemoteOs::Bsd);
+        assert_eq!(RemoteOs::from_uname("OpenBSD"), RemoteOs::Bsd);
+        assert_eq!(RemoteOs::from_uname("SunOS"), RemoteOs::Other);
+    }
+
+    /// Runs `script` in `sh` after `prelude`, with `$OUT` naming a scratch
+    /// file; returns what was written there, if anything.
+    fn run_with(prelude: &str, script: &str, name: &str) -> Option<String> {
+        let out = std::env::temp_dir().join(format!("termissh-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_file(&out);
+        std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("{prelude}\n{script}"))
+            .env("OUT", &out)
+            .status()
+            .unwrap();
+        let written = std::fs::read_to_string(&out).ok();
+        let _ = std::fs::remove_file(&out);
+        written
+    }
+
+    #[test]
+    fn cron_delete_keeps_the_crontab_when_listing_fails() {
+        let fake = |list: &str| {
+            format!("crontab() {{ if [ \"$1\" = -l ]; then {list}; else cat > \"$OUT\"; fi; }}")
+        };
+        let cmd = cron_delete_command("0 * * * * a", false);
+        let ok = fake("printf '0 * * * * a\\n1 * * * * b\\n'");
+        assert_eq!(run_with(&ok, &cmd, "cron-ok").as_deref(), Some("1 * * * * b\n"));
+        assert_eq!(run_with(&fake("return 1"), &cmd, "cron-fail"), None);
+    }
+
+    #[test]
+    fn authorized_key_removal_never_replaces_on_error() {
+        let home = std::env::temp_dir().join(format!("termissh-test-keys-{}", std::process::id()));
+        let keys = home.join(".ssh").join("authorized_keys");
+        let _ = std::fs::remove_dir_all(&home);
+        std::fs::create_dir_all(home.join(".ssh")).unwrap();
+        let run = |line: &str| {
+            std::process::Command::new("sh")
+                .arg("-c")
+                .arg(authorized_key_remove_command(line))
+                .env("HOME", &home)
+                .output()
+                .unwrap()
+                .status
+                .success()
+        };
+
+        std::fs::write(&keys, "ssh-ed25519 AAAA a\nssh-ed25519 BBBB b\n").unwrap();
+        assert!(run("ssh-ed25519 AAAA a"));
+        assert_eq!(std::fs::read_to_string(&keys).unwrap(), "ssh-ed25519 BBBB b\n");
+
+        // grep fails (exit 2) on a directory; nothing may be moved into place.
+        std::fs::remove_file(&keys).unwrap();
+        std::fs::create_dir(&keys).unwrap();
+        assert!(!run("ssh-ed25519 BBBB b"));
+        assert!(keys.is_dir() && std::fs::read_dir(&keys).unwrap().next().is_none());
+        assert!(!home.join(".ssh").join("authorized_keys.tmp").exists());
+        let _ = std::fs::remove_dir_all(&home);
+    }
+}
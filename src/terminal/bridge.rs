@@ -1,15 +1,115 @@
+//! Launches the relay child and feeds it. The GUI writes framed messages to
+//! the relay's stdin (see [`crate::terminal::protocol`]):
+//!
+//! | tag | payload                                |
+//! |-----|----------------------------------------|
+//! | 0   | raw input bytes for the remote shell   |
+//! | 1   | `cols: u16`, `rows: u16`, big-endian   |
+//! | 2   | signal name, e.g. `INT`                |
+//!
+//! each preceded by the tag byte and a big-endian `u32` payload length.
+//! The relay's stdout is the unframed remote output.
+
 use crate::config::Host;
-use crate::terminal::relay_mode::INTERNAL_RELAY_ARG;
-use anyhow::{Context, Result};
+use crate::terminal::relay_mode::{self, INTERNAL_RELAY_ARG, RELAY_VERSION_ARG};
+use anyhow::{anyhow, Context, Result};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::io::Read;
 use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
-/// Resolve relay launcher path (single-binary mode uses current executable).
+/// Name of the single binary that also serves as the relay.
+const RELAY_BINARY: &str = "termissh";
+/// Standalone relay shipped next to the GUI by older packages.
+const BUNDLED_RELAY: &str = "termissh-relay";
+
+/// How long a fallback relay gets to answer `--relay-version`.
+const VERSION_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Resolve relay launcher path, returning the one that was chosen. Single-binary
+/// mode uses the current executable; when that is gone (e.g. replaced by an
+/// update while running) `termissh` is looked up in the executable's directory,
+/// then on `$PATH`, then the bundled `termissh-relay` next to the executable.
+/// A fallback is only used if it reports this very version, since it is
+/// handed the host's credentials.
 pub fn find_relay_binary() -> Result<String> {
-    let exe = std::env::current_exe().context("Cannot get current executable path")?;
-    Ok(exe.to_string_lossy().to_string())
+    let exe = std::env::current_exe().ok();
+    let exe_dir = exe.as_deref().and_then(Path::parent).map(Path::to_path_buf);
+    let file_name = |name: &str| format!("{name}{}", std::env::consts::EXE_SUFFIX);
+
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    candidates.extend(exe.clone());
+    if let Some(dir) = &exe_dir {
+        candidates.push(dir.join(file_name(RELAY_BINARY)));
+    }
+    if let Some(path) = std::env::var_os("PATH") {
+        candidates.extend(std::env::split_paths(&path).map(|d| d.join(file_name(RELAY_BINARY))));
+    }
+    if let Some(dir) = &exe_dir {
+        candidates.push(dir.join(file_name(BUNDLED_RELAY)));
+    }
+
+    if let Some(exe) = exe.as_deref().filter(|p| p.is_file()) {
+        return Ok(exe.to_string_lossy().to_string());
+    }
+    let mut rejected = Vec::new();
+    for candidate in candidates.iter().skip(usize::from(exe.is_some())).filter(|p| p.is_file()) {
+        if reports_our_version(candidate) {
+            return Ok(candidate.to_string_lossy().to_string());
+        }
+        rejected.push(candidate.display().to_string());
+    }
+    if !rejected.is_empty() {
+        return Err(anyhow!(
+            "No matching relay found: {} did not report {}. Restart termissh after updating it, then reconnect.",
+            rejected.join(", "),
+            relay_mode::relay_version()
+        ));
+    }
+    let searched = match &exe_dir {
+        Some(dir) => format!("{}, $PATH", dir.display()),
+        None => "$PATH".to_string(),
+    };
+    Err(anyhow!(
+        "Relay not found (searched {searched}). The relay is the termissh binary itself: \
+build it with `cargo build --release --bin termissh` and start target/release/{}, \
+or install it somewhere on your PATH, then reconnect.",
+        file_name(RELAY_BINARY)
+    ))
+}
+
+/// Whether `path --relay-version` prints this build's relay version in time.
+fn reports_our_version(path: &Path) -> bool {
+    let mut cmd = Command::new(path);
+    cmd.arg(RELAY_VERSION_ARG)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    let Ok(mut child) = cmd.spawn() else { return false; };
+    let deadline = Instant::now() + VERSION_CHECK_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(20)),
+            // Something that doesn't know the flag may start a GUI or wait for input.
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return false;
+            }
+        }
+    }
+    let mut out = String::new();
+    child.stdout.take().is_some_and(|mut s| s.read_to_string(&mut out).is_ok())
+        && out.trim() == relay_mode::relay_version()
 }
 
 /// Build environment variables for relay mode.
@@ -22,7 +122,34 @@ pub fn build_relay_env(host: &Host) -> HashMap<String, String> {
         "TERMISSH_PASS".to_string(),
         host.password.clone().unwrap_or_default(),
     );
-    env.insert("TERM".to_string(), "xterm-256color".to_string());
+    if let Some(key) = &host.identity_file {
+        env.insert("TERMISSH_KEY".to_string(), key.clone());
+    }
+    if let Some(jump) = &host.jump_host {
+        env.insert("TERMISSH_JUMP".to_string(), jump.clone());
+    }
+    if !host.env.is_empty() {
+        if let Ok(vars) = serde_json::to_string(&host.env) {
+            env.insert("TERMISSH_ENV".to_string(), vars);
+        }
+    }
+    if let Some(passphrase) = &host.key_passphrase {
+        env.insert("TERMISSH_KEY_PASS".to_string(), passphrase.clone());
+    }
+    env.insert(
+        "TERMISSH_TIMEOUT".to_string(),
+        crate::ssh::connect_timeout().to_string(),
+    );
+    env.insert(
+        "TERMISSH_KEEPALIVE".to_string(),
+        crate::ssh::keepalive().to_string(),
+    );
+    let term = host
+        .env
+        .iter()
+        .find(|(k, _)| k == "TERM")
+        .map_or_else(crate::ssh::term_type, |(_, v)| v.clone());
+    env.insert("TERM".to_string(), term);
     env.insert("COLUMNS".to_string(), "132".to_string());
     env.insert("LINES".to_string(), "40".to_string());
     env
@@ -0,0 +1,295 @@
+//! OSC 8 hyperlinks in terminal output.
+//!
+//! vt100 drops OSC 8 (`ESC ] 8 ; params ; URI ST … ESC ] 8 ; ; ST`), so the
+//! raw output is scanned as it is fed to the parser, and each link is kept
+//! as the cells its text was printed on. Rows are counted from the top of
+//! the scrollback so links follow the screen as it scrolls; rendering only
+//! marks those cells, and only while they still show the same text, so the
+//! same words printed elsewhere never turn into the link.
+//!
+//! The same scan picks up OSC 7 (`ESC ] 7 ; file://host/path ST`), which
+//! shells use to report their working directory.
+
+use std::collections::VecDeque;
+
+use vt100::{Parser, Screen};
+
+/// Links remembered per terminal; older ones are forgotten first.
+const MAX_LINKS: usize = 200;
+
+/// Longest OSC payload kept; anything longer is not a link worth tracking.
+const MAX_OSC_LEN: usize = 4096;
+
+#[derive(Default)]
+enum State {
+    #[default]
+    Ground,
+    Esc,
+    Csi,
+    Osc(Vec<u8>),
+    OscEsc(Vec<u8>),
+}
+
+/// Cells of one row that an OSC 8 link was printed on.
+struct Link {
+    /// Row counted from the top of the scrollback.
+    row: usize,
+    /// Columns `start..end`.
+    start: u16,
+    end: u16,
+    /// Printed on the alternate screen, which has no scrollback.
+    alternate: bool,
+    /// What the cells showed when the link ended.
+    text: String,
+    uri: String,
+}
+
+/// Incremental scanner; escape sequences may be split across chunks.
+#[derive(Default)]
+pub struct LinkTracker {
+    state: State,
+    /// URI of the link being printed, and the row and column it starts at.
+    open: Option<(String, usize, u16)>,
+    /// Oldest first.
+    links: VecDeque<Link>,
+    /// Lines in the parser's scrollback after the last chunk.
+    depth: usize,
+    /// Working directory from the last OSC 7.
+    cwd: Option<String>,
+}
+
+/// Lines currently in `parser`'s scrollback. It stops growing once the
+/// scrollback is full; links then drift and are dropped by the text check.
+fn scrollback_depth(parser: &mut Parser) -> usize {
+    let view = parser.screen().scrollback();
+    parser.set_scrollback(usize::MAX);
+    let depth = parser.screen().scrollback();
+    parser.set_scrollback(view);
+    depth
+}
+
+/// Text of columns `start..end` of visible row `row`.
+fn cells_text(screen: &Screen, row: u16, start: u16, end: u16) -> String {
+    (start..end)
+        .filter_map(|col| screen.cell(row, col))
+        .filter(|cell| !cell.is_wide_continuation())
+        .map(|cell| if cell.has_contents() { cell.contents() } else { " ".to_string() })
+        .collect()
+}
+
+impl LinkTracker {
+    /// Feeds `chunk` to `parser`, noting where OSC 8 links start and end.
+    pub fn process(&mut self, parser: &mut Parser, chunk: &[u8]) {
+        let mut fed = 0;
+        for (i, &b) in chunk.iter().enumerate() {
+            self.state = match std::mem::take(&mut self.state) {
+                State::Ground => match b {
+                    0x1b => State::Esc,
+                    _ => State::Ground,
+                },
+                State::Esc => match b {
+                    b'[' => State::Csi,
+                    b']' => State::Osc(Vec::new()),
+                    _ => State::Ground,
+                },
+                State::Csi => match b {
+                    0x40..=0x7e => State::Ground,
+                    _ => State::Csi,
+                },
+                State::Osc(mut buf) => match b {
+                    0x07 => {
+                        if self.osc(&buf) {
+                            parser.process(&chunk[fed..=i]);
+                            fed = i + 1;
+                            self.osc8(parser, &buf);
+                        }
+                        State::Ground
+                    }
+                    0x1b => State::OscEsc(buf),
+                    _ => {
+                        if buf.len() < MAX_OSC_LEN {
+                            buf.push(b);
+                        }
+                        State::Osc(buf)
+                    }
+                },
+                State::OscEsc(buf) => {
+                    // ESC \ ends the sequence; any other byte aborts it.
+                    if b == b'\\' && self.osc(&buf) {
+                        parser.process(&chunk[fed..=i]);
+                        fed = i + 1;
+                        self.osc8(parser, &buf);
+                    }
+                    State::Ground
+                }
+            };
+        }
+        parser.process(&chunk[fed..]);
+        self.depth = scrollback_depth(parser);
+    }
+
+    pub fn cwd(&self) -> Option<&str> {
+        self.cwd.as_deref()
+    }
+
+    /// Handles an OSC 7; returns whether `payload` is an OSC 8, which needs
+    /// the cursor position once the output before it has been parsed.
+    fn osc(&mut self, payload: &[u8]) -> bool {
+        if let Some(url) = payload.strip_prefix(b"7;") {
+            self.cwd = cwd_from_url(&String::from_utf8_lossy(url));
+        }
+        payload.starts_with(b"8;")
+    }
+
+    /// Ends the open link at the cursor and opens the one `payload` starts, if any.
+    fn osc8(&mut self, parser: &mut Parser, payload: &[u8]) {
+        let depth = scrollback_depth(parser);
+        let screen = parser.screen();
+        let (cursor_row, cursor_col) = screen.cursor_position();
+        let (rows, cols) = screen.size();
+        let end_row = depth + cursor_row as usize;
+        if let Some((uri, start_row, start_col)) = self.open.take() {
+            let alternate = screen.alternate_screen();
+            // Rows already scrolled out of the scrollback are skipped.
+            for row in start_row.max(depth)..=end_row.min(depth + rows as usize - 1) {
+                let start = if row == start_row { start_col } else { 0 };
+                let end = if row == end_row { cursor_col } else { cols };
+                if start >= end {
+                    continue;
+                }
+                let text = cells_text(screen, (row - depth) as u16, start, end);
+                if text.trim().is_empty() {
+                    continue;
+                }
+                self.links.retain(|l| l.row != row || l.alternate != alternate || l.end <= start || end <= l.start);
+                self.links.push_back(Link { row, start, end, alternate, text, uri: uri.clone() });
+                if self.links.len() > MAX_LINKS {
+                    self.links.pop_front();
+                }
+            }
+        }
+        let uri = payload[2..].splitn(2, |&c| c == b';').nth(1).unwrap_or_default();
+        if !uri.is_empty() {
+            self.open = Some((String::from_utf8_lossy(uri).into_owned(), end_row, cursor_col));
+        }
+    }
+
+    /// Column ranges and targets of the OSC 8 links on visible row `row`
+    /// that still show the text they were printed with.
+    pub fn links_on_row(&self, screen: &Screen, row: u16) -> Vec<(u16, u16, &str)> {
+        let Some(abs_row) = (self.depth + row as usize).checked_sub(screen.scrollback()) else {
+            return Vec::new();
+        };
+        self.links
+            .iter()
+            .filter(|l| l.row == abs_row && l.alternate == screen.alternate_screen())
+            .filter(|l| cells_text(screen, row, l.start, l.end) == l.text)
+            .map(|l| (l.start, l.end, l.uri.as_str()))
+            .collect()
+    }
+}
+
+/// Path of a `file://host/path` URL, percent-decoded.
+fn cwd_from_url(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("file://")?;
+    let path = &rest[rest.find('/')?..];
+    let bytes = path.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| path.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(b) => {
+                out.push(b);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    Some(String::from_utf8_lossy(&out).into_owned())
+}
+
+/// Byte ranges and targets of the links in one rendered line: the OSC 8
+/// links already found on it (`osc_links`), then, with `plain_urls`, bare
+/// `http(s)://` URLs.
+pub fn find_links(
+    line: &str,
+    osc_links: Vec<(usize, usize, String)>,
+    plain_urls: bool,
+) -> Vec<(usize, usize, String)> {
+    let mut found = osc_links;
+    let overlaps = |found: &[(usize, usize, String)], s: usize, e: usize| {
+        found.iter().any(|&(fs, fe, _)| s < fe && fs < e)
+    };
+    if plain_urls {
+        for start in line.match_indices("http").map(|(i, _)| i) {
+            let rest = &line[start..];
+            if !(rest.starts_with("http://") || rest.starts_with("https://")) {
+                continue;
+            }
+            let len = rest
+                .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>' | '`'))
+                .unwrap_or(rest.len());
+            let url = rest[..len].trim_end_matches(['.', ',', ';', ':', ')', ']', '}', '!', '?']);
+            let end = start + url.len();
+            if url.len() > "https://".len() && !overlaps(&found, start, end) {
+                found.push((start, end, url.to_string()));
+            }
+        }
+    }
+    found
+}
+
+/// Whether `uri` may be handed to the system browser. Remote output chooses
+/// link targets, so local files and custom URL handlers are refused.
+pub fn is_openable(uri: &str) -> bool {
+    let lower = uri.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(tracker: &mut LinkTracker, parser: &mut Parser, output: &str) {
+        tracker.process(parser, output.as_bytes());
+    }
+
+    fn row_links(tracker: &LinkTracker, parser: &Parser, row: u16) -> Vec<(u16, u16, String)> {
+        tracker
+            .links_on_row(parser.screen(), row)
+            .into_iter()
+            .map(|(s, e, uri)| (s, e, uri.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn only_the_printed_cells_are_linked() {
+        let (mut tracker, mut parser) = (LinkTracker::default(), Parser::new(5, 40, 100));
+        feed(&mut tracker, &mut parser, "see \x1b]8;;https://evil.example\x07docs\x1b]8;;\x07\r\ndocs\r\n");
+        assert_eq!(row_links(&tracker, &parser, 0), [(4, 8, "https://evil.example".to_string())]);
+        assert!(row_links(&tracker, &parser, 1).is_empty());
+    }
+
+    #[test]
+    fn links_follow_scrolling_and_vanish_when_overwritten() {
+        let (mut tracker, mut parser) = (LinkTracker::default(), Parser::new(3, 40, 100));
+        feed(&mut tracker, &mut parser, "\x1b]8;;https://a.example\x1b\\a\x1b]8;;\x1b\\\r\n");
+        feed(&mut tracker, &mut parser, "x\r\ny\r\nz");
+        assert!(row_links(&tracker, &parser, 0).is_empty());
+        parser.set_scrollback(2);
+        assert_eq!(row_links(&tracker, &parser, 0), [(0, 1, "https://a.example".to_string())]);
+        parser.set_scrollback(0);
+
+        feed(&mut tracker, &mut parser, "\x1b[H\x1b]8;;https://b.example\x07b\x1b]8;;\x07");
+        assert_eq!(row_links(&tracker, &parser, 0), [(0, 1, "https://b.example".to_string())]);
+        feed(&mut tracker, &mut parser, "\x1b[Hc");
+        assert!(row_links(&tracker, &parser, 0).is_empty());
+    }
+}
@@ -1,2 +1,4 @@
 pub mod bridge;
+pub mod hyperlink;
+pub mod protocol;
 pub mod relay_mode;
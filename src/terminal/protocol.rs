@@ -0,0 +1,127 @@
+//! Framing for the GUI → relay stdin pipe.
+//!
+//! Every frame is a one-byte tag, a big-endian `u32` payload length and the
+//! payload. Keystrokes travel as `Data`; window size changes as `Resize` so
+//! the relay can forward them to the remote PTY; `Signal` asks the relay to
+//! signal the remote foreground process.
+
+use std::io::{self, Read, Write};
+
+const TAG_DATA: u8 = 0;
+const TAG_RESIZE: u8 = 1;
+const TAG_SIGNAL: u8 = 2;
+
+/// Largest payload accepted; a corrupt length must not allocate gigabytes.
+const MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+    Data(Vec<u8>),
+    Resize { cols: u16, rows: u16 },
+    /// Signal name without the `SIG` prefix, as in SSH signal requests ("INT").
+    Signal(String),
+}
+
+pub fn write_frame(w: &mut impl Write, frame: &Frame) -> io::Result<()> {
+    let (tag, payload) = match frame {
+        Frame::Data(bytes) => (TAG_DATA, bytes.clone()),
+        Frame::Resize { cols, rows } => {
+            let mut payload = cols.to_be_bytes().to_vec();
+            payload.extend_from_slice(&rows.to_be_bytes());
+            (TAG_RESIZE, payload)
+        }
+        Frame::Signal(name) => (TAG_SIGNAL, name.as_bytes().to_vec()),
+    };
+    let mut header = [0u8; 5];
+    header[0] = tag;
+    header[1..].copy_from_slice(&(payload.len() as u32).to_be_bytes());
+    w.write_all(&header)?;
+    w.write_all(&payload)?;
+    w.flush()
+}
+
+pub fn write_data(w: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    write_frame(w, &Frame::Data(bytes.to_vec()))
+}
+
+/// Reads the next frame; `Ok(None)` on a clean end of stream.
+pub fn read_frame(r: &mut impl Read) -> io::Result<Option<Frame>> {
+    let mut header = [0u8; 5];
+    match r.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("relay frame of {} bytes is too large", len),
+        ));
+    }
+    let mut payload = vec![0u8; len];
+    r.read_exact(&mut payload)?;
+
+    match header[0] {
+        TAG_DATA => Ok(Some(Frame::Data(payload))),
+        TAG_RESIZE if len == 4 => Ok(Some(Frame::Resize {
+            cols: u16::from_be_bytes([payload[0], payload[1]]),
+            rows: u16::from_be_bytes([payload[2], payload[3]]),
+        })),
+        TAG_SIGNAL => Ok(Some(Frame::Signal(String::from_utf8_lossy(&payload).into_owned()))),
+        tag => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unknown relay frame tag {}", tag),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(frame: Frame) {
+        let mut buf = Vec::new();
+        write_frame(&mut buf, &frame).unwrap();
+        let mut r = buf.as_slice();
+        assert_eq!(read_frame(&mut r).unwrap(), Some(frame));
+        assert_eq!(read_frame(&mut r).unwrap(), None);
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        round_trip(Frame::Data(b"ls -la\r".to_vec()));
+        round_trip(Frame::Data(Vec::new()));
+        round_trip(Frame::Resize { cols: 132, rows: 40 });
+        round_trip(Frame::Signal("INT".to_string()));
+    }
+
+    #[test]
+    fn truncated_payload_is_an_error() {
+        let mut buf = Vec::new();
+        write_data(&mut buf, b"hello").unwrap();
+        buf.truncate(buf.len() - 2);
+        let err = read_frame(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn unknown_tag_is_rejected() {
+        let buf = [9u8, 0, 0, 0, 1, b'x'];
+        let err = read_frame(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn resize_with_wrong_length_is_rejected() {
+        let buf = [TAG_RESIZE, 0, 0, 0, 2, 0, 80];
+        assert!(read_frame(&mut buf.as_slice()).is_err());
+    }
+
+    #[test]
+    fn oversized_length_is_rejected_before_reading() {
+        let buf = [TAG_DATA, 0xff, 0xff, 0xff, 0xff];
+        let err = read_frame(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,12 +1,26 @@
 use std::env;
 use std::io::{self, Read, Write};
-use std::net::TcpStream;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
+use crate::config::Host;
+use crate::ssh;
+use crate::terminal::protocol::{self, Frame};
+
 pub const INTERNAL_RELAY_ARG: &str = "--relay-internal";
+/// Makes the binary print `relay_version()` and exit, so the GUI can check a
+/// relay other than itself before trusting it with credentials.
+pub const RELAY_VERSION_ARG: &str = "--relay-version";
+
+pub fn relay_version() -> String {
+    format!("termissh-relay {}", env!("CARGO_PKG_VERSION"))
+}
+
+pub fn is_version_query() -> bool {
+    env::args().nth(1).as_deref() == Some(RELAY_VERSION_ARG)
+}
 
 pub fn is_internal_relay_mode() -> bool {
     env::args().any(|arg| arg == INTERNAL_RELAY_ARG)
@@ -18,36 +32,35 @@ fn fatal(message: &str) -> ! {
 }
 
 pub fn run_from_env() {
-    let host = env::var("TERMISSH_HOST").unwrap_or_else(|_| fatal("TERMISSH_HOST not set"));
-    let port: u16 = env::var("TERMISSH_PORT")
-        .unwrap_or_else(|_| "22".to_string())
-        .parse()
-        .unwrap_or(22);
-    let user = env::var("TERMISSH_USER").unwrap_or_else(|_| fatal("TERMISSH_USER not set"));
-    let pass = env::var("TERMISSH_PASS").unwrap_or_default();
+    if let Some(secs) = env::var("TERMISSH_TIMEOUT").ok().and_then(|t| t.parse().ok()) {
+        ssh::set_connect_timeout(secs);
+    }
+    if let Some(secs) = env::var("TERMISSH_KEEPALIVE").ok().and_then(|t| t.parse().ok()) {
+        ssh::set_keepalive(secs);
+    }
 
-    let tcp = match TcpStream::connect(format!("{}:{}", host, port)) {
-        Ok(tcp) => tcp,
-        Err(e) => fatal(&format!("Connection failed: {}", e)),
+    let host = Host {
+        hostname: env::var("TERMISSH_HOST").unwrap_or_else(|_| fatal("TERMISSH_HOST not set")),
+        port: env::var("TERMISSH_PORT")
+            .unwrap_or_else(|_| "22".to_string())
+            .parse()
+            .unwrap_or(22),
+        username: env::var("TERMISSH_USER").unwrap_or_else(|_| fatal("TERMISSH_USER not set")),
+        password: env::var("TERMISSH_PASS").ok().filter(|p| !p.is_empty()),
+        identity_file: env::var("TERMISSH_KEY").ok().filter(|k| !k.is_empty()),
+        key_passphrase: env::var("TERMISSH_KEY_PASS").ok().filter(|p| !p.is_empty()),
+        jump_host: env::var("TERMISSH_JUMP").ok().filter(|j| !j.is_empty()),
+        env: env::var("TERMISSH_ENV")
+            .ok()
+            .and_then(|vars| serde_json::from_str(&vars).ok())
+            .unwrap_or_default(),
+        ..Default::default()
     };
 
-    let mut sess = ssh2::Session::new().expect("Failed to create SSH session");
-    sess.set_tcp_stream(tcp);
-    if let Err(e) = sess.handshake() {
-        fatal(&format!("SSH handshake failed: {}", e));
-    }
-
-    let mut authenticated = false;
-    if sess.userauth_agent(&user).is_ok() {
-        authenticated = true;
-    }
-    if !authenticated && !pass.is_empty() {
-        if let Err(e) = sess.userauth_password(&user, &pass) {
-            fatal(&format!("Password auth failed: {}", e));
-        }
-    } else if !authenticated {
-        fatal("Authentication failed: no password and agent auth failed");
-    }
+    let sess = match ssh::open_session(&host) {
+        Ok(sess) => sess,
+        Err(e) => fatal(&e),
+    };
 
     let mut channel = match sess.channel_session() {
         Ok(ch) => ch,
@@ -63,11 +76,40 @@ pub fn run_from_env() {
         .parse()
         .unwrap_or(40);
 
-    if let Err(e) = channel.request_pty("xterm-256color", None, Some((cols, rows, 0, 0))) {
+    // TERM travels with the PTY request; servers rarely accept it via setenv.
+    // Old servers that refuse an unknown type still know plain xterm.
+    let term = env::var("TERM").unwrap_or_else(|_| ssh::DEFAULT_TERM_TYPE.to_string());
+    let mut pty = channel.request_pty(&term, None, Some((cols, rows, 0, 0)));
+    if pty.is_err() && term != "xterm" {
+        pty = channel.request_pty("xterm", None, Some((cols, rows, 0, 0)));
+    }
+    if let Err(e) = pty {
         fatal(&format!("PTY request failed: {}", e));
     }
+    // The screen renders 24-bit color; worth a try, but never a reason to
+    // start the shell differently.
+    if !host.env.iter().any(|(k, _)| k == "COLORTERM") {
+        let _ = channel.setenv("COLORTERM", "truecolor");
+    }
 
-    if let Err(e) = channel.shell() {
+    // The rest is offered via setenv; whatever the server's AcceptEnv refuses
+    // is passed by starting the login shell under `env` instead.
+    let refused: Vec<&(String, String)> = host
+        .env
+        .iter()
+        .filter(|(k, _)| k != "TERM" && Host::is_env_name(k))
+        .filter(|(k, v)| channel.setenv(k, v).is_err())
+        .collect();
+    let started = if refused.is_empty() {
+        channel.shell()
+    } else {
+        let assignments: Vec<String> = refused
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, crate::syspanel::shell_quote(v)))
+            .collect();
+        channel.exec(&format!("exec env {} \"${{SHELL:-/bin/sh}}\" -l", assignments.join(" ")))
+    };
+    if let Err(e) = started {
         fatal(&format!("Shell request failed: {}", e));
     }
 
@@ -78,6 +120,7 @@ pub fn run_from_env() {
 
     let ch_read = channel.clone();
     let r1 = running.clone();
+    let keepalive_sess = sess.clone();
     let stdout_thread = thread::spawn(move || {
         let mut buf = [0u8; 8192];
         let stdout = io::stdout();
@@ -97,6 +140,10 @@ pub fn run_from_env() {
                     let _ = out.flush();
                 }
                 Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    // Idle: libssh2 sends a keepalive once the interval has passed.
+                    if ssh::keepalive() > 0 {
+                        let _ = keepalive_sess.keepalive_send();
+                    }
                     thread::sleep(Duration::from_millis(5));
                 }
                 Err(_) => {
@@ -115,26 +162,28 @@ pub fn run_from_env() {
     let ch_write = channel.clone();
     let r2 = running.clone();
     let stdin_thread = thread::spawn(move || {
-        let mut buf = [0u8; 8192];
         let stdin = io::stdin();
+        let mut input = stdin.lock();
         while r2.load(Ordering::Relaxed) {
-            match stdin.lock().read(&mut buf) {
-                Ok(0) => {
-                    r2.store(false, Ordering::Relaxed);
-                    break;
+            let result = match protocol::read_frame(&mut input) {
+                Ok(Some(Frame::Data(bytes))) => {
+                    // The session is non-blocking: a full window means wait, not fail.
+                    ssh::write_all_shared(&ch_write, &bytes)
                 }
-                Ok(n) => {
-                    let mut ch = ch_write.lock().unwrap();
-                    if ch.write_all(&buf[..n]).is_err() {
-                        r2.store(false, Ordering::Relaxed);
-                        break;
-                    }
-                    let _ = ch.flush();
-                }
-                Err(_) => {
-                    r2.store(false, Ordering::Relaxed);
-                    break;
+                Ok(Some(Frame::Resize { cols, rows })) => {
+                    resize_pty(&ch_write, cols, rows);
+                    Ok(())
                 }
+                Ok(Some(Frame::Signal(name))) => match signal_char(&name) {
+                    Some(c) => ssh::write_all_shared(&ch_write, &[c]),
+                    None => Ok(()),
+                },
+                Ok(None) => Err(io::ErrorKind::UnexpectedEof.into()),
+                Err(e) => Err(e),
+            };
+            if result.is_err() {
+                r2.store(false, Ordering::Relaxed);
+                break;
             }
         }
     });
@@ -147,3 +196,33 @@ pub fn run_from_env() {
     let _ = stdin_thread; // suppress unused-variable warning; thread drops when we exit
     std::process::exit(0);
 }
+
+/// Control character the remote PTY turns into signal `name` for its
+/// foreground process. libssh2 has no signal request, and most servers ignore
+/// it anyway; the line discipline does the same job for the usual signals.
+fn signal_char(name: &str) -> Option<u8> {
+    match name {
+        "INT" => Some(0x03),
+        "QUIT" => Some(0x1c),
+        "TSTP" => Some(0x1a),
+        _ => None,
+    }
+}
+
+/// Sends a window-change request, retrying while the non-blocking session
+/// reports `EAGAIN`; the remote side then delivers `SIGWINCH`.
+fn resize_pty(channel: &Mutex<ssh2::Channel>, cols: u16, rows: u16) {
+    const LIBSSH2_ERROR_EAGAIN: i32 = -37;
+    for _ in 0..100 {
+        let result = channel
+            .lock()
+            .unwrap()
+            .request_pty_size(cols as u32, rows as u32, None, None);
+        match result {
+            Err(e) if e.code() == ssh2::ErrorCode::Session(LIBSSH2_ERROR_EAGAIN) => {
+                thread::sleep(Duration::from_millis(5));
+            }
+            _ => return,
+        }
+    }
+}
@@ -1,4 +1,9 @@
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
 use iced::Color;
+use serde::{Deserialize, Serialize};
 
 use crate::config::{AppTheme, LayoutPreset};
 
@@ -133,8 +138,245 @@ pub struct Palette {
     pub border_focused: Color,
 }
 
+/// Colors of `AppTheme::Custom`, each written as `#rrggbb`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PaletteSpec {
+    pub bg_primary: String,
+    pub bg_secondary: String,
+    pub bg_tertiary: String,
+    pub bg_hover: String,
+    pub bg_active: String,
+    pub text_primary: String,
+    pub text_secondary: String,
+    pub text_muted: String,
+    pub accent: String,
+    pub accent_hover: String,
+    pub success: String,
+    pub warning: String,
+    pub danger: String,
+    pub border: String,
+    pub border_focused: String,
+}
+
+impl Default for PaletteSpec {
+    fn default() -> Self {
+        Self::from_palette(&palette(AppTheme::Dark))
+    }
+}
+
+impl PaletteSpec {
+    /// Field keys paired with the labels shown in the theme editor.
+    pub const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("bg_primary", "Background"),
+        ("bg_secondary", "Panel"),
+        ("bg_tertiary", "Input / Card"),
+        ("bg_hover", "Hover"),
+        ("bg_active", "Selected"),
+        ("text_primary", "Text"),
+        ("text_secondary", "Secondary Text"),
+        ("text_muted", "Muted Text"),
+        ("accent", "Accent"),
+        ("accent_hover", "Accent Hover"),
+        ("success", "Success"),
+        ("warning", "Warning"),
+        ("danger", "Danger"),
+        ("border", "Border"),
+        ("border_focused", "Focused Border"),
+    ];
+
+    pub fn from_palette(p: &Palette) -> Self {
+        Self {
+            bg_primary: to_hex(p.bg_primary),
+            bg_secondary: to_hex(p.bg_secondary),
+            bg_tertiary: to_hex(p.bg_tertiary),
+            bg_hover: to_hex(p.bg_hover),
+            bg_active: to_hex(p.bg_active),
+            text_primary: to_hex(p.text_primary),
+            text_secondary: to_hex(p.text_secondary),
+            text_muted: to_hex(p.text_muted),
+            accent: to_hex(p.accent),
+            accent_hover: to_hex(p.accent_hover),
+            success: to_hex(p.success),
+            warning: to_hex(p.warning),
+            danger: to_hex(p.danger),
+            border: to_hex(p.border),
+            border_focused: to_hex(p.border_focused),
+        }
+    }
+
+    fn field_mut(&mut self, key: &str) -> Option<&mut String> {
+        Some(match key {
+            "bg_primary" => &mut self.bg_primary,
+            "bg_secondary" => &mut self.bg_secondary,
+            "bg_tertiary" => &mut self.bg_tertiary,
+            "bg_hover" => &mut self.bg_hover,
+            "bg_active" => &mut self.bg_active,
+            "text_primary" => &mut self.text_primary,
+            "text_secondary" => &mut self.text_secondary,
+            "text_muted" => &mut self.text_muted,
+            "accent" => &mut self.accent,
+            "accent_hover" => &mut self.accent_hover,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "danger" => &mut self.danger,
+            "border" => &mut self.border,
+            "border_focused" => &mut self.border_focused,
+            _ => return None,
+        })
+    }
+
+    pub fn get(&self, key: &str) -> &str {
+        match key {
+            "bg_primary" => &self.bg_primary,
+            "bg_secondary" => &self.bg_secondary,
+            "bg_tertiary" => &self.bg_tertiary,
+            "bg_hover" => &self.bg_hover,
+            "bg_active" => &self.bg_active,
+            "text_primary" => &self.text_primary,
+            "text_secondary" => &self.text_secondary,
+            "text_muted" => &self.text_muted,
+            "accent" => &self.accent,
+            "accent_hover" => &self.accent_hover,
+            "success" => &self.success,
+            "warning" => &self.warning,
+            "danger" => &self.danger,
+            "border" => &self.border,
+            "border_focused" => &self.border_focused,
+            _ => "",
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: String) {
+        if let Some(field) = self.field_mut(key) {
+            *field = value;
+        }
+    }
+
+    /// Resolves the spec into colors; unparsable entries keep `fallback`'s.
+    pub fn to_palette(&self, fallback: &Palette) -> Palette {
+        let c = |hex: &str, d: Color| parse_hex(hex).unwrap_or(d);
+        Palette {
+            bg_primary: c(&self.bg_primary, fallback.bg_primary),
+            bg_secondary: c(&self.bg_secondary, fallback.bg_secondary),
+            bg_tertiary: c(&self.bg_tertiary, fallback.bg_tertiary),
+            bg_hover: c(&self.bg_hover, fallback.bg_hover),
+            bg_active: c(&self.bg_active, fallback.bg_active),
+            text_primary: c(&self.text_primary, fallback.text_primary),
+            text_secondary: c(&self.text_secondary, fallback.text_secondary),
+            text_muted: c(&self.text_muted, fallback.text_muted),
+            accent: c(&self.accent, fallback.accent),
+            accent_hover: c(&self.accent_hover, fallback.accent_hover),
+            success: c(&self.success, fallback.success),
+            warning: c(&self.warning, fallback.warning),
+            danger: c(&self.danger, fallback.danger),
+            border: c(&self.border, fallback.border),
+            border_focused: c(&self.border_focused, fallback.border_focused),
+        }
+    }
+}
+
+/// Parses `#rrggbb` (the `#` is optional).
+pub fn parse_hex(s: &str) -> Option<Color> {
+    let hex = s.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::from_rgb8(channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn to_hex(c: Color) -> String {
+    let [r, g, b, _] = c.into_rgba8();
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Resolved `AppTheme::Custom` colors; `palette` can't reach the config.
+static CUSTOM_PALETTE: RwLock<Option<Palette>> = RwLock::new(None);
+
+pub fn set_custom_palette(spec: &PaletteSpec) {
+    let resolved = spec.to_palette(&palette(AppTheme::Dark));
+    if let Ok(mut slot) = CUSTOM_PALETTE.write() {
+        *slot = Some(resolved);
+    }
+}
+
+/// Whether `AppTheme::Auto` currently resolves to the light palette.
+static AUTO_LIGHT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_auto_theme(resolved: AppTheme) {
+    AUTO_LIGHT.store(resolved.is_light(), Ordering::Relaxed);
+}
+
+/// Built-in theme matching the OS light/dark preference; Dark when the
+/// preference can't be read.
+pub fn detect_system_theme() -> AppTheme {
+    if system_prefers_light() {
+        AppTheme::Light
+    } else {
+        AppTheme::Dark
+    }
+}
+
+fn command_stdout(program: &str, args: &[&str]) -> String {
+    Command::new(program)
+        .args(args)
+        .output()
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "linux")]
+fn system_prefers_light() -> bool {
+    // GNOME 42+ (and most GTK desktops) expose an explicit preference.
+    let scheme = command_stdout("gsettings", &["get", "org.gnome.desktop.interface", "color-scheme"]);
+    match scheme.trim_matches('\'') {
+        "prefer-dark" => false,
+        "prefer-light" => true,
+        "" => false,
+        _ => {
+            let gtk = command_stdout("gsettings", &["get", "org.gnome.desktop.interface", "gtk-theme"]);
+            !gtk.to_lowercase().contains("dark")
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn system_prefers_light() -> bool {
+    // The key only exists while dark mode is on.
+    !command_stdout("defaults", &["read", "-g", "AppleInterfaceStyle"]).contains("Dark")
+}
+
+#[cfg(target_os = "windows")]
+fn system_prefers_light() -> bool {
+    let out = command_stdout(
+        "reg",
+        &[
+            "query",
+            r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+            "/v",
+            "AppsUseLightTheme",
+        ],
+    );
+    out.lines().any(|l| l.contains("AppsUseLightTheme") && l.trim_end().ends_with("0x1"))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn system_prefers_light() -> bool {
+    false
+}
+
 pub fn palette(theme: AppTheme) -> Palette {
     match theme {
+        AppTheme::Auto => palette(if AUTO_LIGHT.load(Ordering::Relaxed) {
+            AppTheme::Light
+        } else {
+            AppTheme::Dark
+        }),
+        AppTheme::Custom => CUSTOM_PALETTE
+            .read()
+            .ok()
+            .and_then(|slot| *slot)
+            .unwrap_or_else(|| palette(AppTheme::Dark)),
         AppTheme::Dark => Palette {
             // Deep Neutral Dark – Soft Contrast, Premium Feel
             bg_primary:     Color::from_rgb8(9,  11,  15),   // Daha derin ama siyah değil
@@ -0,0 +1,101 @@
+use std::collections::VecDeque;
+
+use iced::widget::canvas::{self, Frame, Geometry, Path, Stroke};
+use iced::{mouse, Element, Length, Point, Rectangle, Renderer, Size, Theme};
+
+use crate::app::Message;
+use crate::syspanel::RemoteInfo;
+use crate::theme;
+
+/// Samples kept per tab; at one every two seconds this is four minutes.
+pub const HISTORY_LEN: usize = 120;
+
+/// Scrolling CPU (filled) and RAM (line) history of a host, newest on the
+/// right. Hovering shows the values of the sample under the cursor.
+struct UsageChart {
+    samples: Vec<RemoteInfo>,
+    p: theme::Palette,
+}
+
+pub fn usage_chart(history: &VecDeque<RemoteInfo>, p: theme::Palette) -> Element<'static, Message> {
+    canvas::Canvas::new(UsageChart { samples: history.iter().copied().collect(), p })
+        .width(Length::Fill)
+        .height(Length::Fixed(44.0))
+        .into()
+}
+
+impl UsageChart {
+    /// Point of sample `i` at `pct`; the newest sample sits on the right edge.
+    fn point(&self, size: Size, i: usize, pct: f32) -> Point {
+        let step = size.width / (HISTORY_LEN - 1) as f32;
+        let x = size.width - (self.samples.len() - 1 - i) as f32 * step;
+        let y = size.height - pct.clamp(0.0, 100.0) / 100.0 * size.height;
+        Point::new(x, y)
+    }
+}
+
+impl canvas::Program<Message> for UsageChart {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let size = bounds.size();
+        let p = self.p;
+
+        frame.fill_rectangle(Point::ORIGIN, size, p.bg_primary);
+        if self.samples.is_empty() {
+            return vec![frame.into_geometry()];
+        }
+
+        let cpu = Path::new(|b| {
+            b.move_to(self.point(size, 0, 0.0));
+            for (i, s) in self.samples.iter().enumerate() {
+                b.line_to(self.point(size, i, s.cpu_usage));
+            }
+            b.line_to(Point::new(size.width, size.height));
+            b.close();
+        });
+        frame.fill(&cpu, iced::Color { a: 0.35, ..p.accent });
+
+        let ram = Path::new(|b| {
+            for (i, s) in self.samples.iter().enumerate() {
+                let pt = self.point(size, i, s.memory_usage);
+                if i == 0 { b.move_to(pt) } else { b.line_to(pt) }
+            }
+        });
+        frame.stroke(&ram, Stroke::default().with_color(p.success).with_width(1.0));
+
+        if let Some(pos) = cursor.position_in(bounds) {
+            let step = size.width / (HISTORY_LEN - 1) as f32;
+            let back = ((size.width - pos.x) / step).round() as usize;
+            if back < self.samples.len() {
+                let i = self.samples.len() - 1 - back;
+                let sample = self.samples[i];
+                let x = self.point(size, i, 0.0).x;
+                let line = Path::line(Point::new(x, 0.0), Point::new(x, size.height));
+                frame.stroke(&line, Stroke::default().with_color(p.text_muted).with_width(1.0));
+                frame.fill_text(canvas::Text {
+                    content: format!(
+                        "CPU {:.0}% · RAM {:.0}% · {}s ago",
+                        sample.cpu_usage,
+                        sample.memory_usage,
+                        back * 2
+                    ),
+                    position: Point::new(2.0, 1.0),
+                    color: p.text_primary,
+                    size: 9.0.into(),
+                    ..Default::default()
+                });
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
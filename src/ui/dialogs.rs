@@ -1,10 +1,11 @@
-use iced::widget::{button, column, container, pick_list, row, scrollable, text, text_input, Column};
+use iced::widget::{button, checkbox, column, container, pick_list, row, scrollable, text, text_input, Column};
 use iced::{Element, Length};
 
 use crate::app::{Message, SecurityFinding, SecuritySeverity};
-use crate::config::{AppTheme, CustomCommand, Language, LayoutPreset};
+use crate::config::{AppTheme, ConfigBackup, CustomCommand, Host, KeyProblem, Language, LayoutPreset, Tunnel};
+use crate::session_log;
 use crate::i18n::Texts;
-use crate::theme;
+use crate::theme::{self, PaletteSpec};
 
 #[derive(Debug, Clone)]
 pub struct ConnectionForm {
@@ -13,6 +14,20 @@ pub struct ConnectionForm {
     pub port: String,
     pub username: String,
     pub password: String,
+    pub identity_file: String,
+    pub jump_host: String,
+    pub group: String,
+    /// Comma-separated tags.
+    pub tags: String,
+    pub on_connect: String,
+    pub collect_stats: bool,
+    pub env: Vec<(String, String)>,
+    /// Name and value of the variable being added to `env`.
+    pub new_env: (String, String),
+    /// Existing group names offered as one-click picks.
+    pub group_options: Vec<String>,
+    /// Reveal the password while editing; masked by default.
+    pub show_password: bool,
 }
 
 impl Default for ConnectionForm {
@@ -23,6 +38,16 @@ impl Default for ConnectionForm {
             port: "22".to_string(),
             username: String::new(),
             password: String::new(),
+            identity_file: String::new(),
+            jump_host: String::new(),
+            group: String::new(),
+            tags: String::new(),
+            on_connect: String::new(),
+            collect_stats: true,
+            env: Vec::new(),
+            new_env: (String::new(), String::new()),
+            group_options: Vec::new(),
+            show_password: false,
         }
     }
 }
@@ -37,6 +62,43 @@ pub struct SettingsForm {
     pub terminal_font_size: f32,
     pub show_borders: bool,
     pub suggestions_enabled: bool,
+    pub background_audit: bool,
+    pub sys_prefetch: bool,
+    pub connect_timeout_secs: u64,
+    pub keepalive_secs: u64,
+    pub clipboard_clear_secs: u64,
+    pub api_timeout_secs: u64,
+    pub auto_log: bool,
+    pub linkify_urls: bool,
+    pub copy_on_select: bool,
+    pub bell_notify: bool,
+    pub log_keep_ansi: bool,
+    pub max_reconnect_attempts: u32,
+    pub title_template: String,
+    pub term_type: String,
+    /// New master password; applied right away by Set, not by Save.
+    pub master_password: String,
+    pub remember_master: bool,
+}
+
+/// Ad-hoc target of the Quick Connect dialog; nothing here is saved.
+#[derive(Debug, Clone, Default)]
+pub struct QuickConnectForm {
+    /// `[user@]host[:port]`.
+    pub target: String,
+    pub password: String,
+    pub error: Option<String>,
+}
+
+/// Startup prompt for a config protected by a master password.
+#[derive(Debug, Clone, Default)]
+pub struct UnlockForm {
+    pub password: String,
+    /// Keep the derived key in the OS keyring so later launches skip this.
+    pub remember: bool,
+    pub error: Option<String>,
+    /// Key derivation is running.
+    pub busy: bool,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -47,14 +109,88 @@ pub struct CustomCommandsForm {
     pub new_description: String,
 }
 
+/// New-tunnel inputs of the Tunnels dialog.
+#[derive(Debug, Clone, Default)]
+pub struct TunnelForm {
+    pub local_port: String,
+    pub remote_host: String,
+    pub remote_port: String,
+}
+
+/// A configured tunnel with its live status, as shown in the Tunnels dialog.
+#[derive(Debug, Clone)]
+pub struct TunnelRow {
+    pub tunnel: Tunnel,
+    pub running: bool,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PassphraseForm {
+    pub alias: String,
+    pub key_path: String,
+    pub passphrase: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HostKeyPrompt {
+    pub alias: String,
+    /// `host` or `[host]:port`, as written to known_hosts.
+    pub entry: String,
+    pub fingerprint: String,
+}
+
 #[derive(Debug, Clone)]
 pub enum DialogState {
     NewConnection(ConnectionForm),
     EditConnection(usize, ConnectionForm),
     Settings(SettingsForm),
+    /// Colors of the custom theme being edited.
+    ThemeEditor(PaletteSpec),
+    /// Previous config versions offered for restore, newest first.
+    ConfigBackups(Vec<ConfigBackup>),
     ConfirmDelete(usize),
+    /// Hosts ticked in multi-select mode, with their aliases for the prompt.
+    ConfirmBulkDelete { indices: Vec<usize>, aliases: Vec<String> },
+    /// Remote SFTP entry awaiting delete confirmation.
+    ConfirmFtpDelete { path: String, is_dir: bool },
+    /// Destructive system panel command awaiting confirmation.
+    ConfirmSysAction { tab_id: u64, prompt: String, label: &'static str, cmd: String },
+    /// Port forwards of one host; rows are refreshed while the dialog is open.
+    Tunnels {
+        host_idx: usize,
+        alias: String,
+        rows: Vec<TunnelRow>,
+        form: TunnelForm,
+        error: Option<String>,
+    },
+    /// Octal permission editor for a remote SFTP entry.
+    FtpChmod { path: String, mode: String, error: Option<String> },
+    KeyPassphrase(usize, PassphraseForm),
+    Unlock(UnlockForm),
+    /// The key of the existing config couldn't be had at startup.
+    KeyUnavailable { problem: KeyProblem, error: Option<String>, key_file: Option<std::path::PathBuf> },
+    QuickConnect(QuickConnectForm),
+    /// Unknown server key of a saved or Quick Connect host.
+    ConfirmHostKey(Box<Host>, HostKeyPrompt),
+    /// Export options; passwords are left out unless explicitly included.
+    ExportHosts { include_passwords: bool },
     CustomCommands(CustomCommandsForm),
-    SecurityAudit(Vec<SecurityFinding>),
+    /// Values for a custom command's `{name}` placeholders, in script order.
+    CommandPrompts { trigger: String, script: String, values: Vec<(String, String)> },
+    /// A custom command that looks destructive, expanded and waiting to be confirmed.
+    ConfirmDestructive { trigger: String, command: String },
+    SecurityAudit {
+        findings: Vec<SecurityFinding>,
+        /// Hide host names and credential details, e.g. while screen sharing.
+        redact: bool,
+        /// Severities currently visible; toggled by the filter chips.
+        shown: Vec<SecuritySeverity>,
+        /// Outcome of the last remediation action: (message, is_error).
+        notice: Option<(String, bool)>,
+        /// Deep scan progress as (hosts done, hosts total) while it runs.
+        scan: Option<(usize, usize)>,
+    },
 }
 
 pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: theme::LayoutConfig) -> Element<'static, Message> {
@@ -68,23 +204,58 @@ pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: them
                 _ => texts.edit_server,
             };
             let form_clone = form.clone();
+            let mut group_picks = row![].spacing(4);
+            for name in form_clone.group_options.iter().take(6) {
+                group_picks = group_picks.push(select_button(
+                    name.clone(),
+                    *name == form_clone.group,
+                    Message::DialogFieldChanged("group".to_string(), name.clone()),
+                    theme,
+                    cr,
+                ));
+            }
             column![
                 text(title).size(16).color(p.text_primary),
                 labeled_input(texts.alias, &form_clone.alias, |v| {
                     Message::DialogFieldChanged("alias".to_string(), v)
-                }, theme, cr),
+                }, Some(connection_field_id("alias")), theme, cr),
                 labeled_input(texts.hostname, &form_clone.hostname, |v| {
                     Message::DialogFieldChanged("hostname".to_string(), v)
-                }, theme, cr),
+                }, Some(connection_field_id("hostname")), theme, cr),
                 labeled_input(texts.port, &form_clone.port, |v| {
                     Message::DialogFieldChanged("port".to_string(), v)
-                }, theme, cr),
+                }, Some(connection_field_id("port")), theme, cr),
                 labeled_input(texts.username, &form_clone.username, |v| {
                     Message::DialogFieldChanged("username".to_string(), v)
-                }, theme, cr),
-                labeled_input(texts.password, &form_clone.password, |v| {
-                    Message::DialogFieldChanged("password".to_string(), v)
-                }, theme, cr),
+                }, Some(connection_field_id("username")), theme, cr),
+                password_input(texts.password, &form_clone.password, form_clone.show_password, theme, cr),
+                labeled_input(texts.identity_file, &form_clone.identity_file, |v| {
+                    Message::DialogFieldChanged("identity_file".to_string(), v)
+                }, Some(connection_field_id("identity_file")), theme, cr),
+                labeled_input(texts.jump_host, &form_clone.jump_host, |v| {
+                    Message::DialogFieldChanged("jump_host".to_string(), v)
+                }, Some(connection_field_id("jump_host")), theme, cr),
+                labeled_input(texts.group, &form_clone.group, |v| {
+                    Message::DialogFieldChanged("group".to_string(), v)
+                }, Some(connection_field_id("group")), theme, cr),
+                group_picks,
+                labeled_input(texts.tags, &form_clone.tags, |v| {
+                    Message::DialogFieldChanged("tags".to_string(), v)
+                }, Some(connection_field_id("tags")), theme, cr),
+                labeled_input(texts.on_connect, &form_clone.on_connect, |v| {
+                    Message::DialogFieldChanged("on_connect".to_string(), v)
+                }, Some(connection_field_id("on_connect")), theme, cr),
+                env_table(texts, &form_clone, theme, cr),
+                checkbox(texts.collect_stats, form_clone.collect_stats)
+                    .on_toggle(|on| Message::DialogFieldChanged("collect_stats".to_string(), on.to_string()))
+                    .size(14)
+                    .text_size(12)
+                    .style(move |_: &iced::Theme, _| checkbox::Style {
+                        background: iced::Background::Color(p.bg_tertiary),
+                        icon_color: p.accent,
+                        border: iced::Border { color: p.border, width: 1.0, radius: cr.into() },
+                        text_color: Some(p.text_secondary),
+                    }),
                 row![
                     dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
                     dialog_button(texts.save, Message::SaveDialog, true, theme, cr),
@@ -103,6 +274,19 @@ pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: them
             let font_size = form_clone.terminal_font_size;
             let borders_on = form_clone.show_borders;
             let suggestions_on = form_clone.suggestions_enabled;
+            let audit_on = form_clone.background_audit;
+            let prefetch_on = form_clone.sys_prefetch;
+            let auto_log = form_clone.auto_log;
+            let linkify_on = form_clone.linkify_urls;
+            let copy_on_select = form_clone.copy_on_select;
+            let bell_notify = form_clone.bell_notify;
+            let keep_ansi = form_clone.log_keep_ansi;
+            let timeout = form_clone.connect_timeout_secs;
+            let keepalive = form_clone.keepalive_secs;
+            let clipboard_clear = form_clone.clipboard_clear_secs;
+            let api_timeout = form_clone.api_timeout_secs;
+            let has_master = crate::config::has_master_password();
+            let max_attempts = form_clone.max_reconnect_attempts;
 
             let theme_picker = pick_list(
                 AppTheme::all(),
@@ -150,26 +334,42 @@ pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: them
                 text(texts.api_key_settings).size(16).color(p.text_primary),
                 labeled_input(texts.api_key, &form_clone.api_key, |v| {
                     Message::DialogFieldChanged("api_key".to_string(), v)
-                }, theme, cr),
+                }, None, theme, cr),
                 labeled_input(texts.api_url, &form_clone.api_url, |v| {
                     Message::DialogFieldChanged("api_url".to_string(), v)
-                }, theme, cr),
+                }, None, theme, cr),
+                column![
+                    labeled_input("Tab Title", &form_clone.title_template, |v| {
+                        Message::DialogFieldChanged("title_template".to_string(), v)
+                    }, None, theme, cr),
+                    text("{alias} {user} {host} {port} {title} (set by the remote) {path}")
+                        .size(10)
+                        .color(p.text_muted),
+                ].spacing(4),
+                column![
+                    labeled_input("Terminal Type", &form_clone.term_type, |v| {
+                        Message::DialogFieldChanged("term_type".to_string(), v)
+                    }, None, theme, cr),
+                    text("TERM for new sessions; old servers fall back to xterm. Set TERM in a host's environment to override it there.")
+                        .size(10)
+                        .color(p.text_muted),
+                ].spacing(4),
                 column![
                     text("Theme").size(11).color(p.text_secondary),
-                    theme_picker,
+                    row![
+                        theme_picker,
+                        select_button("Customize…", false, Message::OpenThemeEditor, theme, cr),
+                    ].spacing(6).align_y(iced::Alignment::Center),
                 ].spacing(4),
                 column![
                     text("Layout").size(11).color(p.text_secondary),
                     layout_picker,
                 ].spacing(4),
                 text("Language").size(11).color(p.text_secondary),
-                row![
-                    select_button("TR", matches!(form_clone.language, Language::Turkish),
-                        Message::SettingsLanguageChanged(Language::Turkish), theme, cr),
-                    select_button("EN", matches!(form_clone.language, Language::English),
-                        Message::SettingsLanguageChanged(Language::English), theme, cr),
-                ]
-                .spacing(8),
+                Language::all().iter().fold(row![].spacing(8), |r, &lang| {
+                    r.push(select_button(lang.code(), form_clone.language == lang,
+                        Message::SettingsLanguageChanged(lang), theme, cr))
+                }),
                 // Terminal appearance
                 text("Terminal Appearance").size(13).color(p.text_primary),
                 column![
@@ -190,6 +390,98 @@ pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: them
                             Message::SettingsFontSizeChanged(font_size + 1.0), theme, cr),
                     ].spacing(6).align_y(iced::Alignment::Center),
                 ].spacing(4),
+                column![
+                    text("Connect Timeout").size(11).color(p.text_secondary),
+                    row![
+                        select_button("-", false,
+                            Message::SettingsConnectTimeoutChanged(timeout.saturating_sub(5)), theme, cr),
+                        container(
+                            text(format!("{}s", timeout)).size(11).color(p.text_primary)
+                        )
+                        .padding([4, 10])
+                        .style(move |_: &iced::Theme| container::Style {
+                            background: Some(iced::Background::Color(p.bg_tertiary)),
+                            border: iced::Border { color: p.border, width: 1.0, radius: cr.into() },
+                            ..Default::default()
+                        }),
+                        select_button("+", false,
+                            Message::SettingsConnectTimeoutChanged(timeout + 5), theme, cr),
+                    ].spacing(6).align_y(iced::Alignment::Center),
+                ].spacing(4),
+                column![
+                    text("Keepalive").size(11).color(p.text_secondary),
+                    row![
+                        select_button("-", false,
+                            Message::SettingsKeepaliveChanged(keepalive.saturating_sub(15)), theme, cr),
+                        container(
+                            text(if keepalive == 0 { "Off".to_string() } else { format!("{}s", keepalive) })
+                                .size(11).color(p.text_primary)
+                        )
+                        .padding([4, 10])
+                        .style(move |_: &iced::Theme| container::Style {
+                            background: Some(iced::Background::Color(p.bg_tertiary)),
+                            border: iced::Border { color: p.border, width: 1.0, radius: cr.into() },
+                            ..Default::default()
+                        }),
+                        select_button("+", false,
+                            Message::SettingsKeepaliveChanged(keepalive + 15), theme, cr),
+                    ].spacing(6).align_y(iced::Alignment::Center),
+                ].spacing(4),
+                column![
+                    text("Clear Clipboard After Secret Paste").size(11).color(p.text_secondary),
+                    row![
+                        select_button("-", false,
+                            Message::SettingsClipboardClearChanged(clipboard_clear.saturating_sub(10)), theme, cr),
+                        container(
+                            text(if clipboard_clear == 0 { "Off".to_string() } else { format!("{}s", clipboard_clear) })
+                                .size(11).color(p.text_primary)
+                        )
+                        .padding([4, 10])
+                        .style(move |_: &iced::Theme| container::Style {
+                            background: Some(iced::Background::Color(p.bg_tertiary)),
+                            border: iced::Border { color: p.border, width: 1.0, radius: cr.into() },
+                            ..Default::default()
+                        }),
+                        select_button("+", false,
+                            Message::SettingsClipboardClearChanged(clipboard_clear + 10), theme, cr),
+                    ].spacing(6).align_y(iced::Alignment::Center),
+                ].spacing(4),
+                column![
+                    text("API Timeout").size(11).color(p.text_secondary),
+                    row![
+                        select_button("-", false,
+                            Message::SettingsApiTimeoutChanged(api_timeout.saturating_sub(5)), theme, cr),
+                        container(
+                            text(format!("{}s", api_timeout)).size(11).color(p.text_primary)
+                        )
+                        .padding([4, 10])
+                        .style(move |_: &iced::Theme| container::Style {
+                            background: Some(iced::Background::Color(p.bg_tertiary)),
+                            border: iced::Border { color: p.border, width: 1.0, radius: cr.into() },
+                            ..Default::default()
+                        }),
+                        select_button("+", false,
+                            Message::SettingsApiTimeoutChanged(api_timeout + 5), theme, cr),
+                    ].spacing(6).align_y(iced::Alignment::Center),
+                ].spacing(4),
+                column![
+                    text("Auto-Reconnect Attempts").size(11).color(p.text_secondary),
+                    row![
+                        select_button("-", false,
+                            Message::SettingsMaxReconnectChanged(max_attempts.saturating_sub(1)), theme, cr),
+                        container(
+                            text(max_attempts.to_string()).size(11).color(p.text_primary)
+                        )
+                        .padding([4, 10])
+                        .style(move |_: &iced::Theme| container::Style {
+                            background: Some(iced::Background::Color(p.bg_tertiary)),
+                            border: iced::Border { color: p.border, width: 1.0, radius: cr.into() },
+                            ..Default::default()
+                        }),
+                        select_button("+", false,
+                            Message::SettingsMaxReconnectChanged(max_attempts + 1), theme, cr),
+                    ].spacing(6).align_y(iced::Alignment::Center),
+                ].spacing(4),
                 column![
                     text("Panel Borders").size(11).color(p.text_secondary),
                     row![
@@ -208,6 +500,109 @@ pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: them
                             Message::SettingsSuggestionsChanged(false), theme, cr),
                     ].spacing(6),
                 ].spacing(4),
+                column![
+                    text("Background Security Audit").size(11).color(p.text_secondary),
+                    row![
+                        select_button("Enabled", audit_on,
+                            Message::SettingsBackgroundAuditChanged(true), theme, cr),
+                        select_button("Disabled", !audit_on,
+                            Message::SettingsBackgroundAuditChanged(false), theme, cr),
+                    ].spacing(6),
+                ].spacing(4),
+                column![
+                    text("System Panel Prefetch").size(11).color(p.text_secondary),
+                    row![
+                        select_button("Enabled", prefetch_on,
+                            Message::SettingsSysPrefetchChanged(true), theme, cr),
+                        select_button("Disabled", !prefetch_on,
+                            Message::SettingsSysPrefetchChanged(false), theme, cr),
+                    ].spacing(6),
+                ].spacing(4),
+                column![
+                    text("Session Logging").size(11).color(p.text_secondary),
+                    row![
+                        select_button("Enabled", auto_log,
+                            Message::SettingsAutoLogChanged(true), theme, cr),
+                        select_button("Disabled", !auto_log,
+                            Message::SettingsAutoLogChanged(false), theme, cr),
+                        select_button("Keep ANSI", keep_ansi,
+                            Message::SettingsLogAnsiChanged(!keep_ansi), theme, cr),
+                    ].spacing(6),
+                ].spacing(4),
+                column![
+                    text("Clickable URLs in Output").size(11).color(p.text_secondary),
+                    row![
+                        select_button("Enabled", linkify_on,
+                            Message::SettingsLinkifyChanged(true), theme, cr),
+                        select_button("Disabled", !linkify_on,
+                            Message::SettingsLinkifyChanged(false), theme, cr),
+                    ].spacing(6),
+                ].spacing(4),
+                column![
+                    text("Copy Selection Automatically").size(11).color(p.text_secondary),
+                    row![
+                        select_button("Enabled", copy_on_select,
+                            Message::SettingsCopyOnSelectChanged(true), theme, cr),
+                        select_button("Disabled", !copy_on_select,
+                            Message::SettingsCopyOnSelectChanged(false), theme, cr),
+                    ].spacing(6),
+                ].spacing(4),
+                column![
+                    text("Notify on Bell in Background Tabs").size(11).color(p.text_secondary),
+                    row![
+                        select_button("Enabled", bell_notify,
+                            Message::SettingsBellNotifyChanged(true), theme, cr),
+                        select_button("Disabled", !bell_notify,
+                            Message::SettingsBellNotifyChanged(false), theme, cr),
+                    ].spacing(6),
+                ].spacing(4),
+                column![
+                    text("Master Password").size(11).color(p.text_secondary),
+                    text(if has_master {
+                        "Set — the config can only be opened with it."
+                    } else {
+                        "Not set — the config key is kept by the OS keyring."
+                    })
+                    .size(10)
+                    .color(p.text_muted),
+                    text_input(if has_master { "New master password" } else { "Master password" }, &form_clone.master_password)
+                        .secure(true)
+                        .on_input(|v| Message::DialogFieldChanged("master_password".to_string(), v))
+                        .on_submit(Message::SetMasterPassword)
+                        .padding(8)
+                        .size(13)
+                        .style(move |_t: &iced::Theme, status: text_input::Status| text_input::Style {
+                            background: iced::Background::Color(p.bg_tertiary),
+                            border: iced::Border {
+                                color: match status {
+                                    text_input::Status::Focused => p.border_focused,
+                                    _ => p.border,
+                                },
+                                width: 1.0,
+                                radius: cr.into(),
+                            },
+                            icon: p.text_muted,
+                            placeholder: p.text_muted,
+                            value: p.text_primary,
+                            selection: p.accent,
+                        }),
+                    checkbox("Remember on this device (OS keyring)", form_clone.remember_master)
+                        .on_toggle(|on| Message::DialogFieldChanged("remember_master".to_string(), on.to_string()))
+                        .size(14)
+                        .text_size(12)
+                        .style(move |_: &iced::Theme, _| checkbox::Style {
+                            background: iced::Background::Color(p.bg_tertiary),
+                            icon_color: p.accent,
+                            border: iced::Border { color: p.border, width: 1.0, radius: cr.into() },
+                            text_color: Some(p.text_secondary),
+                        }),
+                    row![
+                        select_button(if has_master { "Change" } else { "Set" }, false,
+                            Message::SetMasterPassword, theme, cr),
+                        select_button("Remove", false, Message::RemoveMasterPassword, theme, cr),
+                    ].spacing(6),
+                ].spacing(4),
+                select_button("Restore previous config…", false, Message::OpenConfigBackups, theme, cr),
                 row![
                     dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
                     dialog_button(texts.save, Message::SaveSettings, true, theme, cr),
@@ -219,6 +614,148 @@ pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: them
             .into()
         }
 
+        DialogState::ThemeEditor(spec) => {
+            let preview = spec.to_palette(&p);
+            let mut fields = Column::new().spacing(6);
+            for &(key, label) in PaletteSpec::FIELDS {
+                let value = spec.get(key).to_string();
+                let color = theme::parse_hex(&value);
+                let swatch = container(text(""))
+                    .width(Length::Fixed(22.0))
+                    .height(Length::Fixed(22.0))
+                    .style(move |_: &iced::Theme| container::Style {
+                        background: color.map(iced::Background::Color),
+                        border: iced::Border {
+                            // An invalid value shows as an empty, red-outlined swatch.
+                            color: if color.is_some() { p.border } else { p.danger },
+                            width: 1.0,
+                            radius: 4.0.into(),
+                        },
+                        ..Default::default()
+                    });
+                fields = fields.push(
+                    row![
+                        text(label).size(11).color(p.text_secondary).width(Length::Fixed(120.0)),
+                        text_input("#rrggbb", &value)
+                            .on_input(move |v| Message::DialogFieldChanged(key.to_string(), v))
+                            .padding([4, 8])
+                            .size(12)
+                            .font(iced::Font::MONOSPACE)
+                            .style(move |_t: &iced::Theme, status: text_input::Status| text_input::Style {
+                                background: iced::Background::Color(p.bg_tertiary),
+                                border: iced::Border {
+                                    color: match status {
+                                        text_input::Status::Focused => p.border_focused,
+                                        _ => p.border,
+                                    },
+                                    width: 1.0,
+                                    radius: cr.into(),
+                                },
+                                icon: p.text_muted,
+                                placeholder: p.text_muted,
+                                value: p.text_primary,
+                                selection: p.accent,
+                            }),
+                        swatch,
+                    ]
+                    .spacing(8)
+                    .align_y(iced::Alignment::Center),
+                );
+            }
+
+            let chip = move |label: &'static str, bg: iced::Color| {
+                container(text(label).size(10).color(preview.bg_primary))
+                    .padding([2, 8])
+                    .style(move |_: &iced::Theme| container::Style {
+                        background: Some(iced::Background::Color(bg)),
+                        border: iced::Border { radius: cr.into(), ..Default::default() },
+                        ..Default::default()
+                    })
+            };
+            let swatch_preview = container(
+                column![
+                    text("Preview").size(13).color(preview.text_primary),
+                    text("Secondary text").size(11).color(preview.text_secondary),
+                    text("Muted text").size(11).color(preview.text_muted),
+                    container(text("Selected row").size(11).color(preview.text_primary))
+                        .padding([3, 8])
+                        .width(Length::Fill)
+                        .style(move |_: &iced::Theme| container::Style {
+                            background: Some(iced::Background::Color(preview.bg_active)),
+                            border: iced::Border { color: preview.border_focused, width: 1.0, radius: cr.into() },
+                            ..Default::default()
+                        }),
+                    row![
+                        chip("Accent", preview.accent),
+                        chip("Success", preview.success),
+                        chip("Warning", preview.warning),
+                        chip("Danger", preview.danger),
+                    ]
+                    .spacing(6),
+                ]
+                .spacing(6),
+            )
+            .padding(12)
+            .width(Length::Fill)
+            .style(move |_: &iced::Theme| container::Style {
+                background: Some(iced::Background::Color(preview.bg_secondary)),
+                border: iced::Border { color: preview.border, width: 1.0, radius: cr.into() },
+                ..Default::default()
+            });
+
+            column![
+                text("Custom Theme").size(16).color(p.text_primary),
+                swatch_preview,
+                scrollable(fields).height(Length::Fixed(300.0)),
+                row![
+                    select_button(format!("Start from {}", theme), false,
+                        Message::ThemeEditorCopyFrom(theme), theme, cr),
+                ],
+                row![
+                    dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
+                    dialog_button("Save & Apply", Message::SaveThemeEditor, true, theme, cr),
+                ]
+                .spacing(8),
+            ]
+            .spacing(12)
+            .width(Length::Fixed(420.0))
+            .into()
+        }
+
+        DialogState::ConfigBackups(backups) => {
+            let mut rows = Column::new().spacing(6);
+            if backups.is_empty() {
+                rows = rows.push(text("No backups yet. One is kept each time the config changes.")
+                    .size(11).color(p.text_muted));
+            }
+            for backup in backups {
+                rows = rows.push(
+                    row![
+                        column![
+                            text(session_log::format_utc(backup.saved_at)).size(12).color(p.text_primary),
+                            text(format!("#{} · {} hosts", backup.index, backup.host_count))
+                                .size(10).color(p.text_muted),
+                        ]
+                        .spacing(2)
+                        .width(Length::Fill),
+                        select_button("Restore", false, Message::RestoreConfigBackup(backup.index), theme, cr),
+                    ]
+                    .spacing(8)
+                    .align_y(iced::Alignment::Center),
+                );
+            }
+            column![
+                text("Restore Previous Config").size(16).color(p.text_primary),
+                text("The current config is kept as a backup, so a restore can be undone.")
+                    .size(11).color(p.text_secondary),
+                scrollable(rows).height(Length::Shrink),
+                row![dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr)],
+            ]
+            .spacing(12)
+            .width(Length::Fixed(380.0))
+            .into()
+        }
+
         DialogState::ConfirmDelete(idx) => {
             let idx = *idx;
             column![
@@ -234,6 +771,576 @@ pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: them
             .into()
         }
 
+        DialogState::ConfirmSysAction { tab_id, prompt, label, cmd } => {
+            column![
+                text(prompt.clone()).size(14).color(p.text_primary),
+                text(cmd.clone()).size(11).color(p.text_muted).font(iced::Font::MONOSPACE),
+                row![
+                    dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
+                    dialog_button(label, Message::SysPanelConfirmed(*tab_id, cmd.clone()), false, theme, cr),
+                ]
+                .spacing(8),
+            ]
+            .spacing(16)
+            .width(Length::Fixed(350.0))
+            .into()
+        }
+
+        DialogState::ConfirmBulkDelete { aliases, .. } => {
+            let mut names = Column::new().spacing(2);
+            for alias in aliases {
+                names = names.push(text(format!("• {}", alias)).size(11).color(p.text_secondary));
+            }
+            column![
+                text(format!("Delete {} selected server(s)?", aliases.len())).size(14).color(p.text_primary),
+                scrollable(names).height(Length::Shrink),
+                row![
+                    dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
+                    dialog_button(texts.delete, Message::ConfirmBulkDelete, false, theme, cr),
+                ]
+                .spacing(8),
+            ]
+            .spacing(16)
+            .width(Length::Fixed(350.0))
+            .into()
+        }
+
+        DialogState::ConfirmFtpDelete { path, is_dir } => {
+            let question = if *is_dir {
+                format!("Delete the remote directory {} and everything in it?", path)
+            } else {
+                format!("Delete the remote file {}?", path)
+            };
+            column![
+                text(question).size(14).color(p.text_primary),
+                row![
+                    dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
+                    dialog_button(texts.delete, Message::FtpDeleteEntry(path.clone()), false, theme, cr),
+                ]
+                .spacing(8),
+            ]
+            .spacing(16)
+            .width(Length::Fixed(350.0))
+            .into()
+        }
+
+        DialogState::Tunnels { alias, rows, form, error, .. } => {
+            let mut list_col = Column::new().spacing(4);
+            if rows.is_empty() {
+                list_col = list_col.push(
+                    text("No tunnels yet. Add one below.")
+                        .size(11)
+                        .color(p.text_muted),
+                );
+            }
+            for (idx, tunnel_row) in rows.iter().enumerate() {
+                let t = &tunnel_row.tunnel;
+                let toggle = if tunnel_row.running {
+                    select_button("Stop", true, Message::TunnelStop(idx), theme, cr)
+                } else {
+                    select_button("Start", false, Message::TunnelStart(idx), theme, cr)
+                };
+                let status_color = if tunnel_row.running { p.success } else { p.text_muted };
+                let row_content = row![
+                    column![
+                        text(format!(
+                            "localhost:{} → {}",
+                            t.local_port,
+                            crate::ssh::socket_addr(&t.remote_host, t.remote_port)
+                        ))
+                        .size(11)
+                        .color(p.text_primary),
+                        text(tunnel_row.status.clone()).size(10).color(status_color),
+                    ]
+                    .spacing(2)
+                    .width(Length::Fill),
+                    toggle,
+                    button(text("✕").size(10).color(p.danger))
+                        .on_press(Message::TunnelRemove(idx))
+                        .padding([1, 6])
+                        .style(move |_t: &iced::Theme, s: button::Status| button::Style {
+                            background: Some(iced::Background::Color(match s {
+                                button::Status::Hovered => p.bg_hover,
+                                _ => iced::Color::TRANSPARENT,
+                            })),
+                            text_color: p.danger,
+                            border: iced::Border {
+                                color: p.border,
+                                width: 1.0,
+                                radius: cr.into(),
+                            },
+                            ..Default::default()
+                        }),
+                ]
+                .spacing(6)
+                .align_y(iced::Alignment::Center);
+                list_col = list_col.push(
+                    container(row_content)
+                        .padding([3, 6])
+                        .width(Length::Fill)
+                        .style(move |_t: &iced::Theme| container::Style {
+                            background: Some(iced::Background::Color(p.bg_tertiary)),
+                            border: iced::Border {
+                                color: p.border,
+                                width: 1.0,
+                                radius: cr.into(),
+                            },
+                            ..Default::default()
+                        }),
+                );
+            }
+
+            let error_line: Element<'static, Message> = match error {
+                Some(e) => text(e.clone()).size(11).color(p.danger).into(),
+                None => iced::widget::Space::new(0.0, 0.0).into(),
+            };
+
+            let add_form = column![
+                text("Add Tunnel").size(12).color(p.text_secondary),
+                row![
+                    labeled_input(
+                        "Local port",
+                        &form.local_port,
+                        |v| Message::DialogFieldChanged("local_port".to_string(), v),
+                        None, theme, cr,
+                    )
+                    .width(Length::Fixed(90.0)),
+                    labeled_input(
+                        "Remote host (from the server)",
+                        &form.remote_host,
+                        |v| Message::DialogFieldChanged("remote_host".to_string(), v),
+                        None, theme, cr,
+                    )
+                    .width(Length::Fill),
+                    labeled_input(
+                        "Remote port",
+                        &form.remote_port,
+                        |v| Message::DialogFieldChanged("remote_port".to_string(), v),
+                        None, theme, cr,
+                    )
+                    .width(Length::Fixed(90.0)),
+                ]
+                .spacing(6),
+                select_button("+ Add", false, Message::TunnelAdd, theme, cr),
+            ]
+            .spacing(8);
+
+            column![
+                text(format!("Tunnels — {}", alias)).size(16).color(p.text_primary),
+                text("Local ports listen on 127.0.0.1 only, like ssh -L.")
+                    .size(10)
+                    .color(p.text_muted),
+                scrollable(list_col).height(Length::Fixed(180.0)),
+                add_form,
+                error_line,
+                row![dialog_button("Close", Message::CloseDialog, false, theme, cr)].spacing(8),
+            ]
+            .spacing(12)
+            .width(Length::Fixed(480.0))
+            .into()
+        }
+
+        DialogState::FtpChmod { path, mode, error } => {
+            let preview = match u32::from_str_radix(mode.trim(), 8) {
+                Ok(bits) if bits <= 0o7777 => crate::ftp::format_mode(bits),
+                _ => "---------".to_string(),
+            };
+            let error_line: Element<'static, Message> = match error {
+                Some(e) => text(e.clone()).size(11).color(p.danger).into(),
+                None => iced::widget::Space::new(0.0, 0.0).into(),
+            };
+            column![
+                text("Permissions").size(16).color(p.text_primary),
+                text(path.clone()).size(11).color(p.text_secondary),
+                row![
+                    text_input("644", mode)
+                        .on_input(|v| Message::DialogFieldChanged("mode".to_string(), v))
+                        .on_submit(Message::FtpApplyChmod)
+                        .padding(8)
+                        .size(13)
+                        .width(Length::Fixed(90.0))
+                        .style(move |_t: &iced::Theme, status: text_input::Status| text_input::Style {
+                            background: iced::Background::Color(p.bg_tertiary),
+                            border: iced::Border {
+                                color: match status {
+                                    text_input::Status::Focused => p.border_focused,
+                                    _ => p.border,
+                                },
+                                width: 1.0,
+                                radius: cr.into(),
+                            },
+                            icon: p.text_muted,
+                            placeholder: p.text_muted,
+                            value: p.text_primary,
+                            selection: p.accent,
+                        }),
+                    text(preview).size(13).font(iced::Font::MONOSPACE).color(p.text_secondary),
+                ]
+                .spacing(12)
+                .align_y(iced::Alignment::Center),
+                error_line,
+                row![
+                    dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
+                    dialog_button("Apply", Message::FtpApplyChmod, true, theme, cr),
+                ]
+                .spacing(8),
+            ]
+            .spacing(12)
+            .width(Length::Fixed(340.0))
+            .into()
+        }
+
+        DialogState::CommandPrompts { trigger, script, values } => {
+            let mut fields = Column::new().spacing(8);
+            for (name, value) in values {
+                let field = name.clone();
+                fields = fields.push(
+                    column![
+                        text(name.clone()).size(11).color(p.text_secondary),
+                        text_input("", value)
+                            .on_input(move |v| Message::DialogFieldChanged(field.clone(), v))
+                            .on_submit(Message::SubmitCommandPrompts)
+                            .padding(8)
+                            .size(13)
+                            .style(move |_t: &iced::Theme, status: text_input::Status| text_input::Style {
+                                background: iced::Background::Color(p.bg_tertiary),
+                                border: iced::Border {
+                                    color: match status {
+                                        text_input::Status::Focused => p.border_focused,
+                                        _ => p.border,
+                                    },
+                                    width: 1.0,
+                                    radius: cr.into(),
+                                },
+                                icon: p.text_muted,
+                                placeholder: p.text_muted,
+                                value: p.text_primary,
+                                selection: p.accent,
+                            }),
+                    ]
+                    .spacing(4),
+                );
+            }
+            column![
+                text(trigger.clone()).size(16).color(p.text_primary),
+                text(script.clone()).size(11).font(iced::Font::MONOSPACE).color(p.text_secondary),
+                fields,
+                row![
+                    dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
+                    dialog_button("Run", Message::SubmitCommandPrompts, true, theme, cr),
+                ]
+                .spacing(8),
+            ]
+            .spacing(12)
+            .width(Length::Fixed(400.0))
+            .into()
+        }
+
+        DialogState::ConfirmDestructive { trigger, command } => {
+            column![
+                text("Run Destructive Command?").size(16).color(p.text_primary),
+                text(format!(
+                    "The custom command '{}' can delete files or wipe disks. Check the exact command before running it.",
+                    trigger
+                ))
+                .size(11)
+                .color(p.text_secondary),
+                container(
+                    text(command.clone())
+                        .size(12)
+                        .font(iced::Font::MONOSPACE)
+                        .color(p.danger),
+                )
+                .padding([6, 8])
+                .width(Length::Fill)
+                .style(move |_t: &iced::Theme| container::Style {
+                    background: Some(iced::Background::Color(p.bg_tertiary)),
+                    border: iced::Border {
+                        color: p.border,
+                        width: 1.0,
+                        radius: cr.into(),
+                    },
+                    ..Default::default()
+                }),
+                row![
+                    dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
+                    dialog_button("Run", Message::RunDestructiveCommand, true, theme, cr),
+                ]
+                .spacing(8),
+            ]
+            .spacing(12)
+            .width(Length::Fixed(420.0))
+            .into()
+        }
+
+        DialogState::KeyPassphrase(_, form) => {
+            let form_clone = form.clone();
+            column![
+                text("Key Passphrase").size(16).color(p.text_primary),
+                text(format!(
+                    "The key {} for {} is encrypted. The passphrase is kept in memory for this session only.",
+                    form_clone.key_path, form_clone.alias
+                ))
+                .size(11)
+                .color(p.text_secondary),
+                text_input("Passphrase", &form_clone.passphrase)
+                    .id(passphrase_input_id())
+                    .secure(true)
+                    .on_input(|v| Message::DialogFieldChanged("passphrase".to_string(), v))
+                    .on_submit(Message::SubmitKeyPassphrase)
+                    .padding(8)
+                    .size(13)
+                    .style(move |_t: &iced::Theme, status: text_input::Status| text_input::Style {
+                        background: iced::Background::Color(p.bg_tertiary),
+                        border: iced::Border {
+                            color: match status {
+                                text_input::Status::Focused => p.border_focused,
+                                _ => p.border,
+                            },
+                            width: 1.0,
+                            radius: cr.into(),
+                        },
+                        icon: p.text_muted,
+                        placeholder: p.text_muted,
+                        value: p.text_primary,
+                        selection: p.accent,
+                    }),
+                row![
+                    dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
+                    dialog_button("Connect", Message::SubmitKeyPassphrase, true, theme, cr),
+                ]
+                .spacing(8),
+            ]
+            .spacing(12)
+            .width(Length::Fixed(380.0))
+            .into()
+        }
+
+        DialogState::QuickConnect(form) => {
+            let form = form.clone();
+            let input_style = move |_t: &iced::Theme, status: text_input::Status| text_input::Style {
+                background: iced::Background::Color(p.bg_tertiary),
+                border: iced::Border {
+                    color: match status {
+                        text_input::Status::Focused => p.border_focused,
+                        _ => p.border,
+                    },
+                    width: 1.0,
+                    radius: cr.into(),
+                },
+                icon: p.text_muted,
+                placeholder: p.text_muted,
+                value: p.text_primary,
+                selection: p.accent,
+            };
+            let mut content = column![
+                text("Quick Connect").size(16).color(p.text_primary),
+                text("Connect without saving the host. Keys from the agent are tried first.")
+                    .size(11)
+                    .color(p.text_secondary),
+                text_input("user@host:port", &form.target)
+                    .id(quick_connect_input_id())
+                    .on_input(|v| Message::DialogFieldChanged("target".to_string(), v))
+                    .on_submit(Message::SubmitQuickConnect)
+                    .padding(8)
+                    .size(13)
+                    .style(input_style),
+                text_input("Password (optional)", &form.password)
+                    .secure(true)
+                    .on_input(|v| Message::DialogFieldChanged("password".to_string(), v))
+                    .on_submit(Message::SubmitQuickConnect)
+                    .padding(8)
+                    .size(13)
+                    .style(input_style),
+            ]
+            .spacing(12)
+            .width(Length::Fixed(380.0));
+            if let Some(error) = form.error {
+                content = content.push(text(error).size(11).color(p.danger));
+            }
+            content
+                .push(
+                    row![
+                        dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
+                        dialog_button("Connect", Message::SubmitQuickConnect, true, theme, cr),
+                    ]
+                    .spacing(8),
+                )
+                .into()
+        }
+
+        DialogState::Unlock(form) => {
+            let form = form.clone();
+            let mut input = text_input("Master password", &form.password)
+                .id(unlock_input_id())
+                .secure(true)
+                .padding(8)
+                .size(13)
+                .style(move |_t: &iced::Theme, status: text_input::Status| text_input::Style {
+                    background: iced::Background::Color(p.bg_tertiary),
+                    border: iced::Border {
+                        color: match status {
+                            text_input::Status::Focused => p.border_focused,
+                            _ => p.border,
+                        },
+                        width: 1.0,
+                        radius: cr.into(),
+                    },
+                    icon: p.text_muted,
+                    placeholder: p.text_muted,
+                    value: p.text_primary,
+                    selection: p.accent,
+                });
+            if !form.busy {
+                input = input
+                    .on_input(|v| Message::DialogFieldChanged("unlock_password".to_string(), v))
+                    .on_submit(Message::SubmitUnlock);
+            }
+            let mut content = column![
+                text("Unlock Config").size(16).color(p.text_primary),
+                text("Saved hosts and credentials are protected by a master password.")
+                    .size(11)
+                    .color(p.text_secondary),
+                input,
+                checkbox("Remember on this device (OS keyring)", form.remember)
+                    .on_toggle(|on| Message::DialogFieldChanged("unlock_remember".to_string(), on.to_string()))
+                    .size(14)
+                    .text_size(12)
+                    .style(move |_: &iced::Theme, _| checkbox::Style {
+                        background: iced::Background::Color(p.bg_tertiary),
+                        icon_color: p.accent,
+                        border: iced::Border { color: p.border, width: 1.0, radius: cr.into() },
+                        text_color: Some(p.text_secondary),
+                    }),
+            ]
+            .spacing(12)
+            .width(Length::Fixed(380.0));
+            if let Some(error) = form.error {
+                content = content.push(text(error).size(11).color(p.danger));
+            }
+            let submit = if form.busy { "Unlocking…" } else { "Unlock" };
+            content
+                .push(
+                    row![
+                        dialog_button("Quit", Message::Quit, false, theme, cr),
+                        dialog_button(submit, Message::SubmitUnlock, true, theme, cr),
+                    ]
+                    .spacing(8),
+                )
+                .into()
+        }
+
+        DialogState::KeyUnavailable { problem, error, key_file } => {
+            let missing = *problem == KeyProblem::Missing;
+            let cause = if missing {
+                "The key that encrypts your saved hosts is missing from the OS keyring."
+            } else {
+                "The key that encrypts your saved hosts can't be read from the OS keyring; it may be locked."
+            };
+            let key_file = key_file.as_ref().map_or_else(|| "config.key".to_string(), |p| p.display().to_string());
+            let mut content = column![
+                text("Config Key Unavailable").size(16).color(p.text_primary),
+                text(cause).size(11).color(p.text_secondary),
+                text(format!(
+                    "Unlock the keyring, or put a config.key from another copy at {}, then retry. Until then the config is left untouched and nothing is saved.",
+                    key_file
+                ))
+                .size(11)
+                .color(p.text_secondary),
+            ]
+            .spacing(12)
+            .width(Length::Fixed(420.0));
+            if missing {
+                content = content.push(
+                    text("Starting over keeps the unreadable file next to the config and saves under a new key.")
+                        .size(11)
+                        .color(p.text_muted),
+                );
+            }
+            if let Some(error) = error {
+                content = content.push(text(error.clone()).size(11).color(p.danger));
+            }
+            let mut buttons = row![dialog_button("Continue Without Saving", Message::CloseDialog, false, theme, cr)].spacing(8);
+            if missing {
+                buttons = buttons.push(dialog_button("Start Over", Message::ReplaceConfigKey, false, theme, cr));
+            }
+            content
+                .push(buttons.push(dialog_button("Retry", Message::RetryConfigKey, true, theme, cr)))
+                .into()
+        }
+
+        DialogState::ConfirmHostKey(_, prompt) => {
+            let prompt = prompt.clone();
+            column![
+                text("Unknown Host Key").size(16).color(p.text_primary),
+                text(format!(
+                    "{} is not in ~/.ssh/known_hosts (connecting to {}). Verify the fingerprint with the server administrator before trusting it.",
+                    prompt.entry, prompt.alias
+                ))
+                .size(11)
+                .color(p.text_secondary),
+                container(
+                    text(prompt.fingerprint.clone())
+                        .size(12)
+                        .font(iced::Font::MONOSPACE)
+                        .color(p.warning),
+                )
+                .padding([6, 8])
+                .width(Length::Fill)
+                .style(move |_t: &iced::Theme| container::Style {
+                    background: Some(iced::Background::Color(p.bg_tertiary)),
+                    border: iced::Border {
+                        color: p.border,
+                        width: 1.0,
+                        radius: cr.into(),
+                    },
+                    ..Default::default()
+                }),
+                row![
+                    dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
+                    dialog_button("Trust and Connect", Message::AcceptHostKey, true, theme, cr),
+                ]
+                .spacing(8),
+            ]
+            .spacing(12)
+            .width(Length::Fixed(420.0))
+            .into()
+        }
+
+        DialogState::ExportHosts { include_passwords } => {
+            let include = *include_passwords;
+            let warning: Element<'static, Message> = if include {
+                text("The file will contain plain-text passwords. Store it somewhere safe.")
+                    .size(11)
+                    .color(p.danger)
+                    .into()
+            } else {
+                text("Passwords are stripped; you will be asked for them on connect.")
+                    .size(11)
+                    .color(p.text_muted)
+                    .into()
+            };
+            column![
+                text("Export Hosts").size(16).color(p.text_primary),
+                row![
+                    select_button("Strip passwords", !include,
+                        Message::ExportIncludePasswordsChanged(false), theme, cr),
+                    select_button("Include passwords", include,
+                        Message::ExportIncludePasswordsChanged(true), theme, cr),
+                ]
+                .spacing(6),
+                warning,
+                row![
+                    dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
+                    dialog_button("Export...", Message::ExportHosts, true, theme, cr),
+                ]
+                .spacing(8),
+            ]
+            .spacing(12)
+            .width(Length::Fixed(380.0))
+            .into()
+        }
+
         DialogState::CustomCommands(form) => {
             let form_clone = form.clone();
 
@@ -302,19 +1409,19 @@ pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: them
                     "Trigger (e.g. -runtest)",
                     &form_clone.new_trigger,
                     |v| Message::DialogFieldChanged("trigger".to_string(), v),
-                    theme, cr,
+                    None, theme, cr,
                 ),
                 labeled_input(
                     "Script (e.g. cd /app && npm test)",
                     &form_clone.new_script,
                     |v| Message::DialogFieldChanged("script".to_string(), v),
-                    theme, cr,
+                    None, theme, cr,
                 ),
                 labeled_input(
                     "Description (optional)",
                     &form_clone.new_description,
                     |v| Message::DialogFieldChanged("description".to_string(), v),
-                    theme, cr,
+                    None, theme, cr,
                 ),
                 button(text("+ Add").size(11).color(p.text_primary))
                     .on_press(Message::AddCustomCommand)
@@ -340,6 +1447,9 @@ pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: them
                 text("Type a trigger (e.g. -runtest) in the terminal and press Enter to execute the script.")
                     .size(10)
                     .color(p.text_muted),
+                text("Scripts may use {host}, {user} and {port}; any other {name} is asked for when run. Write \\{name} to send {name} as is.")
+                    .size(10)
+                    .color(p.text_muted),
                 list_scroll,
                 add_form,
                 row![
@@ -353,11 +1463,39 @@ pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: them
             .into()
         }
 
-        DialogState::SecurityAudit(findings) => {
+        DialogState::SecurityAudit { findings, redact, shown, notice, scan } => {
             let findings_clone = findings.clone();
+            let redact = *redact;
             let mut findings_col = Column::new().spacing(6);
 
-            for finding in &findings_clone {
+            let mut chips = row![].spacing(6);
+            for severity in SecuritySeverity::ALL {
+                let count = findings_clone.iter().filter(|f| f.severity == severity).count();
+                chips = chips.push(select_button(
+                    format!("{} {} {}", severity.icon(), severity.label(), count),
+                    shown.contains(&severity),
+                    Message::SecurityAuditToggleSeverity(severity),
+                    theme,
+                    cr,
+                ));
+            }
+
+            // Redacted mode collapses findings into severity/category counts so
+            // no alias, command or password detail reaches the screen.
+            let mut entries: Vec<(SecurityFinding, usize)> = Vec::new();
+            for finding in findings_clone.iter().filter(|f| shown.contains(&f.severity)) {
+                if !redact {
+                    entries.push((finding.clone(), 1));
+                } else if let Some(entry) = entries.iter_mut().find(|(f, _)| {
+                    f.severity == finding.severity && f.redacted == finding.redacted
+                }) {
+                    entry.1 += 1;
+                } else {
+                    entries.push((finding.clone(), 1));
+                }
+            }
+
+            for (finding, count) in &entries {
                 let sev_color = match finding.severity {
                     SecuritySeverity::Critical => iced::Color::from_rgb8(220, 38, 38),
                     SecuritySeverity::High     => iced::Color::from_rgb8(234, 88, 12),
@@ -370,11 +1508,39 @@ pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: them
                     finding.severity.label(),
                     finding.category
                 );
-                let finding_row = column![
+                let message = if !redact {
+                    finding.message.clone()
+                } else if *count > 1 {
+                    format!("{} (×{})", finding.redacted, count)
+                } else {
+                    finding.redacted.clone()
+                };
+                let mut finding_row = column![
                     text(badge_text).size(9).color(sev_color),
-                    text(finding.message.clone()).size(11).color(p.text_primary),
+                    text(message).size(11).color(p.text_primary),
                 ]
                 .spacing(2);
+                // A collapsed redacted entry may span several hosts, so only
+                // single findings get a remediation button.
+                if let (Some(action), 1) = (finding.action, *count) {
+                    finding_row = finding_row.push(
+                        button(text(action.label()).size(10).color(p.text_primary))
+                            .on_press(Message::SecurityAuditFix(action))
+                            .padding([3, 8])
+                            .style(move |_t: &iced::Theme, status: button::Status| button::Style {
+                                background: Some(iced::Background::Color(match status {
+                                    button::Status::Hovered => p.accent_hover,
+                                    _ => p.accent,
+                                })),
+                                text_color: p.text_primary,
+                                border: iced::Border {
+                                    radius: cr.into(),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            }),
+                    );
+                }
 
                 findings_col = findings_col.push(
                     container(finding_row)
@@ -402,12 +1568,58 @@ pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: them
                 format!("{} high/critical issue(s) require attention.", count_critical)
             };
             let summary_color = if count_critical == 0 { p.success } else { p.danger };
+            let visible = findings_clone.iter().filter(|f| shown.contains(&f.severity)).count();
+            let filter_line = text(format!("Showing {} of {} finding(s)", visible, findings_clone.len()))
+                .size(10)
+                .color(p.text_muted);
+
+            // Deep scan logs into every saved host, so it only runs on request.
+            let scan_line: Element<'static, Message> = match scan {
+                Some((done, total)) => column![
+                    text(format!("Deep scan: {done} of {total} host(s) checked"))
+                        .size(11)
+                        .color(p.text_secondary),
+                    iced::widget::progress_bar(0.0..=(*total).max(1) as f32, *done as f32)
+                        .height(Length::Fixed(4.0)),
+                ]
+                .spacing(4)
+                .into(),
+                None => Column::new().into(),
+            };
+            let deep_scan_button: Element<'static, Message> = if scan.is_some() {
+                dialog_button("Cancel Scan", Message::SecurityAuditCancelScan, false, theme, cr)
+            } else {
+                dialog_button("Deep Scan", Message::SecurityAuditDeepScan, false, theme, cr)
+            };
+
+            let notice_line: Element<'static, Message> = match notice {
+                Some((msg, is_error)) => text(msg.clone())
+                    .size(11)
+                    .color(if *is_error { p.danger } else { p.success })
+                    .into(),
+                None => Column::new().into(),
+            };
 
             column![
                 text("Security Audit").size(16).color(p.text_primary),
                 text(summary).size(11).color(summary_color),
+                notice_line,
+                chips,
+                filter_line,
+                row![
+                    select_button("Show details", !redact,
+                        Message::SecurityAuditRedactChanged(false), theme, cr),
+                    select_button("Hide sensitive details", redact,
+                        Message::SecurityAuditRedactChanged(true), theme, cr),
+                ].spacing(6),
+                scan_line,
                 scrollable(findings_col).height(Length::Fixed(340.0)),
-                dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
+                row![
+                    dialog_button(texts.cancel, Message::CloseDialog, false, theme, cr),
+                    dialog_button("Export Report", Message::SecurityAuditExport, true, theme, cr),
+                    deep_scan_button,
+                ]
+                .spacing(8),
             ]
             .spacing(12)
             .width(Length::Fixed(500.0))
@@ -442,19 +1654,99 @@ pub fn view_dialog(texts: &Texts, state: &DialogState, theme: AppTheme, lc: them
     card.into()
 }
 
+/// Widget id of a connection form input, used to focus it from quick-fix actions.
+pub fn passphrase_input_id() -> text_input::Id {
+    text_input::Id::new("key-passphrase")
+}
+
+pub fn quick_connect_input_id() -> text_input::Id {
+    text_input::Id::new("quick-connect-target")
+}
+
+pub fn unlock_input_id() -> text_input::Id {
+    text_input::Id::new("master-password")
+}
+
+pub fn connection_field_id(field: &str) -> text_input::Id {
+    text_input::Id::new(format!("connection-{}", field))
+}
+
+/// Masked password field with an eye toggle to reveal what was typed.
+fn password_input<'a>(
+    label: &'static str,
+    value: &str,
+    visible: bool,
+    theme: AppTheme,
+    cr: f32,
+) -> Column<'a, Message> {
+    let p = theme::palette(theme);
+    let value_owned = value.to_string();
+
+    let input = text_input("", &value_owned)
+        .id(connection_field_id("password"))
+        .secure(!visible)
+        .on_input(|v| Message::DialogFieldChanged("password".to_string(), v))
+        .padding(8)
+        .size(13)
+        .style(move |_t: &iced::Theme, status: text_input::Status| text_input::Style {
+            background: iced::Background::Color(p.bg_tertiary),
+            border: iced::Border {
+                color: match status {
+                    text_input::Status::Focused => p.border_focused,
+                    _ => p.border,
+                },
+                width: 1.0,
+                radius: cr.into(),
+            },
+            icon: p.text_muted,
+            placeholder: p.text_muted,
+            value: p.text_primary,
+            selection: p.accent,
+        });
+
+    let eye = button(text(if visible { "◉" } else { "◎" }).size(13).color(p.text_secondary))
+        .on_press(Message::DialogTogglePasswordVisible)
+        .padding([7, 10])
+        .style(move |_t: &iced::Theme, status: button::Status| button::Style {
+            background: Some(iced::Background::Color(match status {
+                button::Status::Hovered => p.bg_hover,
+                _ => p.bg_tertiary,
+            })),
+            text_color: p.text_secondary,
+            border: iced::Border {
+                color: p.border,
+                width: 1.0,
+                radius: cr.into(),
+            },
+            ..Default::default()
+        });
+
+    column![
+        text(label).size(11).color(p.text_secondary),
+        row![input, eye].spacing(4).align_y(iced::Alignment::Center),
+    ]
+    .spacing(4)
+}
+
 fn labeled_input<'a>(
     label: &'static str,
     value: &str,
     on_input: impl Fn(String) -> Message + 'static,
+    id: Option<text_input::Id>,
     theme: AppTheme,
     cr: f32,
 ) -> Column<'a, Message> {
     let p = theme::palette(theme);
     let value_owned = value.to_string();
 
+    let mut input = text_input("", &value_owned);
+    if let Some(id) = id {
+        input = input.id(id);
+    }
+
     column![
         text(label).size(11).color(p.text_secondary),
-        text_input("", &value_owned)
+        input
             .on_input(on_input)
             .padding(8)
             .size(13)
@@ -515,8 +1807,62 @@ fn dialog_button(
         .into()
 }
 
+/// Environment variables of the connection form: one row per variable and
+/// an input row to add another.
+fn env_table(texts: &Texts, form: &ConnectionForm, theme: AppTheme, cr: f32) -> Element<'static, Message> {
+    let p = theme::palette(theme);
+    let input_style = move |_t: &iced::Theme, status: text_input::Status| text_input::Style {
+        background: iced::Background::Color(p.bg_tertiary),
+        border: iced::Border {
+            color: match status {
+                text_input::Status::Focused => p.border_focused,
+                _ => p.border,
+            },
+            width: 1.0,
+            radius: cr.into(),
+        },
+        icon: p.text_muted,
+        placeholder: p.text_muted,
+        value: p.text_primary,
+        selection: p.accent,
+    };
+    let mut rows = Column::new().spacing(4).push(text(texts.environment).size(11).color(p.text_secondary));
+    for (i, (name, value)) in form.env.iter().enumerate() {
+        rows = rows.push(
+            row![
+                text(format!("{}={}", name, value)).size(11).color(p.text_primary).width(Length::Fill),
+                select_button("×", false, Message::ConnectionEnvRemove(i), theme, cr),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+        );
+    }
+    rows.push(
+        row![
+            text_input("NAME", &form.new_env.0)
+                .on_input(|v| Message::DialogFieldChanged("env_name".to_string(), v))
+                .on_submit(Message::ConnectionEnvAdd)
+                .padding(6)
+                .size(12)
+                .width(Length::FillPortion(2))
+                .style(input_style),
+            text_input("value", &form.new_env.1)
+                .on_input(|v| Message::DialogFieldChanged("env_value".to_string(), v))
+                .on_submit(Message::ConnectionEnvAdd)
+                .padding(6)
+                .size(12)
+                .width(Length::FillPortion(3))
+                .style(input_style),
+            select_button("Add", false, Message::ConnectionEnvAdd, theme, cr),
+        ]
+        .spacing(6)
+        .align_y(iced::Alignment::Center),
+    )
+    .into()
+}
+
 fn select_button(
-    label: &'static str,
+    label: impl ToString,
     selected: bool,
     msg: Message,
     theme: AppTheme,
@@ -524,7 +1870,7 @@ fn select_button(
 ) -> Element<'static, Message> {
     let p = theme::palette(theme);
 
-    button(text(label).size(12).color(p.text_primary))
+    button(text(label.to_string()).size(12).color(p.text_primary))
         .on_press(msg)
         .padding([6, 12])
         .style(move |_t: &iced::Theme, status: button::Status| {
@@ -1,4 +1,6 @@
-use iced::widget::{button, column, container, row, scrollable, text, text_input, Column};
+use iced::widget::{
+    button, column, container, progress_bar, row, scrollable, text, text_input, Column,
+};
 use iced::{Alignment, Element, Length};
 
 use crate::app::{FtpLayout, FtpState, FtpStatus, Message};
@@ -61,21 +63,82 @@ pub fn view(state: &FtpState, theme: AppTheme, lc: theme::LayoutConfig) -> Eleme
         nav_btn("Up", Message::FtpNavigate(parent), can_go_up, p, cr),
         nav_btn("/root", Message::FtpNavigate("/".to_string()), can_root, p, cr),
         nav_btn("Refresh", Message::FtpRefresh, !in_search, p, cr),
+        nav_btn("New Folder", Message::FtpStartMakeDir, !in_search, p, cr),
         nav_btn("Upload", Message::FtpPickUploadFile, !in_search, p, cr),
     ]
     .spacing(4)
     .align_y(Alignment::Center);
 
+    // ── Inline "New Folder" input ─────────────────────────────────────
+    let new_folder: Element<'static, Message> = match &state.new_folder {
+        Some(name) => {
+            let submit = Message::FtpMakeDir(name.clone());
+            row![
+                text_input("Folder name", name)
+                    .on_input(Message::FtpNewFolderInput)
+                    .on_submit(submit.clone())
+                    .padding([3, 6])
+                    .size(11)
+                    .width(Length::Fill)
+                    .style(move |_t: &iced::Theme, _status: text_input::Status| text_input::Style {
+                        background: iced::Background::Color(p.bg_primary),
+                        border: iced::Border {
+                            color: p.border_focused,
+                            width: 1.0,
+                            radius: cr.into(),
+                        },
+                        icon: p.text_muted,
+                        placeholder: p.text_muted,
+                        value: p.text_primary,
+                        selection: p.accent,
+                    }),
+                nav_btn("Create", submit, !name.trim().is_empty(), p, cr),
+                nav_btn("Esc", Message::FtpCancelMakeDir, true, p, cr),
+            ]
+            .spacing(4)
+            .align_y(Alignment::Center)
+            .into()
+        }
+        None => iced::widget::Space::new(0.0, 0.0).into(),
+    };
+
     // ── Notification bar ──────────────────────────────────────────────
-    let notification: Element<'static, Message> = match &state.notification {
-        Some((msg, is_err)) => {
+    let notification: Element<'static, Message> = match (&state.transfer, &state.notification) {
+        (Some((done, total)), notice) => {
+            let label = notice.as_ref().map(|(msg, _)| msg.clone()).unwrap_or_default();
+            let detail = if *total > 0 {
+                format!("{} {} / {}", label, ftp::format_size(*done), ftp::format_size(*total))
+            } else {
+                format!("{} {}", label, ftp::format_size(*done))
+            };
+            container(
+                column![
+                    text(detail).size(10).color(p.text_secondary),
+                    progress_bar(0.0..=(*total).max(1) as f32, *done as f32)
+                        .height(Length::Fixed(3.0))
+                        .style(move |_t: &iced::Theme| progress_bar::Style {
+                            background: iced::Background::Color(p.bg_tertiary),
+                            bar: iced::Background::Color(p.accent),
+                            border: iced::Border {
+                                radius: cr.into(),
+                                ..Default::default()
+                            },
+                        }),
+                ]
+                .spacing(2),
+            )
+            .padding([2, 8])
+            .width(Length::Fill)
+            .into()
+        }
+        (None, Some((msg, is_err))) => {
             let color = if *is_err { p.danger } else { p.success };
             container(text(msg.clone()).size(10).color(color))
                 .padding([2, 8])
                 .width(Length::Fill)
                 .into()
         }
-        None => iced::widget::Space::new(0.0, 0.0).into(),
+        (None, None) => iced::widget::Space::new(0.0, 0.0).into(),
     };
 
     // ── File / search result list ─────────────────────────────────────
@@ -100,7 +163,11 @@ pub fn view(state: &FtpState, theme: AppTheme, lc: theme::LayoutConfig) -> Eleme
     } else {
         let mut col = Column::new().spacing(0);
         for entry in &state.entries {
-            col = col.push(entry_row(entry, p, cr));
+            let row = match &state.renaming {
+                Some((path, name)) if *path == entry.path => rename_row(path, name, p, cr),
+                _ => entry_row(entry, p, cr),
+            };
+            col = col.push(row);
         }
         col
     };
@@ -115,7 +182,7 @@ pub fn view(state: &FtpState, theme: AppTheme, lc: theme::LayoutConfig) -> Eleme
 
     let panel = column![
         container(
-            column![header, search_bar].spacing(6)
+            column![header, search_bar, new_folder].spacing(6)
         )
         .width(Length::Fill)
         .padding([4, 8])
@@ -165,6 +232,8 @@ fn entry_row(entry: &crate::ftp::FtpEntry, p: crate::theme::Palette, cr: f32) ->
         ftp::format_size(entry.size)
     };
 
+    let mode_str = entry.perm.map(ftp::format_mode).unwrap_or_default();
+
     let msg = if is_dir {
         Message::FtpNavigate(path.clone())
     } else {
@@ -174,12 +243,16 @@ fn entry_row(entry: &crate::ftp::FtpEntry, p: crate::theme::Palette, cr: f32) ->
     let name_color = if is_dir { p.accent } else { p.text_primary };
     let prefix = if is_dir { "▸ " } else { "  " };
 
-    button(
+    let open = button(
         row![
             text(format!("{}{}", prefix, name))
                 .size(11)
                 .color(name_color)
                 .width(Length::Fill),
+            text(mode_str)
+                .size(10)
+                .font(iced::Font::MONOSPACE)
+                .color(p.text_muted),
             text(size_str)
                 .size(10)
                 .color(p.text_muted)
@@ -202,7 +275,52 @@ fn entry_row(entry: &crate::ftp::FtpEntry, p: crate::theme::Palette, cr: f32) ->
             ..Default::default()
         },
         ..Default::default()
-    })
+    });
+
+    row![
+        open,
+        nav_btn(
+            "Mod",
+            Message::FtpOpenChmod(path.clone(), entry.perm.unwrap_or(0o644)),
+            entry.perm.is_some(),
+            p,
+            cr,
+        ),
+        nav_btn("Ren", Message::FtpStartRename(path.clone()), true, p, cr),
+        nav_btn("Del", Message::FtpAskDelete(path, is_dir), true, p, cr),
+    ]
+    .spacing(2)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+fn rename_row(path: &str, name: &str, p: crate::theme::Palette, cr: f32) -> Element<'static, Message> {
+    let submit = Message::FtpRename(path.to_string(), name.to_string());
+    row![
+        text_input("New name", name)
+            .on_input(Message::FtpRenameInput)
+            .on_submit(submit.clone())
+            .padding([3, 6])
+            .size(11)
+            .width(Length::Fill)
+            .style(move |_t: &iced::Theme, _status: text_input::Status| text_input::Style {
+                background: iced::Background::Color(p.bg_primary),
+                border: iced::Border {
+                    color: p.border_focused,
+                    width: 1.0,
+                    radius: cr.into(),
+                },
+                icon: p.text_muted,
+                placeholder: p.text_muted,
+                value: p.text_primary,
+                selection: p.accent,
+            }),
+        nav_btn("OK", submit, !name.trim().is_empty(), p, cr),
+        nav_btn("Esc", Message::FtpCancelRename, true, p, cr),
+    ]
+    .spacing(2)
+    .padding([2, 10])
+    .align_y(Alignment::Center)
     .into()
 }
 
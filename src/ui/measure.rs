@@ -0,0 +1,55 @@
+//! An invisible, fill-sized widget that reports the size it is laid out at.
+//!
+//! Stacked over another widget it measures the space that widget gets, which
+//! iced otherwise only exposes through the `lazy` feature's `responsive`.
+
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer;
+use iced::advanced::widget::{Tree, Widget};
+use iced::{mouse, Element, Length, Rectangle, Size};
+
+pub struct Measure<'a> {
+    on_layout: Box<dyn Fn(Size) + 'a>,
+}
+
+pub fn measure<'a>(on_layout: impl Fn(Size) + 'a) -> Measure<'a> {
+    Measure {
+        on_layout: Box::new(on_layout),
+    }
+}
+
+impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer> for Measure<'_>
+where
+    Renderer: renderer::Renderer,
+{
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Fill, Length::Fill)
+    }
+
+    fn layout(&self, _tree: &mut Tree, _renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        let size = limits.resolve(Length::Fill, Length::Fill, Size::ZERO);
+        (self.on_layout)(size);
+        layout::Node::new(size)
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        _renderer: &mut Renderer,
+        _theme: &Theme,
+        _style: &renderer::Style,
+        _layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+    ) {
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<Measure<'a>> for Element<'a, Message, Theme, Renderer>
+where
+    Renderer: renderer::Renderer + 'a,
+{
+    fn from(measure: Measure<'a>) -> Self {
+        Element::new(measure)
+    }
+}
@@ -4,3 +4,5 @@ pub mod tab_bar;
 pub mod status_bar;
 pub mod dialogs;
 pub mod ftp_panel;
+pub mod measure;
+pub mod chart;
@@ -1,27 +1,73 @@
-use iced::widget::{button, column, container, progress_bar, row, scrollable, text, text_input, Column};
+use iced::widget::{button, checkbox, column, container, pick_list, progress_bar, row, scrollable, text, text_input, tooltip, Column, Row};
 use iced::{Alignment, Element, Length};
 
-use crate::app::{LocalSystemInfo, Message};
-use crate::config::{AppTheme, Host};
+use crate::app::{structure_depth, HostErrorKind, LocalSystemInfo, Message};
+use crate::syspanel::RemoteInfo;
+use crate::config::{AppTheme, Host, HostSort};
 use crate::i18n::Texts;
 use crate::theme;
-use std::collections::HashMap;
+use crate::ui::chart;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Bucket name for hosts without a group.
+pub const UNGROUPED: &str = "Ungrouped";
+/// Collapse key of that bucket; a group really named "Ungrouped" can't clash with it.
+const UNGROUPED_KEY: &str = "\0ungrouped";
+
+/// The host list and how it is filtered, sorted and marked.
+pub struct HostListView<'a> {
+    pub hosts: &'a [Host],
+    pub search_query: &'a str,
+    pub selected_host: Option<usize>,
+    /// Checked rows while multi-select is on.
+    pub checked_hosts: Option<&'a HashSet<usize>>,
+    pub sort: HostSort,
+    /// Collapse keys of the folded groups (see `ToggleHostGroup`).
+    pub collapsed_groups: &'a HashSet<String>,
+}
+
+/// Ping state shown in the host list.
+pub struct PingView<'a> {
+    /// Latest one-shot result per host index.
+    pub results: &'a HashMap<usize, Option<u128>>,
+    /// Rolling samples from the latency monitor.
+    pub history: &'a HashMap<usize, VecDeque<Option<u128>>>,
+    /// Host index the monitor is currently pinging.
+    pub monitored: Option<usize>,
+    /// Port scan results by hostname (`None`: still probing).
+    pub scans: &'a HashMap<String, Vec<(u16, Option<bool>)>>,
+}
+
+/// Usage gauges at the bottom of the sidebar.
+pub struct MonitorView<'a> {
+    pub local: &'a LocalSystemInfo,
+    /// Label, usage and recent samples of the active tab's host, once fetched.
+    pub remote: Option<(&'a str, &'a RemoteInfo, &'a VecDeque<RemoteInfo>)>,
+}
+
+/// How a host row is marked.
+struct RowMark {
+    selected: bool,
+    /// Show the reorder arrows (selected row, manual order only).
+    movable: bool,
+    /// Checkbox state while multi-select is on; clicking the row toggles it.
+    checked: Option<bool>,
+}
 
 pub fn view(
     texts: &Texts,
-    hosts: &[Host],
-    search_query: &str,
-    selected_host: Option<usize>,
-    ping_results: &HashMap<usize, Option<u128>>,
-    system_info: &LocalSystemInfo,
-    _structure: &[String],
+    list: HostListView<'_>,
+    ping: PingView<'_>,
+    monitor: MonitorView<'_>,
+    structure: &[String],
     theme: AppTheme,
     lc: theme::LayoutConfig,
 ) -> Element<'static, Message> {
+    let HostListView { hosts, search_query, selected_host, checked_hosts, sort, collapsed_groups } = list;
     let p = theme::palette(theme);
     let cr = lc.corner_radius;
 
-    let search = text_input(texts.search_placeholder, search_query)
+    let search_input = text_input(texts.search_placeholder, search_query)
         .on_input(Message::SearchInput)
         .padding([6, 8])
         .size(11)
@@ -40,128 +86,143 @@ pub fn view(
             value: p.text_primary,
             selection: p.accent,
         });
+    let select_label = if checked_hosts.is_some() { "Done" } else { "Select" };
+    let search = row![
+        search_input,
+        action_button(select_label, Message::ToggleMultiSelect, false, theme, cr),
+    ]
+    .spacing(4)
+    .align_y(Alignment::Center);
+
+    let sort_picker = pick_list(HostSort::all(), Some(sort), Message::HostSortChanged)
+        .text_size(10)
+        .padding([3, 6])
+        .width(Length::Fill)
+        .style(move |_t: &iced::Theme, status: pick_list::Status| pick_list::Style {
+            text_color: p.text_secondary,
+            placeholder_color: p.text_muted,
+            handle_color: p.text_muted,
+            background: iced::Background::Color(p.bg_primary),
+            border: iced::Border {
+                color: match status {
+                    pick_list::Status::Hovered | pick_list::Status::Opened => p.border_focused,
+                    _ => p.border,
+                },
+                width: 1.0,
+                radius: cr.into(),
+            },
+        });
 
     let query_lower = search_query.to_lowercase();
-    let filtered_hosts: Vec<(usize, &Host)> = hosts
+    let mut filtered_hosts: Vec<(usize, &Host)> = hosts
         .iter()
         .enumerate()
-        .filter(|(_, h)| {
-            if query_lower.is_empty() {
-                return true;
-            }
-            h.alias.to_lowercase().contains(&query_lower)
-                || h.hostname.to_lowercase().contains(&query_lower)
-                || h.username.to_lowercase().contains(&query_lower)
-        })
+        .filter(|(_, h)| matches_query(h, &query_lower))
         .collect();
+    sort_hosts(&mut filtered_hosts, sort, ping.results);
 
-    let mut host_list = Column::new().spacing(1);
-    for (idx, host) in &filtered_hosts {
-        let is_selected = selected_host == Some(*idx);
-        let is_synced = host.id.is_some();
-        let dot_color = if is_synced { p.success } else { p.text_muted };
-
-        let ping_text = match ping_results.get(idx) {
-            Some(Some(ms)) if *ms < 100 => text(format!("{}ms", ms)).size(9).color(p.success),
-            Some(Some(ms)) if *ms < 300 => text(format!("{}ms", ms)).size(9).color(p.warning),
-            Some(Some(ms)) => text(format!("{}ms", ms)).size(9).color(p.danger),
-            Some(None) => text("×").size(9).color(p.danger),
-            None => text("").size(9),
-        };
+    // Hosts are bucketed by group (sorted, "Ungrouped" last); with no groups
+    // at all the list stays flat. Collapsing is ignored while searching.
+    let mut groups: Vec<Option<&str>> = Vec::new();
+    for (_, host) in &filtered_hosts {
+        let group = host.group.as_deref().filter(|g| !g.is_empty());
+        if !groups.contains(&group) {
+            groups.push(group);
+        }
+    }
+    groups.sort_by(|a, b| match (a, b) {
+        (None, None) => std::cmp::Ordering::Equal,
+        (None, _) => std::cmp::Ordering::Greater,
+        (_, None) => std::cmp::Ordering::Less,
+        (Some(a), Some(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+    });
+    let grouped = groups.iter().any(Option::is_some);
 
-        let alias = host.alias.clone();
-        let host_info = format!("{}@{}", host.username, host.hostname);
-        let i = *idx;
+    let mut host_list = Column::new().spacing(1);
+    for group in groups {
+        let members: Vec<&(usize, &Host)> = filtered_hosts
+            .iter()
+            .filter(|(_, h)| h.group.as_deref().filter(|g| !g.is_empty()) == group)
+            .collect();
+        let key = group.unwrap_or(UNGROUPED_KEY).to_string();
+        let collapsed = grouped && query_lower.is_empty() && collapsed_groups.contains(&key);
 
-        let host_btn = button(
-            row![
-                text(if is_synced { "●" } else { "○" })
-                    .size(7)
-                    .color(dot_color),
-                column![
-                    text(alias).size(11).color(p.text_primary),
-                    text(host_info).size(9).color(p.text_muted),
-                ]
-                .spacing(1),
-                iced::widget::horizontal_space(),
-                ping_text,
-            ]
-            .spacing(6)
-            .align_y(Alignment::Center),
-        )
-        .on_press(Message::ConnectToHost(i))
-        .width(Length::Fill)
-        .padding([5, 8])
-        .style(move |_t: &iced::Theme, status: button::Status| {
-            let bg = if is_selected {
-                p.bg_active
-            } else {
-                match status {
-                    button::Status::Hovered => p.bg_hover,
-                    _ => iced::Color::TRANSPARENT,
-                }
-            };
-            button::Style {
-                background: Some(iced::Background::Color(bg)),
-                text_color: p.text_primary,
-                border: iced::Border {
-                    radius: cr.into(),
-                    ..Default::default()
+        if grouped {
+            let label = group.unwrap_or(UNGROUPED).to_string();
+            host_list = host_list.push(group_header(key, label, members.len(), collapsed, p, cr));
+        }
+        if collapsed {
+            continue;
+        }
+        for (idx, host) in members {
+            host_list = host_list.push(host_entry(
+                *idx,
+                host,
+                RowMark {
+                    selected: selected_host == Some(*idx),
+                    movable: sort == HostSort::Manual,
+                    checked: checked_hosts.map(|c| c.contains(idx)),
                 },
-                ..Default::default()
-            }
-        });
-
-        host_list = host_list.push(host_btn);
+                ping.results.get(idx),
+                ping.history.get(idx),
+                p,
+                cr,
+            ));
+        }
     }
 
-    let context_buttons: Element<'static, Message> = if let Some(sel) = selected_host {
+    let context_buttons: Element<'static, Message> = if let Some(checked) = checked_hosts {
+        let delete: Element<'static, Message> = if checked.is_empty() {
+            text("Click or press Space to select").size(9).color(p.text_muted).into()
+        } else {
+            action_button("Delete selected", Message::OpenBulkDeleteConfirm, true, theme, cr)
+        };
         row![
-            action_button("Edit", Message::OpenEditDialog(sel), false, theme, cr),
-            action_button("Del", Message::OpenDeleteConfirm(sel), true, theme, cr),
+            text(format!("{} selected", checked.len())).size(10).color(p.text_secondary),
+            iced::widget::horizontal_space(),
+            delete,
         ]
         .spacing(4)
+        .align_y(Alignment::Center)
         .into()
+    } else if let Some(sel) = selected_host {
+        let monitor_label = if ping.monitored == Some(sel) { "Stop" } else { "Monitor" };
+        let actions = row![
+            action_button("Edit", Message::OpenEditDialog(sel), false, theme, cr),
+            action_button("Del", Message::OpenDeleteConfirm(sel), true, theme, cr),
+            action_button(monitor_label, Message::ToggleLatencyMonitor(sel), false, theme, cr),
+            action_button("Tunnels", Message::OpenTunnels(sel), false, theme, cr),
+            action_button("Ports", Message::ScanPorts(sel), false, theme, cr),
+        ]
+        .spacing(4);
+        match hosts.get(sel).and_then(|h| ping.scans.get(&h.hostname)) {
+            Some(ports) => column![actions, port_scan_row(ports, p)].spacing(4).into(),
+            None => actions.into(),
+        }
     } else {
         row![].into()
     };
 
-    // Compact system monitor
-    let cpu_pct = system_info.cpu_usage;
-    let ram_pct = system_info.memory_usage;
-    let dsk_pct = system_info.disk_usage_percent;
-
-    let sys_monitor = column![
-        row![
-            text("CPU").size(9).color(p.text_muted).width(Length::Fixed(26.0)),
-            progress_bar(0.0..=100.0, cpu_pct).height(3).width(Length::Fill),
-            text(format!("{:.0}%", cpu_pct)).size(9).color(p.text_muted).width(Length::Fixed(28.0)),
-        ]
-        .spacing(4)
-        .align_y(Alignment::Center),
-        row![
-            text("RAM").size(9).color(p.text_muted).width(Length::Fixed(26.0)),
-            progress_bar(0.0..=100.0, ram_pct).height(3).width(Length::Fill),
-            text(format!("{:.0}%", ram_pct)).size(9).color(p.text_muted).width(Length::Fixed(28.0)),
-        ]
-        .spacing(4)
-        .align_y(Alignment::Center),
-        row![
-            text("DSK").size(9).color(p.text_muted).width(Length::Fixed(26.0)),
-            progress_bar(0.0..=100.0, dsk_pct).height(3).width(Length::Fill),
-            text(format!("{:.0}%", dsk_pct)).size(9).color(p.text_muted).width(Length::Fixed(28.0)),
-        ]
-        .spacing(4)
-        .align_y(Alignment::Center),
-    ]
-    .spacing(4);
+    // Compact system monitor: the active tab's host above the local machine
+    let local = monitor.local;
+    let mut sys_monitor = Column::new().spacing(4);
+    if let Some((label, remote, history)) = monitor.remote {
+        sys_monitor = sys_monitor
+            .push(text(label.to_string()).size(9).color(p.text_secondary))
+            .push(gauges(remote.cpu_usage, remote.memory_usage, remote.disk_usage_percent, p))
+            .push(chart::usage_chart(history, p))
+            .push(text("Local").size(9).color(p.text_secondary));
+    }
+    sys_monitor = sys_monitor.push(gauges(local.cpu_usage, local.memory_usage, local.disk_usage_percent, p));
 
     let sidebar_content = column![
         search,
+        sort_picker,
         scrollable(host_list)
             .height(Length::Fill)
             .style(hidden_scrollbar_style),
         context_buttons,
+        structure_tree(structure, p, cr),
         container(iced::widget::horizontal_rule(1))
             .padding([4, 0]),
         sys_monitor,
@@ -184,6 +245,432 @@ pub fn view(
         .into()
 }
 
+/// Scanned ports of the selected host: green when open, dim when closed,
+/// `…` while the probe runs.
+fn port_scan_row(ports: &[(u16, Option<bool>)], p: theme::Palette) -> Element<'static, Message> {
+    let mut chips = Row::new().spacing(6);
+    for &(port, open) in ports {
+        let (mark, color) = match open {
+            Some(true) => ("●", p.success),
+            Some(false) => ("○", p.text_muted),
+            None => ("…", p.text_muted),
+        };
+        chips = chips.push(text(format!("{mark}{port}")).size(9).color(color));
+    }
+    chips.wrap().into()
+}
+
+/// CPU, RAM and disk bars with their percentages.
+fn gauges(cpu_pct: f32, ram_pct: f32, dsk_pct: f32, p: theme::Palette) -> Element<'static, Message> {
+    let gauge = |label: &'static str, pct: f32| {
+        row![
+            text(label).size(9).color(p.text_muted).width(Length::Fixed(26.0)),
+            progress_bar(0.0..=100.0, pct).height(3).width(Length::Fill),
+            text(format!("{:.0}%", pct)).size(9).color(p.text_muted).width(Length::Fixed(28.0)),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center)
+    };
+    column![gauge("CPU", cpu_pct), gauge("RAM", ram_pct), gauge("DSK", dsk_pct)]
+        .spacing(4)
+        .into()
+}
+
+/// Remote directory tree of the active tab; `[D]` rows open and close.
+fn structure_tree(structure: &[String], p: theme::Palette, cr: f32) -> Element<'static, Message> {
+    if structure.is_empty() {
+        return column![].into();
+    }
+    let mut list = Column::new();
+    for (i, line) in structure.iter().enumerate() {
+        let (depth, entry) = structure_depth(line);
+        let indent = iced::Padding { left: depth as f32 * 10.0, ..iced::Padding::ZERO };
+        let item: Element<'static, Message> = match entry.strip_prefix("[D] ") {
+            Some(name) => {
+                let open = structure.get(i + 1).is_some_and(|next| structure_depth(next).0 > depth);
+                button(text(format!("{} {}", if open { "▾" } else { "▸" }, name)).size(10))
+                    .on_press(Message::StructureToggleDir(i))
+                    .padding([1, 4])
+                    .width(Length::Fill)
+                    .style(move |_t: &iced::Theme, status: button::Status| button::Style {
+                        background: Some(iced::Background::Color(match status {
+                            button::Status::Hovered => p.bg_hover,
+                            _ => iced::Color::TRANSPARENT,
+                        })),
+                        text_color: p.text_primary,
+                        border: iced::Border {
+                            radius: cr.into(),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .into()
+            }
+            None => {
+                let label = match entry.strip_prefix("[F] ") {
+                    Some(name) => format!("  {}", name),
+                    None => entry.to_string(),
+                };
+                container(text(label).size(10).color(p.text_muted))
+                    .padding([1, 4])
+                    .into()
+            }
+        };
+        list = list.push(container(item).padding(indent));
+    }
+    column![
+        row![
+            text("Files").size(10).color(p.text_secondary),
+            iced::widget::horizontal_space(),
+            move_button("⟳", Message::RefreshStructure, p, cr),
+        ]
+        .align_y(Alignment::Center),
+        scrollable(list)
+            .height(Length::Fixed(160.0))
+            .style(hidden_scrollbar_style),
+    ]
+    .spacing(2)
+    .into()
+}
+
+/// Orders the host list; hosts without a ping or connection time go last,
+/// ties keep their saved order.
+fn sort_hosts(hosts: &mut [(usize, &Host)], sort: HostSort, pings: &HashMap<usize, Option<u128>>) {
+    match sort {
+        HostSort::Manual => {}
+        HostSort::Alias => hosts.sort_by_key(|(_, h)| h.alias.to_lowercase()),
+        HostSort::LastConnected => {
+            hosts.sort_by_key(|(_, h)| std::cmp::Reverse(h.last_connected))
+        }
+        HostSort::Latency => hosts.sort_by_key(|(idx, _)| match pings.get(idx) {
+            Some(Some(ms)) => (0, *ms),
+            Some(None) => (1, 0),
+            None => (2, 0),
+        }),
+    }
+}
+
+/// Search filter: every `tag:x` token must be one of the host's tags and
+/// the remaining free text must match alias, hostname or username.
+fn matches_query(host: &Host, query_lower: &str) -> bool {
+    let mut free_text: Vec<&str> = Vec::new();
+    for token in query_lower.split_whitespace() {
+        match token.strip_prefix("tag:") {
+            Some(tag) => {
+                if !tag.is_empty() && !host.tags.iter().any(|t| t.to_lowercase() == tag) {
+                    return false;
+                }
+            }
+            None => free_text.push(token),
+        }
+    }
+    if free_text.is_empty() {
+        return true;
+    }
+    let text = free_text.join(" ");
+    host.alias.to_lowercase().contains(&text)
+        || host.hostname.to_lowercase().contains(&text)
+        || host.username.to_lowercase().contains(&text)
+}
+
+/// Stable chip color for a tag, so `prod` looks the same on every host.
+fn tag_color(tag: &str, p: theme::Palette) -> iced::Color {
+    let choices = [p.accent, p.success, p.warning, p.danger, p.text_secondary];
+    let hash = tag.bytes().fold(0usize, |acc, b| acc.wrapping_mul(31).wrapping_add(b as usize));
+    choices[hash % choices.len()]
+}
+
+/// One clickable host row: sync dot (or checkbox), alias / user@host, error
+/// badge and ping.
+fn host_entry(
+    idx: usize,
+    host: &Host,
+    mark: RowMark,
+    ping: Option<&Option<u128>>,
+    history: Option<&VecDeque<Option<u128>>>,
+    p: theme::Palette,
+    cr: f32,
+) -> Element<'static, Message> {
+    let is_selected = mark.selected;
+    let is_synced = host.id.is_some();
+    let dot_color = if is_synced { p.success } else { p.text_muted };
+
+    let ping_text = match ping {
+        Some(Some(ms)) if *ms < 100 => text(format!("{}ms", ms)).size(9).color(p.success),
+        Some(Some(ms)) if *ms < 300 => text(format!("{}ms", ms)).size(9).color(p.warning),
+        Some(Some(ms)) => text(format!("{}ms", ms)).size(9).color(p.danger),
+        Some(None) => text("×").size(9).color(p.danger),
+        None => text("").size(9),
+    };
+
+    let mut tag_chips = row![].spacing(3);
+    for tag in &host.tags {
+        let color = tag_color(tag, p);
+        tag_chips = tag_chips.push(
+            container(text(tag.clone()).size(8).color(color))
+                .padding([0, 4])
+                .style(move |_t: &iced::Theme| container::Style {
+                    border: iced::Border {
+                        color,
+                        width: 1.0,
+                        radius: 6.0.into(),
+                    },
+                    ..Default::default()
+                }),
+        );
+    }
+
+    // Latency monitor sparkline; stats on hover
+    let latency: Element<'static, Message> = match history.filter(|h| !h.is_empty()) {
+        Some(samples) => {
+            let stats = latency_stats(samples);
+            tooltip(
+                text(sparkline(samples)).size(9).color(p.accent),
+                container(text(stats).size(9).color(p.text_primary))
+                    .padding([3, 6])
+                    .style(move |_t: &iced::Theme| container::Style {
+                        background: Some(iced::Background::Color(p.bg_tertiary)),
+                        border: iced::Border {
+                            color: p.border,
+                            width: 1.0,
+                            radius: cr.into(),
+                        },
+                        ..Default::default()
+                    }),
+                tooltip::Position::Bottom,
+            )
+            .into()
+        }
+        None => column![].into(),
+    };
+
+    // Reorder arrows on the selected row only, to keep the list calm
+    let move_buttons: Element<'static, Message> = if is_selected && mark.movable {
+        column![
+            move_button("▲", Message::MoveHostUp(idx), p, cr),
+            move_button("▼", Message::MoveHostDown(idx), p, cr),
+        ]
+        .into()
+    } else {
+        column![].into()
+    };
+
+    let alias = host.alias.clone();
+    let host_info = format!("{}@{}", host.username, host.hostname);
+
+    // Last connection error: red "!" with the error on hover, click opens the quick-fix
+    let error_badge: Element<'static, Message> = match &host.last_error {
+        Some(err) => {
+            let kind = HostErrorKind::classify(err);
+            let when = host
+                .last_error_at
+                .map(format_ago)
+                .unwrap_or_default();
+            let tip = container(
+                column![
+                    text(err.clone()).size(10).color(p.danger),
+                    text(when).size(9).color(p.text_muted),
+                    text(kind.fix_hint()).size(9).color(p.text_secondary),
+                ]
+                .spacing(2),
+            )
+            .padding([4, 8])
+            .max_width(260)
+            .style(move |_t: &iced::Theme| container::Style {
+                background: Some(iced::Background::Color(p.bg_tertiary)),
+                border: iced::Border {
+                    color: p.danger,
+                    width: 1.0,
+                    radius: cr.into(),
+                },
+                ..Default::default()
+            });
+            tooltip(
+                button(text("!").size(10).color(p.danger))
+                    .on_press(Message::OpenHostFix(idx))
+                    .padding([0, 5])
+                    .style(move |_t: &iced::Theme, status: button::Status| button::Style {
+                        background: Some(iced::Background::Color(match status {
+                            button::Status::Hovered => p.bg_hover,
+                            _ => iced::Color::TRANSPARENT,
+                        })),
+                        text_color: p.danger,
+                        border: iced::Border {
+                            color: p.danger,
+                            width: 1.0,
+                            radius: cr.into(),
+                        },
+                        ..Default::default()
+                    }),
+                tip,
+                tooltip::Position::Right,
+            )
+            .into()
+        }
+        None => text("").size(9).into(),
+    };
+
+    let lead: Element<'static, Message> = match mark.checked {
+        Some(checked) => checkbox("", checked)
+            .on_toggle(move |_| Message::ToggleHostChecked(idx))
+            .size(12)
+            .spacing(0)
+            .into(),
+        None => text(if is_synced { "●" } else { "○" })
+            .size(7)
+            .color(dot_color)
+            .into(),
+    };
+    let on_press = match mark.checked {
+        Some(_) => Message::ToggleHostChecked(idx),
+        None => Message::ConnectToHost(idx),
+    };
+
+    let host_btn = button(
+        row![
+            lead,
+            column![
+                text(alias).size(11).color(p.text_primary),
+                text(host_info).size(9).color(p.text_muted),
+                tag_chips,
+                latency,
+            ]
+            .spacing(1),
+            iced::widget::horizontal_space(),
+            error_badge,
+            ping_text,
+            move_buttons,
+        ]
+        .spacing(6)
+        .align_y(Alignment::Center),
+    )
+    .on_press(on_press)
+    .width(Length::Fill)
+    .padding([5, 8])
+    .style(move |_t: &iced::Theme, status: button::Status| {
+        let bg = if is_selected {
+            p.bg_active
+        } else {
+            match status {
+                button::Status::Hovered => p.bg_hover,
+                _ => iced::Color::TRANSPARENT,
+            }
+        };
+        button::Style {
+            background: Some(iced::Background::Color(bg)),
+            text_color: p.text_primary,
+            border: iced::Border {
+                radius: cr.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    });
+
+    host_btn.into()
+}
+
+/// Unicode block sparkline, scaled to the window's max; lost probes show as `·`.
+fn sparkline(samples: &VecDeque<Option<u128>>) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = samples.iter().flatten().copied().max().unwrap_or(1).max(1);
+    samples
+        .iter()
+        .map(|s| match s {
+            Some(ms) => BLOCKS[((*ms * 7) / max) as usize],
+            None => '·',
+        })
+        .collect()
+}
+
+fn latency_stats(samples: &VecDeque<Option<u128>>) -> String {
+    let ok: Vec<u128> = samples.iter().flatten().copied().collect();
+    let loss = (samples.len() - ok.len()) * 100 / samples.len();
+    match (ok.iter().min(), ok.iter().max()) {
+        (Some(min), Some(max)) => format!(
+            "avg {}ms · min {}ms · max {}ms · loss {}%",
+            ok.iter().sum::<u128>() / ok.len() as u128,
+            min,
+            max,
+            loss
+        ),
+        _ => format!("no replies · loss {}%", loss),
+    }
+}
+
+fn move_button(label: &'static str, msg: Message, p: theme::Palette, cr: f32) -> Element<'static, Message> {
+    button(text(label).size(7).color(p.text_muted))
+        .on_press(msg)
+        .padding([0, 3])
+        .style(move |_t: &iced::Theme, status: button::Status| button::Style {
+            background: Some(iced::Background::Color(match status {
+                button::Status::Hovered => p.bg_hover,
+                _ => iced::Color::TRANSPARENT,
+            })),
+            text_color: p.text_muted,
+            border: iced::Border {
+                radius: cr.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
+/// Collapsible header for a sidebar host group.
+fn group_header(
+    key: String,
+    label: String,
+    count: usize,
+    collapsed: bool,
+    p: theme::Palette,
+    cr: f32,
+) -> Element<'static, Message> {
+    let arrow = if collapsed { "▸" } else { "▾" };
+    button(
+        row![
+            text(arrow).size(9).color(p.text_muted),
+            text(label).size(10).color(p.text_secondary),
+            text(format!("({})", count)).size(9).color(p.text_muted),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center),
+    )
+    .on_press(Message::ToggleHostGroup(key))
+    .width(Length::Fill)
+    .padding([3, 6])
+    .style(move |_t: &iced::Theme, status: button::Status| button::Style {
+        background: Some(iced::Background::Color(match status {
+            button::Status::Hovered => p.bg_hover,
+            _ => iced::Color::TRANSPARENT,
+        })),
+        text_color: p.text_secondary,
+        border: iced::Border {
+            radius: cr.into(),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .into()
+}
+
+pub fn format_ago(timestamp: u64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(timestamp);
+    let secs = now.saturating_sub(timestamp);
+    if secs < 60 {
+        "just now".to_string()
+    } else if secs < 3600 {
+        format!("{} min ago", secs / 60)
+    } else if secs < 86_400 {
+        format!("{} h ago", secs / 3600)
+    } else {
+        format!("{} d ago", secs / 86_400)
+    }
+}
+
 fn action_button(
     label: &'static str,
     msg: Message,
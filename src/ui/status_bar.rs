@@ -1,4 +1,4 @@
-use iced::widget::{container, horizontal_space, row, text};
+use iced::widget::{button, container, horizontal_space, row, text};
 use iced::{Alignment, Element, Length};
 
 use crate::app::Message;
@@ -6,9 +6,24 @@ use crate::config::{AppTheme, Language};
 use crate::i18n::Texts;
 use crate::theme;
 
+/// What the status bar says about API sync.
+pub enum SyncStatus {
+    /// No API key; hosts are only stored locally.
+    Local,
+    /// Key set, no sync finished yet this session.
+    Idle,
+    Syncing,
+    /// Host count and seconds since the sync finished.
+    Synced(usize, u64),
+    /// Last sync failed; the host list is the cached copy. Holds the number
+    /// of changes queued for the next sync.
+    Offline(usize),
+}
+
 pub fn view(
     texts: &Texts,
-    has_api_key: bool,
+    sync: SyncStatus,
+    notification: Option<&(String, bool)>,
     language: Language,
     theme: AppTheme,
     lc: theme::LayoutConfig,
@@ -16,19 +31,47 @@ pub fn view(
     let p = theme::palette(theme);
     let cr = lc.corner_radius;
 
-    let sync_indicator = if has_api_key {
-        text(format!("● {}", texts.sync_status_connected))
-            .size(10)
-            .color(p.success)
-    } else {
-        text(format!("○ {}", texts.sync_status_local))
-            .size(10)
-            .color(p.text_muted)
+    let (sync_label, sync_color) = match sync {
+        SyncStatus::Local => (format!("○ {}", texts.sync_status_local), p.text_muted),
+        SyncStatus::Idle => (format!("● {}", texts.sync_status_connected), p.success),
+        SyncStatus::Syncing => ("⟳ syncing…".to_string(), p.accent),
+        SyncStatus::Synced(count, secs) => {
+            let ago = if secs < 60 { "just now".to_string() } else { format!("{} min ago", secs / 60) };
+            (format!("● {} · {} hosts · {}", texts.sync_status_connected, count, ago), p.success)
+        }
+        SyncStatus::Offline(0) => ("✕ offline — showing cached hosts · retry".to_string(), p.danger),
+        SyncStatus::Offline(queued) => (
+            format!("✕ offline — showing cached hosts · {} change(s) queued · retry", queued),
+            p.danger,
+        ),
     };
+    // Click to sync now; a no-op without an API key.
+    let sync_indicator = button(text(sync_label).size(10).color(sync_color))
+        .on_press(Message::SyncFromApi)
+        .padding(0)
+        .style(move |_t: &iced::Theme, _status: button::Status| button::Style {
+            background: None,
+            text_color: sync_color,
+            ..Default::default()
+        });
+
+    let lang_text = language.code();
 
-    let lang_text = match language {
-        Language::Turkish => "TR",
-        Language::English => "EN",
+    // App-wide notice (import results etc.); click to dismiss
+    let notice: Element<'static, Message> = match notification {
+        Some((msg, is_error)) => {
+            let color = if *is_error { p.danger } else { p.success };
+            button(text(msg.clone()).size(10).color(color))
+                .on_press(Message::DismissNotification)
+                .padding([0, 6])
+                .style(move |_t: &iced::Theme, _status: button::Status| button::Style {
+                    background: None,
+                    text_color: color,
+                    ..Default::default()
+                })
+                .into()
+        }
+        None => text("").size(10).into(),
     };
 
     let bar = row![
@@ -38,6 +81,7 @@ pub fn view(
         text("  ·  ").size(10).color(p.border),
         text("termissh.org").size(10).color(p.accent),
         horizontal_space(),
+        notice,
         sync_indicator,
         text("  ·  ").size(10).color(p.border),
         text(lang_text).size(10).color(p.text_muted),
@@ -5,21 +5,35 @@ use crate::app::{Message, TerminalTab};
 use crate::config::AppTheme;
 use crate::theme;
 
-pub fn view(tabs: &[TerminalTab], active_tab: Option<usize>, theme: AppTheme, lc: theme::LayoutConfig) -> Element<'static, Message> {
+/// `titles` are the labels to show, one per tab (see `title_template`).
+pub fn view(
+    tabs: &[TerminalTab],
+    titles: &[String],
+    active_tab: Option<usize>,
+    theme: AppTheme,
+    lc: theme::LayoutConfig,
+) -> Element<'static, Message> {
     let p = theme::palette(theme);
     let cr = lc.corner_radius;
     let mut tab_row: Row<'static, Message> = Row::new().spacing(2).padding([2, 6]);
 
     for (idx, tab) in tabs.iter().enumerate() {
         let is_active = active_tab == Some(idx);
-        let label = tab.label.clone();
+        let label = titles.get(idx).cloned().unwrap_or_else(|| tab.label.clone());
         let connected = tab.connected;
+        let activity = tab.has_activity && !is_active;
 
-        let dot_color = if connected { p.success } else { p.text_muted };
+        let dot_color = if activity {
+            p.warning
+        } else if connected {
+            p.success
+        } else {
+            p.text_muted
+        };
 
         let tab_btn = button(
             row![
-                text(if connected { "●" } else { "○" })
+                text(if connected || activity { "●" } else { "○" })
                     .size(8)
                     .color(dot_color),
                 text(label)
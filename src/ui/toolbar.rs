@@ -1,21 +1,52 @@
-use iced::widget::{button, container, horizontal_space, row, text};
+use std::fmt;
+
+use iced::widget::{button, container, horizontal_space, pick_list, row, text};
 use iced::{Alignment, Element, Length};
 
 use crate::app::Message;
 use crate::config::AppTheme;
 use crate::i18n::Texts;
 use crate::theme;
+use crate::ui::sidebar::format_ago;
+
+/// A recently connected host offered by the Recent menu.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentHost {
+    pub idx: usize,
+    pub alias: String,
+    /// Unix time of the last connection attempt.
+    pub at: u64,
+}
+
+impl fmt::Display for RecentHost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}  ·  {}", self.alias, format_ago(self.at))
+    }
+}
 
-pub fn view(texts: &Texts, theme: AppTheme, lc: theme::LayoutConfig) -> Element<'static, Message> {
+pub fn view(
+    texts: &Texts,
+    audit_alerts: usize,
+    broadcast: bool,
+    recent: Vec<RecentHost>,
+    theme: AppTheme,
+    lc: theme::LayoutConfig,
+) -> Element<'static, Message> {
     let p = theme::palette(theme);
     let cr = lc.corner_radius;
 
     let toolbar = row![
         toolbar_button("+ New", Message::OpenNewDialog, theme, cr),
+        toolbar_button("Quick Connect", Message::OpenQuickConnect, theme, cr),
+        recent_menu(recent, theme, cr),
         toolbar_button("Ping", Message::PingAll, theme, cr),
+        toolbar_button("Import", Message::ImportHosts, theme, cr),
+        toolbar_button("Import from ~/.ssh/config", Message::ImportSshConfig, theme, cr),
+        toolbar_button("Export", Message::OpenExportHosts, theme, cr),
         horizontal_space(),
+        broadcast_button(broadcast, theme, cr),
         toolbar_button("Aliases", Message::OpenCustomCommands, theme, cr),
-        toolbar_button("Security", Message::OpenSecurityAudit, theme, cr),
+        security_button(audit_alerts, theme, cr),
         toolbar_button("FTP", Message::FtpToggle, theme, cr),
         toolbar_button(texts.settings, Message::OpenSettings, theme, cr),
     ]
@@ -37,14 +68,89 @@ pub fn view(texts: &Texts, theme: AppTheme, lc: theme::LayoutConfig) -> Element<
         .into()
 }
 
+/// Dropdown of the last connected hosts; picking one reconnects.
+fn recent_menu(recent: Vec<RecentHost>, theme: AppTheme, cr: f32) -> Element<'static, Message> {
+    let p = theme::palette(theme);
+    pick_list(recent, None::<RecentHost>, |r| Message::ConnectToHost(r.idx))
+        .placeholder("Recent")
+        .text_size(11)
+        .padding([3, 10])
+        .style(move |_t: &iced::Theme, status: pick_list::Status| pick_list::Style {
+            text_color: p.text_primary,
+            placeholder_color: p.text_primary,
+            handle_color: p.text_muted,
+            background: iced::Background::Color(match status {
+                pick_list::Status::Hovered | pick_list::Status::Opened => p.bg_hover,
+                _ => iced::Color::TRANSPARENT,
+            }),
+            border: iced::Border {
+                radius: cr.into(),
+                ..Default::default()
+            },
+        })
+        .into()
+}
+
+/// Security button with a badge counting new Critical/High audit findings.
+fn security_button(alerts: usize, theme: AppTheme, cr: f32) -> Element<'static, Message> {
+    if alerts == 0 {
+        return toolbar_button("Security", Message::OpenSecurityAudit, theme, cr);
+    }
+    let p = theme::palette(theme);
+
+    let badge = container(text(alerts.to_string()).size(9).color(iced::Color::WHITE))
+        .padding([0, 5])
+        .style(move |_t: &iced::Theme| container::Style {
+            background: Some(iced::Background::Color(p.danger)),
+            border: iced::Border {
+                radius: 8.0.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+    let content = row![text("Security").size(11).color(p.text_primary), badge]
+        .spacing(4)
+        .align_y(Alignment::Center);
+    toolbar_button_with(content.into(), Message::OpenSecurityAudit, theme, cr)
+}
+
+/// Toggle for sending keystrokes to every open tab; drawn in red while on.
+fn broadcast_button(active: bool, theme: AppTheme, cr: f32) -> Element<'static, Message> {
+    if !active {
+        return toolbar_button("Broadcast", Message::TerminalToggleBroadcast, theme, cr);
+    }
+    let p = theme::palette(theme);
+    toolbar_button_with(
+        text("Broadcast ON").size(11).color(p.danger).into(),
+        Message::TerminalToggleBroadcast,
+        theme,
+        cr,
+    )
+}
+
 fn toolbar_button(label: &'static str, msg: Message, theme: AppTheme, cr: f32) -> Element<'static, Message> {
     let p = theme::palette(theme);
 
-    button(
+    toolbar_button_with(
         text(label)
             .size(11)
-            .color(p.text_primary),
+            .color(p.text_primary)
+            .into(),
+        msg,
+        theme,
+        cr,
     )
+}
+
+fn toolbar_button_with(
+    content: Element<'static, Message>,
+    msg: Message,
+    theme: AppTheme,
+    cr: f32,
+) -> Element<'static, Message> {
+    let p = theme::palette(theme);
+
+    button(content)
     .on_press(msg)
     .padding([3, 10])
     .style(move |_t: &iced::Theme, status: button::Status| {